@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS revisions (
+    id TEXT PRIMARY KEY DEFAULT gen_random_uuid()::text,
+    seq BIGINT GENERATED ALWAYS AS IDENTITY,
+    link_id TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    action TEXT NOT NULL,
+    changes JSONB NOT NULL DEFAULT '[]',
+    link JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS revisions_link_id_idx ON revisions (link_id, seq);
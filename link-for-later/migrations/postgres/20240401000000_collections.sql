@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS collections (
+    id TEXT PRIMARY KEY DEFAULT gen_random_uuid()::text,
+    owner TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    position BIGINT NOT NULL DEFAULT 0,
+    links JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS collections_owner_idx ON collections (owner);
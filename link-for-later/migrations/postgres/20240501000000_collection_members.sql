@@ -0,0 +1,3 @@
+ALTER TABLE collections ADD COLUMN IF NOT EXISTS members JSONB NOT NULL DEFAULT '[]';
+
+CREATE INDEX IF NOT EXISTS collections_members_idx ON collections USING GIN (members);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS shares (
+    id TEXT PRIMARY KEY DEFAULT gen_random_uuid()::text,
+    owner TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    target TEXT NOT NULL,
+    token TEXT NOT NULL UNIQUE,
+    expires_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS shares_owner_idx ON shares (owner);
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS reminders (
+    id TEXT PRIMARY KEY DEFAULT gen_random_uuid()::text,
+    owner TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    link_id TEXT NOT NULL,
+    remind_at TIMESTAMPTZ NOT NULL,
+    notified_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS reminders_owner_idx ON reminders (owner);
+CREATE INDEX IF NOT EXISTS reminders_due_idx ON reminders (remind_at) WHERE notified_at IS NULL;
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS highlights (
+    id TEXT PRIMARY KEY DEFAULT gen_random_uuid()::text,
+    seq BIGINT GENERATED ALWAYS AS IDENTITY,
+    link_id TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    quote TEXT NOT NULL,
+    note TEXT NOT NULL DEFAULT '',
+    selectors JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS highlights_link_id_idx ON highlights (link_id, seq);
+CREATE INDEX IF NOT EXISTS highlights_owner_idx ON highlights (owner, seq);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS progress (
+    id TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    percentage INTEGER NOT NULL DEFAULT 0,
+    position BIGINT NOT NULL DEFAULT 0,
+    time_spent BIGINT NOT NULL DEFAULT 0,
+    finished_at TIMESTAMPTZ,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS progress_owner_idx ON progress (owner);
@@ -0,0 +1,3 @@
+ALTER TABLE links ADD COLUMN deleted_at TEXT;
+
+CREATE INDEX IF NOT EXISTS links_deleted_at_idx ON links (deleted_at) WHERE deleted_at IS NOT NULL;
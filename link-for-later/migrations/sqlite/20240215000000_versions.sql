@@ -0,0 +1 @@
+ALTER TABLE links ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
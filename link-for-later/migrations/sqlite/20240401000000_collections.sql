@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS collections (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    owner TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    position INTEGER NOT NULL DEFAULT 0,
+    links TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS collections_owner_idx ON collections (owner);
@@ -0,0 +1 @@
+ALTER TABLE collections ADD COLUMN members TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS shares (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    owner TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    target TEXT NOT NULL,
+    token TEXT NOT NULL UNIQUE,
+    expires_at TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS shares_owner_idx ON shares (owner);
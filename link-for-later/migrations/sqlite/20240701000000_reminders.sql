@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS reminders (
+    id TEXT PRIMARY KEY DEFAULT (lower(hex(randomblob(16)))),
+    owner TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    link_id TEXT NOT NULL,
+    remind_at TEXT NOT NULL,
+    notified_at TEXT,
+    created_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS reminders_owner_idx ON reminders (owner);
+CREATE INDEX IF NOT EXISTS reminders_due_idx ON reminders (remind_at) WHERE notified_at IS NULL;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS details (
+    id TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    priority TEXT NOT NULL DEFAULT 'normal',
+    rating INTEGER,
+    notes TEXT NOT NULL DEFAULT '',
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS details_owner_idx ON details (owner);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS highlights (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    id TEXT NOT NULL UNIQUE DEFAULT (lower(hex(randomblob(16)))),
+    link_id TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    quote TEXT NOT NULL,
+    note TEXT NOT NULL DEFAULT '',
+    selectors TEXT NOT NULL DEFAULT '[]',
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS highlights_link_id_idx ON highlights (link_id, seq);
+CREATE INDEX IF NOT EXISTS highlights_owner_idx ON highlights (owner, seq);
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS progress (
+    id TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    percentage INTEGER NOT NULL DEFAULT 0,
+    position INTEGER NOT NULL DEFAULT 0,
+    time_spent INTEGER NOT NULL DEFAULT 0,
+    finished_at TEXT,
+    updated_at TEXT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS progress_owner_idx ON progress (owner);
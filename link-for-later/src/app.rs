@@ -1,76 +1,413 @@
-use std::{error, fmt, sync::Arc};
+use std::{error, fmt, path::Path, sync::Arc, time::Duration};
 
 use axum::Router;
 
 use crate::{
     controller, repository,
-    repository::{DynLinks as DynLinksRepository, DynUsers as DynUsersRepository},
+    repository::{
+        DynCollections as DynCollectionsRepository, DynDetails as DynDetailsRepository,
+        DynHealth as DynHealthRepository, DynHighlights as DynHighlightsRepository,
+        DynLinks as DynLinksRepository, DynProgress as DynProgressRepository,
+        DynReminders as DynRemindersRepository, DynRevisions as DynRevisionsRepository,
+        DynShares as DynSharesRepository, DynSnapshots as DynSnapshotsRepository,
+        DynUsers as DynUsersRepository, DynWebhooks as DynWebhooksRepository,
+    },
     service,
+    service::notifiers::DynMailer,
     service::{
-        DynAnalysis as DynAnalysisService, DynLinks as DynLinksService, DynUsers as DynUsersService,
+        DynAnalysis as DynAnalysisService, DynCollections as DynCollectionsService,
+        DynDetails as DynDetailsService, DynHealth as DynHealthService,
+        DynHighlights as DynHighlightsService, DynLinks as DynLinksService,
+        DynNotifier as DynNotifierService, DynProgress as DynProgressService,
+        DynReminders as DynRemindersService, DynShares as DynSharesService,
+        DynSnapshots as DynSnapshotsService, DynUsers as DynUsersService,
+        DynWebhooks as DynWebhooksService,
     },
     types::Database,
 };
 
+const SNAPSHOTS_DIRECTORY_KEY: &str = "SNAPSHOTS_DIRECTORY";
+const SKIP_MALFORMED_DOCUMENTS_KEY: &str = "MONGODB_SKIP_MALFORMED_DOCUMENTS";
+const HEALTH_CHECK_INTERVAL_SECS_KEY: &str = "HEALTH_CHECK_INTERVAL_SECS";
+const INMEMORY_SNAPSHOT_INTERVAL_SECS_KEY: &str = "INMEMORY_SNAPSHOT_INTERVAL_SECS";
+const DEFAULT_INMEMORY_SNAPSHOT_INTERVAL_SECS: u64 = 300;
+const TRASH_RETENTION_DAYS_KEY: &str = "TRASH_RETENTION_DAYS";
+const DEFAULT_TRASH_RETENTION_DAYS: i64 = 30;
+const TRASH_PURGE_INTERVAL_SECS_KEY: &str = "TRASH_PURGE_INTERVAL_SECS";
+const REMINDER_NOTIFIER_KEY: &str = "REMINDER_NOTIFIER";
+const REMINDER_INTERVAL_SECS_KEY: &str = "REMINDER_INTERVAL_SECS";
+
+/// Builds the router with the repositories of the given database.
+///
+/// # Panics
+///
+/// Panics if a persistent in-memory database cannot be restored, run
+/// [`migrate`] first to get this as an error instead.
 pub fn new(db: Database) -> Router {
-    let links_service = Arc::new(service::links::ServiceProvider::default()) as DynLinksService;
-    let users_service = Arc::new(service::users::ServiceProvider::default()) as DynUsersService;
-    let analysis_service =
-        Arc::new(service::analysis::ServiceProvider::default()) as DynAnalysisService;
-    let (links_repo, users_repo) = match db {
-        Database::MongoDb(db) => (
-            Arc::new(repository::mongodb::LinksRepositoryProvider::new(&db)) as DynLinksRepository,
-            Arc::new(repository::mongodb::UsersRepositoryProvider::new(&db)) as DynUsersRepository,
-        ),
-        Database::InMemory => (
-            Arc::new(repository::inmemory::LinksRepositoryProvider::default())
-                as DynLinksRepository,
-            Arc::new(repository::inmemory::UsersRepositoryProvider::default())
-                as DynUsersRepository,
-        ),
-    };
-
-    let state = State::new(
-        links_service,
-        users_service,
-        analysis_service,
-        links_repo,
-        users_repo,
-    );
+    router(
+        db,
+        None,
+        std::env::var(SKIP_MALFORMED_DOCUMENTS_KEY).is_ok(),
+    )
+}
+
+/// Builds the router like [`new`], sending reminders by email through
+/// `mailer` instead of the notifier picked with `REMINDER_NOTIFIER`.
+///
+/// # Panics
+///
+/// Panics if a persistent in-memory database cannot be restored, run
+/// [`migrate`] first to get this as an error instead.
+pub fn with_mailer(db: Database, mailer: DynMailer) -> Router {
+    router(
+        db,
+        Some(mailer),
+        std::env::var(SKIP_MALFORMED_DOCUMENTS_KEY).is_ok(),
+    )
+}
+
+/// Builds the router like [`new`], with the `MongoDB` repository leaving out
+/// the links it can't read back whether `MONGODB_SKIP_MALFORMED_DOCUMENTS` is
+/// set or not.
+///
+/// # Panics
+///
+/// Panics if a persistent in-memory database cannot be restored, run
+/// [`migrate`] first to get this as an error instead.
+pub fn skipping_malformed_documents(db: Database) -> Router {
+    router(db, None, true)
+}
+
+fn router(db: Database, mailer: Option<DynMailer>, skip_malformed_documents: bool) -> Router {
+    let context = repositories(db, ContextBuilder::default(), skip_malformed_documents);
+    let context = match std::env::var(SNAPSHOTS_DIRECTORY_KEY) {
+        Ok(snapshots_directory) => context.snapshots_repo(Arc::new(
+            repository::filesystem::SnapshotsRepositoryProvider::new(Path::new(
+                &snapshots_directory,
+            )),
+        )),
+        Err(_) => context,
+    }
+    .build();
+
+    if let Some(interval) = std::env::var(HEALTH_CHECK_INTERVAL_SECS_KEY)
+        .ok()
+        .and_then(|interval| interval.parse().ok())
+    {
+        service::health::schedule(context.clone(), Duration::from_secs(interval));
+    }
+
+    if let Some(interval) = std::env::var(TRASH_PURGE_INTERVAL_SECS_KEY)
+        .ok()
+        .and_then(|interval| interval.parse().ok())
+    {
+        service::links::schedule_purge(
+            context.clone(),
+            trash_retention(),
+            Duration::from_secs(interval),
+        );
+    }
+
+    if let Some(interval) = std::env::var(REMINDER_INTERVAL_SECS_KEY)
+        .ok()
+        .and_then(|interval| interval.parse().ok())
+    {
+        service::reminders::schedule(
+            context.clone(),
+            notifier(mailer, &context),
+            Duration::from_secs(interval),
+        );
+    }
+
+    routes(State::new(context))
+}
+
+/// Merges the routers of every resource over the same state.
+fn routes(state: State) -> Router {
     Router::new()
         .merge(controller::routes::links::router(state.clone()))
+        .merge(controller::routes::collections::router(state.clone()))
+        .merge(controller::routes::details::router(state.clone()))
+        .merge(controller::routes::health::router(state.clone()))
+        .merge(controller::routes::highlights::router(state.clone()))
+        .merge(controller::routes::progress::router(state.clone()))
+        .merge(controller::routes::reminders::router(state.clone()))
+        .merge(controller::routes::shares::router(state.clone()))
+        .merge(controller::routes::snapshots::router(state.clone()))
         .merge(controller::routes::users::router(state.clone()))
+        .merge(controller::routes::webhooks::router(state.clone()))
         .with_state(state)
 }
 
+/// Picks how reminders reach users: by email when a mailer is plugged in,
+/// otherwise through their webhooks unless `REMINDER_NOTIFIER` asks for the
+/// log.
+fn notifier(mailer: Option<DynMailer>, context: &Context) -> DynNotifierService {
+    if let Some(mailer) = mailer {
+        return Arc::new(service::notifiers::MailNotifier::new(mailer)) as DynNotifierService;
+    }
+    match std::env::var(REMINDER_NOTIFIER_KEY).as_deref() {
+        Ok("log") => Arc::new(service::notifiers::LogNotifier::default()) as DynNotifierService,
+        _ => Arc::new(service::notifiers::WebhookNotifier::new(context.clone()))
+            as DynNotifierService,
+    }
+}
+
+fn trash_retention() -> chrono::Duration {
+    let retention_days = std::env::var(TRASH_RETENTION_DAYS_KEY)
+        .ok()
+        .and_then(|days| days.parse().ok())
+        .unwrap_or(DEFAULT_TRASH_RETENTION_DAYS);
+    chrono::Duration::days(retention_days)
+}
+
+fn repositories(
+    db: Database,
+    context: ContextBuilder,
+    skip_malformed_documents: bool,
+) -> ContextBuilder {
+    match db {
+        Database::MongoDb(db) => context
+            .links_repo(Arc::new(
+                repository::mongodb::LinksRepositoryProvider::new(&db)
+                    .skip_malformed_documents(skip_malformed_documents),
+            ))
+            .users_repo(Arc::new(repository::mongodb::UsersRepositoryProvider::new(
+                &db,
+            )))
+            .snapshots_repo(Arc::new(
+                repository::mongodb::SnapshotsRepositoryProvider::new(&db),
+            ))
+            .health_repo(Arc::new(
+                repository::mongodb::HealthRepositoryProvider::new(&db),
+            ))
+            .webhooks_repo(Arc::new(
+                repository::mongodb::WebhooksRepositoryProvider::new(&db),
+            ))
+            .revisions_repo(Arc::new(
+                repository::mongodb::RevisionsRepositoryProvider::new(&db),
+            ))
+            .collections_repo(Arc::new(
+                repository::mongodb::CollectionsRepositoryProvider::new(&db),
+            ))
+            .shares_repo(Arc::new(
+                repository::mongodb::SharesRepositoryProvider::new(&db),
+            ))
+            .reminders_repo(Arc::new(
+                repository::mongodb::RemindersRepositoryProvider::new(&db),
+            ))
+            .details_repo(Arc::new(
+                repository::mongodb::DetailsRepositoryProvider::new(&db),
+            ))
+            .highlights_repo(Arc::new(
+                repository::mongodb::HighlightsRepositoryProvider::new(&db),
+            ))
+            .progress_repo(Arc::new(
+                repository::mongodb::ProgressRepositoryProvider::new(&db),
+            )),
+        Database::Postgres(pool) => sql_repositories(&pool, context),
+        Database::Sqlite(path) => sql_repositories(&repository::sqlite::connect(&path), context),
+        Database::InMemory => context,
+        Database::PersistentInMemory(directory) => {
+            let repositories = Arc::new(
+                repository::inmemory::Repositories::persistent(&directory)
+                    .unwrap_or_else(|e| panic!("Unable to restore in-memory database: {e}")),
+            );
+            let interval = std::env::var(INMEMORY_SNAPSHOT_INTERVAL_SECS_KEY)
+                .ok()
+                .and_then(|interval| interval.parse().ok())
+                .unwrap_or(DEFAULT_INMEMORY_SNAPSHOT_INTERVAL_SECS);
+            repository::inmemory::schedule_snapshots(
+                repositories.clone(),
+                Duration::from_secs(interval),
+            );
+            context
+                .links_repo(repositories.links.clone())
+                .users_repo(repositories.users.clone())
+                .snapshots_repo(repositories.snapshots.clone())
+                .health_repo(repositories.health.clone())
+                .webhooks_repo(repositories.webhooks.clone())
+                .revisions_repo(repositories.revisions.clone())
+                .collections_repo(repositories.collections.clone())
+                .shares_repo(repositories.shares.clone())
+                .reminders_repo(repositories.reminders.clone())
+                .details_repo(repositories.details.clone())
+                .highlights_repo(repositories.highlights.clone())
+                .progress_repo(repositories.progress.clone())
+        }
+    }
+}
+
+fn sql_repositories<B: repository::sql::Backend>(
+    pool: &B,
+    context: ContextBuilder,
+) -> ContextBuilder {
+    context
+        .links_repo(Arc::new(repository::sql::LinksRepositoryProvider::new(
+            pool,
+        )))
+        .users_repo(Arc::new(repository::sql::UsersRepositoryProvider::new(
+            pool,
+        )))
+        .snapshots_repo(Arc::new(repository::sql::SnapshotsRepositoryProvider::new(
+            pool,
+        )))
+        .health_repo(Arc::new(repository::sql::HealthRepositoryProvider::new(
+            pool,
+        )))
+        .webhooks_repo(Arc::new(repository::sql::WebhooksRepositoryProvider::new(
+            pool,
+        )))
+        .revisions_repo(Arc::new(repository::sql::RevisionsRepositoryProvider::new(
+            pool,
+        )))
+        .collections_repo(Arc::new(
+            repository::sql::CollectionsRepositoryProvider::new(pool),
+        ))
+        .shares_repo(Arc::new(repository::sql::SharesRepositoryProvider::new(
+            pool,
+        )))
+        .reminders_repo(Arc::new(repository::sql::RemindersRepositoryProvider::new(
+            pool,
+        )))
+        .details_repo(Arc::new(repository::sql::DetailsRepositoryProvider::new(
+            pool,
+        )))
+        .highlights_repo(Arc::new(
+            repository::sql::HighlightsRepositoryProvider::new(pool),
+        ))
+        .progress_repo(Arc::new(repository::sql::ProgressRepositoryProvider::new(
+            pool,
+        )))
+}
+
+/// Prepares the database before it is handed to [`new`], applying any pending
+/// schema migrations for the databases that need them.
+///
+/// # Errors
+///
+/// Returns an error if the migrations could not be applied.
+pub async fn migrate(db: &Database) -> Result<(), Error> {
+    match db {
+        Database::Postgres(pool) => repository::postgres::migrate(pool).await,
+        Database::Sqlite(path) => {
+            let pool = repository::sqlite::connect(path);
+            let result = repository::sqlite::migrate(&pool).await;
+            pool.close().await;
+            result
+        }
+        Database::PersistentInMemory(directory) => {
+            // replaying the write logs into fresh snapshots also checks that they can be restored
+            repository::inmemory::Repositories::persistent(directory)?.snapshot()
+        }
+        Database::MongoDb(db) => repository::mongodb::migrate(db).await,
+        Database::InMemory => Ok(()),
+    }
+}
+
 #[allow(missing_debug_implementations)]
 #[derive(Clone)]
 pub struct State {
+    context: Context,
+}
+
+#[allow(clippy::must_use_candidate)]
+impl State {
+    pub const fn new(context: Context) -> Self {
+        Self { context }
+    }
+
+    pub const fn context(&self) -> &Context {
+        &self.context
+    }
+
+    pub fn links_service(&self) -> &DynLinksService {
+        self.context.links_service()
+    }
+
+    pub fn users_service(&self) -> &DynUsersService {
+        self.context.users_service()
+    }
+
+    pub fn analysis_service(&self) -> &DynAnalysisService {
+        self.context.analysis_service()
+    }
+
+    pub fn summary_service(&self) -> &DynAnalysisService {
+        self.context.summary_service()
+    }
+
+    pub fn snapshots_service(&self) -> &DynSnapshotsService {
+        self.context.snapshots_service()
+    }
+
+    pub fn health_service(&self) -> &DynHealthService {
+        self.context.health_service()
+    }
+
+    pub fn webhooks_service(&self) -> &DynWebhooksService {
+        self.context.webhooks_service()
+    }
+
+    pub fn collections_service(&self) -> &DynCollectionsService {
+        self.context.collections_service()
+    }
+
+    pub fn shares_service(&self) -> &DynSharesService {
+        self.context.shares_service()
+    }
+
+    pub fn reminders_service(&self) -> &DynRemindersService {
+        self.context.reminders_service()
+    }
+
+    pub fn details_service(&self) -> &DynDetailsService {
+        self.context.details_service()
+    }
+
+    pub fn highlights_service(&self) -> &DynHighlightsService {
+        self.context.highlights_service()
+    }
+
+    pub fn progress_service(&self) -> &DynProgressService {
+        self.context.progress_service()
+    }
+}
+
+/// The services and repositories a request is handled with, which services
+/// reach each other and their data through.
+#[allow(missing_debug_implementations)]
+#[derive(Clone)]
+pub struct Context {
     links_service: DynLinksService,
     users_service: DynUsersService,
     analysis_service: DynAnalysisService,
+    summary_service: DynAnalysisService,
+    snapshots_service: DynSnapshotsService,
+    health_service: DynHealthService,
+    webhooks_service: DynWebhooksService,
+    collections_service: DynCollectionsService,
+    shares_service: DynSharesService,
+    reminders_service: DynRemindersService,
+    details_service: DynDetailsService,
+    highlights_service: DynHighlightsService,
+    progress_service: DynProgressService,
     links_repo: DynLinksRepository,
     users_repo: DynUsersRepository,
+    snapshots_repo: DynSnapshotsRepository,
+    health_repo: DynHealthRepository,
+    webhooks_repo: DynWebhooksRepository,
+    revisions_repo: DynRevisionsRepository,
+    collections_repo: DynCollectionsRepository,
+    shares_repo: DynSharesRepository,
+    reminders_repo: DynRemindersRepository,
+    details_repo: DynDetailsRepository,
+    highlights_repo: DynHighlightsRepository,
+    progress_repo: DynProgressRepository,
 }
 
 #[allow(clippy::must_use_candidate)]
-impl State {
-    pub fn new(
-        links_service: DynLinksService,
-        users_service: DynUsersService,
-        analysis_service: DynAnalysisService,
-        links_repo: DynLinksRepository,
-        users_repo: DynUsersRepository,
-    ) -> Self {
-        Self {
-            links_service,
-            users_service,
-            analysis_service,
-            links_repo,
-            users_repo,
-        }
-    }
-
+impl Context {
     pub fn links_service(&self) -> &DynLinksService {
         &self.links_service
     }
@@ -83,6 +420,46 @@ impl State {
         &self.analysis_service
     }
 
+    pub fn summary_service(&self) -> &DynAnalysisService {
+        &self.summary_service
+    }
+
+    pub fn snapshots_service(&self) -> &DynSnapshotsService {
+        &self.snapshots_service
+    }
+
+    pub fn health_service(&self) -> &DynHealthService {
+        &self.health_service
+    }
+
+    pub fn webhooks_service(&self) -> &DynWebhooksService {
+        &self.webhooks_service
+    }
+
+    pub fn collections_service(&self) -> &DynCollectionsService {
+        &self.collections_service
+    }
+
+    pub fn shares_service(&self) -> &DynSharesService {
+        &self.shares_service
+    }
+
+    pub fn reminders_service(&self) -> &DynRemindersService {
+        &self.reminders_service
+    }
+
+    pub fn details_service(&self) -> &DynDetailsService {
+        &self.details_service
+    }
+
+    pub fn highlights_service(&self) -> &DynHighlightsService {
+        &self.highlights_service
+    }
+
+    pub fn progress_service(&self) -> &DynProgressService {
+        &self.progress_service
+    }
+
     pub fn links_repo(&self) -> &DynLinksRepository {
         &self.links_repo
     }
@@ -90,6 +467,230 @@ impl State {
     pub fn users_repo(&self) -> &DynUsersRepository {
         &self.users_repo
     }
+
+    pub fn snapshots_repo(&self) -> &DynSnapshotsRepository {
+        &self.snapshots_repo
+    }
+
+    pub fn health_repo(&self) -> &DynHealthRepository {
+        &self.health_repo
+    }
+
+    pub fn webhooks_repo(&self) -> &DynWebhooksRepository {
+        &self.webhooks_repo
+    }
+
+    pub fn revisions_repo(&self) -> &DynRevisionsRepository {
+        &self.revisions_repo
+    }
+
+    pub fn collections_repo(&self) -> &DynCollectionsRepository {
+        &self.collections_repo
+    }
+
+    pub fn shares_repo(&self) -> &DynSharesRepository {
+        &self.shares_repo
+    }
+
+    pub fn reminders_repo(&self) -> &DynRemindersRepository {
+        &self.reminders_repo
+    }
+
+    pub fn details_repo(&self) -> &DynDetailsRepository {
+        &self.details_repo
+    }
+
+    pub fn highlights_repo(&self) -> &DynHighlightsRepository {
+        &self.highlights_repo
+    }
+
+    pub fn progress_repo(&self) -> &DynProgressRepository {
+        &self.progress_repo
+    }
+}
+
+/// Builds a [`Context`] from the in-memory repositories and the default
+/// services, replacing those that are given.
+#[allow(missing_debug_implementations)]
+#[must_use]
+pub struct ContextBuilder {
+    context: Context,
+}
+
+impl Default for ContextBuilder {
+    fn default() -> Self {
+        Self {
+            context: Context {
+                links_service: Arc::new(service::links::ServiceProvider::default()),
+                users_service: Arc::new(service::users::ServiceProvider::default()),
+                analysis_service: Arc::new(service::analysis::ServiceProvider::default()),
+                summary_service: Arc::new(service::summary::ServiceProvider::default()),
+                snapshots_service: Arc::new(service::snapshots::ServiceProvider::default()),
+                health_service: Arc::new(service::health::ServiceProvider::default()),
+                webhooks_service: Arc::new(service::webhooks::ServiceProvider::default()),
+                collections_service: Arc::new(service::collections::ServiceProvider::default()),
+                shares_service: Arc::new(service::shares::ServiceProvider::default()),
+                reminders_service: Arc::new(service::reminders::ServiceProvider::default()),
+                details_service: Arc::new(service::details::ServiceProvider::default()),
+                highlights_service: Arc::new(service::highlights::ServiceProvider::default()),
+                progress_service: Arc::new(service::progress::ServiceProvider::default()),
+                links_repo: Arc::new(repository::inmemory::LinksRepositoryProvider::default()),
+                users_repo: Arc::new(repository::inmemory::UsersRepositoryProvider::default()),
+                snapshots_repo: Arc::new(
+                    repository::inmemory::SnapshotsRepositoryProvider::default(),
+                ),
+                health_repo: Arc::new(repository::inmemory::HealthRepositoryProvider::default()),
+                webhooks_repo: Arc::new(repository::inmemory::WebhooksRepositoryProvider::default()),
+                revisions_repo: Arc::new(
+                    repository::inmemory::RevisionsRepositoryProvider::default(),
+                ),
+                collections_repo: Arc::new(
+                    repository::inmemory::CollectionsRepositoryProvider::default(),
+                ),
+                shares_repo: Arc::new(repository::inmemory::SharesRepositoryProvider::default()),
+                reminders_repo: Arc::new(
+                    repository::inmemory::RemindersRepositoryProvider::default(),
+                ),
+                details_repo: Arc::new(repository::inmemory::DetailsRepositoryProvider::default()),
+                highlights_repo: Arc::new(
+                    repository::inmemory::HighlightsRepositoryProvider::default(),
+                ),
+                progress_repo: Arc::new(repository::inmemory::ProgressRepositoryProvider::default()),
+            },
+        }
+    }
+}
+
+#[allow(clippy::must_use_candidate)]
+impl ContextBuilder {
+    pub fn links_service(mut self, links_service: DynLinksService) -> Self {
+        self.context.links_service = links_service;
+        self
+    }
+
+    pub fn users_service(mut self, users_service: DynUsersService) -> Self {
+        self.context.users_service = users_service;
+        self
+    }
+
+    pub fn analysis_service(mut self, analysis_service: DynAnalysisService) -> Self {
+        self.context.analysis_service = analysis_service;
+        self
+    }
+
+    pub fn summary_service(mut self, summary_service: DynAnalysisService) -> Self {
+        self.context.summary_service = summary_service;
+        self
+    }
+
+    pub fn snapshots_service(mut self, snapshots_service: DynSnapshotsService) -> Self {
+        self.context.snapshots_service = snapshots_service;
+        self
+    }
+
+    pub fn health_service(mut self, health_service: DynHealthService) -> Self {
+        self.context.health_service = health_service;
+        self
+    }
+
+    pub fn webhooks_service(mut self, webhooks_service: DynWebhooksService) -> Self {
+        self.context.webhooks_service = webhooks_service;
+        self
+    }
+
+    pub fn collections_service(mut self, collections_service: DynCollectionsService) -> Self {
+        self.context.collections_service = collections_service;
+        self
+    }
+
+    pub fn shares_service(mut self, shares_service: DynSharesService) -> Self {
+        self.context.shares_service = shares_service;
+        self
+    }
+
+    pub fn reminders_service(mut self, reminders_service: DynRemindersService) -> Self {
+        self.context.reminders_service = reminders_service;
+        self
+    }
+
+    pub fn details_service(mut self, details_service: DynDetailsService) -> Self {
+        self.context.details_service = details_service;
+        self
+    }
+
+    pub fn highlights_service(mut self, highlights_service: DynHighlightsService) -> Self {
+        self.context.highlights_service = highlights_service;
+        self
+    }
+
+    pub fn progress_service(mut self, progress_service: DynProgressService) -> Self {
+        self.context.progress_service = progress_service;
+        self
+    }
+
+    pub fn links_repo(mut self, links_repo: DynLinksRepository) -> Self {
+        self.context.links_repo = links_repo;
+        self
+    }
+
+    pub fn users_repo(mut self, users_repo: DynUsersRepository) -> Self {
+        self.context.users_repo = users_repo;
+        self
+    }
+
+    pub fn snapshots_repo(mut self, snapshots_repo: DynSnapshotsRepository) -> Self {
+        self.context.snapshots_repo = snapshots_repo;
+        self
+    }
+
+    pub fn health_repo(mut self, health_repo: DynHealthRepository) -> Self {
+        self.context.health_repo = health_repo;
+        self
+    }
+
+    pub fn webhooks_repo(mut self, webhooks_repo: DynWebhooksRepository) -> Self {
+        self.context.webhooks_repo = webhooks_repo;
+        self
+    }
+
+    pub fn revisions_repo(mut self, revisions_repo: DynRevisionsRepository) -> Self {
+        self.context.revisions_repo = revisions_repo;
+        self
+    }
+
+    pub fn collections_repo(mut self, collections_repo: DynCollectionsRepository) -> Self {
+        self.context.collections_repo = collections_repo;
+        self
+    }
+
+    pub fn shares_repo(mut self, shares_repo: DynSharesRepository) -> Self {
+        self.context.shares_repo = shares_repo;
+        self
+    }
+
+    pub fn reminders_repo(mut self, reminders_repo: DynRemindersRepository) -> Self {
+        self.context.reminders_repo = reminders_repo;
+        self
+    }
+
+    pub fn details_repo(mut self, details_repo: DynDetailsRepository) -> Self {
+        self.context.details_repo = details_repo;
+        self
+    }
+
+    pub fn highlights_repo(mut self, highlights_repo: DynHighlightsRepository) -> Self {
+        self.context.highlights_repo = highlights_repo;
+        self
+    }
+
+    pub fn progress_repo(mut self, progress_repo: DynProgressRepository) -> Self {
+        self.context.progress_repo = progress_repo;
+        self
+    }
+
+    pub fn build(self) -> Context {
+        self.context
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -97,6 +698,17 @@ pub enum Error {
     LinkNotFound(String),
     UserAlreadyExists(String),
     UserNotFound(String),
+    SnapshotNotFound(String),
+    HealthNotFound(String),
+    WebhookNotFound(String),
+    RevisionNotFound(String),
+    CollectionNotFound(String),
+    ShareNotFound(String),
+    ReminderNotFound(String),
+    DetailsNotFound(String),
+    HighlightNotFound(String),
+    ProgressNotFound(String),
+    PreconditionFailed(String),
     IncorrectPassword(String),
     Authorization(String),
     Validation(String),
@@ -113,6 +725,17 @@ impl fmt::Display for Error {
             Self::LinkNotFound(_) => write!(f, "link item not found"),
             Self::UserAlreadyExists(_) => write!(f, "user already registered"),
             Self::UserNotFound(_) => write!(f, "user not found"),
+            Self::SnapshotNotFound(_) => write!(f, "snapshot not found"),
+            Self::HealthNotFound(_) => write!(f, "link health not checked yet"),
+            Self::WebhookNotFound(_) => write!(f, "webhook not found"),
+            Self::RevisionNotFound(_) => write!(f, "revision not found"),
+            Self::CollectionNotFound(_) => write!(f, "collection not found"),
+            Self::ShareNotFound(_) => write!(f, "share not found"),
+            Self::ReminderNotFound(_) => write!(f, "reminder not found"),
+            Self::DetailsNotFound(_) => write!(f, "link details not found"),
+            Self::HighlightNotFound(_) => write!(f, "highlight not found"),
+            Self::ProgressNotFound(_) => write!(f, "reading progress not found"),
+            Self::PreconditionFailed(_) => write!(f, "link item has been modified"),
             Self::IncorrectPassword(_) => write!(f, "incorrect password for user"),
             Self::Authorization(_) => write!(f, "invalid authorization token"),
             Self::Validation(_) => write!(f, "invalid request"),
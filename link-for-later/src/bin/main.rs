@@ -1,6 +1,10 @@
 use mongodb::{options::ClientOptions, Client};
+use sqlx::postgres::PgPoolOptions;
 
 const INMEMORY_DB_KEY: &str = "INMEMORY_DB";
+const INMEMORY_DATA_DIRECTORY_KEY: &str = "INMEMORY_DATA_DIRECTORY";
+const POSTGRES_URL_KEY: &str = "POSTGRES_URL";
+const SQLITE_PATH_KEY: &str = "SQLITE_PATH";
 const MONGODB_URI_KEY: &str = "MONGODB_URI";
 const MONGODB_DATABASE_NAME_KEY: &str = "MONGODB_DATABASE_NAME";
 
@@ -12,8 +16,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .init();
 
     let app = if std::env::var(INMEMORY_DB_KEY).is_ok() {
-        tracing::info!("Using in-memory database");
-        link_for_later::app::new(link_for_later::DatabaseType::InMemory)
+        if let Ok(directory) = std::env::var(INMEMORY_DATA_DIRECTORY_KEY) {
+            tracing::info!("Using in-memory database persisted to {}", directory);
+
+            let db = link_for_later::DatabaseType::PersistentInMemory(directory.into());
+            link_for_later::app::migrate(&db).await?;
+
+            link_for_later::app::new(db)
+        } else {
+            tracing::info!("Using in-memory database");
+            link_for_later::app::new(link_for_later::DatabaseType::InMemory)
+        }
+    } else if let Ok(path) = std::env::var(SQLITE_PATH_KEY) {
+        tracing::info!("Using sqlite database");
+
+        let db = link_for_later::DatabaseType::Sqlite(path.into());
+        link_for_later::app::migrate(&db).await?;
+
+        link_for_later::app::new(db)
+    } else if let Ok(url) = std::env::var(POSTGRES_URL_KEY) {
+        tracing::info!("Using postgres database");
+
+        let pool = PgPoolOptions::new().connect(&url).await?;
+        let db = link_for_later::DatabaseType::Postgres(pool);
+        link_for_later::app::migrate(&db).await?;
+
+        link_for_later::app::new(db)
     } else {
         tracing::info!("Using mongodb database");
 
@@ -22,9 +50,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         let client_options = ClientOptions::parse(uri).await?;
         let client = Client::with_options(client_options)?;
-        let db = client.database(&database_name);
+        let db = link_for_later::DatabaseType::MongoDb(client.database(&database_name));
+        link_for_later::app::migrate(&db).await?;
 
-        link_for_later::app::new(link_for_later::DatabaseType::MongoDb(db))
+        link_for_later::app::new(db)
     };
 
     let listener = tokio::net::TcpListener::bind("0.0.0.0:8080").await?;
@@ -25,6 +25,50 @@ impl IntoResponse for AppError {
                 tracing::debug!("{}: {}", error_message, e.to_string());
                 (StatusCode::BAD_REQUEST, error_message)
             }
+            Self::SnapshotNotFound(ref e) => {
+                tracing::debug!("{}: {}", error_message, e.to_string());
+                (StatusCode::NOT_FOUND, error_message)
+            }
+            Self::HealthNotFound(ref e) => {
+                tracing::debug!("{}: {}", error_message, e.to_string());
+                (StatusCode::NOT_FOUND, error_message)
+            }
+            Self::WebhookNotFound(ref e) => {
+                tracing::debug!("{}: {}", error_message, e.to_string());
+                (StatusCode::NOT_FOUND, error_message)
+            }
+            Self::RevisionNotFound(ref e) => {
+                tracing::debug!("{}: {}", error_message, e.to_string());
+                (StatusCode::NOT_FOUND, error_message)
+            }
+            Self::CollectionNotFound(ref e) => {
+                tracing::debug!("{}: {}", error_message, e.to_string());
+                (StatusCode::NOT_FOUND, error_message)
+            }
+            Self::ShareNotFound(ref e) => {
+                tracing::debug!("{}: {}", error_message, e.to_string());
+                (StatusCode::NOT_FOUND, error_message)
+            }
+            Self::ReminderNotFound(ref e) => {
+                tracing::debug!("{}: {}", error_message, e.to_string());
+                (StatusCode::NOT_FOUND, error_message)
+            }
+            Self::DetailsNotFound(ref e) => {
+                tracing::debug!("{}: {}", error_message, e.to_string());
+                (StatusCode::NOT_FOUND, error_message)
+            }
+            Self::HighlightNotFound(ref e) => {
+                tracing::debug!("{}: {}", error_message, e.to_string());
+                (StatusCode::NOT_FOUND, error_message)
+            }
+            Self::ProgressNotFound(ref e) => {
+                tracing::debug!("{}: {}", error_message, e.to_string());
+                (StatusCode::NOT_FOUND, error_message)
+            }
+            Self::PreconditionFailed(ref e) => {
+                tracing::debug!("{}: {}", error_message, e.to_string());
+                (StatusCode::PRECONDITION_FAILED, error_message)
+            }
             Self::IncorrectPassword(ref e) => {
                 tracing::debug!("{}: {}", error_message, e.to_string());
                 (StatusCode::UNAUTHORIZED, error_message)
@@ -63,6 +107,7 @@ mod tests {
 
     use super::*;
 
+    #[allow(clippy::too_many_lines)]
     #[test]
     fn test_error_response() {
         assert_eq!(
@@ -95,6 +140,72 @@ mod tests {
                 .status(),
             StatusCode::BAD_REQUEST
         );
+        assert_eq!(
+            AppError::SnapshotNotFound("link".into())
+                .into_response()
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            AppError::HealthNotFound("link".into())
+                .into_response()
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            AppError::WebhookNotFound("webhook".into())
+                .into_response()
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            AppError::RevisionNotFound("revision".into())
+                .into_response()
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            AppError::CollectionNotFound("collection".into())
+                .into_response()
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            AppError::ShareNotFound("share".into())
+                .into_response()
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            AppError::ReminderNotFound("reminder".into())
+                .into_response()
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            AppError::DetailsNotFound("link".into())
+                .into_response()
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            AppError::HighlightNotFound("highlight".into())
+                .into_response()
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            AppError::ProgressNotFound("link".into())
+                .into_response()
+                .status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            AppError::PreconditionFailed("link".into())
+                .into_response()
+                .status(),
+            StatusCode::PRECONDITION_FAILED
+        );
         assert_eq!(
             AppError::IncorrectPassword("user".into())
                 .into_response()
@@ -1,2 +1,11 @@
+pub mod collections;
+pub mod details;
+pub mod health;
+pub mod highlights;
 pub mod links;
+pub mod progress;
+pub mod reminders;
+pub mod shares;
+pub mod snapshots;
 pub mod users;
+pub mod webhooks;
@@ -0,0 +1,614 @@
+use axum::{
+    extract::{self, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing, Json, Router,
+};
+use chrono::Utc;
+use validator::Validate;
+
+use crate::types::{
+    AppError, AppState, Claims, Collection, CollectionLinksRequest, CollectionRequest,
+    LinkQueryBuilder, MemberRequest,
+};
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .nest(
+            "/v1",
+            Router::new()
+                .route("/collections", routing::get(list))
+                .route("/collections", routing::post(post))
+                .route("/collections/shared", routing::get(shared))
+                .route("/collections/:id", routing::get(get))
+                .route("/collections/:id", routing::put(put))
+                .route("/collections/:id", routing::delete(delete))
+                .route("/collections/:id/links", routing::get(links))
+                .route("/collections/:id/links", routing::put(reorder))
+                .route("/collections/:id/links/:link_id", routing::put(add_link))
+                .route(
+                    "/collections/:id/links/:link_id",
+                    routing::delete(remove_link),
+                )
+                .route("/collections/:id/members/:user", routing::put(share))
+                .route("/collections/:id/members/:user", routing::delete(unshare)),
+        )
+        .with_state(state)
+}
+
+async fn list(State(app_state): State<AppState>, user: Claims) -> impl IntoResponse {
+    let query = LinkQueryBuilder::default()
+        .user(user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .collections_service()
+        .search(app_state.context(), &query)
+        .await
+    {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn shared(State(app_state): State<AppState>, user: Claims) -> impl IntoResponse {
+    let query = LinkQueryBuilder::default().user(user.id()).build();
+    match app_state
+        .collections_service()
+        .shared(app_state.context(), &query)
+        .await
+    {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn post(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Json(payload): extract::Json<CollectionRequest>,
+) -> impl IntoResponse {
+    match payload.validate() {
+        Ok(()) => {}
+        Err(e) => {
+            return AppError::Validation(format!("post_collection() {e:?}")).into_response();
+        }
+    }
+
+    let collection = Collection::new(
+        user.id(),
+        payload.name(),
+        payload.description(),
+        payload.position(),
+        &Utc::now(),
+    );
+    match app_state
+        .collections_service()
+        .create(app_state.context(), &collection)
+        .await
+    {
+        Ok(collection) => (StatusCode::CREATED, Json(collection)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn get(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .collections_service()
+        .get(app_state.context(), &query)
+        .await
+    {
+        Ok(collection) => Json(collection).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn put(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+    Json(payload): extract::Json<CollectionRequest>,
+) -> impl IntoResponse {
+    match payload.validate() {
+        Ok(()) => {}
+        Err(e) => {
+            return AppError::Validation(format!("put_collection() {e:?}")).into_response();
+        }
+    }
+
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    let collection = Collection::new(
+        user.id(),
+        payload.name(),
+        payload.description(),
+        payload.position(),
+        &Utc::now(),
+    );
+    match app_state
+        .collections_service()
+        .update(app_state.context(), &query, &collection)
+        .await
+    {
+        Ok(collection) => Json(collection).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn delete(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .collections_service()
+        .delete(app_state.context(), &query)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn links(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .collections_service()
+        .links(app_state.context(), &query)
+        .await
+    {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn reorder(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+    Json(payload): extract::Json<CollectionLinksRequest>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .collections_service()
+        .reorder(app_state.context(), &query, payload.links())
+        .await
+    {
+        Ok(collection) => Json(collection).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn add_link(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path((id, link_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .collections_service()
+        .add_link(app_state.context(), &query, &link_id)
+        .await
+    {
+        Ok(collection) => Json(collection).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn remove_link(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path((id, link_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .collections_service()
+        .remove_link(app_state.context(), &query, &link_id)
+        .await
+    {
+        Ok(collection) => Json(collection).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn share(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path((id, member)): Path<(String, String)>,
+    Json(payload): extract::Json<MemberRequest>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .collections_service()
+        .share(app_state.context(), &query, &member, payload.role())
+        .await
+    {
+        Ok(collection) => Json(collection).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn unshare(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path((id, member)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .collections_service()
+        .unshare(app_state.context(), &query, &member)
+        .await
+    {
+        Ok(collection) => Json(collection).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::State, http::StatusCode};
+    use http_body_util::BodyExt;
+    use serde_json::json;
+
+    use crate::{
+        app::ContextBuilder,
+        service::MockCollections as MockCollectionsService,
+        types::{LinkItem, LinkItemBuilder, Member, Role},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_collections() {
+        let collection = Collection::new("user", "Reading", "", 0, &Utc::now()).with_id("1");
+        let expected_body = json!([collection]).to_string();
+
+        let mut mock_collections_service = MockCollectionsService::new();
+        mock_collections_service
+            .expect_search()
+            .withf(|_, query| query.user() == "user" && !query.is_from_admin())
+            .times(1)
+            .returning(move |_, _| Ok(vec![collection.clone()]));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .collections_service(Arc::new(mock_collections_service))
+                .build(),
+        );
+        let response = list(State(app_state), Claims::new("user", false, 0, 0)).await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, expected_body);
+    }
+
+    #[tokio::test]
+    async fn test_post_collection() {
+        let request: CollectionRequest =
+            serde_json::from_value(json!({"name": "Reading", "position": 2})).unwrap();
+
+        let mut mock_collections_service = MockCollectionsService::new();
+        mock_collections_service
+            .expect_create()
+            .withf(|_, collection| {
+                collection.owner() == "user"
+                    && collection.name() == "Reading"
+                    && collection.position() == 2
+            })
+            .times(1)
+            .returning(|_, collection| Ok(collection.clone().with_id("1")));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .collections_service(Arc::new(mock_collections_service))
+                .build(),
+        );
+        let response = post(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Json(request),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::CREATED, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: Collection = serde_json::from_str(body).unwrap();
+        assert_eq!(body.id(), "1");
+    }
+
+    #[tokio::test]
+    async fn test_post_collection_without_name() {
+        let request: CollectionRequest = serde_json::from_value(json!({"name": ""})).unwrap();
+
+        let mut mock_collections_service = MockCollectionsService::new();
+        mock_collections_service.expect_create().times(0);
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .collections_service(Arc::new(mock_collections_service))
+                .build(),
+        );
+        let response = post(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_get_collection_not_found() {
+        let mut mock_collections_service = MockCollectionsService::new();
+        mock_collections_service
+            .expect_get()
+            .withf(|_, query| query.id() == "1")
+            .times(1)
+            .returning(|_, _| Err(AppError::CollectionNotFound("1".into())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .collections_service(Arc::new(mock_collections_service))
+                .build(),
+        );
+        let response = get(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_FOUND, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, json!({"error": "collection not found"}).to_string());
+    }
+
+    #[tokio::test]
+    async fn test_delete_collection() {
+        let mut mock_collections_service = MockCollectionsService::new();
+        mock_collections_service
+            .expect_delete()
+            .withf(|_, query| query.id() == "1" && query.user() == "user")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .collections_service(Arc::new(mock_collections_service))
+                .build(),
+        );
+        let response = delete(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NO_CONTENT, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_get_collection_links() {
+        let item = LinkItemBuilder::new("http://link")
+            .id("2")
+            .owner("user")
+            .build();
+        let retrieved_item = item.clone();
+
+        let mut mock_collections_service = MockCollectionsService::new();
+        mock_collections_service
+            .expect_links()
+            .withf(|_, query| query.id() == "1")
+            .times(1)
+            .returning(move |_, _| Ok(vec![retrieved_item.clone()]));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .collections_service(Arc::new(mock_collections_service))
+                .build(),
+        );
+        let response = links(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: Vec<LinkItem> = serde_json::from_str(body).unwrap();
+        assert_eq!(body, vec![item]);
+    }
+
+    #[tokio::test]
+    async fn test_add_link_to_collection() {
+        let mut mock_collections_service = MockCollectionsService::new();
+        mock_collections_service
+            .expect_add_link()
+            .withf(|_, query, link_id| query.id() == "1" && link_id == "2")
+            .times(1)
+            .returning(|_, _, _| {
+                Ok(Collection::new("user", "Reading", "", 0, &Utc::now())
+                    .with_id("1")
+                    .with_links(&["2".into()]))
+            });
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .collections_service(Arc::new(mock_collections_service))
+                .build(),
+        );
+        let response = add_link(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path((String::from("1"), String::from("2"))),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_reorder_collection_links_invalid() {
+        let request: CollectionLinksRequest =
+            serde_json::from_value(json!({"links": ["2", "1"]})).unwrap();
+
+        let mut mock_collections_service = MockCollectionsService::new();
+        mock_collections_service
+            .expect_reorder()
+            .withf(|_, _, links| links == ["2", "1"])
+            .times(1)
+            .returning(|_, _, _| Err(AppError::Validation("reorder()".into())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .collections_service(Arc::new(mock_collections_service))
+                .build(),
+        );
+        let response = reorder(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_get_shared_collections() {
+        let collection = Collection::new("owner", "Reading", "", 0, &Utc::now())
+            .with_id("1")
+            .with_members(&[Member::new("user", Role::Viewer)]);
+        let expected_body = json!([collection]).to_string();
+
+        let mut mock_collections_service = MockCollectionsService::new();
+        mock_collections_service
+            .expect_shared()
+            .withf(|_, query| query.user() == "user")
+            .times(1)
+            .returning(move |_, _| Ok(vec![collection.clone()]));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .collections_service(Arc::new(mock_collections_service))
+                .build(),
+        );
+        let response = shared(State(app_state), Claims::new("user", false, 0, 0)).await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, expected_body);
+    }
+
+    #[tokio::test]
+    async fn test_share_collection() {
+        let request: MemberRequest = serde_json::from_value(json!({"role": "editor"})).unwrap();
+
+        let mut mock_collections_service = MockCollectionsService::new();
+        mock_collections_service
+            .expect_share()
+            .withf(|_, query, member, role| {
+                query.id() == "1" && member == "member" && *role == Role::Editor
+            })
+            .times(1)
+            .returning(|_, _, member, role| {
+                Ok(Collection::new("user", "Reading", "", 0, &Utc::now())
+                    .with_id("1")
+                    .with_members(&[Member::new(member, role)]))
+            });
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .collections_service(Arc::new(mock_collections_service))
+                .build(),
+        );
+        let response = share(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path((String::from("1"), String::from("member"))),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_share_collection_with_unknown_user() {
+        let request: MemberRequest = serde_json::from_value(json!({"role": "viewer"})).unwrap();
+
+        let mut mock_collections_service = MockCollectionsService::new();
+        mock_collections_service
+            .expect_share()
+            .times(1)
+            .returning(|_, _, member, _| Err(AppError::UserNotFound(member.to_owned())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .collections_service(Arc::new(mock_collections_service))
+                .build(),
+        );
+        let response = share(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path((String::from("1"), String::from("unknown"))),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+    }
+}
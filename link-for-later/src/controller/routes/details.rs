@@ -0,0 +1,219 @@
+use axum::{
+    extract::{self, Path, State},
+    response::IntoResponse,
+    routing, Json, Router,
+};
+use chrono::Utc;
+use validator::Validate;
+
+use crate::types::{AppError, AppState, Claims, DetailsRequest, LinkDetails, LinkQueryBuilder};
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .nest(
+            "/v1",
+            Router::new()
+                .route("/links/:id/details", routing::get(get))
+                .route("/links/:id/details", routing::put(put)),
+        )
+        .with_state(state)
+}
+
+async fn get(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .details_service()
+        .get(app_state.context(), &query)
+        .await
+    {
+        Ok(details) => Json(details).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn put(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+    Json(payload): extract::Json<DetailsRequest>,
+) -> impl IntoResponse {
+    match payload.validate() {
+        Ok(()) => {}
+        Err(e) => {
+            return AppError::Validation(format!("put_details() {e:?}")).into_response();
+        }
+    }
+
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    let details = LinkDetails::new(
+        &id,
+        user.id(),
+        payload.priority(),
+        payload.rating(),
+        payload.notes(),
+        &Utc::now(),
+    );
+    match app_state
+        .details_service()
+        .update(app_state.context(), &query, &details)
+        .await
+    {
+        Ok(details) => Json(details).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::State, http::StatusCode};
+    use http_body_util::BodyExt;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use crate::{app::ContextBuilder, service::MockDetails as MockDetailsService, types::Priority};
+
+    use super::*;
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_get_details(#[case] is_admin: bool, #[case] user: &str) {
+        let get_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+        let details = LinkDetails::new("1", "user", Priority::High, Some(4), "notes", &Utc::now());
+        let retrieved_details = details.clone();
+
+        let mut mock_details_service = MockDetailsService::new();
+        mock_details_service
+            .expect_get()
+            .withf(move |_, query| query == &get_query)
+            .times(1)
+            .returning(move |_, _| Ok(retrieved_details.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .details_service(Arc::new(mock_details_service))
+                .build(),
+        );
+        let response = get(
+            State(app_state),
+            Claims::new(user, is_admin, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: LinkDetails = serde_json::from_str(body).unwrap();
+        assert_eq!(body, details);
+    }
+
+    #[tokio::test]
+    async fn test_put_details() {
+        let request: DetailsRequest =
+            serde_json::from_value(json!({"priority": "high", "rating": 5, "notes": "# Worth it"}))
+                .unwrap();
+
+        let mut mock_details_service = MockDetailsService::new();
+        mock_details_service
+            .expect_update()
+            .withf(|_, query, details| {
+                query.id() == "1"
+                    && query.user() == "user"
+                    && details.priority() == Priority::High
+                    && details.rating() == Some(5)
+                    && details.notes() == "# Worth it"
+            })
+            .times(1)
+            .returning(|_, _, details| Ok(details.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .details_service(Arc::new(mock_details_service))
+                .build(),
+        );
+        let response = put(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+    }
+
+    #[rstest]
+    #[case(json!({"rating": 0}))]
+    #[case(json!({"rating": 6}))]
+    #[tokio::test]
+    async fn test_put_details_invalid_rating(#[case] request: serde_json::Value) {
+        let request: DetailsRequest = serde_json::from_value(request).unwrap();
+
+        let mut mock_details_service = MockDetailsService::new();
+        mock_details_service.expect_update().times(0);
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .details_service(Arc::new(mock_details_service))
+                .build(),
+        );
+        let response = put(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            Json(request),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, json!({"error": "invalid request"}).to_string());
+    }
+
+    #[tokio::test]
+    async fn test_put_details_link_not_found() {
+        let request: DetailsRequest = serde_json::from_value(json!({})).unwrap();
+
+        let mut mock_details_service = MockDetailsService::new();
+        mock_details_service
+            .expect_update()
+            .times(1)
+            .returning(|_, query, _| Err(AppError::LinkNotFound(query.id().to_owned())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .details_service(Arc::new(mock_details_service))
+                .build(),
+        );
+        let response = put(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_FOUND, parts.status);
+    }
+}
@@ -0,0 +1,131 @@
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing, Json, Router,
+};
+
+use crate::types::{AppState, Claims, LinkQueryBuilder};
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .nest(
+            "/v1",
+            Router::new().route("/links/:id/health", routing::get(get)),
+        )
+        .with_state(state)
+}
+
+async fn get(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .health_service()
+        .get(app_state.context(), &query)
+        .await
+    {
+        Ok(health) => Json(health).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::State, http::StatusCode};
+    use chrono::Utc;
+    use http_body_util::BodyExt;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use crate::{
+        app::ContextBuilder,
+        service::MockHealth as MockHealthService,
+        types::{AppError, LinkHealth},
+    };
+
+    use super::*;
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_get_health(#[case] is_admin: bool, #[case] user: &str) {
+        let get_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+        let health = LinkHealth::new("1", "http://link", 301, "https://link/", &Utc::now());
+        let retrieved_health = health.clone();
+
+        let mut mock_health_service = MockHealthService::new();
+        mock_health_service
+            .expect_get()
+            .withf(move |_, query| query == &get_query)
+            .times(1)
+            .returning(move |_, _| Ok(retrieved_health.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .health_service(Arc::new(mock_health_service))
+                .build(),
+        );
+        let response = get(
+            State(app_state),
+            Claims::new(user, is_admin, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: LinkHealth = serde_json::from_str(body).unwrap();
+        assert_eq!(body, health);
+    }
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_get_health_not_found(#[case] is_admin: bool, #[case] user: &str) {
+        let get_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+
+        let mut mock_health_service = MockHealthService::new();
+        mock_health_service
+            .expect_get()
+            .withf(move |_, query| query == &get_query)
+            .times(1)
+            .returning(|_, _| Err(AppError::HealthNotFound("1".into())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .health_service(Arc::new(mock_health_service))
+                .build(),
+        );
+        let response = get(
+            State(app_state),
+            Claims::new(user, is_admin, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_FOUND, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(
+            body,
+            json!({"error": "link health not checked yet"}).to_string()
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use axum::{
+    extract::{self, Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    routing, Json, Router,
+};
+use chrono::Utc;
+use validator::Validate;
+
+use crate::types::{AppError, AppState, Claims, Highlight, HighlightRequest, LinkQueryBuilder};
+
+const MARKDOWN_CONTENT_TYPE: &str = "text/markdown; charset=utf-8";
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .nest(
+            "/v1",
+            Router::new()
+                .route("/links/:id/highlights", routing::get(list))
+                .route("/links/:id/highlights", routing::post(post))
+                .route("/links/:id/highlights/:highlight_id", routing::put(put))
+                .route(
+                    "/links/:id/highlights/:highlight_id",
+                    routing::delete(delete),
+                )
+                .route("/highlights/export", routing::get(export)),
+        )
+        .with_state(state)
+}
+
+async fn list(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .highlights_service()
+        .search(app_state.context(), &query)
+        .await
+    {
+        Ok(highlights) => Json(highlights).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn post(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+    Json(payload): extract::Json<HighlightRequest>,
+) -> impl IntoResponse {
+    match payload.validate() {
+        Ok(()) => {}
+        Err(e) => {
+            return AppError::Validation(format!("post_highlight() {e:?}")).into_response();
+        }
+    }
+
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    let highlight = Highlight::new(
+        &id,
+        user.id(),
+        payload.quote(),
+        payload.note(),
+        payload.selectors(),
+        &Utc::now(),
+    );
+    match app_state
+        .highlights_service()
+        .create(app_state.context(), &query, &highlight)
+        .await
+    {
+        Ok(highlight) => (StatusCode::CREATED, Json(highlight)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn put(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path((id, highlight_id)): Path<(String, String)>,
+    Json(payload): extract::Json<HighlightRequest>,
+) -> impl IntoResponse {
+    match payload.validate() {
+        Ok(()) => {}
+        Err(e) => {
+            return AppError::Validation(format!("put_highlight() {e:?}")).into_response();
+        }
+    }
+
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    let highlight = Highlight::new(
+        &id,
+        user.id(),
+        payload.quote(),
+        payload.note(),
+        payload.selectors(),
+        &Utc::now(),
+    )
+    .with_id(&highlight_id);
+    match app_state
+        .highlights_service()
+        .update(app_state.context(), &query, &highlight)
+        .await
+    {
+        Ok(highlight) => Json(highlight).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn delete(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path((id, highlight_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .highlights_service()
+        .delete(app_state.context(), &query, &highlight_id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn export(State(app_state): State<AppState>, user: Claims) -> impl IntoResponse {
+    let query = LinkQueryBuilder::default().user(user.id()).build();
+    match app_state
+        .highlights_service()
+        .export(app_state.context(), &query)
+        .await
+    {
+        Ok(markdown) => ([(header::CONTENT_TYPE, MARKDOWN_CONTENT_TYPE)], markdown).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::State, http::StatusCode};
+    use http_body_util::BodyExt;
+    use serde_json::json;
+
+    use crate::{
+        app::ContextBuilder, service::MockHighlights as MockHighlightsService, types::Selector,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_highlights() {
+        let highlights = vec![Highlight::new("1", "user", "quote", "", &[], &Utc::now())];
+        let retrieved_highlights = highlights.clone();
+
+        let mut mock_highlights_service = MockHighlightsService::new();
+        mock_highlights_service
+            .expect_search()
+            .withf(|_, query| query.id() == "1" && query.user() == "user")
+            .times(1)
+            .returning(move |_, _| Ok(retrieved_highlights.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .highlights_service(Arc::new(mock_highlights_service))
+                .build(),
+        );
+        let response = list(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: Vec<Highlight> = serde_json::from_str(body).unwrap();
+        assert_eq!(body, highlights);
+    }
+
+    #[tokio::test]
+    async fn test_post_highlight() {
+        let request: HighlightRequest = serde_json::from_value(json!({
+            "quote": "quote",
+            "note": "note",
+            "selectors": [
+                {"type": "TextQuoteSelector", "exact": "quote", "prefix": "a "},
+                {"type": "TextPositionSelector", "start": 10, "end": 15}
+            ]
+        }))
+        .unwrap();
+
+        let mut mock_highlights_service = MockHighlightsService::new();
+        mock_highlights_service
+            .expect_create()
+            .withf(|_, query, highlight| {
+                query.id() == "1"
+                    && highlight.quote() == "quote"
+                    && highlight.note() == "note"
+                    && highlight.selectors()
+                        == [
+                            Selector::TextQuoteSelector {
+                                exact: "quote".into(),
+                                prefix: "a ".into(),
+                                suffix: String::new(),
+                            },
+                            Selector::TextPositionSelector { start: 10, end: 15 },
+                        ]
+            })
+            .times(1)
+            .returning(|_, _, highlight| Ok(highlight.clone().with_id("1")));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .highlights_service(Arc::new(mock_highlights_service))
+                .build(),
+        );
+        let response = post(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::CREATED, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_post_highlight_empty_quote() {
+        let request: HighlightRequest = serde_json::from_value(json!({"quote": ""})).unwrap();
+
+        let mut mock_highlights_service = MockHighlightsService::new();
+        mock_highlights_service.expect_create().times(0);
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .highlights_service(Arc::new(mock_highlights_service))
+                .build(),
+        );
+        let response = post(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_put_highlight_not_found() {
+        let request: HighlightRequest = serde_json::from_value(json!({"quote": "quote"})).unwrap();
+
+        let mut mock_highlights_service = MockHighlightsService::new();
+        mock_highlights_service
+            .expect_update()
+            .withf(|_, query, highlight| query.id() == "1" && highlight.id() == "2")
+            .times(1)
+            .returning(|_, _, highlight| {
+                Err(AppError::HighlightNotFound(highlight.id().to_owned()))
+            });
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .highlights_service(Arc::new(mock_highlights_service))
+                .build(),
+        );
+        let response = put(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path((String::from("1"), String::from("2"))),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_FOUND, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_delete_highlight() {
+        let mut mock_highlights_service = MockHighlightsService::new();
+        mock_highlights_service
+            .expect_delete()
+            .withf(|_, query, id| query.id() == "1" && id == "2")
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .highlights_service(Arc::new(mock_highlights_service))
+                .build(),
+        );
+        let response = delete(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path((String::from("1"), String::from("2"))),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NO_CONTENT, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_export_highlights() {
+        let mut mock_highlights_service = MockHighlightsService::new();
+        mock_highlights_service
+            .expect_export()
+            .withf(|_, query| query.user() == "user")
+            .times(1)
+            .returning(|_, _| Ok("# Highlights\n".to_owned()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .highlights_service(Arc::new(mock_highlights_service))
+                .build(),
+        );
+        let response = export(State(app_state), Claims::new("user", false, 0, 0)).await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+        assert_eq!(parts.headers[header::CONTENT_TYPE], MARKDOWN_CONTENT_TYPE);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        assert_eq!(std::str::from_utf8(&body).unwrap(), "# Highlights\n");
+    }
+}
@@ -1,13 +1,18 @@
 use axum::{
-    extract::{self, Path, State},
-    http::StatusCode,
+    extract::{self, Path, Query, State},
+    http::{header, HeaderMap, HeaderName, StatusCode},
     response::IntoResponse,
     routing, Json, Router,
 };
+use serde::Deserialize;
 use validator::Validate;
 
-use crate::types::{
-    AppError, AppState, Claims, LinkItemBuilder, LinkItemRequest, LinkQueryBuilder,
+use crate::{
+    service::links::{etag, etag_matches},
+    types::{
+        AppError, AppState, Claims, DetailsFilter, LinkItemBuilder, LinkItemRequest,
+        LinkPatchRequest, LinkQueryBuilder, NextStrategy, Priority,
+    },
 };
 
 pub fn router(state: AppState) -> Router<AppState> {
@@ -17,23 +22,74 @@ pub fn router(state: AppState) -> Router<AppState> {
             Router::new()
                 .route("/links", routing::get(list))
                 .route("/links", routing::post(post))
+                .route("/links/trash", routing::get(trash))
+                .route("/links/next", routing::get(next))
                 .route("/links/:id", routing::get(get))
                 .route("/links/:id", routing::put(put))
-                .route("/links/:id", routing::delete(delete)),
+                .route("/links/:id", routing::patch(patch))
+                .route("/links/:id", routing::delete(delete))
+                .route("/links/:id/restore", routing::post(restore))
+                .route("/links/:id/history", routing::get(history))
+                .route(
+                    "/links/:id/history/:revision_id/revert",
+                    routing::post(revert),
+                ),
         )
         .with_state(state)
 }
 
-async fn list(State(app_state): State<AppState>, user: Claims) -> impl IntoResponse {
+#[derive(Debug, Default, Deserialize)]
+struct ListParams {
+    health: Option<String>,
+    priority: Option<Priority>,
+    min_rating: Option<u8>,
+    sort: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NextParams {
+    strategy: Option<NextStrategy>,
+    minutes: Option<usize>,
+}
+
+async fn list(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Query(params): Query<ListParams>,
+) -> impl IntoResponse {
     let query = LinkQueryBuilder::default()
         .user(user.id())
         .is_from_admin(user.is_admin())
         .build();
-    match app_state
-        .links_service()
-        .search(Box::new(app_state.links_repo().clone()), &query)
-        .await
-    {
+    let result = match params.health.as_deref() {
+        None => {
+            app_state
+                .links_service()
+                .search(app_state.context(), &query)
+                .await
+        }
+        Some("broken") => {
+            app_state
+                .health_service()
+                .search(app_state.context(), &query)
+                .await
+        }
+        Some(health) => {
+            return AppError::Validation(format!("list_links() unknown health {health}"))
+                .into_response();
+        }
+    };
+    let filter = DetailsFilter::new(params.priority, params.min_rating, params.sort.as_deref());
+    let result = match result {
+        Ok(items) if !filter.is_empty() => {
+            app_state
+                .details_service()
+                .search(app_state.context(), &query, items, &filter)
+                .await
+        }
+        result => result,
+    };
+    match result {
         Ok(list) => Json(list).into_response(),
         Err(e) => e.into_response(),
     }
@@ -59,11 +115,7 @@ async fn post(
         .build();
     match app_state
         .links_service()
-        .create(
-            Box::new(app_state.analysis_service().clone()),
-            Box::new(app_state.links_repo().clone()),
-            &item,
-        )
+        .create(app_state.context(), &item)
         .await
     {
         Ok(item) => (StatusCode::CREATED, Json(item)).into_response(),
@@ -75,16 +127,25 @@ async fn get(
     State(app_state): State<AppState>,
     user: Claims,
     Path(id): Path<String>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     let query = LinkQueryBuilder::new(&id, user.id())
         .is_from_admin(user.is_admin())
         .build();
     match app_state
         .links_service()
-        .get(Box::new(app_state.links_repo().clone()), &query)
+        .get_versioned(app_state.context(), &query)
         .await
     {
-        Ok(item) => Json(item).into_response(),
+        Ok(link) => {
+            let etag = etag(&link);
+            if header_value(&headers, &header::IF_NONE_MATCH)
+                .is_some_and(|tags| etag_matches(tags, &etag, true))
+            {
+                return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+            }
+            ([(header::ETAG, etag)], Json(link.link())).into_response()
+        }
         Err(e) => e.into_response(),
     }
 }
@@ -93,6 +154,7 @@ async fn put(
     State(app_state): State<AppState>,
     user: Claims,
     Path(id): Path<String>,
+    headers: HeaderMap,
     Json(payload): extract::Json<LinkItemRequest>,
 ) -> impl IntoResponse {
     match payload.validate() {
@@ -117,14 +179,46 @@ async fn put(
     match app_state
         .links_service()
         .update(
-            Box::new(app_state.analysis_service().clone()),
-            Box::new(app_state.links_repo().clone()),
+            app_state.context(),
             &query,
             &item,
+            header_value(&headers, &header::IF_MATCH).map(str::to_owned),
         )
         .await
     {
-        Ok(item) => Json(item).into_response(),
+        Ok(link) => ([(header::ETAG, etag(&link))], Json(link.link())).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn patch(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(payload): extract::Json<LinkPatchRequest>,
+) -> impl IntoResponse {
+    match payload.validate() {
+        Ok(()) => {}
+        Err(e) => {
+            return AppError::Validation(format!("patch_link() {e:?}")).into_response();
+        }
+    }
+
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .links_service()
+        .patch(
+            app_state.context(),
+            &query,
+            &payload,
+            header_value(&headers, &header::IF_MATCH).map(str::to_owned),
+        )
+        .await
+    {
+        Ok(link) => ([(header::ETAG, etag(&link))], Json(link.link())).into_response(),
         Err(e) => e.into_response(),
     }
 }
@@ -133,13 +227,18 @@ async fn delete(
     State(app_state): State<AppState>,
     user: Claims,
     Path(id): Path<String>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     let query = LinkQueryBuilder::new(&id, user.id())
         .is_from_admin(user.is_admin())
         .build();
     match app_state
         .links_service()
-        .delete(Box::new(app_state.links_repo().clone()), &query)
+        .delete(
+            app_state.context(),
+            &query,
+            header_value(&headers, &header::IF_MATCH).map(str::to_owned),
+        )
         .await
     {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
@@ -147,6 +246,101 @@ async fn delete(
     }
 }
 
+async fn next(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Query(params): Query<NextParams>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::default().user(user.id()).build();
+    match app_state
+        .links_service()
+        .next(
+            app_state.context(),
+            &query,
+            params.strategy.unwrap_or_default(),
+            params.minutes,
+        )
+        .await
+    {
+        Ok(Some(item)) => Json(item).into_response(),
+        Ok(None) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn trash(State(app_state): State<AppState>, user: Claims) -> impl IntoResponse {
+    let query = LinkQueryBuilder::default()
+        .user(user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .links_service()
+        .trash(app_state.context(), &query)
+        .await
+    {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn restore(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .links_service()
+        .restore(app_state.context(), &query)
+        .await
+    {
+        Ok(item) => Json(item).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn history(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .links_service()
+        .history(app_state.context(), &query)
+        .await
+    {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn revert(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path((id, revision_id)): Path<(String, String)>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .links_service()
+        .revert(app_state.context(), &query, &revision_id)
+        .await
+    {
+        Ok(link) => ([(header::ETAG, etag(&link))], Json(link.link())).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+fn header_value<'a>(headers: &'a HeaderMap, name: &HeaderName) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -157,13 +351,12 @@ mod tests {
     use serde_json::json;
 
     use crate::{
-        repository::{MockLinks as MockLinksRepo, MockUsers as MockUsersRepo},
-        service::DynLinks as DynLinksService,
+        app::ContextBuilder,
         service::{
-            MockAnalysis as MockAnalysisService, MockLinks as MockLinksService,
-            MockUsers as MockUsersService,
+            MockDetails as MockDetailsService, MockHealth as MockHealthService,
+            MockLinks as MockLinksService,
         },
-        types::LinkItem,
+        types::{FieldChange, LinkItem, Revision, TrashedLink, VersionedLink},
     };
 
     use super::*;
@@ -185,8 +378,17 @@ mod tests {
             .times(1)
             .returning(|_, _| Ok(vec![]));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
-        let response = list(State(app_state), Claims::new(user, is_admin, 0, 0)).await;
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = list(
+            State(app_state),
+            Claims::new(user, is_admin, 0, 0),
+            Query(ListParams::default()),
+        )
+        .await;
 
         let (parts, body) = response.into_response().into_parts();
         assert_eq!(StatusCode::OK, parts.status);
@@ -216,8 +418,17 @@ mod tests {
             .times(1)
             .returning(move |_, _| Ok(vec![item.clone()]));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
-        let response = list(State(app_state), Claims::new(user, is_admin, 0, 0)).await;
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = list(
+            State(app_state),
+            Claims::new(user, is_admin, 0, 0),
+            Query(ListParams::default()),
+        )
+        .await;
 
         let (parts, body) = response.into_response().into_parts();
         assert_eq!(StatusCode::OK, parts.status);
@@ -247,8 +458,17 @@ mod tests {
             .times(1)
             .returning(|_, _| Err(AppError::Test));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
-        let response = list(State(app_state), Claims::new(user, is_admin, 0, 0)).await;
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = list(
+            State(app_state),
+            Claims::new(user, is_admin, 0, 0),
+            Query(ListParams::default()),
+        )
+        .await;
 
         let (parts, body) = response.into_response().into_parts();
         assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, parts.status);
@@ -258,6 +478,135 @@ mod tests {
         assert_eq!(body, json!({"error": "test error"}).to_string());
     }
 
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_get_broken_links(#[case] is_admin: bool, #[case] user: &str) {
+        let search_query = LinkQueryBuilder::default()
+            .user(user)
+            .is_from_admin(is_admin)
+            .build();
+        let item = LinkItemBuilder::new("http://broken-link")
+            .id("1")
+            .owner("user")
+            .build();
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service.expect_search().times(0);
+
+        let mut mock_health_service = MockHealthService::new();
+        mock_health_service
+            .expect_search()
+            .withf(move |_, query| query == &search_query)
+            .times(1)
+            .returning(move |_, _| Ok(vec![item.clone()]));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .health_service(Arc::new(mock_health_service))
+                .build(),
+        );
+        let response = list(
+            State(app_state),
+            Claims::new(user, is_admin, 0, 0),
+            Query(ListParams {
+                health: Some(String::from("broken")),
+                ..ListParams::default()
+            }),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: Vec<LinkItem> = serde_json::from_str(body).unwrap();
+        assert!(body.len() == 1);
+        assert!(body[0].url() == "http://broken-link");
+    }
+
+    #[tokio::test]
+    async fn test_get_links_by_details() {
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let searched_item = item.clone();
+        let expected_filter = DetailsFilter::new(Some(Priority::High), Some(4), Some("rating"));
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_search()
+            .times(1)
+            .returning(move |_, _| Ok(vec![item.clone()]));
+
+        let mut mock_details_service = MockDetailsService::new();
+        mock_details_service
+            .expect_search()
+            .withf(move |_, query, items, filter| {
+                query.user() == "user" && items.len() == 1 && filter == &expected_filter
+            })
+            .times(1)
+            .returning(move |_, _, _, _| Ok(vec![searched_item.clone()]));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .details_service(Arc::new(mock_details_service))
+                .build(),
+        );
+        let response = list(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Query(ListParams {
+                priority: Some(Priority::High),
+                min_rating: Some(4),
+                sort: Some(String::from("rating")),
+                ..ListParams::default()
+            }),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: Vec<LinkItem> = serde_json::from_str(body).unwrap();
+        assert!(body.len() == 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_links_unknown_health() {
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service.expect_search().times(0);
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = list(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Query(ListParams {
+                health: Some(String::from("unknown")),
+                ..ListParams::default()
+            }),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, json!({"error": "invalid request"}).to_string());
+    }
+
     #[rstest]
     #[tokio::test]
     async fn test_post_link(#[values(true, false)] is_admin: bool) {
@@ -271,11 +620,15 @@ mod tests {
         let mut mock_links_service = MockLinksService::new();
         mock_links_service
             .expect_create()
-            .withf(move |_, _, item| item == &item_to_create)
+            .withf(move |_, item| item == &item_to_create)
             .times(1)
-            .returning(move |_, _, _| Ok(created_item.clone()));
+            .returning(move |_, _| Ok(created_item.clone()));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
         let response = post(
             State(app_state),
             Claims::new("user", is_admin, 0, 0),
@@ -302,7 +655,11 @@ mod tests {
         let mut mock_links_service = MockLinksService::new();
         mock_links_service.expect_create().times(0);
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
         let response = post(
             State(app_state),
             Claims::new("user", is_admin, 0, 0),
@@ -327,11 +684,15 @@ mod tests {
         let mut mock_links_service = MockLinksService::new();
         mock_links_service
             .expect_create()
-            .withf(move |_, _, item| item == &item_to_create)
+            .withf(move |_, item| item == &item_to_create)
             .times(1)
-            .returning(|_, _, _| Err(AppError::Test));
+            .returning(|_, _| Err(AppError::Test));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
         let response = post(
             State(app_state),
             Claims::new("user", is_admin, 0, 0),
@@ -355,28 +716,38 @@ mod tests {
         let get_query = LinkQueryBuilder::new("1", user)
             .is_from_admin(is_admin)
             .build();
-        let item = LinkItemBuilder::new("http://link")
-            .id("1")
-            .owner("user")
-            .build();
+        let link = VersionedLink::new(
+            &LinkItemBuilder::new("http://link")
+                .id("1")
+                .owner("user")
+                .build(),
+            1,
+        );
+        let expected_etag = etag(&link);
 
         let mut mock_links_service = MockLinksService::new();
         mock_links_service
-            .expect_get()
+            .expect_get_versioned()
             .withf(move |_, query| query == &get_query)
             .times(1)
-            .returning(move |_, _| Ok(item.clone()));
+            .returning(move |_, _| Ok(link.clone()));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
         let response = get(
             State(app_state),
             Claims::new(user, is_admin, 0, 0),
             Path(String::from("1")),
+            HeaderMap::new(),
         )
         .await;
 
         let (parts, body) = response.into_response().into_parts();
         assert_eq!(StatusCode::OK, parts.status);
+        assert_eq!(parts.headers[header::ETAG], expected_etag.as_str());
 
         let body = body.collect().await.unwrap().to_bytes();
         let body = std::str::from_utf8(&body).unwrap();
@@ -386,6 +757,50 @@ mod tests {
         assert!(body.url() == "http://link");
     }
 
+    #[tokio::test]
+    async fn test_get_link_not_modified() {
+        let link = VersionedLink::new(
+            &LinkItemBuilder::new("http://link")
+                .id("1")
+                .owner("user")
+                .build(),
+            1,
+        );
+        let current_etag = etag(&link);
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_get_versioned()
+            .times(1)
+            .returning(move |_, _| Ok(link.clone()));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::IF_NONE_MATCH,
+            format!("W/{current_etag}").parse().unwrap(),
+        );
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = get(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            headers,
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_MODIFIED, parts.status);
+        assert_eq!(parts.headers[header::ETAG], current_etag.as_str());
+
+        let body = body.collect().await.unwrap().to_bytes();
+        assert!(body.is_empty());
+    }
+
     #[rstest]
     #[case(true, "admin")]
     #[case(false, "user")]
@@ -397,16 +812,21 @@ mod tests {
 
         let mut mock_links_service = MockLinksService::new();
         mock_links_service
-            .expect_get()
+            .expect_get_versioned()
             .withf(move |_, query| query == &get_query)
             .times(1)
             .returning(|_, _| Err(AppError::Test));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
         let response = get(
             State(app_state),
             Claims::new(user, is_admin, 0, 0),
             Path(String::from("1")),
+            HeaderMap::new(),
         )
         .await;
 
@@ -433,15 +853,22 @@ mod tests {
         let mut mock_links_service = MockLinksService::new();
         mock_links_service
             .expect_update()
-            .withf(move |_, _, query, item| query == &update_query && item == &item_to_update)
+            .withf(move |_, query, item, if_match| {
+                query == &update_query && item == &item_to_update && if_match.is_none()
+            })
             .times(1)
-            .returning(move |_, _, _, _| Ok(updated_item.clone()));
+            .returning(move |_, _, _, _| Ok(VersionedLink::new(&updated_item, 2)));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
         let response = put(
             State(app_state),
             Claims::new(user, is_admin, 0, 0),
             Path(String::from("1")),
+            HeaderMap::new(),
             Json(request),
         )
         .await;
@@ -464,11 +891,16 @@ mod tests {
         let mut mock_links_service = MockLinksService::new();
         mock_links_service.expect_update().times(0);
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
         let response = put(
             State(app_state),
             Claims::new("user", is_admin, 0, 0),
             Path(String::from("1")),
+            HeaderMap::new(),
             Json(request),
         )
         .await;
@@ -495,15 +927,22 @@ mod tests {
         let mut mock_links_service = MockLinksService::new();
         mock_links_service
             .expect_update()
-            .withf(move |_, _, query, item| query == &update_query && item == &item_to_update)
+            .withf(move |_, query, item, if_match| {
+                query == &update_query && item == &item_to_update && if_match.is_none()
+            })
             .times(1)
             .returning(|_, _, _, _| Err(AppError::Test));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
         let response = put(
             State(app_state),
             Claims::new(user, is_admin, 0, 0),
             Path(String::from("1")),
+            HeaderMap::new(),
             Json(request),
         )
         .await;
@@ -516,6 +955,119 @@ mod tests {
         assert_eq!(body, json!({"error": "test error"}).to_string());
     }
 
+    #[tokio::test]
+    async fn test_put_link_precondition_failed() {
+        let request = LinkItemRequest::new("http://link");
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_update()
+            .withf(|_, _, _, if_match| if_match.as_deref() == Some("\"outdated\""))
+            .times(1)
+            .returning(|_, _, _, _| Err(AppError::PreconditionFailed("1".into())));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_MATCH, "\"outdated\"".parse().unwrap());
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = put(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            headers,
+            Json(request),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::PRECONDITION_FAILED, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(
+            body,
+            json!({"error": "link item has been modified"}).to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_patch_link() {
+        let request: LinkPatchRequest = serde_json::from_value(json!({"label": "read"})).unwrap();
+        let patch_query = LinkQueryBuilder::new("1", "user").build();
+        let patched_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .label("read")
+            .build();
+
+        let item = LinkItemBuilder::new("http://link").id("1").build();
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_patch()
+            .withf(move |_, query, patch, if_match| {
+                query == &patch_query && patch.apply(&item) == patched_item && if_match.is_none()
+            })
+            .times(1)
+            .returning(|_, query, patch, _| {
+                let item = LinkItemBuilder::new("http://link").id(query.id()).build();
+                Ok(VersionedLink::new(&patch.apply(&item), 2))
+            });
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = patch(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+        assert!(parts.headers.contains_key(header::ETAG));
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: LinkItem = serde_json::from_str(body).unwrap();
+        assert!(body.url() == "http://link");
+        assert!(body.label() == "read");
+    }
+
+    #[tokio::test]
+    async fn test_patch_link_invalid_url() {
+        let request: LinkPatchRequest =
+            serde_json::from_value(json!({"url": "invalid-link"})).unwrap();
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service.expect_patch().times(0);
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = patch(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            HeaderMap::new(),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+    }
+
     #[rstest]
     #[case(true, "admin")]
     #[case(false, "user")]
@@ -528,15 +1080,20 @@ mod tests {
         let mut mock_links_service = MockLinksService::new();
         mock_links_service
             .expect_delete()
-            .withf(move |_, query| query == &delete_query)
+            .withf(move |_, query, if_match| query == &delete_query && if_match.is_none())
             .times(1)
-            .returning(move |_, _| Ok(()));
+            .returning(move |_, _, _| Ok(()));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
         let response = delete(
             State(app_state),
             Claims::new(user, is_admin, 0, 0),
             Path(String::from("1")),
+            HeaderMap::new(),
         )
         .await;
 
@@ -556,15 +1113,20 @@ mod tests {
         let mut mock_links_service = MockLinksService::new();
         mock_links_service
             .expect_delete()
-            .withf(move |_, query| query == &delete_query)
+            .withf(move |_, query, if_match| query == &delete_query && if_match.is_none())
             .times(1)
-            .returning(|_, _| Err(AppError::Test));
+            .returning(|_, _, _| Err(AppError::Test));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_links_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
         let response = delete(
             State(app_state),
             Claims::new(user, is_admin, 0, 0),
             Path(String::from("1")),
+            HeaderMap::new(),
         )
         .await;
 
@@ -576,23 +1138,285 @@ mod tests {
         assert_eq!(body, json!({"error": "test error"}).to_string());
     }
 
-    struct AppStateBuilder {
-        links_service: DynLinksService,
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_get_trash(#[case] is_admin: bool, #[case] user: &str) {
+        let trash_query = LinkQueryBuilder::default()
+            .user(user)
+            .is_from_admin(is_admin)
+            .build();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner(user)
+            .build();
+        let trashed_link = TrashedLink::new(&item, &chrono::Utc::now());
+        let expected_body = json!([trashed_link]).to_string();
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_trash()
+            .withf(move |_, query| query == &trash_query)
+            .times(1)
+            .returning(move |_, _| Ok(vec![trashed_link.clone()]));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = trash(State(app_state), Claims::new(user, is_admin, 0, 0)).await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, expected_body);
     }
 
-    impl AppStateBuilder {
-        fn new(links_service: DynLinksService) -> Self {
-            Self { links_service }
-        }
+    #[rstest]
+    #[case(NextParams::default(), NextStrategy::Oldest, None)]
+    #[case(NextParams { strategy: Some(NextStrategy::Shortest), minutes: Some(10) }, NextStrategy::Shortest, Some(10))]
+    #[tokio::test]
+    async fn test_next_link(
+        #[case] params: NextParams,
+        #[case] expected_strategy: NextStrategy,
+        #[case] expected_minutes: Option<usize>,
+    ) {
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let expected_body = json!(item).to_string();
 
-        fn build(self) -> AppState {
-            AppState::new(
-                self.links_service,
-                Arc::new(MockUsersService::new()),
-                Arc::new(MockAnalysisService::new()),
-                Arc::new(MockLinksRepo::new()),
-                Arc::new(MockUsersRepo::new()),
-            )
-        }
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_next()
+            .withf(move |_, query, strategy, minutes| {
+                query.user() == "user"
+                    && *strategy == expected_strategy
+                    && *minutes == expected_minutes
+            })
+            .times(1)
+            .returning(move |_, _, _, _| Ok(Some(item.clone())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = next(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Query(params),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, expected_body);
+    }
+
+    #[tokio::test]
+    async fn test_next_link_none() {
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_next()
+            .times(1)
+            .returning(|_, _, _, _| Ok(None));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = next(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Query(NextParams::default()),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NO_CONTENT, parts.status);
+    }
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_restore_link(#[case] is_admin: bool, #[case] user: &str) {
+        let restore_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner(user)
+            .build();
+        let expected_body = json!(item).to_string();
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_restore()
+            .withf(move |_, query| query == &restore_query)
+            .times(1)
+            .returning(move |_, _| Ok(item.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = restore(
+            State(app_state),
+            Claims::new(user, is_admin, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, expected_body);
+    }
+
+    #[tokio::test]
+    async fn test_restore_link_not_found() {
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_restore()
+            .times(1)
+            .returning(|_, _| Err(AppError::LinkNotFound("1".into())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = restore(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_FOUND, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_get_history() {
+        let history_query = LinkQueryBuilder::new("1", "user").build();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let revision = Revision::new(
+            &item,
+            "user",
+            "update",
+            &[FieldChange::new("title", "", "Link")],
+            &chrono::Utc::now(),
+        )
+        .with_id("1");
+        let expected_body = json!([revision]).to_string();
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_history()
+            .withf(move |_, query| query == &history_query)
+            .times(1)
+            .returning(move |_, _| Ok(vec![revision.clone()]));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = history(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, expected_body);
+    }
+
+    #[tokio::test]
+    async fn test_revert_link() {
+        let revert_query = LinkQueryBuilder::new("1", "user").build();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let expected_body = json!(item).to_string();
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_revert()
+            .withf(move |_, query, revision_id| query == &revert_query && revision_id == "2")
+            .times(1)
+            .returning(move |_, _, _| Ok(VersionedLink::new(&item, 3)));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = revert(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path((String::from("1"), String::from("2"))),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+        assert!(parts.headers.contains_key(header::ETAG));
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, expected_body);
+    }
+
+    #[tokio::test]
+    async fn test_revert_link_revision_not_found() {
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_revert()
+            .times(1)
+            .returning(|_, _, _| Err(AppError::RevisionNotFound("2".into())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .links_service(Arc::new(mock_links_service))
+                .build(),
+        );
+        let response = revert(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path((String::from("1"), String::from("2"))),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_FOUND, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, json!({"error": "revision not found"}).to_string());
     }
 }
@@ -0,0 +1,260 @@
+use axum::{
+    extract::{self, Path, Query, State},
+    response::IntoResponse,
+    routing, Json, Router,
+};
+use chrono::Utc;
+use serde::Deserialize;
+use validator::Validate;
+
+use crate::{
+    service::progress::DEFAULT_STATS_WEEKS,
+    types::{AppError, AppState, Claims, LinkQueryBuilder, ProgressRequest, ReadingProgress},
+};
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .nest(
+            "/v1",
+            Router::new()
+                .route("/links/:id/progress", routing::get(get))
+                .route("/links/:id/progress", routing::put(put))
+                .route("/progress/stats", routing::get(stats)),
+        )
+        .with_state(state)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StatsParams {
+    weeks: Option<u32>,
+}
+
+async fn get(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .progress_service()
+        .get(app_state.context(), &query)
+        .await
+    {
+        Ok(progress) => Json(progress).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn put(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+    Json(payload): extract::Json<ProgressRequest>,
+) -> impl IntoResponse {
+    match payload.validate() {
+        Ok(()) => {}
+        Err(e) => {
+            return AppError::Validation(format!("put_progress() {e:?}")).into_response();
+        }
+    }
+
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    let progress = ReadingProgress::new(
+        &id,
+        user.id(),
+        payload.percentage(),
+        payload.position(),
+        payload.time_spent(),
+        &Utc::now(),
+    );
+    match app_state
+        .progress_service()
+        .update(app_state.context(), &query, &progress)
+        .await
+    {
+        Ok(progress) => Json(progress).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn stats(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Query(params): Query<StatsParams>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::default().user(user.id()).build();
+    match app_state
+        .progress_service()
+        .stats(
+            app_state.context(),
+            &query,
+            params.weeks.unwrap_or(DEFAULT_STATS_WEEKS),
+            &Utc::now(),
+        )
+        .await
+    {
+        Ok(stats) => Json(stats).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::State, http::StatusCode};
+    use http_body_util::BodyExt;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use crate::{
+        app::ContextBuilder,
+        service::MockProgress as MockProgressService,
+        types::{ReadingStats, WeeklyReads},
+    };
+
+    use super::*;
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_get_progress(#[case] is_admin: bool, #[case] user: &str) {
+        let get_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+        let progress = ReadingProgress::new("1", "user", 40, 1200, 90, &Utc::now());
+        let retrieved_progress = progress.clone();
+
+        let mut mock_progress_service = MockProgressService::new();
+        mock_progress_service
+            .expect_get()
+            .withf(move |_, query| query == &get_query)
+            .times(1)
+            .returning(move |_, _| Ok(retrieved_progress.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .progress_service(Arc::new(mock_progress_service))
+                .build(),
+        );
+        let response = get(
+            State(app_state),
+            Claims::new(user, is_admin, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: ReadingProgress = serde_json::from_str(body).unwrap();
+        assert_eq!(body, progress);
+    }
+
+    #[tokio::test]
+    async fn test_put_progress() {
+        let request: ProgressRequest =
+            serde_json::from_value(json!({"percentage": 50, "position": 1500, "time_spent": 30}))
+                .unwrap();
+
+        let mut mock_progress_service = MockProgressService::new();
+        mock_progress_service
+            .expect_update()
+            .withf(|_, query, progress| {
+                query.id() == "1"
+                    && query.user() == "user"
+                    && progress.percentage() == 50
+                    && progress.position() == 1500
+                    && progress.time_spent() == 30
+            })
+            .times(1)
+            .returning(|_, _, progress| Ok(progress.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .progress_service(Arc::new(mock_progress_service))
+                .build(),
+        );
+        let response = put(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_put_progress_invalid_percentage() {
+        let request: ProgressRequest = serde_json::from_value(json!({"percentage": 101})).unwrap();
+
+        let mut mock_progress_service = MockProgressService::new();
+        mock_progress_service.expect_update().times(0);
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .progress_service(Arc::new(mock_progress_service))
+                .build(),
+        );
+        let response = put(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            Json(request),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, json!({"error": "invalid request"}).to_string());
+    }
+
+    #[rstest]
+    #[case(None, DEFAULT_STATS_WEEKS)]
+    #[case(Some(4), 4)]
+    #[tokio::test]
+    async fn test_progress_stats(#[case] weeks: Option<u32>, #[case] expected_weeks: u32) {
+        let reading_stats = ReadingStats::new(&[WeeklyReads::new(Utc::now().date_naive(), 3)], 240);
+        let retrieved_stats = reading_stats.clone();
+
+        let mut mock_progress_service = MockProgressService::new();
+        mock_progress_service
+            .expect_stats()
+            .withf(move |_, query, weeks, _| query.user() == "user" && *weeks == expected_weeks)
+            .times(1)
+            .returning(move |_, _, _, _| Ok(retrieved_stats.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .progress_service(Arc::new(mock_progress_service))
+                .build(),
+        );
+        let response = stats(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Query(StatsParams { weeks }),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: ReadingStats = serde_json::from_str(body).unwrap();
+        assert_eq!(body, reading_stats);
+    }
+}
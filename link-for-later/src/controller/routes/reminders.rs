@@ -0,0 +1,358 @@
+use axum::{
+    extract::{self, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing, Json, Router,
+};
+use chrono::Utc;
+use validator::Validate;
+
+use crate::{
+    service::reminders::{next_digest_at, REMINDER_DIGEST, REMINDER_LINK},
+    types::{
+        AppError, AppState, Claims, DigestRequest, LinkQueryBuilder, Reminder, ReminderRequest,
+        SnoozeRequest,
+    },
+};
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .nest(
+            "/v1",
+            Router::new()
+                .route("/reminders", routing::get(list))
+                .route("/reminders", routing::post(post))
+                .route("/reminders/digest", routing::put(digest))
+                .route("/reminders/:id/snooze", routing::post(snooze))
+                .route("/reminders/:id/dismiss", routing::post(dismiss)),
+        )
+        .with_state(state)
+}
+
+async fn list(State(app_state): State<AppState>, user: Claims) -> impl IntoResponse {
+    let query = LinkQueryBuilder::default()
+        .user(user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .reminders_service()
+        .search(app_state.context(), &query)
+        .await
+    {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn post(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Json(payload): extract::Json<ReminderRequest>,
+) -> impl IntoResponse {
+    match payload.validate() {
+        Ok(()) => {}
+        Err(e) => {
+            return AppError::Validation(format!("post_reminder() {e:?}")).into_response();
+        }
+    }
+
+    let now = Utc::now();
+    let reminder = Reminder::new(
+        user.id(),
+        REMINDER_LINK,
+        payload.link_id(),
+        payload.remind_at(),
+        &now,
+    );
+    match app_state
+        .reminders_service()
+        .create(app_state.context(), &reminder)
+        .await
+    {
+        Ok(reminder) => (StatusCode::CREATED, Json(reminder)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn digest(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Json(payload): extract::Json<DigestRequest>,
+) -> impl IntoResponse {
+    match payload.validate() {
+        Ok(()) => {}
+        Err(e) => {
+            return AppError::Validation(format!("put_digest() {e:?}")).into_response();
+        }
+    }
+
+    let now = Utc::now();
+    let reminder = Reminder::new(
+        user.id(),
+        REMINDER_DIGEST,
+        "",
+        &next_digest_at(payload.hour(), &now),
+        &now,
+    );
+    match app_state
+        .reminders_service()
+        .create(app_state.context(), &reminder)
+        .await
+    {
+        Ok(reminder) => Json(reminder).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn snooze(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+    Json(payload): extract::Json<SnoozeRequest>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .reminders_service()
+        .snooze(app_state.context(), &query, payload.until())
+        .await
+    {
+        Ok(reminder) => Json(reminder).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn dismiss(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .reminders_service()
+        .dismiss(app_state.context(), &query)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::State, http::StatusCode};
+    use chrono::Duration;
+    use http_body_util::BodyExt;
+    use serde_json::json;
+
+    use crate::{app::ContextBuilder, service::MockReminders as MockRemindersService};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_reminders() {
+        let now = Utc::now();
+        let reminder = Reminder::new("user", REMINDER_LINK, "1", &now, &now).with_id("1");
+        let expected_body = json!([reminder]).to_string();
+
+        let mut mock_reminders_service = MockRemindersService::new();
+        mock_reminders_service
+            .expect_search()
+            .withf(|_, query| query.user() == "user" && !query.is_from_admin())
+            .times(1)
+            .returning(move |_, _| Ok(vec![reminder.clone()]));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .reminders_service(Arc::new(mock_reminders_service))
+                .build(),
+        );
+        let response = list(State(app_state), Claims::new("user", false, 0, 0)).await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, expected_body);
+    }
+
+    #[tokio::test]
+    async fn test_post_reminder() {
+        let remind_at = Utc::now() + Duration::hours(1);
+        let request: ReminderRequest =
+            serde_json::from_value(json!({"link_id": "1", "remind_at": remind_at})).unwrap();
+
+        let mut mock_reminders_service = MockRemindersService::new();
+        mock_reminders_service
+            .expect_create()
+            .withf(move |_, reminder| {
+                reminder.owner() == "user"
+                    && reminder.kind() == REMINDER_LINK
+                    && reminder.link_id() == "1"
+                    && *reminder.remind_at() == remind_at
+            })
+            .times(1)
+            .returning(|_, reminder| Ok(reminder.clone().with_id("1")));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .reminders_service(Arc::new(mock_reminders_service))
+                .build(),
+        );
+        let response = post(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::CREATED, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_put_digest() {
+        let request: DigestRequest = serde_json::from_value(json!({"hour": 8})).unwrap();
+
+        let mut mock_reminders_service = MockRemindersService::new();
+        mock_reminders_service
+            .expect_create()
+            .withf(|_, reminder| {
+                reminder.kind() == REMINDER_DIGEST
+                    && reminder.link_id().is_empty()
+                    && *reminder.remind_at() > Utc::now()
+            })
+            .times(1)
+            .returning(|_, reminder| Ok(reminder.clone().with_id("1")));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .reminders_service(Arc::new(mock_reminders_service))
+                .build(),
+        );
+        let response = digest(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_put_digest_invalid_hour() {
+        let request: DigestRequest = serde_json::from_value(json!({"hour": 24})).unwrap();
+
+        let mut mock_reminders_service = MockRemindersService::new();
+        mock_reminders_service.expect_create().times(0);
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .reminders_service(Arc::new(mock_reminders_service))
+                .build(),
+        );
+        let response = digest(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_snooze_reminder() {
+        let until = Utc::now() + Duration::hours(1);
+        let request: SnoozeRequest = serde_json::from_value(json!({"until": until})).unwrap();
+
+        let mut mock_reminders_service = MockRemindersService::new();
+        mock_reminders_service
+            .expect_snooze()
+            .withf(move |_, query, snoozed_until| {
+                query.id() == "1" && query.user() == "user" && *snoozed_until == until
+            })
+            .times(1)
+            .returning(|_, _, until| {
+                Ok(Reminder::new("user", REMINDER_LINK, "1", until, &Utc::now()).with_id("1"))
+            });
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .reminders_service(Arc::new(mock_reminders_service))
+                .build(),
+        );
+        let response = snooze(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_dismiss_reminder() {
+        let mut mock_reminders_service = MockRemindersService::new();
+        mock_reminders_service
+            .expect_dismiss()
+            .withf(|_, query| query.id() == "1" && query.user() == "user")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .reminders_service(Arc::new(mock_reminders_service))
+                .build(),
+        );
+        let response = dismiss(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NO_CONTENT, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_dismiss_reminder_not_found() {
+        let mut mock_reminders_service = MockRemindersService::new();
+        mock_reminders_service
+            .expect_dismiss()
+            .times(1)
+            .returning(|_, query| Err(AppError::ReminderNotFound(query.id().to_owned())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .reminders_service(Arc::new(mock_reminders_service))
+                .build(),
+        );
+        let response = dismiss(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_FOUND, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, json!({"error": "reminder not found"}).to_string());
+    }
+}
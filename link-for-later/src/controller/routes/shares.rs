@@ -0,0 +1,448 @@
+use axum::{
+    extract::{self, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+    routing, Json, Router,
+};
+use chrono::Utc;
+use validator::Validate;
+
+use crate::{
+    service::{
+        links::etag_matches,
+        shares::{atom, feed_etag},
+    },
+    types::{AppError, AppState, Claims, LinkQueryBuilder, Share, ShareRequest},
+};
+
+const ATOM_CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .nest(
+            "/v1",
+            Router::new()
+                .route("/shares", routing::get(list))
+                .route("/shares", routing::post(post))
+                .route("/shares/:id", routing::delete(delete))
+                // anyone holding the token can see what it shares, no account needed
+                .route("/public/:token", routing::get(view))
+                .route("/public/:token/feed", routing::get(feed)),
+        )
+        .with_state(state)
+}
+
+async fn list(State(app_state): State<AppState>, user: Claims) -> impl IntoResponse {
+    let query = LinkQueryBuilder::default()
+        .user(user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .shares_service()
+        .search(app_state.context(), &query)
+        .await
+    {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn post(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Json(payload): extract::Json<ShareRequest>,
+) -> impl IntoResponse {
+    match payload.validate() {
+        Ok(()) => {}
+        Err(e) => {
+            return AppError::Validation(format!("post_share() {e:?}")).into_response();
+        }
+    }
+
+    let share = Share::new(
+        user.id(),
+        payload.kind(),
+        payload.id(),
+        payload.expires_at(),
+        &Utc::now(),
+    );
+    match app_state
+        .shares_service()
+        .create(app_state.context(), &share)
+        .await
+    {
+        Ok(share) => (StatusCode::CREATED, Json(share)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn delete(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .shares_service()
+        .delete(app_state.context(), &query)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn view(State(app_state): State<AppState>, Path(token): Path<String>) -> impl IntoResponse {
+    match app_state
+        .shares_service()
+        .view(app_state.context(), &token)
+        .await
+    {
+        Ok(view) => Json(view).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn feed(
+    State(app_state): State<AppState>,
+    Path(token): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let feed = match app_state
+        .shares_service()
+        .feed(app_state.context(), &token)
+        .await
+    {
+        Ok(feed) => feed,
+        Err(e) => return e.into_response(),
+    };
+
+    let body = atom(&feed);
+    let etag = feed_etag(&body);
+    // the newest entry can disappear from a feed, so only the ETag can tell
+    // whether it changed; a Last-Modified date could go backwards
+    let is_not_modified = headers
+        .get(header::IF_NONE_MATCH)
+        .and_then(|tags| tags.to_str().ok())
+        .is_some_and(|tags| etag_matches(tags, &etag, true));
+    if is_not_modified {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+
+    (
+        [
+            (header::CONTENT_TYPE, ATOM_CONTENT_TYPE.to_owned()),
+            (header::ETAG, etag),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::State, http::StatusCode};
+    use chrono::DateTime;
+    use http_body_util::BodyExt;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use crate::{
+        app::ContextBuilder,
+        service::MockShares as MockSharesService,
+        types::{Feed, LinkItemBuilder, PublicLink, SharedView},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_shares() {
+        let share = Share::new("user", "link", "1", None, &Utc::now())
+            .with_id("1")
+            .with_token("token");
+        let expected_body = json!([share]).to_string();
+
+        let mut mock_shares_service = MockSharesService::new();
+        mock_shares_service
+            .expect_search()
+            .withf(|_, query| query.user() == "user" && !query.is_from_admin())
+            .times(1)
+            .returning(move |_, _| Ok(vec![share.clone()]));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .shares_service(Arc::new(mock_shares_service))
+                .build(),
+        );
+        let response = list(State(app_state), Claims::new("user", false, 0, 0)).await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, expected_body);
+    }
+
+    #[tokio::test]
+    async fn test_post_share() {
+        let request: ShareRequest = serde_json::from_value(json!({
+            "kind": "collection",
+            "id": "1",
+            "expires_at": "2100-01-01T00:00:00Z"
+        }))
+        .unwrap();
+
+        let mut mock_shares_service = MockSharesService::new();
+        mock_shares_service
+            .expect_create()
+            .withf(|_, share| {
+                share.owner() == "user"
+                    && share.kind() == "collection"
+                    && share.target() == "1"
+                    && share.expires_at().is_some()
+            })
+            .times(1)
+            .returning(|_, share| Ok(share.clone().with_id("1").with_token("token")));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .shares_service(Arc::new(mock_shares_service))
+                .build(),
+        );
+        let response = post(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Json(request),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::CREATED, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: Share = serde_json::from_str(body).unwrap();
+        assert_eq!(body.token(), "token");
+    }
+
+    #[tokio::test]
+    async fn test_post_share_without_id() {
+        let request: ShareRequest =
+            serde_json::from_value(json!({"kind": "link", "id": ""})).unwrap();
+
+        let mut mock_shares_service = MockSharesService::new();
+        mock_shares_service.expect_create().times(0);
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .shares_service(Arc::new(mock_shares_service))
+                .build(),
+        );
+        let response = post(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_delete_share() {
+        let mut mock_shares_service = MockSharesService::new();
+        mock_shares_service
+            .expect_delete()
+            .withf(|_, query| query.id() == "1" && query.user() == "user")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .shares_service(Arc::new(mock_shares_service))
+                .build(),
+        );
+        let response = delete(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NO_CONTENT, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_view_share() {
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let view = SharedView::Link(PublicLink::from(&item));
+        let expected_body = json!(view).to_string();
+
+        let mut mock_shares_service = MockSharesService::new();
+        mock_shares_service
+            .expect_view()
+            .withf(|_, token| token == "token")
+            .times(1)
+            .returning(move |_, _| Ok(view.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .shares_service(Arc::new(mock_shares_service))
+                .build(),
+        );
+        let response = super::view(State(app_state), Path(String::from("token"))).await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, expected_body);
+        assert!(!body.contains("user"));
+    }
+
+    #[tokio::test]
+    async fn test_view_share_not_found() {
+        let mut mock_shares_service = MockSharesService::new();
+        mock_shares_service
+            .expect_view()
+            .times(1)
+            .returning(|_, token| Err(AppError::ShareNotFound(token.to_owned())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .shares_service(Arc::new(mock_shares_service))
+                .build(),
+        );
+        let response = super::view(State(app_state), Path(String::from("revoked"))).await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_FOUND, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, json!({"error": "share not found"}).to_string());
+    }
+
+    #[tokio::test]
+    async fn test_feed() {
+        let updated_at = DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .updated_at(&updated_at)
+            .build();
+        let feed = Feed::new("1", "Unread links", &updated_at, &[item]);
+
+        let mut mock_shares_service = MockSharesService::new();
+        mock_shares_service
+            .expect_feed()
+            .withf(|_, token| token == "token")
+            .times(1)
+            .returning(move |_, _| Ok(feed.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .shares_service(Arc::new(mock_shares_service))
+                .build(),
+        );
+        let response = super::feed(
+            State(app_state),
+            Path(String::from("token")),
+            HeaderMap::new(),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+        assert_eq!(parts.headers[header::CONTENT_TYPE], ATOM_CONTENT_TYPE);
+        assert!(!parts.headers.contains_key(header::LAST_MODIFIED));
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(parts.headers[header::ETAG], feed_etag(body).as_str());
+        assert!(body.contains("<link href=\"http://link\"/>"));
+    }
+
+    #[rstest]
+    #[case(header::IF_NONE_MATCH, "current", StatusCode::NOT_MODIFIED)]
+    #[case(header::IF_NONE_MATCH, "\"stale\"", StatusCode::OK)]
+    #[case(
+        header::IF_MODIFIED_SINCE,
+        "Tue, 02 Jan 2024 03:04:05 GMT",
+        StatusCode::OK
+    )]
+    #[tokio::test]
+    async fn test_feed_conditional(
+        #[case] name: header::HeaderName,
+        #[case] value: &str,
+        #[case] expected_status: StatusCode,
+    ) {
+        let updated_at = DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let feed = Feed::new("1", "Unread links", &updated_at, &[]);
+        let current_etag = feed_etag(&atom(&feed));
+
+        let mut mock_shares_service = MockSharesService::new();
+        mock_shares_service
+            .expect_feed()
+            .times(1)
+            .returning(move |_, _| Ok(feed.clone()));
+
+        let mut headers = HeaderMap::new();
+        let value = if value == "current" {
+            current_etag.clone()
+        } else {
+            value.to_owned()
+        };
+        headers.insert(name, value.parse().unwrap());
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .shares_service(Arc::new(mock_shares_service))
+                .build(),
+        );
+        let response = super::feed(State(app_state), Path(String::from("token")), headers).await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(expected_status, parts.status);
+        assert_eq!(parts.headers[header::ETAG], current_etag.as_str());
+    }
+
+    #[tokio::test]
+    async fn test_feed_not_found() {
+        let mut mock_shares_service = MockSharesService::new();
+        mock_shares_service
+            .expect_feed()
+            .times(1)
+            .returning(|_, token| Err(AppError::ShareNotFound(token.to_owned())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .shares_service(Arc::new(mock_shares_service))
+                .build(),
+        );
+        let response = super::feed(
+            State(app_state),
+            Path(String::from("revoked")),
+            HeaderMap::new(),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_FOUND, parts.status);
+    }
+}
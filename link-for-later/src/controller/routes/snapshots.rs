@@ -0,0 +1,146 @@
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+    routing, Router,
+};
+
+use crate::types::{AppState, Claims, LinkQueryBuilder};
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .nest(
+            "/v1",
+            Router::new().route("/links/:id/snapshot", routing::get(get)),
+        )
+        .with_state(state)
+}
+
+async fn get(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let query = LinkQueryBuilder::new(&id, user.id())
+        .is_from_admin(user.is_admin())
+        .build();
+    match app_state
+        .snapshots_service()
+        .get(app_state.context(), &query)
+        .await
+    {
+        Ok(snapshot) => (
+            [
+                (header::CONTENT_TYPE, snapshot.content_type().to_owned()),
+                // snapshots are third-party content, never let them run scripts on our origin
+                (header::CONTENT_SECURITY_POLICY, String::from("sandbox")),
+                (header::X_CONTENT_TYPE_OPTIONS, String::from("nosniff")),
+            ],
+            snapshot.content().to_owned(),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::State, http::StatusCode};
+    use chrono::Utc;
+    use http_body_util::BodyExt;
+    use rstest::rstest;
+    use serde_json::json;
+
+    use crate::{
+        app::ContextBuilder,
+        service::MockSnapshots as MockSnapshotsService,
+        types::{AppError, Snapshot},
+    };
+
+    use super::*;
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_get_snapshot(#[case] is_admin: bool, #[case] user: &str) {
+        let get_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+        let snapshot = Snapshot::new(
+            "1",
+            "http://link",
+            "text/html; charset=utf-8",
+            "<p>link</p>",
+            &Utc::now(),
+        );
+
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service
+            .expect_get()
+            .withf(move |_, query| query == &get_query)
+            .times(1)
+            .returning(move |_, _| Ok(snapshot.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .snapshots_service(Arc::new(mock_snapshots_service))
+                .build(),
+        );
+        let response = get(
+            State(app_state),
+            Claims::new(user, is_admin, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+        assert_eq!(
+            parts.headers[header::CONTENT_TYPE],
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(parts.headers[header::CONTENT_SECURITY_POLICY], "sandbox");
+
+        let body = body.collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], b"<p>link</p>");
+    }
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_get_snapshot_not_found(#[case] is_admin: bool, #[case] user: &str) {
+        let get_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service
+            .expect_get()
+            .withf(move |_, query| query == &get_query)
+            .times(1)
+            .returning(|_, _| Err(AppError::SnapshotNotFound("1".into())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .snapshots_service(Arc::new(mock_snapshots_service))
+                .build(),
+        );
+        let response = get(
+            State(app_state),
+            Claims::new(user, is_admin, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_FOUND, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, json!({"error": "snapshot not found"}).to_string());
+    }
+}
@@ -1,8 +1,19 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, routing, Json, Router};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing, Json, Router,
+};
+use chrono::Utc;
+use serde::Deserialize;
 use validator::Validate;
 
-use crate::types::{
-    AppError, AppState, UserInfoBuilder, UserLoginRequest, UserLoginResponse, UserRegisterRequest,
+use crate::{
+    service::users::DEFAULT_STATS_DAYS,
+    types::{
+        AppError, AppState, Claims, UserInfoBuilder, UserLoginRequest, UserLoginResponse,
+        UserRegisterRequest,
+    },
 };
 
 pub fn router(state: AppState) -> Router<AppState> {
@@ -13,7 +24,8 @@ pub fn router(state: AppState) -> Router<AppState> {
                 "/users",
                 Router::new()
                     .route("/login", routing::post(login))
-                    .route("/register", routing::post(register)),
+                    .route("/register", routing::post(register))
+                    .route("/me/stats", routing::get(stats)),
             ),
         )
         .with_state(state)
@@ -30,13 +42,12 @@ async fn register(
         }
     }
 
-    let users_repo = app_state.users_repo().clone();
     let user_info = UserInfoBuilder::new(payload.email(), payload.password())
         .admin(payload.admin())
         .build();
     match app_state
         .users_service()
-        .register(Box::new(users_repo), &user_info)
+        .register(app_state.context(), &user_info)
         .await
     {
         Ok(_) => StatusCode::CREATED.into_response(),
@@ -55,11 +66,10 @@ async fn login(
         }
     }
 
-    let users_repo = app_state.users_repo().clone();
     let user_info = UserInfoBuilder::new(payload.email(), payload.password()).build();
     match app_state
         .users_service()
-        .login(Box::new(users_repo), &user_info)
+        .login(app_state.context(), &user_info)
         .await
     {
         Ok(token) => {
@@ -70,6 +80,31 @@ async fn login(
     }
 }
 
+#[derive(Debug, Default, Deserialize)]
+struct StatsParams {
+    days: Option<u32>,
+}
+
+async fn stats(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Query(params): Query<StatsParams>,
+) -> impl IntoResponse {
+    match app_state
+        .users_service()
+        .stats(
+            app_state.context(),
+            user.id(),
+            params.days.unwrap_or(DEFAULT_STATS_DAYS),
+            &Utc::now().date_naive(),
+        )
+        .await
+    {
+        Ok(stats) => Json(stats).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -79,13 +114,9 @@ mod tests {
     use serde_json::json;
 
     use crate::{
-        repository::{MockLinks as MockLinksRepo, MockUsers as MockUsersRepo},
-        service::DynUsers as DynUsersService,
-        service::{
-            MockAnalysis as MockAnalysisService, MockLinks as MockLinksService,
-            MockUsers as MockUsersService,
-        },
-        types::Token,
+        app::ContextBuilder,
+        service::MockUsers as MockUsersService,
+        types::{DailyCount, LibraryStats, Token},
     };
 
     use super::*;
@@ -108,7 +139,11 @@ mod tests {
             .times(1)
             .returning(move |_, _| Ok(registered_user.clone()));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_users_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .users_service(Arc::new(mock_users_service))
+                .build(),
+        );
         let response = register(State(app_state), Json(request)).await;
 
         let (parts, body) = response.into_response().into_parts();
@@ -126,7 +161,11 @@ mod tests {
         let mut mock_users_service = MockUsersService::new();
         mock_users_service.expect_register().times(0);
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_users_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .users_service(Arc::new(mock_users_service))
+                .build(),
+        );
         let response = register(State(app_state), Json(request)).await;
 
         let (parts, body) = response.into_response().into_parts();
@@ -152,7 +191,11 @@ mod tests {
             .times(1)
             .returning(|_, _| Err(AppError::Test));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_users_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .users_service(Arc::new(mock_users_service))
+                .build(),
+        );
         let response = register(State(app_state), Json(request)).await;
 
         let (parts, body) = response.into_response().into_parts();
@@ -176,7 +219,11 @@ mod tests {
             .times(1)
             .returning(move |_, _| Ok(token.clone()));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_users_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .users_service(Arc::new(mock_users_service))
+                .build(),
+        );
         let response = login(State(app_state), Json(request)).await;
 
         let (parts, body) = response.into_response().into_parts();
@@ -194,7 +241,11 @@ mod tests {
         let mut mock_users_service = MockUsersService::new();
         mock_users_service.expect_login().times(0);
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_users_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .users_service(Arc::new(mock_users_service))
+                .build(),
+        );
         let response = login(State(app_state), Json(request)).await;
 
         let (parts, body) = response.into_response().into_parts();
@@ -217,7 +268,11 @@ mod tests {
             .times(1)
             .returning(|_, _| Err(AppError::Test));
 
-        let app_state = AppStateBuilder::new(Arc::new(mock_users_service)).build();
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .users_service(Arc::new(mock_users_service))
+                .build(),
+        );
         let response = login(State(app_state), Json(request)).await;
 
         let (parts, body) = response.into_response().into_parts();
@@ -228,23 +283,64 @@ mod tests {
         assert_eq!(body, json!({"error": "test error"}).to_string());
     }
 
-    struct AppStateBuilder {
-        users_service: DynUsersService,
+    #[rstest]
+    #[case(None, DEFAULT_STATS_DAYS)]
+    #[case(Some(7), 7)]
+    #[tokio::test]
+    async fn test_stats(#[case] days: Option<u32>, #[case] expected_days: u32) {
+        let library_stats =
+            LibraryStats::default().with_daily(&[DailyCount::new(Utc::now().date_naive(), 2, 1)]);
+        let retrieved_stats = library_stats.clone();
+
+        let mut mock_users_service = MockUsersService::new();
+        mock_users_service
+            .expect_stats()
+            .withf(move |_, user, days, _| user == "user@test.com" && *days == expected_days)
+            .times(1)
+            .returning(move |_, _, _, _| Ok(retrieved_stats.clone()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .users_service(Arc::new(mock_users_service))
+                .build(),
+        );
+        let response = stats(
+            State(app_state),
+            Claims::new("user@test.com", false, 0, 0),
+            Query(StatsParams { days }),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: LibraryStats = serde_json::from_str(body).unwrap();
+        assert_eq!(body, library_stats);
     }
 
-    impl AppStateBuilder {
-        fn new(users_service: DynUsersService) -> Self {
-            Self { users_service }
-        }
+    #[tokio::test]
+    async fn test_stats_invalid_days() {
+        let mut mock_users_service = MockUsersService::new();
+        mock_users_service
+            .expect_stats()
+            .times(1)
+            .returning(|_, _, _, _| Err(AppError::Validation("stats()".into())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .users_service(Arc::new(mock_users_service))
+                .build(),
+        );
+        let response = stats(
+            State(app_state),
+            Claims::new("user@test.com", false, 0, 0),
+            Query(StatsParams { days: Some(0) }),
+        )
+        .await;
 
-        fn build(self) -> AppState {
-            AppState::new(
-                Arc::new(MockLinksService::new()),
-                self.users_service,
-                Arc::new(MockAnalysisService::new()),
-                Arc::new(MockLinksRepo::new()),
-                Arc::new(MockUsersRepo::new()),
-            )
-        }
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
     }
 }
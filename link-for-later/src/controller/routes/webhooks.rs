@@ -0,0 +1,295 @@
+use axum::{
+    extract::{self, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    routing, Json, Router,
+};
+use chrono::Utc;
+use validator::Validate;
+
+use crate::types::{AppError, AppState, Claims, Webhook, WebhookCreatedResponse, WebhookRequest};
+
+pub fn router(state: AppState) -> Router<AppState> {
+    Router::new()
+        .nest(
+            "/v1",
+            Router::new()
+                .route("/webhooks", routing::get(list))
+                .route("/webhooks", routing::post(post))
+                .route("/webhooks/:id", routing::delete(delete))
+                .route("/webhooks/:id/deliveries", routing::get(deliveries)),
+        )
+        .with_state(state)
+}
+
+async fn list(State(app_state): State<AppState>, user: Claims) -> impl IntoResponse {
+    match app_state
+        .webhooks_service()
+        .search(app_state.context(), user.id())
+        .await
+    {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn post(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Json(payload): extract::Json<WebhookRequest>,
+) -> impl IntoResponse {
+    match payload.validate() {
+        Ok(()) => {}
+        Err(e) => {
+            return AppError::Validation(format!("post_webhook() {e:?}")).into_response();
+        }
+    }
+
+    let webhook = Webhook::new(
+        user.id(),
+        payload.url(),
+        payload.secret(),
+        payload.events(),
+        &Utc::now(),
+    );
+    match app_state
+        .webhooks_service()
+        .create(app_state.context(), &webhook)
+        .await
+    {
+        Ok(webhook) => (
+            StatusCode::CREATED,
+            Json(WebhookCreatedResponse::from(webhook)),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn delete(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match app_state
+        .webhooks_service()
+        .delete(app_state.context(), user.id(), &id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn deliveries(
+    State(app_state): State<AppState>,
+    user: Claims,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    match app_state
+        .webhooks_service()
+        .deliveries(app_state.context(), user.id(), &id)
+        .await
+    {
+        Ok(list) => Json(list).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::{extract::State, http::StatusCode};
+    use http_body_util::BodyExt;
+    use serde_json::json;
+
+    use crate::{
+        app::ContextBuilder, service::MockWebhooks as MockWebhooksService, types::WebhookDelivery,
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_get_webhooks() {
+        let webhook = Webhook::new("user", "http://hook", "secret", &[], &Utc::now()).with_id("1");
+        let retrieved_webhook = webhook.clone();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_search()
+            .withf(|_, owner| owner == "user")
+            .times(1)
+            .returning(move |_, _| Ok(vec![retrieved_webhook.clone()]));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .webhooks_service(Arc::new(mock_webhooks_service))
+                .build(),
+        );
+        let response = list(State(app_state), Claims::new("user", false, 0, 0)).await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: Vec<serde_json::Value> = serde_json::from_str(body).unwrap();
+        assert_eq!(body.len(), 1);
+        assert_eq!(body[0]["id"], webhook.id());
+        assert!(body[0].get("secret").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_post_webhook() {
+        let request: WebhookRequest = serde_json::from_value(json!({
+            "url": "http://hook",
+            "secret": "secret",
+            "events": ["link.created"],
+        }))
+        .unwrap();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_create()
+            .withf(|_, webhook| {
+                webhook.owner() == "user"
+                    && webhook.url() == "http://hook"
+                    && webhook.events() == ["link.created"]
+            })
+            .times(1)
+            .returning(|_, webhook| Ok(webhook.clone().with_id("1")));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .webhooks_service(Arc::new(mock_webhooks_service))
+                .build(),
+        );
+        let response = post(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Json(request),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::CREATED, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["id"], "1");
+        assert_eq!(body["secret"], "secret");
+    }
+
+    #[tokio::test]
+    async fn test_post_webhook_invalid_url() {
+        let request: WebhookRequest =
+            serde_json::from_value(json!({"url": "invalid-url"})).unwrap();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_create().times(0);
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .webhooks_service(Arc::new(mock_webhooks_service))
+                .build(),
+        );
+        let response = post(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Json(request),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::BAD_REQUEST, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_delete_webhook() {
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_delete()
+            .withf(|_, owner, id| owner == "user" && id == "1")
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .webhooks_service(Arc::new(mock_webhooks_service))
+                .build(),
+        );
+        let response = delete(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, _) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NO_CONTENT, parts.status);
+    }
+
+    #[tokio::test]
+    async fn test_delete_webhook_not_found() {
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_delete()
+            .withf(|_, owner, id| owner == "user" && id == "1")
+            .times(1)
+            .returning(|_, _, _| Err(AppError::WebhookNotFound("1".into())));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .webhooks_service(Arc::new(mock_webhooks_service))
+                .build(),
+        );
+        let response = delete(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::NOT_FOUND, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        assert_eq!(body, json!({"error": "webhook not found"}).to_string());
+    }
+
+    #[tokio::test]
+    async fn test_get_webhook_deliveries() {
+        let delivery = WebhookDelivery::new("1", "link.created", "2", 200, 1, "", &Utc::now());
+        let retrieved_delivery = delivery.clone();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_deliveries()
+            .withf(|_, owner, id| owner == "user" && id == "1")
+            .times(1)
+            .returning(move |_, _, _| Ok(vec![retrieved_delivery.clone()]));
+
+        let app_state = AppState::new(
+            ContextBuilder::default()
+                .webhooks_service(Arc::new(mock_webhooks_service))
+                .build(),
+        );
+        let response = deliveries(
+            State(app_state),
+            Claims::new("user", false, 0, 0),
+            Path(String::from("1")),
+        )
+        .await;
+
+        let (parts, body) = response.into_response().into_parts();
+        assert_eq!(StatusCode::OK, parts.status);
+
+        let body = body.collect().await.unwrap().to_bytes();
+        let body = std::str::from_utf8(&body).unwrap();
+        let body: Vec<WebhookDelivery> = serde_json::from_str(body).unwrap();
+        assert_eq!(body, vec![delivery]);
+    }
+}
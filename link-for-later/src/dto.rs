@@ -0,0 +1,325 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::{
+    entity::{Priority, Role, Selector, Webhook},
+    types::{LinkItem, LinkItemBuilder},
+};
+
+/// The fields of a link to change, leaving out any that should stay as
+/// they are.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate)]
+pub struct LinkPatchRequest {
+    #[validate(url)]
+    url: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    word_count: Option<usize>,
+    reading_time: Option<usize>,
+    summary: Option<String>,
+    label: Option<String>,
+}
+
+impl LinkPatchRequest {
+    /// The link as it is after the change.
+    pub fn apply(&self, item: &LinkItem) -> LinkItem {
+        let mut builder = LinkItemBuilder::from(item.clone());
+        if let Some(url) = &self.url {
+            builder = builder.url(url);
+        }
+        if let Some(title) = &self.title {
+            builder = builder.title(title);
+        }
+        if let Some(description) = &self.description {
+            builder = builder.description(description);
+        }
+        if let Some(word_count) = self.word_count {
+            builder = builder.word_count(word_count);
+        }
+        if let Some(reading_time) = self.reading_time {
+            builder = builder.reading_time(reading_time);
+        }
+        if let Some(summary) = &self.summary {
+            builder = builder.summary(summary);
+        }
+        if let Some(label) = &self.label {
+            builder = builder.label(label);
+        }
+        builder.build()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate)]
+pub struct WebhookRequest {
+    #[validate(url)]
+    url: String,
+    #[serde(default)]
+    secret: String,
+    #[serde(default)]
+    events: Vec<String>,
+}
+
+impl WebhookRequest {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    pub fn events(&self) -> &[String] {
+        &self.events
+    }
+}
+
+/// The response to creating a webhook, the only one that has its secret.
+#[derive(Clone, Debug, Serialize)]
+pub struct WebhookCreatedResponse {
+    #[serde(flatten)]
+    webhook: Webhook,
+    secret: String,
+}
+
+impl From<Webhook> for WebhookCreatedResponse {
+    fn from(webhook: Webhook) -> Self {
+        Self {
+            secret: webhook.secret().to_owned(),
+            webhook,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate)]
+pub struct CollectionRequest {
+    #[validate(length(min = 1))]
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    position: i64,
+}
+
+impl CollectionRequest {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub const fn position(&self) -> i64 {
+        self.position
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CollectionLinksRequest {
+    links: Vec<String>,
+}
+
+impl CollectionLinksRequest {
+    pub fn links(&self) -> &[String] {
+        &self.links
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct MemberRequest {
+    role: Role,
+}
+
+impl MemberRequest {
+    pub const fn role(&self) -> Role {
+        self.role
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate)]
+pub struct ShareRequest {
+    kind: String,
+    #[validate(length(min = 1))]
+    id: String,
+    #[serde(default)]
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl ShareRequest {
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub const fn expires_at(&self) -> Option<&DateTime<Utc>> {
+        self.expires_at.as_ref()
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate)]
+pub struct ReminderRequest {
+    #[validate(length(min = 1))]
+    link_id: String,
+    remind_at: DateTime<Utc>,
+}
+
+impl ReminderRequest {
+    pub fn link_id(&self) -> &str {
+        &self.link_id
+    }
+
+    pub const fn remind_at(&self) -> &DateTime<Utc> {
+        &self.remind_at
+    }
+}
+
+/// The hour of the day, in UTC, at which the daily digest goes out.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate)]
+pub struct DigestRequest {
+    #[validate(range(max = 23))]
+    hour: u32,
+}
+
+impl DigestRequest {
+    pub const fn hour(&self) -> u32 {
+        self.hour
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate)]
+pub struct SnoozeRequest {
+    until: DateTime<Utc>,
+}
+
+impl SnoozeRequest {
+    pub const fn until(&self) -> &DateTime<Utc> {
+        &self.until
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate)]
+pub struct DetailsRequest {
+    #[serde(default)]
+    priority: Priority,
+    #[serde(default)]
+    #[validate(range(min = 1, max = 5))]
+    rating: Option<u8>,
+    #[serde(default)]
+    #[validate(length(max = 10000))]
+    notes: String,
+}
+
+impl DetailsRequest {
+    pub const fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    pub const fn rating(&self) -> Option<u8> {
+        self.rating
+    }
+
+    pub fn notes(&self) -> &str {
+        &self.notes
+    }
+}
+
+/// Narrows down a list of links to those with the given priority and at
+/// least the given rating, and orders it by `sort`, best first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DetailsFilter {
+    priority: Option<Priority>,
+    min_rating: Option<u8>,
+    sort: Option<String>,
+}
+
+impl DetailsFilter {
+    pub fn new(priority: Option<Priority>, min_rating: Option<u8>, sort: Option<&str>) -> Self {
+        Self {
+            priority,
+            min_rating,
+            sort: sort.map(ToOwned::to_owned),
+        }
+    }
+
+    pub const fn priority(&self) -> Option<Priority> {
+        self.priority
+    }
+
+    pub const fn min_rating(&self) -> Option<u8> {
+        self.min_rating
+    }
+
+    pub fn sort(&self) -> Option<&str> {
+        self.sort.as_deref()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.priority.is_none() && self.min_rating.is_none() && self.sort.is_none()
+    }
+}
+
+/// How the next link to read is picked among the unread ones: the oldest
+/// saved, any of them, the quickest to read, or any of them with the higher
+/// priority links more likely.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NextStrategy {
+    #[default]
+    Oldest,
+    Random,
+    Shortest,
+    Priority,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate)]
+pub struct HighlightRequest {
+    #[validate(length(min = 1))]
+    quote: String,
+    #[serde(default)]
+    note: String,
+    #[serde(default)]
+    selectors: Vec<Selector>,
+}
+
+impl HighlightRequest {
+    pub fn quote(&self) -> &str {
+        &self.quote
+    }
+
+    pub fn note(&self) -> &str {
+        &self.note
+    }
+
+    pub fn selectors(&self) -> &[Selector] {
+        &self.selectors
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, Validate)]
+pub struct ProgressRequest {
+    #[validate(range(max = 100))]
+    percentage: u8,
+    #[serde(default)]
+    position: u32,
+    #[serde(default)]
+    time_spent: u32,
+}
+
+impl ProgressRequest {
+    pub const fn percentage(&self) -> u8 {
+        self.percentage
+    }
+
+    pub const fn position(&self) -> u32 {
+        self.position
+    }
+
+    /// Seconds spent reading since the previous report.
+    pub const fn time_spent(&self) -> u32 {
+        self.time_spent
+    }
+}
@@ -0,0 +1,1310 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use link_for_later_types::entity::LinkItem;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Snapshot {
+    id: String,
+    url: String,
+    content_type: String,
+    content: String,
+    captured_at: DateTime<Utc>,
+}
+
+impl Snapshot {
+    pub fn new(
+        id: &str,
+        url: &str,
+        content_type: &str,
+        content: &str,
+        captured_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: id.to_owned(),
+            url: url.to_owned(),
+            content_type: content_type.to_owned(),
+            content: content.to_owned(),
+            captured_at: *captured_at,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn content_type(&self) -> &str {
+        &self.content_type
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    pub const fn captured_at(&self) -> &DateTime<Utc> {
+        &self.captured_at
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LinkHealth {
+    id: String,
+    url: String,
+    status_code: u16,
+    redirect_url: String,
+    checked_at: DateTime<Utc>,
+}
+
+impl LinkHealth {
+    pub fn new(
+        id: &str,
+        url: &str,
+        status_code: u16,
+        redirect_url: &str,
+        checked_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: id.to_owned(),
+            url: url.to_owned(),
+            status_code,
+            redirect_url: redirect_url.to_owned(),
+            checked_at: *checked_at,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Status code of the last check, `0` if the link could not be reached at all.
+    pub const fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    pub fn redirect_url(&self) -> &str {
+        &self.redirect_url
+    }
+
+    pub const fn checked_at(&self) -> &DateTime<Utc> {
+        &self.checked_at
+    }
+
+    pub const fn is_broken(&self) -> bool {
+        self.status_code == 0 || self.status_code >= 400
+    }
+}
+
+/// A webhook as it is returned to its owner. The secret is never serialized
+/// here: it is only given back once, when the webhook is created.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Webhook {
+    id: String,
+    owner: String,
+    url: String,
+    #[serde(skip_serializing, default)]
+    secret: String,
+    events: Vec<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    pub fn new(
+        owner: &str,
+        url: &str,
+        secret: &str,
+        events: &[String],
+        created_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: String::new(),
+            owner: owner.to_owned(),
+            url: url.to_owned(),
+            secret: secret.to_owned(),
+            events: events.to_vec(),
+            created_at: *created_at,
+        }
+    }
+
+    #[must_use]
+    pub fn with_id(mut self, id: &str) -> Self {
+        id.clone_into(&mut self.id);
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    pub fn events(&self) -> &[String] {
+        &self.events
+    }
+
+    pub const fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    /// A webhook without any explicit events is subscribed to all of them.
+    pub fn is_subscribed(&self, event: &str) -> bool {
+        self.events.is_empty() || self.events.iter().any(|e| e == event)
+    }
+}
+
+/// A webhook as it is kept by the repositories that store documents, secret
+/// included.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StoredWebhook {
+    id: String,
+    owner: String,
+    url: String,
+    secret: String,
+    events: Vec<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl StoredWebhook {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+}
+
+impl From<&Webhook> for StoredWebhook {
+    fn from(webhook: &Webhook) -> Self {
+        Self {
+            id: webhook.id.clone(),
+            owner: webhook.owner.clone(),
+            url: webhook.url.clone(),
+            secret: webhook.secret.clone(),
+            events: webhook.events.clone(),
+            created_at: webhook.created_at,
+        }
+    }
+}
+
+impl From<StoredWebhook> for Webhook {
+    fn from(webhook: StoredWebhook) -> Self {
+        Self {
+            id: webhook.id,
+            owner: webhook.owner,
+            url: webhook.url,
+            secret: webhook.secret,
+            events: webhook.events,
+            created_at: webhook.created_at,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WebhookDelivery {
+    webhook_id: String,
+    event: String,
+    link_id: String,
+    status_code: u16,
+    attempts: u32,
+    error: String,
+    delivered_at: DateTime<Utc>,
+}
+
+impl WebhookDelivery {
+    pub fn new(
+        webhook_id: &str,
+        event: &str,
+        link_id: &str,
+        status_code: u16,
+        attempts: u32,
+        error: &str,
+        delivered_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            webhook_id: webhook_id.to_owned(),
+            event: event.to_owned(),
+            link_id: link_id.to_owned(),
+            status_code,
+            attempts,
+            error: error.to_owned(),
+            delivered_at: *delivered_at,
+        }
+    }
+
+    pub fn webhook_id(&self) -> &str {
+        &self.webhook_id
+    }
+
+    pub fn event(&self) -> &str {
+        &self.event
+    }
+
+    pub fn link_id(&self) -> &str {
+        &self.link_id
+    }
+
+    pub const fn status_code(&self) -> u16 {
+        self.status_code
+    }
+
+    pub const fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn error(&self) -> &str {
+        &self.error
+    }
+
+    pub const fn delivered_at(&self) -> &DateTime<Utc> {
+        &self.delivered_at
+    }
+
+    pub const fn is_success(&self) -> bool {
+        self.status_code >= 200 && self.status_code < 300
+    }
+}
+
+/// A deleted link, kept in the trash until it is restored or purged.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TrashedLink {
+    #[serde(flatten)]
+    link: LinkItem,
+    deleted_at: DateTime<Utc>,
+}
+
+impl TrashedLink {
+    pub fn new(link: &LinkItem, deleted_at: &DateTime<Utc>) -> Self {
+        Self {
+            link: link.clone(),
+            deleted_at: *deleted_at,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        self.link.id()
+    }
+
+    pub fn owner(&self) -> &str {
+        self.link.owner()
+    }
+
+    pub const fn link(&self) -> &LinkItem {
+        &self.link
+    }
+
+    pub const fn deleted_at(&self) -> &DateTime<Utc> {
+        &self.deleted_at
+    }
+}
+
+/// A link along with its version, which goes up by one with every write to
+/// the link and serves as its entity tag.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VersionedLink {
+    link: LinkItem,
+    version: u64,
+}
+
+impl VersionedLink {
+    pub fn new(link: &LinkItem, version: u64) -> Self {
+        Self {
+            link: link.clone(),
+            version,
+        }
+    }
+
+    pub const fn link(&self) -> &LinkItem {
+        &self.link
+    }
+
+    pub const fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn into_link(self) -> LinkItem {
+        self.link
+    }
+}
+
+/// A single field of a link that was changed from `old` to `new`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FieldChange {
+    field: String,
+    old: String,
+    new: String,
+}
+
+impl FieldChange {
+    pub fn new(field: &str, old: &str, new: &str) -> Self {
+        Self {
+            field: field.to_owned(),
+            old: old.to_owned(),
+            new: new.to_owned(),
+        }
+    }
+
+    pub fn field(&self) -> &str {
+        &self.field
+    }
+
+    pub fn old(&self) -> &str {
+        &self.old
+    }
+
+    pub fn new_value(&self) -> &str {
+        &self.new
+    }
+}
+
+/// An entry in the edit history of a link, recording who changed it and
+/// keeping the link as it was before the change so it can be reverted to.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Revision {
+    id: String,
+    link_id: String,
+    actor: String,
+    action: String,
+    changes: Vec<FieldChange>,
+    link: LinkItem,
+    created_at: DateTime<Utc>,
+}
+
+impl Revision {
+    pub fn new(
+        link: &LinkItem,
+        actor: &str,
+        action: &str,
+        changes: &[FieldChange],
+        created_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: String::new(),
+            link_id: link.id().to_owned(),
+            actor: actor.to_owned(),
+            action: action.to_owned(),
+            changes: changes.to_vec(),
+            link: link.clone(),
+            created_at: *created_at,
+        }
+    }
+
+    #[must_use]
+    pub fn with_id(mut self, id: &str) -> Self {
+        id.clone_into(&mut self.id);
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn link_id(&self) -> &str {
+        &self.link_id
+    }
+
+    pub fn actor(&self) -> &str {
+        &self.actor
+    }
+
+    pub fn action(&self) -> &str {
+        &self.action
+    }
+
+    pub fn changes(&self) -> &[FieldChange] {
+        &self.changes
+    }
+
+    pub const fn link(&self) -> &LinkItem {
+        &self.link
+    }
+
+    pub const fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+}
+
+/// What a member of a shared collection may do, each role allowing all that
+/// the roles before it allow.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Sees the collection and its links.
+    #[default]
+    Viewer,
+    /// Also adds, removes, reorders and edits the links.
+    Editor,
+    /// Also renames the collection, deletes its links and manages its members.
+    Admin,
+}
+
+/// A user a collection has been shared with.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Member {
+    user: String,
+    role: Role,
+}
+
+impl Member {
+    pub fn new(user: &str, role: Role) -> Self {
+        Self {
+            user: user.to_owned(),
+            role,
+        }
+    }
+
+    pub fn user(&self) -> &str {
+        &self.user
+    }
+
+    pub const fn role(&self) -> Role {
+        self.role
+    }
+}
+
+/// A named group of links owned by a user. A link can be in any number of
+/// collections, and `links` keeps the ids of its links in the order the
+/// owner arranged them.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Collection {
+    id: String,
+    owner: String,
+    name: String,
+    description: String,
+    position: i64,
+    links: Vec<String>,
+    #[serde(default)]
+    members: Vec<Member>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl Collection {
+    pub fn new(
+        owner: &str,
+        name: &str,
+        description: &str,
+        position: i64,
+        created_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: String::new(),
+            owner: owner.to_owned(),
+            name: name.to_owned(),
+            description: description.to_owned(),
+            position,
+            links: Vec::new(),
+            members: Vec::new(),
+            created_at: *created_at,
+            updated_at: *created_at,
+        }
+    }
+
+    #[must_use]
+    pub fn with_id(mut self, id: &str) -> Self {
+        id.clone_into(&mut self.id);
+        self
+    }
+
+    #[must_use]
+    pub fn with_links(mut self, links: &[String]) -> Self {
+        self.links = links.to_vec();
+        self
+    }
+
+    #[must_use]
+    pub fn with_members(mut self, members: &[Member]) -> Self {
+        self.members = members.to_vec();
+        self
+    }
+
+    #[must_use]
+    pub const fn with_updated_at(mut self, updated_at: &DateTime<Utc>) -> Self {
+        self.updated_at = *updated_at;
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub const fn position(&self) -> i64 {
+        self.position
+    }
+
+    pub fn links(&self) -> &[String] {
+        &self.links
+    }
+
+    pub fn members(&self) -> &[Member] {
+        &self.members
+    }
+
+    /// The role `user` has been given on the collection, if it is shared with them.
+    pub fn role(&self, user: &str) -> Option<Role> {
+        self.members
+            .iter()
+            .find(|member| member.user() == user)
+            .map(Member::role)
+    }
+
+    pub const fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub const fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+/// A public, read-only view of a link, a collection or the unread links of
+/// a user, reached with an unguessable token instead of an account. Deleting
+/// the share revokes it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Share {
+    id: String,
+    owner: String,
+    kind: String,
+    target: String,
+    token: String,
+    expires_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl Share {
+    pub fn new(
+        owner: &str,
+        kind: &str,
+        target: &str,
+        expires_at: Option<&DateTime<Utc>>,
+        created_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: String::new(),
+            owner: owner.to_owned(),
+            kind: kind.to_owned(),
+            target: target.to_owned(),
+            token: String::new(),
+            expires_at: expires_at.copied(),
+            created_at: *created_at,
+        }
+    }
+
+    #[must_use]
+    pub fn with_id(mut self, id: &str) -> Self {
+        id.clone_into(&mut self.id);
+        self
+    }
+
+    #[must_use]
+    pub fn with_token(mut self, token: &str) -> Self {
+        token.clone_into(&mut self.token);
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// The id of the shared link or collection, or the owner for a feed.
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    pub const fn expires_at(&self) -> Option<&DateTime<Utc>> {
+        self.expires_at.as_ref()
+    }
+
+    pub const fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn is_expired(&self, now: &DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= *now)
+    }
+}
+
+/// What a share shows of a link, leaving out its owner and labels.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PublicLink {
+    url: String,
+    title: String,
+    description: String,
+    word_count: usize,
+    reading_time: usize,
+    summary: String,
+}
+
+impl From<&LinkItem> for PublicLink {
+    fn from(item: &LinkItem) -> Self {
+        Self {
+            url: item.url().to_owned(),
+            title: item.title().to_owned(),
+            description: item.description().to_owned(),
+            word_count: item.word_count(),
+            reading_time: item.reading_time(),
+            summary: item.summary().to_owned(),
+        }
+    }
+}
+
+/// What a share shows of a collection: its name and its links in order,
+/// leaving out its owner and members.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PublicCollection {
+    name: String,
+    description: String,
+    links: Vec<PublicLink>,
+}
+
+impl PublicCollection {
+    pub fn new(collection: &Collection, links: &[LinkItem]) -> Self {
+        Self {
+            name: collection.name().to_owned(),
+            description: collection.description().to_owned(),
+            links: links.iter().map(PublicLink::from).collect(),
+        }
+    }
+
+    pub fn links(&self) -> &[PublicLink] {
+        &self.links
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SharedView {
+    Link(PublicLink),
+    Collection(PublicCollection),
+    Links(Vec<PublicLink>),
+}
+
+/// The links behind a share as a feed, newest first. It is only ever
+/// rendered through the fields `PublicLink` also exposes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Feed {
+    id: String,
+    title: String,
+    updated: DateTime<Utc>,
+    entries: Vec<LinkItem>,
+}
+
+impl Feed {
+    pub fn new(id: &str, title: &str, updated: &DateTime<Utc>, entries: &[LinkItem]) -> Self {
+        Self {
+            id: id.to_owned(),
+            title: title.to_owned(),
+            updated: *updated,
+            entries: entries.to_vec(),
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub const fn updated(&self) -> &DateTime<Utc> {
+        &self.updated
+    }
+
+    pub fn entries(&self) -> &[LinkItem] {
+        &self.entries
+    }
+}
+
+/// A nudge to read a link once `remind_at` comes, or for a digest, a daily
+/// round-up of the unread links of its owner that moves on to the next day
+/// every time it goes out.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Reminder {
+    id: String,
+    owner: String,
+    kind: String,
+    link_id: String,
+    remind_at: DateTime<Utc>,
+    notified_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl Reminder {
+    pub fn new(
+        owner: &str,
+        kind: &str,
+        link_id: &str,
+        remind_at: &DateTime<Utc>,
+        created_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: String::new(),
+            owner: owner.to_owned(),
+            kind: kind.to_owned(),
+            link_id: link_id.to_owned(),
+            remind_at: *remind_at,
+            notified_at: None,
+            created_at: *created_at,
+        }
+    }
+
+    #[must_use]
+    pub fn with_id(mut self, id: &str) -> Self {
+        id.clone_into(&mut self.id);
+        self
+    }
+
+    /// Moves the reminder to `remind_at`, to go out again then.
+    #[must_use]
+    pub const fn with_remind_at(mut self, remind_at: &DateTime<Utc>) -> Self {
+        self.remind_at = *remind_at;
+        self.notified_at = None;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_notified_at(mut self, notified_at: &DateTime<Utc>) -> Self {
+        self.notified_at = Some(*notified_at);
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// The id of the link to read, empty for a digest.
+    pub fn link_id(&self) -> &str {
+        &self.link_id
+    }
+
+    pub const fn remind_at(&self) -> &DateTime<Utc> {
+        &self.remind_at
+    }
+
+    pub const fn notified_at(&self) -> Option<&DateTime<Utc>> {
+        self.notified_at.as_ref()
+    }
+
+    pub const fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub fn is_due(&self, now: &DateTime<Utc>) -> bool {
+        self.notified_at.is_none() && self.remind_at <= *now
+    }
+}
+
+/// How soon the owner of a link means to get to it.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+impl Priority {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Normal => "normal",
+            Self::High => "high",
+        }
+    }
+}
+
+/// What the owner of a link keeps about it next to the link itself. A link
+/// without any is at normal priority, unrated and without notes.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LinkDetails {
+    id: String,
+    owner: String,
+    priority: Priority,
+    rating: Option<u8>,
+    notes: String,
+    updated_at: DateTime<Utc>,
+}
+
+impl LinkDetails {
+    pub fn new(
+        id: &str,
+        owner: &str,
+        priority: Priority,
+        rating: Option<u8>,
+        notes: &str,
+        updated_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: id.to_owned(),
+            owner: owner.to_owned(),
+            priority,
+            rating,
+            notes: notes.to_owned(),
+            updated_at: *updated_at,
+        }
+    }
+
+    /// The id of the link the details are about.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub const fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// From 1 to 5, given once the link has been read.
+    pub const fn rating(&self) -> Option<u8> {
+        self.rating
+    }
+
+    /// Free-form notes, in Markdown.
+    pub fn notes(&self) -> &str {
+        &self.notes
+    }
+
+    pub const fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+/// Where a highlight sits on its page, as the selectors of the W3C Web
+/// Annotation model.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum Selector {
+    /// The highlighted text with some of the text around it, to find the
+    /// passage again after the page changed.
+    TextQuoteSelector {
+        exact: String,
+        #[serde(default)]
+        prefix: String,
+        #[serde(default)]
+        suffix: String,
+    },
+    /// The offsets of the first highlighted character and of the one after
+    /// the last in the text of the page.
+    TextPositionSelector { start: u32, end: u32 },
+}
+
+/// A passage of a link kept by its owner, with an optional note.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Highlight {
+    id: String,
+    link_id: String,
+    owner: String,
+    quote: String,
+    note: String,
+    selectors: Vec<Selector>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl Highlight {
+    pub fn new(
+        link_id: &str,
+        owner: &str,
+        quote: &str,
+        note: &str,
+        selectors: &[Selector],
+        created_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: String::new(),
+            link_id: link_id.to_owned(),
+            owner: owner.to_owned(),
+            quote: quote.to_owned(),
+            note: note.to_owned(),
+            selectors: selectors.to_vec(),
+            created_at: *created_at,
+            updated_at: *created_at,
+        }
+    }
+
+    #[must_use]
+    pub fn with_id(mut self, id: &str) -> Self {
+        id.clone_into(&mut self.id);
+        self
+    }
+
+    #[must_use]
+    pub const fn with_updated_at(mut self, updated_at: &DateTime<Utc>) -> Self {
+        self.updated_at = *updated_at;
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn link_id(&self) -> &str {
+        &self.link_id
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    pub fn quote(&self) -> &str {
+        &self.quote
+    }
+
+    /// Free-form note on the passage, empty when there is none.
+    pub fn note(&self) -> &str {
+        &self.note
+    }
+
+    pub fn selectors(&self) -> &[Selector] {
+        &self.selectors
+    }
+
+    pub const fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+
+    pub const fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+/// How far the reader of a link got through it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReadingProgress {
+    id: String,
+    owner: String,
+    percentage: u8,
+    position: u32,
+    time_spent: u32,
+    finished_at: Option<DateTime<Utc>>,
+    updated_at: DateTime<Utc>,
+}
+
+impl ReadingProgress {
+    pub fn new(
+        id: &str,
+        owner: &str,
+        percentage: u8,
+        position: u32,
+        time_spent: u32,
+        updated_at: &DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: id.to_owned(),
+            owner: owner.to_owned(),
+            percentage,
+            position,
+            time_spent,
+            finished_at: None,
+            updated_at: *updated_at,
+        }
+    }
+
+    #[must_use]
+    pub const fn with_finished_at(mut self, finished_at: &DateTime<Utc>) -> Self {
+        self.finished_at = Some(*finished_at);
+        self
+    }
+
+    /// The id of the link the progress is about.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// From 0 to 100.
+    pub const fn percentage(&self) -> u8 {
+        self.percentage
+    }
+
+    /// Scroll position on the page, as reported by the client.
+    pub const fn position(&self) -> u32 {
+        self.position
+    }
+
+    /// Seconds spent reading the link in total.
+    pub const fn time_spent(&self) -> u32 {
+        self.time_spent
+    }
+
+    /// When the link was first read to the end.
+    pub const fn finished_at(&self) -> Option<&DateTime<Utc>> {
+        self.finished_at.as_ref()
+    }
+
+    pub const fn updated_at(&self) -> &DateTime<Utc> {
+        &self.updated_at
+    }
+}
+
+/// The number of links finished in the week starting on `week`, a Monday.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WeeklyReads {
+    week: NaiveDate,
+    count: usize,
+}
+
+impl WeeklyReads {
+    pub const fn new(week: NaiveDate, count: usize) -> Self {
+        Self { week, count }
+    }
+
+    pub const fn week(&self) -> NaiveDate {
+        self.week
+    }
+
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// What the reading progress of a user adds up to.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReadingStats {
+    read_per_week: Vec<WeeklyReads>,
+    average_reading_time: u32,
+}
+
+impl ReadingStats {
+    pub fn new(read_per_week: &[WeeklyReads], average_reading_time: u32) -> Self {
+        Self {
+            read_per_week: read_per_week.to_vec(),
+            average_reading_time,
+        }
+    }
+
+    /// Oldest week first, including the weeks without any.
+    pub fn read_per_week(&self) -> &[WeeklyReads] {
+        &self.read_per_week
+    }
+
+    /// Seconds spent on a finished link, on average.
+    pub const fn average_reading_time(&self) -> u32 {
+        self.average_reading_time
+    }
+}
+
+/// How many links of a user are left to read and how many have been read.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LinkCounts {
+    unread: usize,
+    read: usize,
+}
+
+impl LinkCounts {
+    pub const fn new(unread: usize, read: usize) -> Self {
+        Self { unread, read }
+    }
+
+    pub const fn unread(&self) -> usize {
+        self.unread
+    }
+
+    pub const fn read(&self) -> usize {
+        self.read
+    }
+}
+
+/// The number of links saved from `domain`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DomainCount {
+    domain: String,
+    count: usize,
+}
+
+impl DomainCount {
+    pub fn new(domain: &str, count: usize) -> Self {
+        Self {
+            domain: domain.to_owned(),
+            count,
+        }
+    }
+
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    pub const fn count(&self) -> usize {
+        self.count
+    }
+}
+
+/// The number of links saved and read on `day`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DailyCount {
+    day: NaiveDate,
+    saved: usize,
+    read: usize,
+}
+
+impl DailyCount {
+    pub const fn new(day: NaiveDate, saved: usize, read: usize) -> Self {
+        Self { day, saved, read }
+    }
+
+    pub const fn day(&self) -> NaiveDate {
+        self.day
+    }
+
+    pub const fn saved(&self) -> usize {
+        self.saved
+    }
+
+    pub const fn read(&self) -> usize {
+        self.read
+    }
+}
+
+/// How long the unread links have been waiting: saved in the last week, the
+/// last 30 days, the last 90 days or before that.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BacklogAge {
+    week: usize,
+    month: usize,
+    quarter: usize,
+    older: usize,
+    oldest: Option<NaiveDate>,
+}
+
+impl BacklogAge {
+    pub const fn new(
+        week: usize,
+        month: usize,
+        quarter: usize,
+        older: usize,
+        oldest: Option<NaiveDate>,
+    ) -> Self {
+        Self {
+            week,
+            month,
+            quarter,
+            older,
+            oldest,
+        }
+    }
+
+    pub const fn week(&self) -> usize {
+        self.week
+    }
+
+    pub const fn month(&self) -> usize {
+        self.month
+    }
+
+    pub const fn quarter(&self) -> usize {
+        self.quarter
+    }
+
+    pub const fn older(&self) -> usize {
+        self.older
+    }
+
+    /// The day the oldest unread link was saved.
+    pub const fn oldest(&self) -> Option<NaiveDate> {
+        self.oldest
+    }
+}
+
+/// What the library of a user adds up to.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LibraryStats {
+    counts: LinkCounts,
+    domains: Vec<DomainCount>,
+    daily: Vec<DailyCount>,
+    backlog: BacklogAge,
+}
+
+impl LibraryStats {
+    pub fn new(
+        counts: LinkCounts,
+        domains: &[DomainCount],
+        daily: &[DailyCount],
+        backlog: BacklogAge,
+    ) -> Self {
+        Self {
+            counts,
+            domains: domains.to_vec(),
+            daily: daily.to_vec(),
+            backlog,
+        }
+    }
+
+    pub fn with_daily(mut self, daily: &[DailyCount]) -> Self {
+        self.daily = daily.to_vec();
+        self
+    }
+
+    pub const fn counts(&self) -> &LinkCounts {
+        &self.counts
+    }
+
+    /// Most saved first.
+    pub fn domains(&self) -> &[DomainCount] {
+        &self.domains
+    }
+
+    /// Oldest day first.
+    pub fn daily(&self) -> &[DailyCount] {
+        &self.daily
+    }
+
+    pub const fn backlog(&self) -> &BacklogAge {
+        &self.backlog
+    }
+}
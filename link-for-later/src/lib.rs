@@ -34,6 +34,9 @@ pub mod app;
 
 mod auth;
 mod controller;
+mod dto;
+mod entity;
+mod outbound;
 mod repository;
 mod service;
 mod types;
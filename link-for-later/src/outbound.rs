@@ -0,0 +1,490 @@
+//! Policy for every outbound HTTP request made by the service.
+//!
+//! Urls saved by users are fetched from inside our own network, so hosts are
+//! resolved up front and requests to private, loopback or link-local
+//! addresses are refused. The checked addresses are pinned for the actual
+//! connection and redirects are followed one hop at a time through the same
+//! checks, so neither DNS rebinding nor a redirect can sneak past the policy.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use reqwest::{Method, Response, StatusCode, Url};
+
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+const MAX_REDIRECTS: usize = 10;
+const MAX_RESPONSE_SIZE: usize = 5 * 1024 * 1024;
+const MAX_CACHED_CLIENTS: usize = 64;
+
+type Result<T> = std::result::Result<T, String>;
+
+#[derive(Clone, Debug)]
+pub struct Policy {
+    timeout: Duration,
+    max_redirects: usize,
+    max_response_size: usize,
+    allow_private_networks: bool,
+}
+
+impl Policy {
+    #[must_use]
+    pub const fn max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    /// Only meant for endpoints configured by the operator (and for tests),
+    /// never for urls that come from users.
+    #[must_use]
+    pub const fn allow_private_networks(mut self) -> Self {
+        self.allow_private_networks = true;
+        self
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(REQUEST_TIMEOUT_SECS),
+            max_redirects: MAX_REDIRECTS,
+            max_response_size: MAX_RESPONSE_SIZE,
+            allow_private_networks: false,
+        }
+    }
+}
+
+/// Clients pinned to the checked addresses of a host, reused for as long as
+/// the host keeps resolving to the same addresses.
+type Clients = HashMap<String, (Vec<SocketAddr>, reqwest::Client)>;
+
+#[derive(Clone, Debug, Default)]
+pub struct Client {
+    policy: Policy,
+    clients: Arc<Mutex<Clients>>,
+}
+
+impl Client {
+    pub fn new(policy: Policy) -> Self {
+        Self {
+            policy,
+            clients: Arc::default(),
+        }
+    }
+
+    pub async fn get(&self, url: &str) -> Result<Response> {
+        self.send(Method::GET, url, &[], None).await
+    }
+
+    pub async fn head(&self, url: &str) -> Result<Response> {
+        self.send(Method::HEAD, url, &[], None).await
+    }
+
+    pub async fn post(
+        &self,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: String,
+    ) -> Result<Response> {
+        self.send(Method::POST, url, headers, Some(body)).await
+    }
+
+    /// Reads the response body, giving up as soon as it grows past the
+    /// maximum response size instead of buffering all of it.
+    pub async fn bytes(&self, mut response: Response) -> Result<Vec<u8>> {
+        if response
+            .content_length()
+            .is_some_and(|length| length > self.policy.max_response_size as u64)
+        {
+            return Err(String::from("response exceeds maximum size"));
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("response.chunk() {e:?}"))?
+        {
+            if body.len() + chunk.len() > self.policy.max_response_size {
+                return Err(String::from("response exceeds maximum size"));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    /// Sends the request and follows its redirects, all within the policy's
+    /// timeout.
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Option<String>,
+    ) -> Result<Response> {
+        tokio::time::timeout(self.policy.timeout, self.follow(method, url, headers, body))
+            .await
+            .map_err(|_| String::from("request timed out"))?
+    }
+
+    async fn follow(
+        &self,
+        method: Method,
+        url: &str,
+        headers: &[(&str, &str)],
+        body: Option<String>,
+    ) -> Result<Response> {
+        let mut method = method;
+        let mut body = body;
+        let mut headers = headers;
+        let mut url = Url::parse(url).map_err(|e| format!("Url::parse() {e:?}"))?;
+        let mut redirects = 0;
+        loop {
+            let client = self.client_for(&url).await?;
+            let mut request = client.request(method.clone(), url.clone());
+            for (name, value) in headers {
+                request = request.header(*name, *value);
+            }
+            if let Some(ref body) = body {
+                request = request.body(body.clone());
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| format!("client.send() {e:?}"))?;
+
+            if !response.status().is_redirection() {
+                return Ok(response);
+            }
+            let Some(location) = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+            else {
+                return Ok(response);
+            };
+            if redirects >= self.policy.max_redirects {
+                return Err(String::from("too many redirects"));
+            }
+            redirects += 1;
+
+            let next = url
+                .join(location)
+                .map_err(|e| format!("url.join() {e:?}"))?;
+            // the caller's headers, such as webhook signatures, are meant for
+            // the server it asked for and are not passed on to another one
+            if next.origin() != url.origin() {
+                headers = &[];
+            }
+            url = next;
+            if !matches!(
+                response.status(),
+                StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT
+            ) && method != Method::HEAD
+            {
+                method = Method::GET;
+                body = None;
+            }
+        }
+    }
+
+    /// Returns a client that can only connect to the checked addresses of the
+    /// url's host.
+    async fn client_for(&self, url: &Url) -> Result<reqwest::Client> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("unsupported scheme {}", url.scheme()));
+        }
+        let Some(host) = url.host_str() else {
+            return Err(String::from("missing host"));
+        };
+
+        let addrs = match host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<IpAddr>()
+        {
+            Ok(ip) if !self.is_allowed(ip) => {
+                return Err(format!("blocked address {ip}"));
+            }
+            Ok(_) => Vec::new(),
+            Err(_) => {
+                let port = url.port_or_known_default().unwrap_or_default();
+                let mut addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+                    .await
+                    .map_err(|e| format!("lookup_host() {e:?}"))?
+                    .collect();
+                if addrs.is_empty() {
+                    return Err(format!("no addresses found for {host}"));
+                }
+                if let Some(addr) = addrs.iter().find(|addr| !self.is_allowed(addr.ip())) {
+                    return Err(format!("{host} resolves to blocked address {}", addr.ip()));
+                }
+                addrs.sort_unstable();
+                addrs
+            }
+        };
+
+        let mut clients = self
+            .clients
+            .lock()
+            .map_err(|e| format!("clients.lock() {e:?}"))?;
+        if let Some((_, client)) = clients.get(host).filter(|(cached, _)| *cached == addrs) {
+            return Ok(client.clone());
+        }
+
+        let builder = reqwest::Client::builder()
+            .timeout(self.policy.timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .no_proxy();
+        let builder = if addrs.is_empty() {
+            builder
+        } else {
+            builder.resolve_to_addrs(host, &addrs)
+        };
+        let client = builder
+            .build()
+            .map_err(|e| format!("client.build() {e:?}"))?;
+
+        if clients.len() >= MAX_CACHED_CLIENTS {
+            clients.clear();
+        }
+        clients.insert(host.to_owned(), (addrs, client.clone()));
+        drop(clients);
+        Ok(client)
+    }
+
+    fn is_allowed(&self, ip: IpAddr) -> bool {
+        self.policy.allow_private_networks || is_public(ip)
+    }
+}
+
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => ip
+            .to_ipv4_mapped()
+            .map_or_else(|| is_public_v6(ip), is_public_v4),
+    }
+}
+
+const fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [first, second, third, _] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        // "this network", carrier-grade NAT, IETF protocol assignments and
+        // benchmarking ranges
+        || first == 0
+        || (first == 100 && (second & 0xc0) == 64)
+        || (first == 192 && second == 0 && third == 0)
+        || (first == 198 && (second & 0xfe) == 18)
+        || first >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let segments = ip.segments();
+    let embedded_v4 = |high: u16, low: u16| {
+        let [a, b] = high.to_be_bytes();
+        let [c, d] = low.to_be_bytes();
+        Ipv4Addr::new(a, b, c, d)
+    };
+    // NAT64, 6to4 and IPv4-compatible addresses reach the embedded IPv4 address
+    if (segments[..6] == [0x64, 0xff9b, 0, 0, 0, 0] || segments[..6] == [0; 6])
+        && !ip.is_loopback()
+        && !ip.is_unspecified()
+    {
+        return is_public_v4(embedded_v4(segments[6], segments[7]));
+    }
+    if segments[0] == 0x2002 {
+        return is_public_v4(embedded_v4(segments[1], segments[2]));
+    }
+    // Teredo tunnels to an address that can't be checked here
+    if segments[..2] == [0x2001, 0] {
+        return false;
+    }
+
+    let first = segments[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // unique local and link-local ranges
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_public() {
+        for ip in [
+            "93.184.216.34",
+            "2606:2800:220:1:248:1893:25c8:1946",
+            "64:ff9b::5db8:d822",
+            "2002:5db8:d822::1",
+        ] {
+            assert!(is_public(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "::ffff:169.254.169.254",
+            "64:ff9b::7f00:1",
+            "64:ff9b::a9fe:a9fe",
+            "2002:7f00:1::",
+            "2002:c0a8:101::1",
+            "::127.0.0.1",
+            "::10.0.0.1",
+            "192.0.0.1",
+            "2001::1",
+            "2001:0:4136:e378:8000:63bf:3fff:fdd2",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocks_private_addresses() {
+        let client = Client::default();
+        for url in [
+            "http://127.0.0.1/",
+            "http://[::1]/",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://localhost/",
+        ] {
+            let response = client.get(url).await;
+            assert!(response.is_err(), "{url}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocks_unsupported_scheme() {
+        let client = Client::new(Policy::default().allow_private_networks());
+        let response = client.get("file:///etc/passwd").await;
+        assert_eq!(response.unwrap_err(), "unsupported scheme file");
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_follows_redirects() {
+        let mut server = mockito::Server::new_async().await;
+        let redirect = server
+            .mock("GET", "/old")
+            .with_status(301)
+            .with_header("location", "/new")
+            .create_async()
+            .await;
+        let target = server
+            .mock("GET", "/new")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = Client::new(Policy::default().allow_private_networks());
+        let response = client.get(&format!("{}/old", server.url())).await.unwrap();
+
+        redirect.assert_async().await;
+        target.assert_async().await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.url().path(), "/new");
+        assert_eq!(client.clients.lock().unwrap().len(), 1);
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_drops_headers_on_redirect_to_another_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mut other_server = mockito::Server::new_async().await;
+        let redirect = server
+            .mock("POST", "/hook")
+            .match_header("x-signature", "sha256=abc")
+            .with_status(307)
+            .with_header("location", &format!("{}/hook", other_server.url()))
+            .create_async()
+            .await;
+        let target = other_server
+            .mock("POST", "/hook")
+            .match_header("x-signature", mockito::Matcher::Missing)
+            .match_body("{}")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let client = Client::new(Policy::default().allow_private_networks());
+        let response = client
+            .post(
+                &format!("{}/hook", server.url()),
+                &[("x-signature", "sha256=abc")],
+                "{}".to_owned(),
+            )
+            .await
+            .unwrap();
+
+        redirect.assert_async().await;
+        target.assert_async().await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_limits_redirects() {
+        let mut server = mockito::Server::new_async().await;
+        let redirect = server
+            .mock("GET", "/loop")
+            .with_status(302)
+            .with_header("location", "/loop")
+            .expect(3)
+            .create_async()
+            .await;
+
+        let client = Client::new(Policy {
+            max_redirects: 2,
+            ..Policy::default().allow_private_networks()
+        });
+        let response = client.get(&format!("{}/loop", server.url())).await;
+
+        redirect.assert_async().await;
+        assert_eq!(response.unwrap_err(), "too many redirects");
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_limits_response_size() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/")
+            .with_status(200)
+            .with_body("x".repeat(64))
+            .create_async()
+            .await;
+
+        let client = Client::new(
+            Policy::default()
+                .allow_private_networks()
+                .max_response_size(32),
+        );
+        let response = client.get(&server.url()).await.unwrap();
+        let body = client.bytes(response).await;
+
+        mock.assert_async().await;
+        assert_eq!(body.unwrap_err(), "response exceeds maximum size");
+    }
+}
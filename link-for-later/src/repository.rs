@@ -1,22 +1,76 @@
 use std::sync::Arc;
 
 use axum::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 #[cfg(test)]
 use mockall::{automock, predicate::*};
 
-use crate::types::{LinkItem, LinkQuery, Result, UserInfo, UserQuery};
+use crate::types::{
+    Collection, Highlight, LibraryStats, LinkDetails, LinkHealth, LinkItem, LinkQuery,
+    ReadingProgress, Reminder, Result, Revision, Share, Snapshot, TrashedLink, UserInfo, UserQuery,
+    VersionedLink, Webhook, WebhookDelivery,
+};
 
 pub type DynLinks = Arc<dyn Links + Send + Sync>;
 pub type DynUsers = Arc<dyn Users + Send + Sync>;
+pub type DynSnapshots = Arc<dyn Snapshots + Send + Sync>;
+pub type DynHealth = Arc<dyn Health + Send + Sync>;
+pub type DynWebhooks = Arc<dyn Webhooks + Send + Sync>;
+pub type DynRevisions = Arc<dyn Revisions + Send + Sync>;
+pub type DynCollections = Arc<dyn Collections + Send + Sync>;
+pub type DynShares = Arc<dyn Shares + Send + Sync>;
+pub type DynReminders = Arc<dyn Reminders + Send + Sync>;
+pub type DynDetails = Arc<dyn Details + Send + Sync>;
+pub type DynHighlights = Arc<dyn Highlights + Send + Sync>;
+pub type DynProgress = Arc<dyn Progress + Send + Sync>;
+
+/// The ages, in days, splitting the unread links into those saved in the last
+/// week, month and quarter.
+const BACKLOG_AGES: [i64; 3] = [7, 30, 90];
 
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait Links {
     async fn find(&self, query: &LinkQuery) -> Result<Vec<LinkItem>>;
     async fn get(&self, query: &LinkQuery) -> Result<LinkItem>;
+    /// Gets the link along with its version.
+    async fn get_versioned(&self, query: &LinkQuery) -> Result<VersionedLink>;
+    /// Creates the link at version 1.
     async fn create(&self, item: &LinkItem) -> Result<LinkItem>;
-    async fn update(&self, query: &LinkQuery, item: &LinkItem) -> Result<LinkItem>;
+    /// Replaces the link and moves it to the next version.
+    async fn update(&self, query: &LinkQuery, item: &LinkItem) -> Result<VersionedLink>;
+    /// Replaces the link only if it is still at `version`, so that a change
+    /// made in the meantime is not overwritten.
+    async fn update_if_unmodified(
+        &self,
+        query: &LinkQuery,
+        item: &LinkItem,
+        version: u64,
+    ) -> Result<VersionedLink>;
+    /// Removes the link for good, whether it is in the trash or not.
     async fn delete(&self, query: &LinkQuery) -> Result<()>;
+    /// Marks the link as deleted at `deleted_at`. Links in the trash are left
+    /// out of every other lookup until they are restored.
+    async fn trash(&self, query: &LinkQuery, deleted_at: &DateTime<Utc>) -> Result<()>;
+    /// Lists the links of `owner` in the trash, or of every user when it is
+    /// empty, in the order they were deleted.
+    async fn find_trashed(&self, owner: &str) -> Result<Vec<TrashedLink>>;
+    /// Lists the links that went to the trash before `deleted_before`.
+    async fn find_expired(&self, deleted_before: &DateTime<Utc>) -> Result<Vec<TrashedLink>>;
+    async fn get_trashed(&self, id: &str) -> Result<TrashedLink>;
+    /// Takes a link back out of the trash.
+    async fn restore(&self, id: &str) -> Result<LinkItem>;
+    /// Adds up the links of `owner`: the links saved each day from `since`
+    /// to `today`, leaving out the days without any, and the `domains` most
+    /// saved domains. Links read each day are left at zero, they are counted
+    /// from the reading progress instead.
+    async fn stats(
+        &self,
+        owner: &str,
+        since: &NaiveDate,
+        today: &NaiveDate,
+        domains: usize,
+    ) -> Result<LibraryStats>;
 }
 
 #[cfg_attr(test, automock)]
@@ -26,5 +80,131 @@ pub trait Users {
     async fn create(&self, info: &UserInfo) -> Result<UserInfo>;
 }
 
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Snapshots {
+    async fn get(&self, id: &str) -> Result<Snapshot>;
+    async fn save(&self, snapshot: &Snapshot) -> Result<Snapshot>;
+    async fn delete(&self, id: &str) -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Health {
+    /// Finds the results of the health check of the links in `ids`.
+    async fn find(&self, ids: &[String]) -> Result<Vec<LinkHealth>>;
+    async fn get(&self, id: &str) -> Result<LinkHealth>;
+    async fn save(&self, health: &LinkHealth) -> Result<LinkHealth>;
+    async fn delete(&self, id: &str) -> Result<()>;
+    /// Deletes the results of the health check of every link not in `ids`.
+    async fn retain(&self, ids: &[String]) -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Webhooks {
+    async fn find(&self, owner: &str) -> Result<Vec<Webhook>>;
+    async fn get(&self, id: &str) -> Result<Webhook>;
+    async fn create(&self, webhook: &Webhook) -> Result<Webhook>;
+    async fn delete(&self, id: &str) -> Result<()>;
+    async fn find_deliveries(&self, webhook_id: &str) -> Result<Vec<WebhookDelivery>>;
+    async fn create_delivery(&self, delivery: &WebhookDelivery) -> Result<WebhookDelivery>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Revisions {
+    /// Lists the revisions of a link, oldest first.
+    async fn find(&self, link_id: &str) -> Result<Vec<Revision>>;
+    async fn get(&self, id: &str) -> Result<Revision>;
+    async fn create(&self, revision: &Revision) -> Result<Revision>;
+    /// Removes the whole history of a link.
+    async fn delete(&self, link_id: &str) -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Collections {
+    /// Lists the collections of `owner`, or of everyone when it is empty,
+    /// ordered by their position.
+    async fn find(&self, owner: &str) -> Result<Vec<Collection>>;
+    /// Lists the collections that have been shared with `member`.
+    async fn find_shared(&self, member: &str) -> Result<Vec<Collection>>;
+    async fn get(&self, id: &str) -> Result<Collection>;
+    async fn create(&self, collection: &Collection) -> Result<Collection>;
+    async fn update(&self, collection: &Collection) -> Result<Collection>;
+    async fn delete(&self, id: &str) -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Shares {
+    /// Lists the shares of `owner`, or of everyone when it is empty.
+    async fn find(&self, owner: &str) -> Result<Vec<Share>>;
+    async fn get(&self, id: &str) -> Result<Share>;
+    async fn get_by_token(&self, token: &str) -> Result<Share>;
+    async fn create(&self, share: &Share) -> Result<Share>;
+    async fn delete(&self, id: &str) -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Reminders {
+    /// Lists the reminders of `owner`, or of everyone when it is empty,
+    /// soonest first.
+    async fn find(&self, owner: &str) -> Result<Vec<Reminder>>;
+    /// Lists the reminders that are due at `now` and have not gone out yet.
+    async fn find_due(&self, now: &DateTime<Utc>) -> Result<Vec<Reminder>>;
+    async fn get(&self, id: &str) -> Result<Reminder>;
+    async fn create(&self, reminder: &Reminder) -> Result<Reminder>;
+    async fn update(&self, reminder: &Reminder) -> Result<Reminder>;
+    /// Marks `reminder` as gone out at `notified_at`, unless it already has
+    /// or was moved since it was read. Returns whether it was marked here, so
+    /// that only one of several running instances sends it.
+    async fn claim(&self, reminder: &Reminder, notified_at: &DateTime<Utc>) -> Result<bool>;
+    async fn delete(&self, id: &str) -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Details {
+    /// Lists the details kept by `owner`, or by everyone when it is empty.
+    async fn find(&self, owner: &str) -> Result<Vec<LinkDetails>>;
+    async fn get(&self, id: &str) -> Result<LinkDetails>;
+    async fn save(&self, details: &LinkDetails) -> Result<LinkDetails>;
+    async fn delete(&self, id: &str) -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Highlights {
+    /// Lists the highlights of a link, oldest first.
+    async fn find(&self, link_id: &str) -> Result<Vec<Highlight>>;
+    /// Lists the highlights on the links of `owner`, oldest first.
+    async fn find_by_owner(&self, owner: &str) -> Result<Vec<Highlight>>;
+    async fn get(&self, id: &str) -> Result<Highlight>;
+    async fn create(&self, highlight: &Highlight) -> Result<Highlight>;
+    async fn update(&self, highlight: &Highlight) -> Result<Highlight>;
+    async fn delete(&self, id: &str) -> Result<()>;
+    /// Removes every highlight of a link.
+    async fn delete_all(&self, link_id: &str) -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Progress {
+    /// Lists the reading progress on the links of `owner`, or on all links
+    /// when `owner` is empty.
+    async fn find(&self, owner: &str) -> Result<Vec<ReadingProgress>>;
+    async fn get(&self, id: &str) -> Result<ReadingProgress>;
+    async fn save(&self, progress: &ReadingProgress) -> Result<ReadingProgress>;
+    async fn delete(&self, id: &str) -> Result<()>;
+}
+
+pub mod filesystem;
 pub mod inmemory;
+mod journal;
 pub mod mongodb;
+pub mod postgres;
+pub mod sql;
+pub mod sqlite;
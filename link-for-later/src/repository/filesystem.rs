@@ -0,0 +1,149 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use axum::async_trait;
+
+use crate::types::{AppError, Result, Snapshot};
+
+use super::Snapshots as SnapshotsRepository;
+
+pub struct SnapshotsRepositoryProvider {
+    snapshots_directory: PathBuf,
+}
+
+impl SnapshotsRepositoryProvider {
+    pub fn new(snapshots_directory: &Path) -> Self {
+        Self {
+            snapshots_directory: snapshots_directory.to_path_buf(),
+        }
+    }
+
+    fn snapshot_path(&self, id: &str) -> Result<PathBuf> {
+        let is_valid_id = !id.is_empty()
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if is_valid_id {
+            Ok(self.snapshots_directory.join(format!("{id}.json")))
+        } else {
+            Err(AppError::SnapshotNotFound(id.to_owned()))
+        }
+    }
+}
+
+#[async_trait]
+impl SnapshotsRepository for SnapshotsRepositoryProvider {
+    async fn get(&self, id: &str) -> Result<Snapshot> {
+        let path = self.snapshot_path(id)?;
+        let contents = match tokio::fs::read(&path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                return Err(AppError::SnapshotNotFound(id.to_owned()))
+            }
+            Err(e) => return Err(AppError::Database(format!("read() {e:?}"))),
+        };
+        serde_json::from_slice(&contents)
+            .map_err(|e| AppError::Database(format!("from_slice() {e:?}")))
+    }
+
+    async fn save(&self, snapshot: &Snapshot) -> Result<Snapshot> {
+        let path = self.snapshot_path(snapshot.id())?;
+        let contents = serde_json::to_vec(snapshot)
+            .map_err(|e| AppError::Database(format!("to_vec() {e:?}")))?;
+
+        tokio::fs::create_dir_all(&self.snapshots_directory)
+            .await
+            .map_err(|e| AppError::Database(format!("create_dir_all() {e:?}")))?;
+
+        // write to a temporary file first so that readers never see a partially written snapshot
+        let temporary_path = path.with_extension("json.tmp");
+        tokio::fs::write(&temporary_path, contents)
+            .await
+            .map_err(|e| AppError::Database(format!("write() {e:?}")))?;
+        tokio::fs::rename(&temporary_path, &path)
+            .await
+            .map_err(|e| AppError::Database(format!("rename() {e:?}")))?;
+
+        Ok(snapshot.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        let path = self.snapshot_path(id)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::Database(format!("remove_file() {e:?}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use chrono::Utc;
+    use rand::Rng;
+
+    use super::*;
+
+    fn snapshots_directory() -> PathBuf {
+        let id = rand::thread_rng().gen::<u32>();
+        std::env::temp_dir().join(format!("link-for-later-snapshots-{id}"))
+    }
+
+    #[tokio::test]
+    async fn test_get_snapshot_not_found() {
+        let snapshots_repository = SnapshotsRepositoryProvider::new(&snapshots_directory());
+
+        let response = snapshots_repository.get("1").await;
+
+        assert_eq!(response, Err(AppError::SnapshotNotFound("1".into())));
+    }
+
+    #[tokio::test]
+    async fn test_get_saved_snapshot() {
+        let snapshot = Snapshot::new("1", "http://link", "text/html", "<p>link</p>", &Utc::now());
+
+        let snapshots_repository = SnapshotsRepositoryProvider::new(&snapshots_directory());
+        let saved_snapshot = snapshots_repository.save(&snapshot).await.unwrap();
+        let retrieved_snapshot = snapshots_repository.get("1").await.unwrap();
+
+        assert_eq!(saved_snapshot, retrieved_snapshot);
+    }
+
+    #[tokio::test]
+    async fn test_save_snapshot_replaces_previous() {
+        let snapshot = Snapshot::new("1", "http://link", "text/html", "<p>old</p>", &Utc::now());
+        let updated_snapshot =
+            Snapshot::new("1", "http://link", "text/html", "<p>new</p>", &Utc::now());
+
+        let snapshots_repository = SnapshotsRepositoryProvider::new(&snapshots_directory());
+        snapshots_repository.save(&snapshot).await.unwrap();
+        snapshots_repository.save(&updated_snapshot).await.unwrap();
+        let retrieved_snapshot = snapshots_repository.get("1").await.unwrap();
+
+        assert_eq!(updated_snapshot, retrieved_snapshot);
+    }
+
+    #[tokio::test]
+    async fn test_delete_saved_snapshot() {
+        let snapshot = Snapshot::new("1", "http://link", "text/html", "<p>link</p>", &Utc::now());
+
+        let snapshots_repository = SnapshotsRepositoryProvider::new(&snapshots_directory());
+        snapshots_repository.save(&snapshot).await.unwrap();
+        snapshots_repository.delete("1").await.unwrap();
+        let response = snapshots_repository.get("1").await;
+
+        assert_eq!(response, Err(AppError::SnapshotNotFound("1".into())));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_invalid_id() {
+        let snapshots_repository = SnapshotsRepositoryProvider::new(&snapshots_directory());
+
+        let response = snapshots_repository.get("../links").await;
+
+        assert_eq!(response, Err(AppError::SnapshotNotFound("../links".into())));
+    }
+}
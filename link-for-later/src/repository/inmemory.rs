@@ -1,285 +1,2445 @@
-use std::sync::Mutex;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard, RwLock,
+    },
+    time::Duration,
+};
 
 use axum::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use dashmap::DashMap;
+use reqwest::Url;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tokio::time::Instant;
 
-use crate::types::{
-    AppError, LinkItem, LinkItemBuilder, LinkQuery, Result, UserInfo, UserInfoBuilder, UserQuery,
+use crate::{
+    service::links::READ_LABEL,
+    types::{
+        AppError, BacklogAge, Collection, DailyCount, DomainCount, Highlight, LibraryStats,
+        LinkCounts, LinkDetails, LinkHealth, LinkItem, LinkItemBuilder, LinkQuery,
+        LinkQueryBuilder, ReadingProgress, Reminder, Result, Revision, Share, Snapshot,
+        StoredWebhook, TrashedLink, UserInfo, UserInfoBuilder, UserQuery, VersionedLink, Webhook,
+        WebhookDelivery,
+    },
 };
 
-use super::{Links as LinksRepository, Users as UsersRepository};
+use super::{
+    journal::{Entry, Journal},
+    Collections as CollectionsRepository, Details as DetailsRepository, Health as HealthRepository,
+    Highlights as HighlightsRepository, Links as LinksRepository, Progress as ProgressRepository,
+    Reminders as RemindersRepository, Revisions as RevisionsRepository, Shares as SharesRepository,
+    Snapshots as SnapshotsRepository, Users as UsersRepository, Webhooks as WebhooksRepository,
+    BACKLOG_AGES,
+};
 
-pub struct LinksRepositoryProvider {
-    links_data: Mutex<Vec<LinkItem>>,
-    links_data_counter: Mutex<Vec<usize>>,
+/// A link as it is kept, along with its version and, for as long as it is in
+/// the trash, the time it was deleted at.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StoredLink {
+    #[serde(flatten)]
+    link: LinkItem,
+    #[serde(default = "first_version")]
+    version: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deleted_at: Option<DateTime<Utc>>,
 }
 
-pub struct UsersRepositoryProvider {
-    users_data: Mutex<Vec<UserInfo>>,
-    users_data_counter: Mutex<Vec<usize>>,
+// links journaled before they had versions start from the first one
+const fn first_version() -> u64 {
+    1
 }
 
-impl Default for LinksRepositoryProvider {
-    fn default() -> Self {
+impl StoredLink {
+    fn id(&self) -> &str {
+        self.link.id()
+    }
+
+    /// The link, unless it is in the trash.
+    fn live(&self) -> Option<&LinkItem> {
+        self.deleted_at.is_none().then_some(&self.link)
+    }
+
+    /// The link, if it is in the trash.
+    fn trashed(&self) -> Option<TrashedLink> {
+        self.deleted_at
+            .map(|deleted_at| TrashedLink::new(&self.link, &deleted_at))
+    }
+
+    /// The link as `link`, at the next version.
+    fn next(&self, link: &LinkItem, deleted_at: Option<DateTime<Utc>>) -> Self {
         Self {
-            links_data: Mutex::new(Vec::new()),
-            links_data_counter: Mutex::new(Vec::new()),
+            link: link.clone(),
+            version: self.version + 1,
+            deleted_at,
         }
     }
 }
 
-impl Default for UsersRepositoryProvider {
-    fn default() -> Self {
+impl From<LinkItem> for StoredLink {
+    fn from(link: LinkItem) -> Self {
         Self {
-            users_data: Mutex::new(Vec::new()),
-            users_data_counter: Mutex::new(Vec::new()),
+            link,
+            version: first_version(),
+            deleted_at: None,
         }
     }
 }
 
-#[async_trait]
-impl LinksRepository for LinksRepositoryProvider {
-    async fn find(&self, query: &LinkQuery) -> Result<Vec<LinkItem>> {
-        let filtered_links: Vec<LinkItem> = self
-            .links_data
-            .lock()
-            .map_err(|e| AppError::Database(format!("find() {e:?}")))?
-            .iter()
-            .filter(|link| {
-                (link.id() == query.id() || query.id().is_empty())
-                    && (link.owner() == query.user() || query.user().is_empty())
-            })
-            .cloned()
-            .collect();
-        Ok(filtered_links)
+/// Links are kept in a sharded map keyed by id, next to an index of the ids
+/// each owner has, so lookups never scan the whole collection and requests
+/// for different links rarely contend on the same lock.
+#[derive(Default)]
+pub struct LinksRepositoryProvider {
+    links_data: DashMap<String, StoredLink>,
+    links_by_owner: DashMap<String, HashSet<String>>,
+    links_data_counter: AtomicUsize,
+    journal: Option<Journal>,
+    // held shared by every change and exclusively while snapshotting, so that
+    // no change can slip in between a snapshot and the truncation of the log
+    journal_lock: RwLock<()>,
+}
+
+#[derive(Default)]
+pub struct UsersRepositoryProvider {
+    users_data: DashMap<String, UserInfo>,
+    users_data_counter: AtomicUsize,
+    journal: Option<Journal>,
+    journal_lock: RwLock<()>,
+}
+
+#[derive(Default)]
+pub struct SnapshotsRepositoryProvider {
+    snapshots_data: Table<Snapshot>,
+}
+
+#[derive(Default)]
+pub struct HealthRepositoryProvider {
+    health_data: Table<LinkHealth>,
+}
+
+#[derive(Default)]
+pub struct WebhooksRepositoryProvider {
+    webhooks_data: Table<StoredWebhook>,
+    deliveries_data: Table<StoredDelivery>,
+}
+
+#[derive(Default)]
+pub struct RevisionsRepositoryProvider {
+    revisions_data: Table<Revision>,
+}
+
+#[derive(Default)]
+pub struct CollectionsRepositoryProvider {
+    collections_data: Table<Collection>,
+}
+
+#[derive(Default)]
+pub struct SharesRepositoryProvider {
+    shares_data: Table<Share>,
+}
+
+#[derive(Default)]
+pub struct RemindersRepositoryProvider {
+    reminders_data: Table<Reminder>,
+}
+
+#[derive(Default)]
+pub struct DetailsRepositoryProvider {
+    details_data: Table<LinkDetails>,
+}
+
+#[derive(Default)]
+pub struct ProgressRepositoryProvider {
+    progress_data: Table<ReadingProgress>,
+}
+
+#[derive(Default)]
+pub struct HighlightsRepositoryProvider {
+    highlights_data: Table<Highlight>,
+}
+
+/// Every in-memory repository, restored from the same directory and
+/// recording their changes there.
+pub struct Repositories {
+    pub links: Arc<LinksRepositoryProvider>,
+    pub users: Arc<UsersRepositoryProvider>,
+    pub snapshots: Arc<SnapshotsRepositoryProvider>,
+    pub health: Arc<HealthRepositoryProvider>,
+    pub webhooks: Arc<WebhooksRepositoryProvider>,
+    pub revisions: Arc<RevisionsRepositoryProvider>,
+    pub collections: Arc<CollectionsRepositoryProvider>,
+    pub shares: Arc<SharesRepositoryProvider>,
+    pub reminders: Arc<RemindersRepositoryProvider>,
+    pub details: Arc<DetailsRepositoryProvider>,
+    pub highlights: Arc<HighlightsRepositoryProvider>,
+    pub progress: Arc<ProgressRepositoryProvider>,
+}
+
+impl LinksRepositoryProvider {
+    /// Restores the links kept in `directory` and records every change made
+    /// from now on there as well.
+    pub fn persistent(directory: &Path) -> Result<Self> {
+        let (journal, links_data, last_id) = Journal::open(directory, "links", StoredLink::id)?;
+        let links_repository = Self {
+            links_data_counter: AtomicUsize::new(last_id),
+            journal: Some(journal),
+            ..Self::default()
+        };
+        for link in links_data {
+            links_repository.insert(link.id().to_owned(), link);
+        }
+        Ok(links_repository)
     }
 
-    async fn get(&self, query: &LinkQuery) -> Result<LinkItem> {
-        self.links_data
-            .lock()
-            .map_err(|e| AppError::Database(format!("get() {e:?}")))?
-            .iter()
-            .find(|link| {
-                link.id() == query.id() && (link.owner() == query.user() || query.user().is_empty())
-            })
-            .cloned()
-            .ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))
+    /// Writes out all links at once so the write log can start over.
+    pub fn snapshot(&self) -> Result<()> {
+        let Some(ref journal) = self.journal else {
+            return Ok(());
+        };
+        let _guard = self
+            .journal_lock
+            .write()
+            .map_err(|e| AppError::Database(format!("snapshot() {e:?}")))?;
+        let links_data = sorted_by_id(
+            self.links_data.iter().map(|link| link.value().clone()),
+            StoredLink::id,
+        );
+        journal.snapshot(&links_data, self.links_data_counter.load(Ordering::SeqCst))
     }
 
-    async fn create(&self, item: &LinkItem) -> Result<LinkItem> {
-        let id = self
-            .links_data_counter
-            .lock()
-            .map_err(|e| AppError::Database(format!("create() {e:?}")))?
-            .len()
-            + 1;
-        let link = LinkItemBuilder::from(item.clone())
-            .id(&id.to_string())
-            .build();
-        self.links_data
-            .lock()
-            .map_err(|e| AppError::Database(format!("create() {e:?}")))?
-            .push(link.clone());
-        self.links_data_counter
-            .lock()
-            .map_err(|e| AppError::Database(format!("create() {e:?}")))?
-            .push(id);
-        Ok(link)
+    fn record(&self, entry: &Entry<&StoredLink>) -> Result<()> {
+        self.journal
+            .as_ref()
+            .map_or(Ok(()), |journal| journal.append(entry))
     }
 
-    async fn update(&self, query: &LinkQuery, item: &LinkItem) -> Result<LinkItem> {
-        self.links_data
-            .lock()
-            .map_err(|e| AppError::Database(format!("update() {e:?}")))?
-            .iter()
-            .find(|link| link.id() == query.id() && link.owner() == item.owner())
-            .cloned()
-            .ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))?;
-        self.delete(query).await?;
-        self.links_data
-            .lock()
-            .map_err(|e| AppError::Database(format!("update() {e:?}")))?
-            .push(item.clone());
-        Ok(item.clone())
+    async fn sync(&self) -> Result<()> {
+        match self.journal {
+            Some(ref journal) => journal.sync().await,
+            None => Ok(()),
+        }
     }
 
-    async fn delete(&self, query: &LinkQuery) -> Result<()> {
-        self.get(query).await?;
-        self.links_data
-            .lock()
-            .map_err(|e| AppError::Database(format!("delete() {e:?}")))?
-            .retain(|link| link.id() != query.id());
-        Ok(())
+    fn insert(&self, id: String, link: StoredLink) {
+        self.links_by_owner
+            .entry(link.link.owner().to_owned())
+            .or_default()
+            .insert(id.clone());
+        self.links_data.insert(id, link);
     }
 }
 
-#[async_trait]
-impl UsersRepository for UsersRepositoryProvider {
-    async fn get(&self, query: &UserQuery) -> Result<UserInfo> {
-        self.users_data
-            .lock()
-            .map_err(|e| AppError::Database(format!("get() {e:?}")))?
-            .iter()
-            .find(|user| user.email() == query.email())
-            .cloned()
-            .ok_or_else(|| AppError::UserNotFound(query.email().to_owned()))
+impl UsersRepositoryProvider {
+    /// Restores the users kept in `directory` and records every change made
+    /// from now on there as well.
+    pub fn persistent(directory: &Path) -> Result<Self> {
+        let (journal, users_data, last_id) = Journal::open(directory, "users", UserInfo::id)?;
+        Ok(Self {
+            users_data: users_data
+                .into_iter()
+                .map(|user| (user.email().to_owned(), user))
+                .collect(),
+            users_data_counter: AtomicUsize::new(last_id),
+            journal: Some(journal),
+            journal_lock: RwLock::default(),
+        })
     }
 
-    async fn create(&self, info: &UserInfo) -> Result<UserInfo> {
-        let id = self
-            .users_data_counter
-            .lock()
-            .map_err(|e| AppError::Database(format!("create() {e:?}")))?
-            .len()
-            + 1;
-        let user = UserInfoBuilder::from(info.clone())
-            .id(&id.to_string())
-            .build();
-        self.users_data
-            .lock()
-            .map_err(|e| AppError::Database(format!("create() {e:?}")))?
-            .push(user.clone());
-        self.users_data_counter
-            .lock()
-            .map_err(|e| AppError::Database(format!("create() {e:?}")))?
-            .push(id);
-        Ok(user)
+    /// Writes out all users at once so the write log can start over.
+    pub fn snapshot(&self) -> Result<()> {
+        let Some(ref journal) = self.journal else {
+            return Ok(());
+        };
+        let _guard = self
+            .journal_lock
+            .write()
+            .map_err(|e| AppError::Database(format!("snapshot() {e:?}")))?;
+        let users_data = sorted_by_id(
+            self.users_data.iter().map(|user| user.value().clone()),
+            UserInfo::id,
+        );
+        journal.snapshot(&users_data, self.users_data_counter.load(Ordering::SeqCst))
+    }
+
+    fn record(&self, entry: &Entry<&UserInfo>) -> Result<()> {
+        self.journal
+            .as_ref()
+            .map_or(Ok(()), |journal| journal.append(entry))
+    }
+
+    async fn sync(&self) -> Result<()> {
+        match self.journal {
+            Some(ref journal) => journal.sync().await,
+            None => Ok(()),
+        }
     }
 }
 
-#[cfg(test)]
-mod tests {
+/// What the smaller repositories keep in a [`Table`], stored under its id.
+trait Stored: Clone + Serialize + DeserializeOwned {
+    fn key(&self) -> &str;
+}
 
-    use crate::types::{LinkQueryBuilder, UserQueryBuilder};
+impl Stored for Snapshot {
+    fn key(&self) -> &str {
+        self.id()
+    }
+}
 
-    use super::*;
+impl Stored for LinkHealth {
+    fn key(&self) -> &str {
+        self.id()
+    }
+}
 
-    #[tokio::test]
-    async fn test_search_links_empty() {
-        let repo_query = LinkQueryBuilder::default().user("user-id").build();
-        let links_repository = LinksRepositoryProvider::default();
+impl Stored for StoredWebhook {
+    fn key(&self) -> &str {
+        self.id()
+    }
+}
 
-        let retrieved_items = links_repository.find(&repo_query).await.unwrap();
-        assert!(retrieved_items.is_empty());
+impl Stored for Revision {
+    fn key(&self) -> &str {
+        self.id()
     }
+}
 
-    #[tokio::test]
-    async fn test_search_created_links() {
-        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+impl Stored for Collection {
+    fn key(&self) -> &str {
+        self.id()
+    }
+}
 
-        let links_repository = LinksRepositoryProvider::default();
-        let created_item = links_repository.create(&item).await.unwrap();
-        let expected_items = vec![created_item.clone()];
+impl Stored for Share {
+    fn key(&self) -> &str {
+        self.id()
+    }
+}
 
-        let repo_query = LinkQueryBuilder::default().user("user-id").build();
-        let retrieved_items = links_repository.find(&repo_query).await.unwrap();
-        assert!(!retrieved_items.is_empty());
-        assert!(retrieved_items
-            .iter()
-            .all(|item| expected_items.contains(item)));
+impl Stored for Reminder {
+    fn key(&self) -> &str {
+        self.id()
     }
+}
 
-    #[tokio::test]
-    async fn test_get_link_not_found() {
-        let repo_query = LinkQueryBuilder::new("1", "user-id").build();
+impl Stored for LinkDetails {
+    fn key(&self) -> &str {
+        self.id()
+    }
+}
 
-        let links_repository = LinksRepositoryProvider::default();
-        let response = links_repository.get(&repo_query).await;
+impl Stored for ReadingProgress {
+    fn key(&self) -> &str {
+        self.id()
+    }
+}
 
-        assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
+impl Stored for Highlight {
+    fn key(&self) -> &str {
+        self.id()
     }
+}
 
-    #[tokio::test]
-    async fn test_get_created_link() {
-        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+/// A webhook delivery, which has no id of its own, numbered in the order it
+/// was made.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StoredDelivery {
+    id: String,
+    #[serde(flatten)]
+    delivery: WebhookDelivery,
+}
 
-        let links_repository = LinksRepositoryProvider::default();
-        let created_item = links_repository.create(&item).await.unwrap();
+impl Stored for StoredDelivery {
+    fn key(&self) -> &str {
+        &self.id
+    }
+}
 
-        let repo_query = LinkQueryBuilder::new(created_item.id(), "user-id").build();
-        let retrieved_item = links_repository.get(&repo_query).await.unwrap();
+/// Items of one kind kept by key under a single lock. When persistent, each
+/// change is written to the journal while the lock is held, so the log has
+/// the changes in the order they were made.
+struct Table<T> {
+    items: Mutex<HashMap<String, T>>,
+    counter: AtomicUsize,
+    journal: Option<Journal>,
+}
 
-        assert_eq!(created_item, retrieved_item);
+impl<T> Default for Table<T> {
+    fn default() -> Self {
+        Self {
+            items: Mutex::default(),
+            counter: AtomicUsize::default(),
+            journal: None,
+        }
     }
+}
 
-    #[tokio::test]
-    async fn test_update_link_not_found() {
-        let repo_query = LinkQueryBuilder::new("1", "user-id").build();
-        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+impl<T: Stored> Table<T> {
+    fn persistent(directory: &Path, name: &str) -> Result<Self> {
+        let (journal, items, last_id) = Journal::open(directory, name, T::key)?;
+        Ok(Self {
+            items: Mutex::new(
+                items
+                    .into_iter()
+                    .map(|item: T| (item.key().to_owned(), item))
+                    .collect(),
+            ),
+            counter: AtomicUsize::new(last_id),
+            journal: Some(journal),
+        })
+    }
 
-        let links_repository = LinksRepositoryProvider::default();
-        let response = links_repository.update(&repo_query, &item).await;
+    fn next_id(&self) -> String {
+        (self.counter.fetch_add(1, Ordering::SeqCst) + 1).to_string()
+    }
 
-        assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
+    fn lock(&self, operation: &str) -> Result<MutexGuard<'_, HashMap<String, T>>> {
+        self.items
+            .lock()
+            .map_err(|e| AppError::Database(format!("{operation}() {e:?}")))
     }
 
-    #[tokio::test]
-    async fn test_update_created_link() {
-        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+    /// Stores `item`, replacing the one with the same key.
+    async fn put(&self, operation: &str, item: T) -> Result<()> {
+        {
+            let mut items = self.lock(operation)?;
+            self.record(&Entry::Put(&item))?;
+            items.insert(item.key().to_owned(), item);
+        }
+        self.sync().await
+    }
 
-        let links_repository = LinksRepositoryProvider::default();
-        let created_item = links_repository.create(&item).await.unwrap();
+    /// Replaces the item with the same key, returning whether there was one.
+    async fn replace(&self, operation: &str, item: T) -> Result<bool> {
+        self.replace_if(operation, item, |_| true).await
+    }
 
-        let repo_query = LinkQueryBuilder::new(created_item.id(), "user-id").build();
-        let item = LinkItemBuilder::from(created_item.clone())
-            .title("title")
-            .build();
-        let updated_item = links_repository.update(&repo_query, &item).await.unwrap();
+    /// Replaces the item with the same key if `condition` holds for it,
+    /// returning whether it was replaced.
+    async fn replace_if(
+        &self,
+        operation: &str,
+        item: T,
+        condition: impl FnOnce(&T) -> bool + Send,
+    ) -> Result<bool> {
+        {
+            let mut items = self.lock(operation)?;
+            let Some(existing) = items
+                .get_mut(item.key())
+                .filter(|existing| condition(existing))
+            else {
+                return Ok(false);
+            };
+            self.record(&Entry::Put(&item))?;
+            *existing = item;
+            drop(items);
+        }
+        self.sync().await?;
+        Ok(true)
+    }
 
-        let repo_query = LinkQueryBuilder::new(updated_item.id(), "user-id").build();
-        let retrieved_item = links_repository.get(&repo_query).await.unwrap();
+    /// Removes the item stored under `key`, returning whether there was one.
+    async fn remove(&self, operation: &str, key: &str) -> Result<bool> {
+        {
+            let mut items = self.lock(operation)?;
+            if !items.contains_key(key) {
+                return Ok(false);
+            }
+            self.record(&Entry::Delete(key.to_owned()))?;
+            items.remove(key);
+        }
+        self.sync().await?;
+        Ok(true)
+    }
 
-        assert_eq!(updated_item, retrieved_item);
+    /// Removes every item for which `remove` returns true.
+    async fn remove_all(&self, operation: &str, remove: impl Fn(&T) -> bool + Send) -> Result<()> {
+        {
+            let mut items = self.lock(operation)?;
+            let keys: Vec<String> = items
+                .values()
+                .filter(|item| remove(item))
+                .map(|item| item.key().to_owned())
+                .collect();
+            for key in keys {
+                self.record(&Entry::Delete(key.clone()))?;
+                items.remove(&key);
+            }
+        }
+        self.sync().await
     }
 
-    #[tokio::test]
-    async fn test_delete_link_not_found() {
-        let repo_query = LinkQueryBuilder::new("1", "user-id").build();
+    /// Writes out all items at once so the write log can start over.
+    fn snapshot(&self) -> Result<()> {
+        let Some(ref journal) = self.journal else {
+            return Ok(());
+        };
+        // holding the lock keeps every change out until the log is truncated
+        let items = self.lock("snapshot")?;
+        let sorted_items = sorted_by_id(items.values(), |item| item.key());
+        let snapshot = journal.snapshot(&sorted_items, self.counter.load(Ordering::SeqCst));
+        drop(items);
+        snapshot
+    }
 
-        let links_repository = LinksRepositoryProvider::default();
-        let response = links_repository.delete(&repo_query).await;
+    fn record(&self, entry: &Entry<&T>) -> Result<()> {
+        self.journal
+            .as_ref()
+            .map_or(Ok(()), |journal| journal.append(entry))
+    }
 
-        assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
+    async fn sync(&self) -> Result<()> {
+        match self.journal {
+            Some(ref journal) => journal.sync().await,
+            None => Ok(()),
+        }
     }
+}
 
-    #[tokio::test]
-    async fn test_delete_created_link() {
-        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+impl Repositories {
+    /// Restores every repository kept in `directory`.
+    pub fn persistent(directory: &Path) -> Result<Self> {
+        Ok(Self {
+            links: Arc::new(LinksRepositoryProvider::persistent(directory)?),
+            users: Arc::new(UsersRepositoryProvider::persistent(directory)?),
+            snapshots: Arc::new(SnapshotsRepositoryProvider {
+                snapshots_data: Table::persistent(directory, "snapshots")?,
+            }),
+            health: Arc::new(HealthRepositoryProvider {
+                health_data: Table::persistent(directory, "health")?,
+            }),
+            webhooks: Arc::new(WebhooksRepositoryProvider {
+                webhooks_data: Table::persistent(directory, "webhooks")?,
+                deliveries_data: Table::persistent(directory, "deliveries")?,
+            }),
+            revisions: Arc::new(RevisionsRepositoryProvider {
+                revisions_data: Table::persistent(directory, "revisions")?,
+            }),
+            collections: Arc::new(CollectionsRepositoryProvider {
+                collections_data: Table::persistent(directory, "collections")?,
+            }),
+            shares: Arc::new(SharesRepositoryProvider {
+                shares_data: Table::persistent(directory, "shares")?,
+            }),
+            reminders: Arc::new(RemindersRepositoryProvider {
+                reminders_data: Table::persistent(directory, "reminders")?,
+            }),
+            details: Arc::new(DetailsRepositoryProvider {
+                details_data: Table::persistent(directory, "details")?,
+            }),
+            highlights: Arc::new(HighlightsRepositoryProvider {
+                highlights_data: Table::persistent(directory, "highlights")?,
+            }),
+            progress: Arc::new(ProgressRepositoryProvider {
+                progress_data: Table::persistent(directory, "progress")?,
+            }),
+        })
+    }
 
-        let links_repository = LinksRepositoryProvider::default();
-        let created_item = links_repository.create(&item).await.unwrap();
+    /// Snapshots every repository, one after the other.
+    pub fn snapshot(&self) -> Result<()> {
+        self.links.snapshot()?;
+        self.users.snapshot()?;
+        self.snapshots.snapshots_data.snapshot()?;
+        self.health.health_data.snapshot()?;
+        self.webhooks.webhooks_data.snapshot()?;
+        self.webhooks.deliveries_data.snapshot()?;
+        self.revisions.revisions_data.snapshot()?;
+        self.collections.collections_data.snapshot()?;
+        self.shares.shares_data.snapshot()?;
+        self.reminders.reminders_data.snapshot()?;
+        self.details.details_data.snapshot()?;
+        self.highlights.highlights_data.snapshot()?;
+        self.progress.progress_data.snapshot()
+    }
+}
 
-        let repo_query = LinkQueryBuilder::new(created_item.id(), "user-id").build();
-        links_repository.delete(&repo_query).await.unwrap();
+fn owned_by(link: &LinkItem, user: &str) -> bool {
+    user.is_empty() || link.owner() == user
+}
 
-        let repo_query = LinkQueryBuilder::new(created_item.id(), "user-id").build();
-        let response = links_repository.get(&repo_query).await;
+/// Ids are handed out in sequence, so ordering by them keeps results in the
+/// order they were created in.
+fn sorted_by_id<T>(items: impl Iterator<Item = T>, id: impl Fn(&T) -> &str) -> Vec<T> {
+    let mut items: Vec<T> = items.collect();
+    items.sort_by_key(|item| id(item).parse::<usize>().unwrap_or(usize::MAX));
+    items
+}
 
-        assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
-    }
+/// The host a link was saved from, which is what the links are grouped by
+/// when counting the most saved domains.
+fn domain(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_owned))
+}
 
-    #[tokio::test]
-    async fn test_get_user_not_found() {
-        let repo_query = UserQueryBuilder::new("user@test.com").build();
+/// Adds up `links` the same way the `MongoDB` repository does with its
+/// aggregation pipeline, for the repositories without one.
+pub fn library_stats(
+    links: &[LinkItem],
+    since: NaiveDate,
+    today: NaiveDate,
+    domains: usize,
+) -> LibraryStats {
+    let is_read = |link: &&LinkItem| link.label() == READ_LABEL;
+    let read_count = links.iter().filter(is_read).count();
+    let counts = LinkCounts::new(links.len() - read_count, read_count);
 
-        let users_repository = UsersRepositoryProvider::default();
-        let response = users_repository.get(&repo_query).await;
+    let mut links_by_domain: HashMap<String, usize> = HashMap::new();
+    for domain in links.iter().filter_map(|link| domain(link.url())) {
+        *links_by_domain.entry(domain).or_default() += 1;
+    }
+    let mut links_by_domain: Vec<(String, usize)> = links_by_domain.into_iter().collect();
+    links_by_domain.sort_by(|(domain, count), (other_domain, other_count)| {
+        other_count
+            .cmp(count)
+            .then_with(|| domain.cmp(other_domain))
+    });
+    let top_domains: Vec<DomainCount> = links_by_domain
+        .iter()
+        .take(domains)
+        .map(|(domain, count)| DomainCount::new(domain, *count))
+        .collect();
 
-        assert_eq!(
-            response,
-            Err(AppError::UserNotFound("user@test.com".into()))
-        );
+    let is_in_window = |day: &NaiveDate| since <= *day && *day <= today;
+    let mut days: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for day in links
+        .iter()
+        .map(|link| link.created_at().date_naive())
+        .filter(is_in_window)
+    {
+        *days.entry(day).or_default() += 1;
     }
+    let daily: Vec<DailyCount> = days
+        .into_iter()
+        .map(|(day, saved)| DailyCount::new(day, saved, 0))
+        .collect();
 
-    #[tokio::test]
-    async fn test_get_created_user() {
-        let user = UserInfoBuilder::new("user@test.com", "test").build();
+    let mut backlog = [0; BACKLOG_AGES.len() + 1];
+    let mut oldest: Option<NaiveDate> = None;
+    for day in links
+        .iter()
+        .filter(|link| !is_read(link))
+        .map(|link| link.created_at().date_naive())
+    {
+        let age = (today - day).num_days();
+        let bucket = BACKLOG_AGES
+            .iter()
+            .position(|&days| age < days)
+            .unwrap_or(BACKLOG_AGES.len());
+        backlog[bucket] += 1;
+        oldest = Some(oldest.map_or(day, |oldest| oldest.min(day)));
+    }
+    let [week, month, quarter, older] = backlog;
 
-        let users_repository = UsersRepositoryProvider::default();
-        let created_user = users_repository.create(&user).await.unwrap();
+    LibraryStats::new(
+        counts,
+        &top_domains,
+        &daily,
+        BacklogAge::new(week, month, quarter, older, oldest),
+    )
+}
 
-        let repo_query = UserQueryBuilder::new("user@test.com").build();
-        let retrieved_user = users_repository.get(&repo_query).await.unwrap();
+/// Periodically snapshots the persistent repositories, keeping their write
+/// logs short and restarts quick.
+pub fn schedule_snapshots(repositories: Arc<Repositories>, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval_at(Instant::now() + interval, interval);
+        loop {
+            ticker.tick().await;
+            tracing::info!("Snapshotting in-memory database");
+            let repositories = Arc::clone(&repositories);
+            let snapshot = tokio::task::spawn_blocking(move || repositories.snapshot())
+                .await
+                .map_err(|e| AppError::Database(format!("spawn_blocking() {e:?}")))
+                .and_then(|snapshot| snapshot);
+            if let Err(e) = snapshot {
+                tracing::error!("Unable to snapshot in-memory database: {}", e);
+            }
+        }
+    });
+}
 
-        assert_eq!(created_user, retrieved_user);
+#[async_trait]
+impl LinksRepository for LinksRepositoryProvider {
+    async fn find(&self, query: &LinkQuery) -> Result<Vec<LinkItem>> {
+        if !query.id().is_empty() {
+            return Ok(self
+                .links_data
+                .get(query.id())
+                .and_then(|link| {
+                    link.live()
+                        .filter(|link| owned_by(link, query.user()))
+                        .cloned()
+                })
+                .map(|link| vec![link])
+                .unwrap_or_default());
+        }
+        if query.user().is_empty() {
+            return Ok(sorted_by_id(
+                self.links_data
+                    .iter()
+                    .filter_map(|link| link.live().cloned()),
+                LinkItem::id,
+            ));
+        }
+
+        // copy the ids out first so the index isn't locked while the links are read
+        let ids: Vec<String> = self
+            .links_by_owner
+            .get(query.user())
+            .map(|ids| ids.iter().cloned().collect())
+            .unwrap_or_default();
+        Ok(sorted_by_id(
+            ids.iter().filter_map(|id| {
+                self.links_data
+                    .get(id)
+                    .and_then(|link| link.live().cloned())
+            }),
+            LinkItem::id,
+        ))
+    }
+
+    async fn get(&self, query: &LinkQuery) -> Result<LinkItem> {
+        self.links_data
+            .get(query.id())
+            .and_then(|link| {
+                link.live()
+                    .filter(|link| owned_by(link, query.user()))
+                    .cloned()
+            })
+            .ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))
+    }
+
+    async fn get_versioned(&self, query: &LinkQuery) -> Result<VersionedLink> {
+        self.links_data
+            .get(query.id())
+            .filter(|link| link.live().is_some_and(|link| owned_by(link, query.user())))
+            .map(|link| VersionedLink::new(&link.link, link.version))
+            .ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))
+    }
+
+    async fn create(&self, item: &LinkItem) -> Result<LinkItem> {
+        let id = self.links_data_counter.fetch_add(1, Ordering::SeqCst) + 1;
+        let link = LinkItemBuilder::from(item.clone())
+            .id(&id.to_string())
+            .build();
+
+        {
+            let _guard = self
+                .journal_lock
+                .read()
+                .map_err(|e| AppError::Database(format!("create() {e:?}")))?;
+            let stored_link = StoredLink::from(link.clone());
+            self.record(&Entry::Put(&stored_link))?;
+            self.insert(id.to_string(), stored_link);
+        }
+        self.sync().await?;
+        Ok(link)
+    }
+
+    async fn update(&self, query: &LinkQuery, item: &LinkItem) -> Result<VersionedLink> {
+        let version = {
+            let _guard = self
+                .journal_lock
+                .read()
+                .map_err(|e| AppError::Database(format!("update() {e:?}")))?;
+            // the entry stays locked until it is replaced, so a concurrent
+            // delete cannot be undone by this update
+            let mut link = self
+                .links_data
+                .get_mut(query.id())
+                .filter(|link| link.live().is_some_and(|link| link.owner() == item.owner()))
+                .ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))?;
+            let stored_link = link.next(item, None);
+            self.record(&Entry::Put(&stored_link))?;
+            *link = stored_link;
+            link.version
+        };
+        self.sync().await?;
+        Ok(VersionedLink::new(item, version))
+    }
+
+    async fn update_if_unmodified(
+        &self,
+        query: &LinkQuery,
+        item: &LinkItem,
+        version: u64,
+    ) -> Result<VersionedLink> {
+        let version = {
+            let _guard = self
+                .journal_lock
+                .read()
+                .map_err(|e| AppError::Database(format!("update_if_unmodified() {e:?}")))?;
+            let mut link = self
+                .links_data
+                .get_mut(query.id())
+                .filter(|link| link.live().is_some_and(|link| link.owner() == item.owner()))
+                .ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))?;
+            if link.version != version {
+                return Err(AppError::PreconditionFailed(query.id().to_owned()));
+            }
+            let stored_link = link.next(item, None);
+            self.record(&Entry::Put(&stored_link))?;
+            *link = stored_link;
+            link.version
+        };
+        self.sync().await?;
+        Ok(VersionedLink::new(item, version))
+    }
+
+    async fn delete(&self, query: &LinkQuery) -> Result<()> {
+        {
+            let _guard = self
+                .journal_lock
+                .read()
+                .map_err(|e| AppError::Database(format!("delete() {e:?}")))?;
+            if !self
+                .links_data
+                .get(query.id())
+                .is_some_and(|link| owned_by(&link.link, query.user()))
+            {
+                return Err(AppError::LinkNotFound(query.id().to_owned()));
+            }
+            self.record(&Entry::Delete(query.id().to_owned()))?;
+            if let Some((id, link)) = self.links_data.remove(query.id()) {
+                if let Some(mut ids) = self.links_by_owner.get_mut(link.link.owner()) {
+                    ids.remove(&id);
+                }
+            }
+        }
+        self.sync().await
+    }
+
+    async fn trash(&self, query: &LinkQuery, deleted_at: &DateTime<Utc>) -> Result<()> {
+        {
+            let _guard = self
+                .journal_lock
+                .read()
+                .map_err(|e| AppError::Database(format!("trash() {e:?}")))?;
+            let mut link = self
+                .links_data
+                .get_mut(query.id())
+                .filter(|link| link.live().is_some_and(|link| owned_by(link, query.user())))
+                .ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))?;
+            let trashed_link = link.next(&link.link, Some(*deleted_at));
+            self.record(&Entry::Put(&trashed_link))?;
+            *link = trashed_link;
+        }
+        self.sync().await
+    }
+
+    async fn find_trashed(&self, owner: &str) -> Result<Vec<TrashedLink>> {
+        let mut trashed_links: Vec<TrashedLink> = if owner.is_empty() {
+            self.links_data
+                .iter()
+                .filter_map(|link| link.trashed())
+                .collect()
+        } else {
+            let ids: Vec<String> = self
+                .links_by_owner
+                .get(owner)
+                .map(|ids| ids.iter().cloned().collect())
+                .unwrap_or_default();
+            ids.iter()
+                .filter_map(|id| self.links_data.get(id).and_then(|link| link.trashed()))
+                .collect()
+        };
+        trashed_links.sort_by_key(|trashed_link| *trashed_link.deleted_at());
+        Ok(trashed_links)
+    }
+
+    async fn find_expired(&self, deleted_before: &DateTime<Utc>) -> Result<Vec<TrashedLink>> {
+        let mut trashed_links: Vec<TrashedLink> = self
+            .links_data
+            .iter()
+            .filter_map(|link| link.trashed())
+            .filter(|trashed_link| trashed_link.deleted_at() < deleted_before)
+            .collect();
+        trashed_links.sort_by_key(|trashed_link| *trashed_link.deleted_at());
+        Ok(trashed_links)
+    }
+
+    async fn get_trashed(&self, id: &str) -> Result<TrashedLink> {
+        self.links_data
+            .get(id)
+            .and_then(|link| link.trashed())
+            .ok_or_else(|| AppError::LinkNotFound(id.to_owned()))
+    }
+
+    async fn restore(&self, id: &str) -> Result<LinkItem> {
+        let restored_item = {
+            let _guard = self
+                .journal_lock
+                .read()
+                .map_err(|e| AppError::Database(format!("restore() {e:?}")))?;
+            let mut link = self
+                .links_data
+                .get_mut(id)
+                .filter(|link| link.deleted_at.is_some())
+                .ok_or_else(|| AppError::LinkNotFound(id.to_owned()))?;
+            let restored_link = link.next(&link.link, None);
+            self.record(&Entry::Put(&restored_link))?;
+            *link = restored_link;
+            link.link.clone()
+        };
+        self.sync().await?;
+        Ok(restored_item)
+    }
+
+    async fn stats(
+        &self,
+        owner: &str,
+        since: &NaiveDate,
+        today: &NaiveDate,
+        domains: usize,
+    ) -> Result<LibraryStats> {
+        let links = self
+            .find(&LinkQueryBuilder::default().user(owner).build())
+            .await?;
+        Ok(library_stats(&links, *since, *today, domains))
+    }
+}
+
+#[async_trait]
+impl UsersRepository for UsersRepositoryProvider {
+    async fn get(&self, query: &UserQuery) -> Result<UserInfo> {
+        self.users_data
+            .get(query.email())
+            .map(|user| user.value().clone())
+            .ok_or_else(|| AppError::UserNotFound(query.email().to_owned()))
+    }
+
+    async fn create(&self, info: &UserInfo) -> Result<UserInfo> {
+        let id = self.users_data_counter.fetch_add(1, Ordering::SeqCst) + 1;
+        let user = UserInfoBuilder::from(info.clone())
+            .id(&id.to_string())
+            .build();
+
+        {
+            let _guard = self
+                .journal_lock
+                .read()
+                .map_err(|e| AppError::Database(format!("create() {e:?}")))?;
+            self.record(&Entry::Put(&user))?;
+            self.users_data
+                .insert(user.email().to_owned(), user.clone());
+        }
+        self.sync().await?;
+        Ok(user)
+    }
+}
+
+#[async_trait]
+impl SnapshotsRepository for SnapshotsRepositoryProvider {
+    async fn get(&self, id: &str) -> Result<Snapshot> {
+        self.snapshots_data
+            .lock("get")?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::SnapshotNotFound(id.to_owned()))
+    }
+
+    async fn save(&self, snapshot: &Snapshot) -> Result<Snapshot> {
+        self.snapshots_data.put("save", snapshot.clone()).await?;
+        Ok(snapshot.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.snapshots_data.remove("delete", id).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HealthRepository for HealthRepositoryProvider {
+    async fn find(&self, ids: &[String]) -> Result<Vec<LinkHealth>> {
+        let health_data = self.health_data.lock("find")?;
+        Ok(ids
+            .iter()
+            .filter_map(|id| health_data.get(id).cloned())
+            .collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<LinkHealth> {
+        self.health_data
+            .lock("get")?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::HealthNotFound(id.to_owned()))
+    }
+
+    async fn save(&self, health: &LinkHealth) -> Result<LinkHealth> {
+        self.health_data.put("save", health.clone()).await?;
+        Ok(health.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.health_data.remove("delete", id).await?;
+        Ok(())
+    }
+
+    async fn retain(&self, ids: &[String]) -> Result<()> {
+        self.health_data
+            .remove_all("retain", |health| !ids.iter().any(|id| id == health.id()))
+            .await
+    }
+}
+
+#[async_trait]
+impl WebhooksRepository for WebhooksRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<Webhook>> {
+        Ok(sorted_by_id(
+            self.webhooks_data
+                .lock("find")?
+                .values()
+                .filter(|webhook| webhook.owner() == owner)
+                .cloned()
+                .map(Webhook::from),
+            Webhook::id,
+        ))
+    }
+
+    async fn get(&self, id: &str) -> Result<Webhook> {
+        self.webhooks_data
+            .lock("get")?
+            .get(id)
+            .cloned()
+            .map(Webhook::from)
+            .ok_or_else(|| AppError::WebhookNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, webhook: &Webhook) -> Result<Webhook> {
+        let webhook = webhook.clone().with_id(&self.webhooks_data.next_id());
+        self.webhooks_data
+            .put("create", StoredWebhook::from(&webhook))
+            .await?;
+        Ok(webhook)
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        if !self.webhooks_data.remove("delete", id).await? {
+            return Err(AppError::WebhookNotFound(id.to_owned()));
+        }
+        self.deliveries_data
+            .remove_all("delete", |stored| stored.delivery.webhook_id() == id)
+            .await
+    }
+
+    async fn find_deliveries(&self, webhook_id: &str) -> Result<Vec<WebhookDelivery>> {
+        let deliveries = sorted_by_id(
+            self.deliveries_data
+                .lock("find_deliveries")?
+                .values()
+                .filter(|stored| stored.delivery.webhook_id() == webhook_id)
+                .cloned(),
+            StoredDelivery::key,
+        );
+        Ok(deliveries
+            .into_iter()
+            .rev()
+            .map(|stored| stored.delivery)
+            .collect())
+    }
+
+    async fn create_delivery(&self, delivery: &WebhookDelivery) -> Result<WebhookDelivery> {
+        let stored = StoredDelivery {
+            id: self.deliveries_data.next_id(),
+            delivery: delivery.clone(),
+        };
+        self.deliveries_data.put("create_delivery", stored).await?;
+        Ok(delivery.clone())
+    }
+}
+
+#[async_trait]
+impl RevisionsRepository for RevisionsRepositoryProvider {
+    async fn find(&self, link_id: &str) -> Result<Vec<Revision>> {
+        Ok(sorted_by_id(
+            self.revisions_data
+                .lock("find")?
+                .values()
+                .filter(|revision| revision.link_id() == link_id)
+                .cloned(),
+            Revision::id,
+        ))
+    }
+
+    async fn get(&self, id: &str) -> Result<Revision> {
+        self.revisions_data
+            .lock("get")?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::RevisionNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, revision: &Revision) -> Result<Revision> {
+        let revision = revision.clone().with_id(&self.revisions_data.next_id());
+        self.revisions_data.put("create", revision.clone()).await?;
+        Ok(revision)
+    }
+
+    async fn delete(&self, link_id: &str) -> Result<()> {
+        self.revisions_data
+            .remove_all("delete", |revision| revision.link_id() == link_id)
+            .await
+    }
+}
+
+#[async_trait]
+impl CollectionsRepository for CollectionsRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<Collection>> {
+        let mut collections: Vec<Collection> = self
+            .collections_data
+            .lock("find")?
+            .values()
+            .filter(|collection| collection.owner() == owner || owner.is_empty())
+            .cloned()
+            .collect();
+        collections.sort_by_key(|collection| (collection.position(), *collection.created_at()));
+        Ok(collections)
+    }
+
+    async fn find_shared(&self, member: &str) -> Result<Vec<Collection>> {
+        let mut collections: Vec<Collection> = self
+            .collections_data
+            .lock("find_shared")?
+            .values()
+            .filter(|collection| collection.role(member).is_some())
+            .cloned()
+            .collect();
+        collections.sort_by_key(|collection| (collection.position(), *collection.created_at()));
+        Ok(collections)
+    }
+
+    async fn get(&self, id: &str) -> Result<Collection> {
+        self.collections_data
+            .lock("get")?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::CollectionNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, collection: &Collection) -> Result<Collection> {
+        let collection = collection.clone().with_id(&self.collections_data.next_id());
+        self.collections_data
+            .put("create", collection.clone())
+            .await?;
+        Ok(collection)
+    }
+
+    async fn update(&self, collection: &Collection) -> Result<Collection> {
+        if !self
+            .collections_data
+            .replace("update", collection.clone())
+            .await?
+        {
+            return Err(AppError::CollectionNotFound(collection.id().to_owned()));
+        }
+        Ok(collection.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        if !self.collections_data.remove("delete", id).await? {
+            return Err(AppError::CollectionNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SharesRepository for SharesRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<Share>> {
+        let mut shares: Vec<Share> = self
+            .shares_data
+            .lock("find")?
+            .values()
+            .filter(|share| share.owner() == owner || owner.is_empty())
+            .cloned()
+            .collect();
+        shares.sort_by_key(|share| *share.created_at());
+        Ok(shares)
+    }
+
+    async fn get(&self, id: &str) -> Result<Share> {
+        self.shares_data
+            .lock("get")?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::ShareNotFound(id.to_owned()))
+    }
+
+    async fn get_by_token(&self, token: &str) -> Result<Share> {
+        self.shares_data
+            .lock("get_by_token")?
+            .values()
+            .find(|share| share.token() == token)
+            .cloned()
+            .ok_or_else(|| AppError::ShareNotFound(token.to_owned()))
+    }
+
+    async fn create(&self, share: &Share) -> Result<Share> {
+        let share = share.clone().with_id(&self.shares_data.next_id());
+        self.shares_data.put("create", share.clone()).await?;
+        Ok(share)
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        if !self.shares_data.remove("delete", id).await? {
+            return Err(AppError::ShareNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RemindersRepository for RemindersRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<Reminder>> {
+        let mut reminders: Vec<Reminder> = self
+            .reminders_data
+            .lock("find")?
+            .values()
+            .filter(|reminder| reminder.owner() == owner || owner.is_empty())
+            .cloned()
+            .collect();
+        reminders.sort_by_key(|reminder| *reminder.remind_at());
+        Ok(reminders)
+    }
+
+    async fn find_due(&self, now: &DateTime<Utc>) -> Result<Vec<Reminder>> {
+        let mut reminders: Vec<Reminder> = self
+            .reminders_data
+            .lock("find_due")?
+            .values()
+            .filter(|reminder| reminder.is_due(now))
+            .cloned()
+            .collect();
+        reminders.sort_by_key(|reminder| *reminder.remind_at());
+        Ok(reminders)
+    }
+
+    async fn get(&self, id: &str) -> Result<Reminder> {
+        self.reminders_data
+            .lock("get")?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::ReminderNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, reminder: &Reminder) -> Result<Reminder> {
+        let reminder = reminder.clone().with_id(&self.reminders_data.next_id());
+        self.reminders_data.put("create", reminder.clone()).await?;
+        Ok(reminder)
+    }
+
+    async fn update(&self, reminder: &Reminder) -> Result<Reminder> {
+        if !self
+            .reminders_data
+            .replace("update", reminder.clone())
+            .await?
+        {
+            return Err(AppError::ReminderNotFound(reminder.id().to_owned()));
+        }
+        Ok(reminder.clone())
+    }
+
+    async fn claim(&self, reminder: &Reminder, notified_at: &DateTime<Utc>) -> Result<bool> {
+        self.reminders_data
+            .replace_if(
+                "claim",
+                reminder.clone().with_notified_at(notified_at),
+                |existing| {
+                    existing.notified_at().is_none() && existing.remind_at() == reminder.remind_at()
+                },
+            )
+            .await
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        if !self.reminders_data.remove("delete", id).await? {
+            return Err(AppError::ReminderNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DetailsRepository for DetailsRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<LinkDetails>> {
+        Ok(self
+            .details_data
+            .lock("find")?
+            .values()
+            .filter(|details| owner.is_empty() || details.owner() == owner)
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<LinkDetails> {
+        self.details_data
+            .lock("get")?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::DetailsNotFound(id.to_owned()))
+    }
+
+    async fn save(&self, details: &LinkDetails) -> Result<LinkDetails> {
+        self.details_data.put("save", details.clone()).await?;
+        Ok(details.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.details_data.remove("delete", id).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HighlightsRepository for HighlightsRepositoryProvider {
+    async fn find(&self, link_id: &str) -> Result<Vec<Highlight>> {
+        Ok(sorted_by_id(
+            self.highlights_data
+                .lock("find")?
+                .values()
+                .filter(|highlight| highlight.link_id() == link_id)
+                .cloned(),
+            Highlight::id,
+        ))
+    }
+
+    async fn find_by_owner(&self, owner: &str) -> Result<Vec<Highlight>> {
+        Ok(sorted_by_id(
+            self.highlights_data
+                .lock("find_by_owner")?
+                .values()
+                .filter(|highlight| highlight.owner() == owner)
+                .cloned(),
+            Highlight::id,
+        ))
+    }
+
+    async fn get(&self, id: &str) -> Result<Highlight> {
+        self.highlights_data
+            .lock("get")?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::HighlightNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, highlight: &Highlight) -> Result<Highlight> {
+        let highlight = highlight.clone().with_id(&self.highlights_data.next_id());
+        self.highlights_data
+            .put("create", highlight.clone())
+            .await?;
+        Ok(highlight)
+    }
+
+    async fn update(&self, highlight: &Highlight) -> Result<Highlight> {
+        if !self
+            .highlights_data
+            .replace("update", highlight.clone())
+            .await?
+        {
+            return Err(AppError::HighlightNotFound(highlight.id().to_owned()));
+        }
+        Ok(highlight.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        if !self.highlights_data.remove("delete", id).await? {
+            return Err(AppError::HighlightNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+
+    async fn delete_all(&self, link_id: &str) -> Result<()> {
+        self.highlights_data
+            .remove_all("delete_all", |highlight| highlight.link_id() == link_id)
+            .await
+    }
+}
+
+#[async_trait]
+impl ProgressRepository for ProgressRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<ReadingProgress>> {
+        Ok(self
+            .progress_data
+            .lock("find")?
+            .values()
+            .filter(|progress| owner.is_empty() || progress.owner() == owner)
+            .cloned()
+            .collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<ReadingProgress> {
+        self.progress_data
+            .lock("get")?
+            .get(id)
+            .cloned()
+            .ok_or_else(|| AppError::ProgressNotFound(id.to_owned()))
+    }
+
+    async fn save(&self, progress: &ReadingProgress) -> Result<ReadingProgress> {
+        self.progress_data.put("save", progress.clone()).await?;
+        Ok(progress.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.progress_data.remove("delete", id).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::{io::Write, path::PathBuf};
+
+    use rand::Rng;
+
+    use chrono::TimeZone;
+
+    use crate::types::{Member, Priority, Role, Selector, UserQueryBuilder};
+
+    use super::*;
+
+    fn data_directory() -> PathBuf {
+        let id = rand::thread_rng().gen::<u32>();
+        std::env::temp_dir().join(format!("link-for-later-inmemory-{id}"))
+    }
+
+    #[tokio::test]
+    async fn test_search_links_empty() {
+        let repo_query = LinkQueryBuilder::default().user("user-id").build();
+        let links_repository = LinksRepositoryProvider::default();
+
+        let retrieved_items = links_repository.find(&repo_query).await.unwrap();
+        assert!(retrieved_items.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_links_stats() {
+        let at = |month, day| Utc.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap();
+        let day = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        let links_repository = LinksRepositoryProvider::default();
+        for (url, label, created_at, updated_at) in [
+            ("http://example.com/1", READ_LABEL, at(5, 1), at(5, 14)),
+            ("https://EXAMPLE.com/2", "", at(5, 14), at(5, 14)),
+            ("http://other.org", "", at(5, 10), at(5, 10)),
+            ("http://example.com/3", "", at(3, 1), at(3, 1)),
+            ("http://third.net", "", at(1, 1), at(1, 1)),
+        ] {
+            let item = LinkItemBuilder::new(url)
+                .owner("user-id")
+                .label(label)
+                .created_at(&created_at)
+                .updated_at(&updated_at)
+                .build();
+            links_repository.create(&item).await.unwrap();
+        }
+        let other_item = LinkItemBuilder::new("http://elsewhere.com")
+            .owner("other-id")
+            .created_at(&at(5, 14))
+            .build();
+        links_repository.create(&other_item).await.unwrap();
+
+        let stats = links_repository
+            .stats("user-id", &day(5, 8), &day(5, 15), 2)
+            .await
+            .unwrap();
+
+        assert_eq!(stats.counts(), &LinkCounts::new(4, 1));
+        assert_eq!(
+            stats.domains(),
+            &[
+                DomainCount::new("example.com", 3),
+                DomainCount::new("other.org", 1)
+            ]
+        );
+        assert_eq!(
+            stats.daily(),
+            &[
+                DailyCount::new(day(5, 10), 1, 0),
+                DailyCount::new(day(5, 14), 1, 0)
+            ]
+        );
+        assert_eq!(
+            stats.backlog(),
+            &BacklogAge::new(2, 0, 1, 1, Some(day(1, 1)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_created_links() {
+        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+
+        let links_repository = LinksRepositoryProvider::default();
+        let created_item = links_repository.create(&item).await.unwrap();
+        let expected_items = vec![created_item.clone()];
+
+        let repo_query = LinkQueryBuilder::default().user("user-id").build();
+        let retrieved_items = links_repository.find(&repo_query).await.unwrap();
+        assert!(!retrieved_items.is_empty());
+        assert!(retrieved_items
+            .iter()
+            .all(|item| expected_items.contains(item)));
+    }
+
+    #[tokio::test]
+    async fn test_get_link_not_found() {
+        let repo_query = LinkQueryBuilder::new("1", "user-id").build();
+
+        let links_repository = LinksRepositoryProvider::default();
+        let response = links_repository.get(&repo_query).await;
+
+        assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
+    }
+
+    #[tokio::test]
+    async fn test_get_created_link() {
+        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+
+        let links_repository = LinksRepositoryProvider::default();
+        let created_item = links_repository.create(&item).await.unwrap();
+
+        let repo_query = LinkQueryBuilder::new(created_item.id(), "user-id").build();
+        let retrieved_item = links_repository.get(&repo_query).await.unwrap();
+
+        assert_eq!(created_item, retrieved_item);
+    }
+
+    #[tokio::test]
+    async fn test_update_link_not_found() {
+        let repo_query = LinkQueryBuilder::new("1", "user-id").build();
+        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+
+        let links_repository = LinksRepositoryProvider::default();
+        let response = links_repository.update(&repo_query, &item).await;
+
+        assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
+    }
+
+    #[tokio::test]
+    async fn test_update_created_link() {
+        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+
+        let links_repository = LinksRepositoryProvider::default();
+        let created_item = links_repository.create(&item).await.unwrap();
+
+        let repo_query = LinkQueryBuilder::new(created_item.id(), "user-id").build();
+        let item = LinkItemBuilder::from(created_item.clone())
+            .title("title")
+            .build();
+        let updated_item = links_repository.update(&repo_query, &item).await.unwrap();
+        assert_eq!(updated_item.version(), 2);
+
+        let retrieved_item = links_repository.get_versioned(&repo_query).await.unwrap();
+
+        assert_eq!(updated_item, retrieved_item);
+    }
+
+    #[tokio::test]
+    async fn test_update_unmodified_link() {
+        let item = LinkItemBuilder::new("http://link")
+            .owner("user-id")
+            .updated_at(&Utc::now())
+            .build();
+
+        let links_repository = LinksRepositoryProvider::default();
+        let created_item = links_repository.create(&item).await.unwrap();
+
+        let repo_query = LinkQueryBuilder::new(created_item.id(), "user-id").build();
+        let item = LinkItemBuilder::from(created_item.clone())
+            .title("title")
+            .updated_at(&Utc::now())
+            .build();
+        let updated_item = links_repository
+            .update_if_unmodified(&repo_query, &item, 1)
+            .await
+            .unwrap();
+        assert_eq!(updated_item, VersionedLink::new(&item, 2));
+
+        // the link has moved on since it was created
+        let response = links_repository
+            .update_if_unmodified(&repo_query, &item, 1)
+            .await;
+        assert_eq!(
+            response,
+            Err(AppError::PreconditionFailed(created_item.id().to_owned()))
+        );
+
+        let retrieved_item = links_repository.get_versioned(&repo_query).await.unwrap();
+        assert_eq!(retrieved_item, updated_item);
+    }
+
+    #[tokio::test]
+    async fn test_delete_link_not_found() {
+        let repo_query = LinkQueryBuilder::new("1", "user-id").build();
+
+        let links_repository = LinksRepositoryProvider::default();
+        let response = links_repository.delete(&repo_query).await;
+
+        assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
+    }
+
+    #[tokio::test]
+    async fn test_delete_created_link() {
+        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+
+        let links_repository = LinksRepositoryProvider::default();
+        let created_item = links_repository.create(&item).await.unwrap();
+
+        let repo_query = LinkQueryBuilder::new(created_item.id(), "user-id").build();
+        links_repository.delete(&repo_query).await.unwrap();
+
+        let repo_query = LinkQueryBuilder::new(created_item.id(), "user-id").build();
+        let response = links_repository.get(&repo_query).await;
+
+        assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
+    }
+
+    #[tokio::test]
+    async fn test_get_user_not_found() {
+        let repo_query = UserQueryBuilder::new("user@test.com").build();
+
+        let users_repository = UsersRepositoryProvider::default();
+        let response = users_repository.get(&repo_query).await;
+
+        assert_eq!(
+            response,
+            Err(AppError::UserNotFound("user@test.com".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_created_user() {
+        let user = UserInfoBuilder::new("user@test.com", "test").build();
+
+        let users_repository = UsersRepositoryProvider::default();
+        let created_user = users_repository.create(&user).await.unwrap();
+
+        let repo_query = UserQueryBuilder::new("user@test.com").build();
+        let retrieved_user = users_repository.get(&repo_query).await.unwrap();
+
+        assert_eq!(created_user, retrieved_user);
+    }
+
+    #[tokio::test]
+    async fn test_get_snapshot_not_found() {
+        let snapshots_repository = SnapshotsRepositoryProvider::default();
+        let response = snapshots_repository.get("1").await;
+
+        assert_eq!(response, Err(AppError::SnapshotNotFound("1".into())));
+    }
+
+    #[tokio::test]
+    async fn test_get_saved_snapshot() {
+        let snapshot = Snapshot::new("1", "http://link", "text/html", "<p>link</p>", &Utc::now());
+
+        let snapshots_repository = SnapshotsRepositoryProvider::default();
+        let saved_snapshot = snapshots_repository.save(&snapshot).await.unwrap();
+        let retrieved_snapshot = snapshots_repository.get("1").await.unwrap();
+
+        assert_eq!(saved_snapshot, retrieved_snapshot);
+    }
+
+    #[tokio::test]
+    async fn test_delete_saved_snapshot() {
+        let snapshot = Snapshot::new("1", "http://link", "text/html", "<p>link</p>", &Utc::now());
+
+        let snapshots_repository = SnapshotsRepositoryProvider::default();
+        snapshots_repository.save(&snapshot).await.unwrap();
+        snapshots_repository.delete("1").await.unwrap();
+        let response = snapshots_repository.get("1").await;
+
+        assert_eq!(response, Err(AppError::SnapshotNotFound("1".into())));
+    }
+
+    #[tokio::test]
+    async fn test_get_health_not_found() {
+        let health_repository = HealthRepositoryProvider::default();
+        let response = health_repository.get("1").await;
+
+        assert_eq!(response, Err(AppError::HealthNotFound("1".into())));
+    }
+
+    #[tokio::test]
+    async fn test_find_saved_health() {
+        let health = LinkHealth::new("1", "http://link", 404, "", &Utc::now());
+        let updated_health = LinkHealth::new("1", "http://link", 200, "", &Utc::now());
+        let other_health = LinkHealth::new("2", "http://other-link", 200, "", &Utc::now());
+
+        let health_repository = HealthRepositoryProvider::default();
+        health_repository.save(&health).await.unwrap();
+        health_repository.save(&updated_health).await.unwrap();
+        health_repository.save(&other_health).await.unwrap();
+        let retrieved_health = health_repository.find(&["1".into()]).await.unwrap();
+
+        assert_eq!(retrieved_health, vec![updated_health]);
+    }
+
+    #[tokio::test]
+    async fn test_retain_saved_health() {
+        let health = LinkHealth::new("1", "http://link", 404, "", &Utc::now());
+        let deleted_health = LinkHealth::new("2", "http://deleted", 200, "", &Utc::now());
+
+        let health_repository = HealthRepositoryProvider::default();
+        health_repository.save(&health).await.unwrap();
+        health_repository.save(&deleted_health).await.unwrap();
+        health_repository.retain(&["1".into()]).await.unwrap();
+
+        assert_eq!(health_repository.get("1").await, Ok(health));
+        assert_eq!(
+            health_repository.get("2").await,
+            Err(AppError::HealthNotFound("2".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_saved_health() {
+        let health = LinkHealth::new("1", "http://link", 404, "", &Utc::now());
+
+        let health_repository = HealthRepositoryProvider::default();
+        health_repository.save(&health).await.unwrap();
+        health_repository.delete("1").await.unwrap();
+        let response = health_repository.get("1").await;
+
+        assert_eq!(response, Err(AppError::HealthNotFound("1".into())));
+    }
+
+    #[tokio::test]
+    async fn test_get_webhook_not_found() {
+        let webhooks_repository = WebhooksRepositoryProvider::default();
+        let response = webhooks_repository.get("1").await;
+
+        assert_eq!(response, Err(AppError::WebhookNotFound("1".into())));
+    }
+
+    #[tokio::test]
+    async fn test_find_created_webhooks() {
+        let webhook = Webhook::new("user-id", "http://hook", "secret", &[], &Utc::now());
+        let other_webhook = Webhook::new("other-id", "http://hook", "secret", &[], &Utc::now());
+
+        let webhooks_repository = WebhooksRepositoryProvider::default();
+        let created_webhook = webhooks_repository.create(&webhook).await.unwrap();
+        webhooks_repository.create(&other_webhook).await.unwrap();
+        let retrieved_webhooks = webhooks_repository.find("user-id").await.unwrap();
+
+        assert_eq!(created_webhook.id(), "1");
+        assert_eq!(retrieved_webhooks, vec![created_webhook]);
+    }
+
+    #[tokio::test]
+    async fn test_delete_created_webhook() {
+        let webhook = Webhook::new("user-id", "http://hook", "secret", &[], &Utc::now());
+        let delivery = WebhookDelivery::new("1", "link.created", "1", 200, 1, "", &Utc::now());
+
+        let webhooks_repository = WebhooksRepositoryProvider::default();
+        webhooks_repository.create(&webhook).await.unwrap();
+        webhooks_repository
+            .create_delivery(&delivery)
+            .await
+            .unwrap();
+        webhooks_repository.delete("1").await.unwrap();
+
+        assert_eq!(
+            webhooks_repository.get("1").await,
+            Err(AppError::WebhookNotFound("1".into()))
+        );
+        assert!(webhooks_repository
+            .find_deliveries("1")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_find_deliveries_newest_first() {
+        let delivery = WebhookDelivery::new("1", "link.created", "1", 500, 3, "", &Utc::now());
+        let retried_delivery =
+            WebhookDelivery::new("1", "link.updated", "1", 200, 1, "", &Utc::now());
+
+        let webhooks_repository = WebhooksRepositoryProvider::default();
+        webhooks_repository
+            .create_delivery(&delivery)
+            .await
+            .unwrap();
+        webhooks_repository
+            .create_delivery(&retried_delivery)
+            .await
+            .unwrap();
+        let deliveries = webhooks_repository.find_deliveries("1").await.unwrap();
+
+        assert_eq!(deliveries, vec![retried_delivery, delivery]);
+    }
+
+    #[tokio::test]
+    async fn test_restore_links_from_write_log() {
+        let directory = data_directory();
+        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+
+        let links_repository = LinksRepositoryProvider::persistent(&directory).unwrap();
+        let created_item = links_repository.create(&item).await.unwrap();
+        let deleted_item = links_repository.create(&item).await.unwrap();
+        let updated_item = LinkItemBuilder::from(created_item.clone())
+            .title("Link")
+            .build();
+        let repo_query = LinkQueryBuilder::new(created_item.id(), "user-id").build();
+        links_repository
+            .update(&repo_query, &updated_item)
+            .await
+            .unwrap();
+        let repo_query = LinkQueryBuilder::new(deleted_item.id(), "user-id").build();
+        links_repository.delete(&repo_query).await.unwrap();
+        drop(links_repository);
+
+        let links_repository = LinksRepositoryProvider::persistent(&directory).unwrap();
+        let repo_query = LinkQueryBuilder::default().user("user-id").build();
+        let retrieved_items = links_repository.find(&repo_query).await.unwrap();
+
+        assert_eq!(retrieved_items, vec![updated_item]);
+    }
+
+    #[tokio::test]
+    async fn test_restore_links_from_snapshot() {
+        let directory = data_directory();
+        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+
+        let links_repository = LinksRepositoryProvider::persistent(&directory).unwrap();
+        let created_item = links_repository.create(&item).await.unwrap();
+        let deleted_item = links_repository.create(&item).await.unwrap();
+        let repo_query = LinkQueryBuilder::new(deleted_item.id(), "user-id").build();
+        links_repository.delete(&repo_query).await.unwrap();
+        links_repository.snapshot().unwrap();
+        drop(links_repository);
+
+        let links_repository = LinksRepositoryProvider::persistent(&directory).unwrap();
+        let repo_query = LinkQueryBuilder::default().user("user-id").build();
+        let retrieved_items = links_repository.find(&repo_query).await.unwrap();
+        let next_item = links_repository.create(&item).await.unwrap();
+
+        assert_eq!(retrieved_items, vec![created_item]);
+        // the id of the deleted link is not handed out again
+        assert_eq!(next_item.id(), "3");
+    }
+
+    #[tokio::test]
+    async fn test_restore_ignores_incomplete_last_entry() {
+        let directory = data_directory();
+        let user = UserInfoBuilder::new("user@test.com", "test").build();
+
+        let users_repository = UsersRepositoryProvider::persistent(&directory).unwrap();
+        let created_user = users_repository.create(&user).await.unwrap();
+        drop(users_repository);
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(directory.join("users.log"))
+            .unwrap()
+            .write_all(br#"{"put":{"email":"#)
+            .unwrap();
+
+        let users_repository = UsersRepositoryProvider::persistent(&directory).unwrap();
+        let other_user = UserInfoBuilder::new("other@test.com", "test").build();
+        let created_other_user = users_repository.create(&other_user).await.unwrap();
+        drop(users_repository);
+
+        // the incomplete entry was cut off, so the one appended after it can be read back
+        let users_repository = UsersRepositoryProvider::persistent(&directory).unwrap();
+        let repo_query = UserQueryBuilder::new("user@test.com").build();
+        let retrieved_user = users_repository.get(&repo_query).await.unwrap();
+        let repo_query = UserQueryBuilder::new("other@test.com").build();
+        let retrieved_other_user = users_repository.get(&repo_query).await.unwrap();
+
+        assert_eq!(created_user, retrieved_user);
+        assert_eq!(created_other_user, retrieved_other_user);
+    }
+
+    #[tokio::test]
+    async fn test_restore_fails_on_corrupted_write_log() {
+        let directory = data_directory();
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("users.log"), "not json\n{}\n").unwrap();
+
+        let users_repository = UsersRepositoryProvider::persistent(&directory);
+
+        assert!(matches!(users_repository, Err(AppError::Database(_))));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn test_concurrent_links() {
+        const OWNERS: usize = 8;
+        const LINKS_PER_OWNER: usize = 250;
+
+        let links_repository = Arc::new(LinksRepositoryProvider::default());
+        let tasks: Vec<_> = (0..OWNERS)
+            .map(|owner| {
+                let links_repository = links_repository.clone();
+                tokio::spawn(async move {
+                    let owner = format!("user-{owner}");
+                    let item = LinkItemBuilder::new("http://link").owner(&owner).build();
+                    for _ in 0..LINKS_PER_OWNER {
+                        let created_item = links_repository.create(&item).await.unwrap();
+                        let repo_query = LinkQueryBuilder::new(created_item.id(), &owner).build();
+                        let updated_item = LinkItemBuilder::from(created_item.clone())
+                            .title("Link")
+                            .build();
+                        links_repository
+                            .update(&repo_query, &updated_item)
+                            .await
+                            .unwrap();
+                        // every other link is deleted again while others are being created
+                        if created_item.id().parse::<usize>().unwrap() % 2 == 0 {
+                            links_repository.delete(&repo_query).await.unwrap();
+                        }
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let repo_query = LinkQueryBuilder::default().build();
+        let all_items = links_repository.find(&repo_query).await.unwrap();
+        let ids: HashSet<&str> = all_items.iter().map(LinkItem::id).collect();
+        assert_eq!(ids.len(), all_items.len());
+        assert_eq!(all_items.len(), OWNERS * LINKS_PER_OWNER / 2);
+        assert!(all_items.iter().all(|item| item.title() == "Link"));
+
+        let next_item = links_repository
+            .create(&LinkItemBuilder::new("http://link").owner("user-0").build())
+            .await
+            .unwrap();
+        assert_eq!(next_item.id(), (OWNERS * LINKS_PER_OWNER + 1).to_string());
+
+        for owner in 0..OWNERS {
+            let owner = format!("user-{owner}");
+            let repo_query = LinkQueryBuilder::default().user(&owner).build();
+            let owned_items = links_repository.find(&repo_query).await.unwrap();
+            assert!(owned_items.iter().all(|item| item.owner() == owner));
+        }
+        let owned_count: usize = (0..OWNERS)
+            .map(|owner| {
+                links_repository
+                    .links_by_owner
+                    .get(&format!("user-{owner}"))
+                    .map_or(0, |ids| ids.len())
+            })
+            .sum();
+        assert_eq!(owned_count, OWNERS * LINKS_PER_OWNER / 2 + 1);
+    }
+
+    #[tokio::test]
+    async fn test_restore_trashed_link() {
+        let item = LinkItemBuilder::new("http://link").owner("user-id").build();
+
+        let links_repository = LinksRepositoryProvider::default();
+        let created_item = links_repository.create(&item).await.unwrap();
+        let repo_query = LinkQueryBuilder::new(created_item.id(), "user-id").build();
+        links_repository
+            .trash(&repo_query, &Utc::now())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            links_repository.get(&repo_query).await,
+            Err(AppError::LinkNotFound(created_item.id().into()))
+        );
+        assert!(links_repository.find(&repo_query).await.unwrap().is_empty());
+
+        let restored_item = links_repository.restore(created_item.id()).await.unwrap();
+        let retrieved_item = links_repository.get(&repo_query).await.unwrap();
+
+        assert_eq!(restored_item, created_item);
+        assert_eq!(retrieved_item, created_item);
+        assert_eq!(
+            links_repository.restore(created_item.id()).await,
+            Err(AppError::LinkNotFound(created_item.id().into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_find_trashed_links() {
+        let links_repository = LinksRepositoryProvider::default();
+        let link = links_repository
+            .create(&LinkItemBuilder::new("http://link").owner("user-id").build())
+            .await
+            .unwrap();
+        let other_link = links_repository
+            .create(
+                &LinkItemBuilder::new("http://link")
+                    .owner("other-id")
+                    .build(),
+            )
+            .await
+            .unwrap();
+        links_repository
+            .create(&LinkItemBuilder::new("http://link").owner("user-id").build())
+            .await
+            .unwrap();
+
+        let deleted_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        links_repository
+            .trash(
+                &LinkQueryBuilder::default().id(link.id()).build(),
+                &deleted_at,
+            )
+            .await
+            .unwrap();
+        links_repository
+            .trash(
+                &LinkQueryBuilder::default().id(other_link.id()).build(),
+                &(deleted_at + chrono::Duration::days(1)),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            links_repository.find_trashed("user-id").await.unwrap(),
+            vec![TrashedLink::new(&link, &deleted_at)]
+        );
+        assert_eq!(links_repository.find_trashed("").await.unwrap().len(), 2);
+        assert_eq!(
+            links_repository
+                .find_expired(&(deleted_at + chrono::Duration::hours(1)))
+                .await
+                .unwrap(),
+            vec![TrashedLink::new(&link, &deleted_at)]
+        );
+        assert_eq!(
+            links_repository.get_trashed(link.id()).await,
+            Ok(TrashedLink::new(&link, &deleted_at))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_trashed_link() {
+        let links_repository = LinksRepositoryProvider::default();
+        let link = links_repository
+            .create(&LinkItemBuilder::new("http://link").owner("user-id").build())
+            .await
+            .unwrap();
+        let repo_query = LinkQueryBuilder::default().id(link.id()).build();
+        links_repository
+            .trash(&repo_query, &Utc::now())
+            .await
+            .unwrap();
+        links_repository.delete(&repo_query).await.unwrap();
+
+        assert_eq!(
+            links_repository.get_trashed(link.id()).await,
+            Err(AppError::LinkNotFound(link.id().into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_trashed_link_survives_restart() {
+        let directory = data_directory();
+        let deleted_at = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let link = {
+            let links_repository = LinksRepositoryProvider::persistent(&directory).unwrap();
+            let link = links_repository
+                .create(&LinkItemBuilder::new("http://link").owner("user-id").build())
+                .await
+                .unwrap();
+            links_repository
+                .trash(
+                    &LinkQueryBuilder::default().id(link.id()).build(),
+                    &deleted_at,
+                )
+                .await
+                .unwrap();
+            link
+        };
+
+        let links_repository = LinksRepositoryProvider::persistent(&directory).unwrap();
+        assert_eq!(
+            links_repository.get_trashed(link.id()).await,
+            Ok(TrashedLink::new(&link, &deleted_at))
+        );
+        assert!(links_repository
+            .find(&LinkQueryBuilder::default().build())
+            .await
+            .unwrap()
+            .is_empty());
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_repositories_survive_restart() {
+        let directory = data_directory();
+        let now = Utc::now();
+        let (collection, webhook) = {
+            let repositories = Repositories::persistent(&directory).unwrap();
+            let collections_repo = &repositories.collections;
+            let collection = collections_repo
+                .create(&Collection::new("user-id", "Kept", "", 1, &now))
+                .await
+                .unwrap();
+            let deleted_collection = collections_repo
+                .create(&Collection::new("user-id", "Deleted", "", 2, &now))
+                .await
+                .unwrap();
+            collections_repo
+                .delete(deleted_collection.id())
+                .await
+                .unwrap();
+            repositories.snapshot().unwrap();
+
+            let collection = collections_repo
+                .update(&collection.with_links(&["1".to_owned()]))
+                .await
+                .unwrap();
+            let webhook = repositories
+                .webhooks
+                .create(&Webhook::new("user-id", "http://hook", "secret", &[], &now))
+                .await
+                .unwrap();
+            for event in ["link.created", "link.updated"] {
+                repositories
+                    .webhooks
+                    .create_delivery(&WebhookDelivery::new(
+                        webhook.id(),
+                        event,
+                        "1",
+                        200,
+                        1,
+                        "",
+                        &now,
+                    ))
+                    .await
+                    .unwrap();
+            }
+            (collection, webhook)
+        };
+
+        let repositories = Repositories::persistent(&directory).unwrap();
+        assert_eq!(
+            repositories.collections.find("user-id").await,
+            Ok(vec![collection])
+        );
+        let new_collection = repositories
+            .collections
+            .create(&Collection::new("user-id", "New", "", 3, &now))
+            .await
+            .unwrap();
+        assert_eq!(new_collection.id(), "3");
+        assert_eq!(
+            repositories.webhooks.find("user-id").await,
+            Ok(vec![webhook.clone()])
+        );
+        let deliveries = repositories
+            .webhooks
+            .find_deliveries(webhook.id())
+            .await
+            .unwrap();
+        let events: Vec<&str> = deliveries.iter().map(WebhookDelivery::event).collect();
+        assert_eq!(events, vec!["link.updated", "link.created"]);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_revisions() {
+        let link = LinkItemBuilder::new("http://link").id("1").build();
+        let other_link = LinkItemBuilder::new("http://other").id("2").build();
+
+        let revisions_repository = RevisionsRepositoryProvider::default();
+        let first = revisions_repository
+            .create(&Revision::new(&link, "user-id", "update", &[], &Utc::now()))
+            .await
+            .unwrap();
+        revisions_repository
+            .create(&Revision::new(
+                &other_link,
+                "user-id",
+                "update",
+                &[],
+                &Utc::now(),
+            ))
+            .await
+            .unwrap();
+        let second = revisions_repository
+            .create(&Revision::new(
+                &link,
+                "admin-id",
+                "delete",
+                &[],
+                &Utc::now(),
+            ))
+            .await
+            .unwrap();
+
+        assert_ne!(first.id(), second.id());
+        assert_eq!(
+            revisions_repository.find("1").await,
+            Ok(vec![first.clone(), second])
+        );
+        assert_eq!(revisions_repository.get(first.id()).await, Ok(first));
+
+        revisions_repository.delete("1").await.unwrap();
+        assert_eq!(revisions_repository.find("1").await, Ok(vec![]));
+        assert_eq!(revisions_repository.find("2").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_collections() {
+        let now = Utc::now();
+        let collections_repository = CollectionsRepositoryProvider::default();
+        let second = collections_repository
+            .create(&Collection::new("user-id", "Second", "", 2, &now))
+            .await
+            .unwrap();
+        let first = collections_repository
+            .create(&Collection::new("user-id", "First", "", 1, &now))
+            .await
+            .unwrap();
+        collections_repository
+            .create(&Collection::new("other-user-id", "Other", "", 0, &now))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            collections_repository.find("user-id").await,
+            Ok(vec![first.clone(), second.clone()])
+        );
+        assert_eq!(collections_repository.find("").await.unwrap().len(), 3);
+
+        let shared = second.with_members(&[Member::new("member-id", Role::Editor)]);
+        collections_repository.update(&shared).await.unwrap();
+        assert_eq!(
+            collections_repository.find_shared("member-id").await,
+            Ok(vec![shared])
+        );
+        assert_eq!(
+            collections_repository.find_shared("user-id").await,
+            Ok(vec![])
+        );
+
+        let updated = first.with_links(&["2".into(), "1".into()]);
+        collections_repository.update(&updated).await.unwrap();
+        assert_eq!(
+            collections_repository.get(updated.id()).await,
+            Ok(updated.clone())
+        );
+
+        collections_repository.delete(updated.id()).await.unwrap();
+        assert_eq!(
+            collections_repository.get(updated.id()).await,
+            Err(AppError::CollectionNotFound(updated.id().into()))
+        );
+        assert_eq!(
+            collections_repository.update(&updated).await,
+            Err(AppError::CollectionNotFound(updated.id().into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shares() {
+        let now = Utc::now();
+        let shares_repository = SharesRepositoryProvider::default();
+        let share = shares_repository
+            .create(&Share::new("user-id", "link", "1", None, &now).with_token("token"))
+            .await
+            .unwrap();
+        shares_repository
+            .create(&Share::new("other-user-id", "link", "2", None, &now).with_token("other"))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            shares_repository.find("user-id").await,
+            Ok(vec![share.clone()])
+        );
+        assert_eq!(shares_repository.find("").await.unwrap().len(), 2);
+        assert_eq!(
+            shares_repository.get_by_token("token").await,
+            Ok(share.clone())
+        );
+
+        shares_repository.delete(share.id()).await.unwrap();
+        assert_eq!(
+            shares_repository.get_by_token("token").await,
+            Err(AppError::ShareNotFound("token".into()))
+        );
+        assert_eq!(
+            shares_repository.delete(share.id()).await,
+            Err(AppError::ShareNotFound(share.id().into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reminders() {
+        let now = Utc::now();
+        let reminders_repository = RemindersRepositoryProvider::default();
+        let later = reminders_repository
+            .create(&Reminder::new(
+                "user-id",
+                "link",
+                "1",
+                &(now + chrono::Duration::hours(1)),
+                &now,
+            ))
+            .await
+            .unwrap();
+        let due = reminders_repository
+            .create(&Reminder::new("user-id", "link", "2", &now, &now))
+            .await
+            .unwrap();
+        reminders_repository
+            .create(&Reminder::new("other-user-id", "digest", "", &now, &now))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            reminders_repository.find("user-id").await,
+            Ok(vec![due.clone(), later.clone()])
+        );
+        assert_eq!(reminders_repository.find_due(&now).await.unwrap().len(), 2);
+
+        let notified = due.with_notified_at(&now);
+        reminders_repository.update(&notified).await.unwrap();
+        assert_eq!(reminders_repository.find_due(&now).await.unwrap().len(), 1);
+        assert_eq!(reminders_repository.get(notified.id()).await, Ok(notified));
+
+        reminders_repository.delete(later.id()).await.unwrap();
+        assert_eq!(
+            reminders_repository.get(later.id()).await,
+            Err(AppError::ReminderNotFound(later.id().into()))
+        );
+        assert_eq!(
+            reminders_repository.update(&later).await,
+            Err(AppError::ReminderNotFound(later.id().into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_claim_reminder() {
+        let now = Utc::now();
+        let reminders_repository = RemindersRepositoryProvider::default();
+        let reminder = reminders_repository
+            .create(&Reminder::new("user-id", "link", "1", &now, &now))
+            .await
+            .unwrap();
+
+        assert_eq!(reminders_repository.claim(&reminder, &now).await, Ok(true));
+        assert_eq!(reminders_repository.claim(&reminder, &now).await, Ok(false));
+        assert!(reminders_repository
+            .find_due(&now)
+            .await
+            .unwrap()
+            .is_empty());
+
+        // a reminder snoozed since it was read is left alone
+        let snoozed = reminder.with_remind_at(&(now + chrono::Duration::hours(1)));
+        reminders_repository.update(&snoozed).await.unwrap();
+        let outdated = snoozed.clone().with_remind_at(&now);
+        assert_eq!(reminders_repository.claim(&outdated, &now).await, Ok(false));
+        assert_eq!(reminders_repository.get(snoozed.id()).await, Ok(snoozed));
+    }
+
+    #[tokio::test]
+    async fn test_details() {
+        let now = Utc::now();
+        let details = LinkDetails::new("1", "user-id", Priority::High, Some(4), "*good*", &now);
+        let other_details = LinkDetails::new("2", "other-id", Priority::Low, None, "", &now);
+        let updated_details = LinkDetails::new("1", "user-id", Priority::Low, Some(5), "", &now);
+
+        let details_repository = DetailsRepositoryProvider::default();
+        details_repository.save(&details).await.unwrap();
+        details_repository.save(&other_details).await.unwrap();
+        details_repository.save(&updated_details).await.unwrap();
+
+        assert_eq!(
+            details_repository.find("user-id").await,
+            Ok(vec![updated_details.clone()])
+        );
+        assert_eq!(details_repository.find("").await.unwrap().len(), 2);
+        assert_eq!(details_repository.get("1").await, Ok(updated_details));
+
+        details_repository.delete("1").await.unwrap();
+        details_repository.delete("1").await.unwrap();
+        assert_eq!(
+            details_repository.get("1").await,
+            Err(AppError::DetailsNotFound("1".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_highlights() {
+        let now = Utc::now();
+        let selectors = [Selector::TextPositionSelector { start: 10, end: 20 }];
+        let highlights_repository = HighlightsRepositoryProvider::default();
+        let first = highlights_repository
+            .create(&Highlight::new(
+                "1", "user-id", "first", "", &selectors, &now,
+            ))
+            .await
+            .unwrap();
+        let second = highlights_repository
+            .create(&Highlight::new("1", "user-id", "second", "note", &[], &now))
+            .await
+            .unwrap();
+        let other = highlights_repository
+            .create(&Highlight::new("2", "other-id", "other", "", &[], &now))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            highlights_repository.find("1").await,
+            Ok(vec![first.clone(), second.clone()])
+        );
+        assert_eq!(
+            highlights_repository.find_by_owner("other-id").await,
+            Ok(vec![other.clone()])
+        );
+
+        let updated = Highlight::new("1", "user-id", "first", "a note", &selectors, &now)
+            .with_id(first.id())
+            .with_updated_at(&Utc::now());
+        highlights_repository.update(&updated).await.unwrap();
+        assert_eq!(highlights_repository.get(first.id()).await, Ok(updated));
+
+        highlights_repository.delete(second.id()).await.unwrap();
+        assert_eq!(
+            highlights_repository.delete(second.id()).await,
+            Err(AppError::HighlightNotFound(second.id().into()))
+        );
+        highlights_repository.delete_all("1").await.unwrap();
+        assert!(highlights_repository.find("1").await.unwrap().is_empty());
+        assert_eq!(highlights_repository.find("2").await, Ok(vec![other]));
+    }
+
+    #[tokio::test]
+    async fn test_progress() {
+        let now = Utc::now();
+        let progress = ReadingProgress::new("1", "user-id", 40, 1200, 60, &now);
+        let other_progress = ReadingProgress::new("2", "other-id", 10, 0, 5, &now);
+        let finished_progress =
+            ReadingProgress::new("1", "user-id", 100, 3000, 180, &now).with_finished_at(&now);
+
+        let progress_repository = ProgressRepositoryProvider::default();
+        progress_repository.save(&progress).await.unwrap();
+        progress_repository.save(&other_progress).await.unwrap();
+        progress_repository.save(&finished_progress).await.unwrap();
+
+        assert_eq!(
+            progress_repository.find("user-id").await,
+            Ok(vec![finished_progress.clone()])
+        );
+        assert_eq!(progress_repository.find("").await.unwrap().len(), 2);
+        assert_eq!(progress_repository.get("1").await, Ok(finished_progress));
+
+        progress_repository.delete("1").await.unwrap();
+        progress_repository.delete("1").await.unwrap();
+        assert_eq!(
+            progress_repository.get("1").await,
+            Err(AppError::ProgressNotFound("1".into()))
+        );
     }
 }
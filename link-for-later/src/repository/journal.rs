@@ -0,0 +1,181 @@
+//! Durable storage for the in-memory repositories.
+//!
+//! Every change is appended to a write log as one JSON line, and from time to
+//! time the whole collection is written out as a snapshot and the log is
+//! truncated. Restoring loads the latest snapshot and replays the log on top.
+//! The highest id handed out is kept as well, so ids of deleted items are not
+//! reused after a restart.
+
+use std::{
+    collections::HashMap,
+    fs::{self, File, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::types::{AppError, Result};
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Entry<T> {
+    Put(T),
+    Delete(String),
+}
+
+#[derive(Serialize, Deserialize)]
+struct Contents<I> {
+    last_id: usize,
+    items: I,
+}
+
+pub struct Journal {
+    snapshot_path: PathBuf,
+    log: Mutex<File>,
+    // the same log, synced to disk without holding the lock above
+    synced_log: Arc<File>,
+}
+
+impl Journal {
+    /// Opens the journal named `name` in `directory`, returning it along with
+    /// the items restored from it and the highest id handed out so far.
+    pub fn open<T, F>(directory: &Path, name: &str, key: F) -> Result<(Self, Vec<T>, usize)>
+    where
+        T: DeserializeOwned,
+        F: Fn(&T) -> &str,
+    {
+        fs::create_dir_all(directory)
+            .map_err(|e| AppError::Database(format!("create_dir_all() {e:?}")))?;
+        let snapshot_path = directory.join(format!("{name}.json"));
+        let log_path = directory.join(format!("{name}.log"));
+
+        let Contents::<Vec<T>> {
+            mut last_id,
+            items: snapshot_items,
+        } = match fs::read(&snapshot_path) {
+            Ok(contents) => serde_json::from_slice(&contents)
+                .map_err(|e| AppError::Database(format!("from_slice() {e:?}")))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => Contents {
+                last_id: 0,
+                items: Vec::new(),
+            },
+            Err(e) => return Err(AppError::Database(format!("read() {e:?}"))),
+        };
+        let mut items: HashMap<String, T> = snapshot_items
+            .into_iter()
+            .map(|item| (key(&item).to_owned(), item))
+            .collect();
+
+        let contents = match fs::read(&log_path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(AppError::Database(format!("read() {e:?}"))),
+        };
+
+        // every entry ends with a newline, anything after the last one is
+        // what a crash in the middle of an append left behind
+        let complete_len = contents
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0, |position| position + 1);
+        let lines: Vec<&[u8]> = contents[..complete_len]
+            .split_inclusive(|&byte| byte == b'\n')
+            .collect();
+        let mut valid_len = 0;
+        for (index, line) in lines.iter().enumerate() {
+            let entry = match serde_json::from_slice::<Entry<T>>(line) {
+                Ok(entry) => entry,
+                Err(e) if index + 1 == lines.len() => {
+                    tracing::warn!("Ignoring incomplete entry at the end of {name}.log: {e}");
+                    break;
+                }
+                Err(e) => return Err(AppError::Database(format!("from_slice() {e:?}"))),
+            };
+            valid_len += line.len();
+            match entry {
+                Entry::Put(item) => {
+                    if let Ok(id) = key(&item).parse() {
+                        last_id = last_id.max(id);
+                    }
+                    items.insert(key(&item).to_owned(), item);
+                }
+                Entry::Delete(id) => {
+                    items.remove(&id);
+                }
+            }
+        }
+
+        let log = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+            .map_err(|e| AppError::Database(format!("open() {e:?}")))?;
+        // cut off the incomplete entry so that the next append starts on a
+        // line of its own
+        if valid_len < contents.len() {
+            tracing::warn!("Truncating {name}.log to its last complete entry");
+            log.set_len(valid_len as u64)
+                .map_err(|e| AppError::Database(format!("set_len() {e:?}")))?;
+        }
+
+        let synced_log = log
+            .try_clone()
+            .map_err(|e| AppError::Database(format!("try_clone() {e:?}")))?;
+        let journal = Self {
+            snapshot_path,
+            log: Mutex::new(log),
+            synced_log: Arc::new(synced_log),
+        };
+        Ok((journal, items.into_values().collect(), last_id))
+    }
+
+    /// Writes `entry` to the log. It only survives a crash once [`sync`] has
+    /// returned as well.
+    ///
+    /// [`sync`]: Journal::sync
+    pub fn append<T: Serialize>(&self, entry: &Entry<T>) -> Result<()> {
+        let mut line =
+            serde_json::to_vec(entry).map_err(|e| AppError::Database(format!("to_vec() {e:?}")))?;
+        line.push(b'\n');
+
+        let mut log = self
+            .log
+            .lock()
+            .map_err(|e| AppError::Database(format!("append() {e:?}")))?;
+        log.write_all(&line)
+            .map_err(|e| AppError::Database(format!("write_all() {e:?}")))
+    }
+
+    /// Waits until every entry appended so far is on disk. The wait happens
+    /// off the async runtime, so no lock should be held across it.
+    pub async fn sync(&self) -> Result<()> {
+        let synced_log = Arc::clone(&self.synced_log);
+        tokio::task::spawn_blocking(move || synced_log.sync_data())
+            .await
+            .map_err(|e| AppError::Database(format!("spawn_blocking() {e:?}")))?
+            .map_err(|e| AppError::Database(format!("sync_data() {e:?}")))
+    }
+
+    /// Replaces the snapshot with `items` and empties the log. Callers must
+    /// keep appends out while this runs so no change is lost in between.
+    pub fn snapshot<T: Serialize>(&self, items: &[T], last_id: usize) -> Result<()> {
+        let contents = serde_json::to_vec(&Contents { last_id, items })
+            .map_err(|e| AppError::Database(format!("to_vec() {e:?}")))?;
+
+        // write to a temporary file first so that a crash never leaves a partial snapshot
+        let temporary_path = self.snapshot_path.with_extension("json.tmp");
+        File::create(&temporary_path)
+            .and_then(|mut file| file.write_all(&contents).and_then(|()| file.sync_all()))
+            .map_err(|e| AppError::Database(format!("write_all() {e:?}")))?;
+        fs::rename(&temporary_path, &self.snapshot_path)
+            .map_err(|e| AppError::Database(format!("rename() {e:?}")))?;
+
+        self.log
+            .lock()
+            .map_err(|e| AppError::Database(format!("snapshot() {e:?}")))?
+            .set_len(0)
+            .map_err(|e| AppError::Database(format!("set_len() {e:?}")))
+    }
+}
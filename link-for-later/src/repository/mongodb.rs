@@ -1,13 +1,39 @@
+use std::collections::BTreeMap;
+
 use axum::async_trait;
-use bson::{doc, to_document};
+use bson::{doc, oid::ObjectId, to_bson, to_document, Bson, Document};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
 use futures::TryStreamExt;
-use mongodb::{options::ReplaceOptions, Collection, Database};
+use mongodb::{
+    error::{ErrorKind, WriteError, WriteFailure},
+    gridfs::FilesCollectionDocument,
+    options::{
+        FindOneAndUpdateOptions, FindOptions, GridFsBucketOptions, GridFsFindOptions,
+        GridFsUploadOptions, ReplaceOptions, ReturnDocument,
+    },
+    Collection, Database, GridFsBucket,
+};
 
-use crate::types::{
-    AppError, LinkItem, LinkItemBuilder, LinkQuery, Result, UserInfo, UserInfoBuilder, UserQuery,
+use reqwest::Url;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    service::links::READ_LABEL,
+    types::{
+        AppError, BacklogAge, Collection as LinkCollection, DailyCount, DomainCount, Highlight,
+        LibraryStats, LinkCounts, LinkDetails, LinkHealth, LinkItem, LinkItemBuilder, LinkQuery,
+        ReadingProgress, Reminder, Result, Revision, Share, Snapshot, StoredWebhook, TrashedLink,
+        UserInfo, UserInfoBuilder, UserQuery, VersionedLink, Webhook, WebhookDelivery,
+    },
 };
 
-use super::{Links as LinksRepository, Users as UsersRepository};
+use super::{
+    Collections as CollectionsRepository, Details as DetailsRepository, Health as HealthRepository,
+    Highlights as HighlightsRepository, Links as LinksRepository, Progress as ProgressRepository,
+    Reminders as RemindersRepository, Revisions as RevisionsRepository, Shares as SharesRepository,
+    Snapshots as SnapshotsRepository, Users as UsersRepository, Webhooks as WebhooksRepository,
+    BACKLOG_AGES,
+};
 
 const LINKS_COLLECTION_NAME_KEY: &str = "LINKS_COLLECTION_NAME";
 const LINKS_COLLECTION_NAME_DEFAULT: &str = "v1/links";
@@ -15,20 +41,462 @@ const LINKS_COLLECTION_NAME_DEFAULT: &str = "v1/links";
 const USERS_COLLECTION_NAME_KEY: &str = "USERS_COLLECTION_NAME";
 const USERS_COLLECTION_NAME_DEFAULT: &str = "v1/users";
 
+const SNAPSHOTS_BUCKET_NAME_KEY: &str = "SNAPSHOTS_BUCKET_NAME";
+const SNAPSHOTS_BUCKET_NAME_DEFAULT: &str = "v1/snapshots";
+
+const HEALTH_COLLECTION_NAME_KEY: &str = "HEALTH_COLLECTION_NAME";
+const HEALTH_COLLECTION_NAME_DEFAULT: &str = "v1/health";
+
+const WEBHOOKS_COLLECTION_NAME_KEY: &str = "WEBHOOKS_COLLECTION_NAME";
+const WEBHOOKS_COLLECTION_NAME_DEFAULT: &str = "v1/webhooks";
+
+const DELIVERIES_COLLECTION_NAME_KEY: &str = "DELIVERIES_COLLECTION_NAME";
+const DELIVERIES_COLLECTION_NAME_DEFAULT: &str = "v1/webhook_deliveries";
+
+const REVISIONS_COLLECTION_NAME_KEY: &str = "REVISIONS_COLLECTION_NAME";
+const REVISIONS_COLLECTION_NAME_DEFAULT: &str = "v1/revisions";
+
+const COLLECTIONS_COLLECTION_NAME_KEY: &str = "COLLECTIONS_COLLECTION_NAME";
+const COLLECTIONS_COLLECTION_NAME_DEFAULT: &str = "v1/collections";
+
+const SHARES_COLLECTION_NAME_KEY: &str = "SHARES_COLLECTION_NAME";
+const SHARES_COLLECTION_NAME_DEFAULT: &str = "v1/shares";
+
+const REMINDERS_COLLECTION_NAME_KEY: &str = "REMINDERS_COLLECTION_NAME";
+const REMINDERS_COLLECTION_NAME_DEFAULT: &str = "v1/reminders";
+
+const DETAILS_COLLECTION_NAME_KEY: &str = "DETAILS_COLLECTION_NAME";
+const DETAILS_COLLECTION_NAME_DEFAULT: &str = "v1/details";
+
+const HIGHLIGHTS_COLLECTION_NAME_KEY: &str = "HIGHLIGHTS_COLLECTION_NAME";
+const HIGHLIGHTS_COLLECTION_NAME_DEFAULT: &str = "v1/highlights";
+
+const PROGRESS_COLLECTION_NAME_KEY: &str = "PROGRESS_COLLECTION_NAME";
+const PROGRESS_COLLECTION_NAME_DEFAULT: &str = "v1/progress";
+
+mod migrations;
+
+/// Applies the pending migrations, see [`migrations`].
+pub async fn migrate(db: &Database) -> Result<()> {
+    migrations::run(db).await
+}
+
+fn collection_name(key: &str, default: &str) -> String {
+    std::env::var(key).unwrap_or_else(|_| default.to_owned())
+}
+
+fn is_duplicate_key(e: &mongodb::error::Error) -> bool {
+    matches!(
+        *e.kind,
+        ErrorKind::Write(WriteFailure::WriteError(WriteError { code: 11000, .. }))
+    )
+}
+
+/// The url links are indexed by, so the same page saved with a differently
+/// cased host, an explicit default port or a fragment can still be found.
+fn normalized_url(url: &str) -> String {
+    Url::parse(url).map_or_else(
+        |_| url.trim().to_owned(),
+        |mut url| {
+            url.set_fragment(None);
+            url.to_string()
+        },
+    )
+}
+
+/// Deserializes every document, failing on the first malformed one unless
+/// `skip_malformed` is set, in which case those are reported and left out.
+fn from_documents<T: DeserializeOwned>(
+    documents: Vec<Document>,
+    skip_malformed: bool,
+) -> Result<Vec<T>> {
+    let mut items = Vec::with_capacity(documents.len());
+    for document in documents {
+        match bson::from_document(document.clone()) {
+            Ok(item) => items.push(item),
+            Err(e) if skip_malformed => {
+                tracing::warn!(
+                    "Skipping malformed document {}: {}",
+                    document
+                        .get("_id")
+                        .map(ToString::to_string)
+                        .unwrap_or_default(),
+                    e
+                );
+            }
+            Err(e) => return Err(AppError::Database(format!("from_document() {e:?}"))),
+        }
+    }
+    Ok(items)
+}
+
+/// The filter of `query`, leaving out the links in the trash.
+fn live_links(query: &LinkQuery) -> Result<Document> {
+    let mut db_query =
+        to_document(query).map_err(|_| AppError::Database("to_document failed".into()))?;
+    db_query.insert("deleted_at", Bson::Null);
+    Ok(db_query)
+}
+
+fn link_document(item: &LinkItem) -> Result<Document> {
+    let mut document =
+        to_document(item).map_err(|_| AppError::Database("to_document failed".into()))?;
+    document.insert("normalized_url", normalized_url(item.url()));
+    Ok(document)
+}
+
+/// The version of a document of the links collection, where links written
+/// before they had versions are at the first one.
+fn version_of(document: &Document) -> u64 {
+    u64::try_from(document.get_i64("version").unwrap_or(1)).unwrap_or_default()
+}
+
+fn versioned_link(document: Document) -> Result<VersionedLink> {
+    let version = version_of(&document);
+    let link: LinkItem = bson::from_document(document)
+        .map_err(|e| AppError::Database(format!("from_document() {e:?}")))?;
+    Ok(VersionedLink::new(&link, version))
+}
+
+/// Inserts `document` under `id`, the same id the caller has already put in
+/// the document itself, so it is complete in a single write.
+async fn insert_with_id<T: Send + Sync>(
+    collection: &Collection<T>,
+    mut document: Document,
+    id: ObjectId,
+) -> mongodb::error::Result<()> {
+    document.insert("_id", id);
+    collection
+        .clone_with_type::<Document>()
+        .insert_one(document, None)
+        .await?;
+    Ok(())
+}
+
+/// The number of links in a group of the links stats pipeline, along with
+/// the day the oldest of them was saved when the group asks for it.
+#[derive(Deserialize)]
+struct GroupCount<K> {
+    #[serde(rename = "_id")]
+    key: Option<K>,
+    count: usize,
+    #[serde(default)]
+    oldest: Option<String>,
+}
+
+/// What each facet of the links stats pipeline adds up to.
+#[derive(Deserialize)]
+struct LinksStatsFacets {
+    counts: Vec<GroupCount<bool>>,
+    domains: Vec<GroupCount<String>>,
+    saved: Vec<GroupCount<String>>,
+    backlog: Vec<GroupCount<usize>>,
+}
+
+/// Builds the pipeline adding up the links of `owner`, with one facet for
+/// each part of the stats. Days are compared as the `YYYY-MM-DD` prefix of
+/// the timestamps links are stored with.
+fn links_stats_pipeline(
+    owner: &str,
+    since: NaiveDate,
+    today: NaiveDate,
+    domains: i64,
+) -> Vec<Document> {
+    let day_of = |field: &str| doc! {"$substrBytes": [field, 0, 10]};
+    let in_window = doc! {"day": {"$gte": since.to_string(), "$lte": today.to_string()}};
+    let host = doc! {"$arrayElemAt": [{"$split": ["$normalized_url", "/"]}, 2]};
+    let domain = doc! {"$arrayElemAt": [{"$split": [host, ":"]}, 0]};
+
+    let mut backlog_branches = vec![];
+    let mut bucket = 0;
+    for days in BACKLOG_AGES {
+        let cutoff = (today - Duration::days(days)).to_string();
+        backlog_branches.push(doc! {"case": {"$gt": ["$day", cutoff]}, "then": bucket});
+        bucket += 1;
+    }
+
+    let mut db_query = doc! {"deleted_at": null};
+    if !owner.is_empty() {
+        db_query.insert("owner", owner);
+    }
+    vec![
+        doc! {"$match": db_query},
+        doc! {"$facet": {
+            "counts": [
+                {"$group": {"_id": {"$eq": ["$label", READ_LABEL]}, "count": {"$sum": 1}}},
+            ],
+            "domains": [
+                {"$group": {"_id": domain, "count": {"$sum": 1}}},
+                {"$match": {"_id": {"$ne": null}}},
+                {"$sort": {"count": -1, "_id": 1}},
+                {"$limit": domains},
+            ],
+            "saved": [
+                {"$project": {"day": day_of("$created_at")}},
+                {"$match": in_window},
+                {"$group": {"_id": "$day", "count": {"$sum": 1}}},
+            ],
+            "backlog": [
+                {"$match": {"label": {"$ne": READ_LABEL}}},
+                {"$project": {"day": day_of("$created_at")}},
+                {"$group": {
+                    "_id": {"$switch": {"branches": backlog_branches, "default": bucket}},
+                    "count": {"$sum": 1},
+                    "oldest": {"$min": "$day"},
+                }},
+            ],
+        }},
+    ]
+}
+
+fn parse_day(day: &str) -> Result<NaiveDate> {
+    day.parse()
+        .map_err(|e| AppError::Database(format!("parse_day() {e:?}")))
+}
+
+impl TryFrom<LinksStatsFacets> for LibraryStats {
+    type Error = AppError;
+
+    fn try_from(facets: LinksStatsFacets) -> Result<Self> {
+        let count_of = |is_read: bool| {
+            facets
+                .counts
+                .iter()
+                .filter(|group| group.key == Some(is_read))
+                .map(|group| group.count)
+                .sum()
+        };
+        let counts = LinkCounts::new(count_of(false), count_of(true));
+
+        let domains: Vec<DomainCount> = facets
+            .domains
+            .iter()
+            .filter_map(|group| {
+                group
+                    .key
+                    .as_ref()
+                    .map(|domain| DomainCount::new(domain, group.count))
+            })
+            .collect();
+
+        let mut days: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+        for group in &facets.saved {
+            if let Some(day) = &group.key {
+                *days.entry(parse_day(day)?).or_default() += group.count;
+            }
+        }
+        let daily: Vec<DailyCount> = days
+            .into_iter()
+            .map(|(day, saved)| DailyCount::new(day, saved, 0))
+            .collect();
+
+        let mut backlog = [0; BACKLOG_AGES.len() + 1];
+        let mut oldest: Option<NaiveDate> = None;
+        for group in &facets.backlog {
+            if let Some(count) = group.key.and_then(|bucket| backlog.get_mut(bucket)) {
+                *count += group.count;
+            }
+            if let Some(day) = &group.oldest {
+                let day = parse_day(day)?;
+                oldest = Some(oldest.map_or(day, |oldest| oldest.min(day)));
+            }
+        }
+        let [week, month, quarter, older] = backlog;
+
+        Ok(Self::new(
+            counts,
+            &domains,
+            &daily,
+            BacklogAge::new(week, month, quarter, older, oldest),
+        ))
+    }
+}
+
 pub struct LinksRepositoryProvider {
     links_collection: Collection<LinkItem>,
+    skip_malformed_documents: bool,
+}
+
+pub struct RevisionsRepositoryProvider {
+    revisions_collection: Collection<Revision>,
+}
+
+pub struct CollectionsRepositoryProvider {
+    collections_collection: Collection<LinkCollection>,
+}
+
+pub struct SharesRepositoryProvider {
+    shares_collection: Collection<Share>,
+}
+
+pub struct RemindersRepositoryProvider {
+    reminders_collection: Collection<Reminder>,
+}
+
+pub struct DetailsRepositoryProvider {
+    details_collection: Collection<LinkDetails>,
+}
+
+pub struct HighlightsRepositoryProvider {
+    highlights_collection: Collection<Highlight>,
+}
+
+pub struct ProgressRepositoryProvider {
+    progress_collection: Collection<ReadingProgress>,
 }
 
 pub struct UsersRepositoryProvider {
     users_collection: Collection<UserInfo>,
 }
 
+pub struct SnapshotsRepositoryProvider {
+    snapshots_bucket: GridFsBucket,
+}
+
+pub struct HealthRepositoryProvider {
+    health_collection: Collection<LinkHealth>,
+}
+
+pub struct WebhooksRepositoryProvider {
+    webhooks_collection: Collection<StoredWebhook>,
+    deliveries_collection: Collection<WebhookDelivery>,
+}
+
 impl LinksRepositoryProvider {
     pub fn new(db: &Database) -> Self {
         let collection_name = std::env::var(LINKS_COLLECTION_NAME_KEY)
             .unwrap_or_else(|_| LINKS_COLLECTION_NAME_DEFAULT.to_owned());
         let links_collection = db.collection::<LinkItem>(&collection_name);
-        Self { links_collection }
+        Self {
+            links_collection,
+            skip_malformed_documents: false,
+        }
+    }
+
+    /// Leaves out the links that can't be read back instead of failing the
+    /// whole request, reporting each one as a warning.
+    #[must_use]
+    pub const fn skip_malformed_documents(mut self, skip_malformed_documents: bool) -> Self {
+        self.skip_malformed_documents = skip_malformed_documents;
+        self
+    }
+
+    async fn find_trashed_links(&self, db_query: Document) -> Result<Vec<TrashedLink>> {
+        let options = FindOptions::builder().sort(doc! {"deleted_at": 1}).build();
+        let result = self
+            .links_collection
+            .clone_with_type::<TrashedLink>()
+            .find(db_query, options)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+
+    /// Replaces the link matching `db_query`, if there is one, and moves it
+    /// to the next version.
+    async fn update_link(
+        &self,
+        db_query: Document,
+        item: &LinkItem,
+    ) -> Result<Option<VersionedLink>> {
+        let opts = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .projection(doc! {"version": 1})
+            .build();
+        let document = self
+            .links_collection
+            .clone_with_type::<Document>()
+            .find_one_and_update(
+                db_query,
+                doc! {"$set": link_document(item)?, "$inc": {"version": 1i64}},
+                opts,
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("find_one_and_update() {e:?}")))?;
+        Ok(document.map(|document| VersionedLink::new(item, version_of(&document))))
+    }
+}
+
+impl RevisionsRepositoryProvider {
+    pub fn new(db: &Database) -> Self {
+        let revisions_collection = db.collection::<Revision>(&collection_name(
+            REVISIONS_COLLECTION_NAME_KEY,
+            REVISIONS_COLLECTION_NAME_DEFAULT,
+        ));
+        Self {
+            revisions_collection,
+        }
+    }
+}
+
+impl CollectionsRepositoryProvider {
+    pub fn new(db: &Database) -> Self {
+        let collections_collection = db.collection::<LinkCollection>(&collection_name(
+            COLLECTIONS_COLLECTION_NAME_KEY,
+            COLLECTIONS_COLLECTION_NAME_DEFAULT,
+        ));
+        Self {
+            collections_collection,
+        }
+    }
+}
+
+impl SharesRepositoryProvider {
+    pub fn new(db: &Database) -> Self {
+        let shares_collection = db.collection::<Share>(&collection_name(
+            SHARES_COLLECTION_NAME_KEY,
+            SHARES_COLLECTION_NAME_DEFAULT,
+        ));
+        Self { shares_collection }
+    }
+}
+
+impl RemindersRepositoryProvider {
+    pub fn new(db: &Database) -> Self {
+        let reminders_collection = db.collection::<Reminder>(&collection_name(
+            REMINDERS_COLLECTION_NAME_KEY,
+            REMINDERS_COLLECTION_NAME_DEFAULT,
+        ));
+        Self {
+            reminders_collection,
+        }
+    }
+}
+
+impl DetailsRepositoryProvider {
+    pub fn new(db: &Database) -> Self {
+        let details_collection = db.collection::<LinkDetails>(&collection_name(
+            DETAILS_COLLECTION_NAME_KEY,
+            DETAILS_COLLECTION_NAME_DEFAULT,
+        ));
+        Self { details_collection }
+    }
+}
+
+impl HighlightsRepositoryProvider {
+    pub fn new(db: &Database) -> Self {
+        let highlights_collection = db.collection::<Highlight>(&collection_name(
+            HIGHLIGHTS_COLLECTION_NAME_KEY,
+            HIGHLIGHTS_COLLECTION_NAME_DEFAULT,
+        ));
+        Self {
+            highlights_collection,
+        }
+    }
+}
+
+impl ProgressRepositoryProvider {
+    pub fn new(db: &Database) -> Self {
+        let progress_collection = db.collection::<ReadingProgress>(&collection_name(
+            PROGRESS_COLLECTION_NAME_KEY,
+            PROGRESS_COLLECTION_NAME_DEFAULT,
+        ));
+        Self {
+            progress_collection,
+        }
     }
 }
 
@@ -41,60 +509,120 @@ impl UsersRepositoryProvider {
     }
 }
 
+impl HealthRepositoryProvider {
+    pub fn new(db: &Database) -> Self {
+        let collection_name = std::env::var(HEALTH_COLLECTION_NAME_KEY)
+            .unwrap_or_else(|_| HEALTH_COLLECTION_NAME_DEFAULT.to_owned());
+        let health_collection = db.collection::<LinkHealth>(&collection_name);
+        Self { health_collection }
+    }
+}
+
+impl WebhooksRepositoryProvider {
+    pub fn new(db: &Database) -> Self {
+        let collection_name = std::env::var(WEBHOOKS_COLLECTION_NAME_KEY)
+            .unwrap_or_else(|_| WEBHOOKS_COLLECTION_NAME_DEFAULT.to_owned());
+        let webhooks_collection = db.collection::<StoredWebhook>(&collection_name);
+        let collection_name = std::env::var(DELIVERIES_COLLECTION_NAME_KEY)
+            .unwrap_or_else(|_| DELIVERIES_COLLECTION_NAME_DEFAULT.to_owned());
+        let deliveries_collection = db.collection::<WebhookDelivery>(&collection_name);
+        Self {
+            webhooks_collection,
+            deliveries_collection,
+        }
+    }
+}
+
+impl SnapshotsRepositoryProvider {
+    pub fn new(db: &Database) -> Self {
+        let bucket_name = std::env::var(SNAPSHOTS_BUCKET_NAME_KEY)
+            .unwrap_or_else(|_| SNAPSHOTS_BUCKET_NAME_DEFAULT.to_owned());
+        let options = GridFsBucketOptions::builder()
+            .bucket_name(bucket_name)
+            .build();
+        let snapshots_bucket = db.gridfs_bucket(options);
+        Self { snapshots_bucket }
+    }
+
+    async fn find_files(&self, id: &str) -> Result<Vec<FilesCollectionDocument>> {
+        let options = GridFsFindOptions::builder()
+            .sort(doc! {"uploadDate": -1})
+            .build();
+        self.snapshots_bucket
+            .find(doc! {"filename": id}, options)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+}
+
 #[async_trait]
 impl LinksRepository for LinksRepositoryProvider {
     async fn find(&self, query: &LinkQuery) -> Result<Vec<LinkItem>> {
-        let db_query =
-            to_document(query).map_err(|_| AppError::Database("to_document failed".into()))?;
         let result = self
             .links_collection
-            .find(db_query, None)
+            .clone_with_type::<Document>()
+            .find(live_links(query)?, None)
             .await
             .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
-        Ok(result.try_collect().await.unwrap_or_else(|_| vec![]))
+        let documents = result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))?;
+        from_documents(documents, self.skip_malformed_documents)
     }
 
     async fn get(&self, query: &LinkQuery) -> Result<LinkItem> {
-        let db_query =
-            to_document(query).map_err(|_| AppError::Database("to_document failed".into()))?;
         let item = self
             .links_collection
-            .find_one(db_query, None)
+            .find_one(live_links(query)?, None)
             .await
             .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
         item.ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))
     }
 
-    async fn create(&self, item: &LinkItem) -> Result<LinkItem> {
-        let result = self
+    async fn get_versioned(&self, query: &LinkQuery) -> Result<VersionedLink> {
+        let document = self
             .links_collection
-            .insert_one(item, None)
+            .clone_with_type::<Document>()
+            .find_one(live_links(query)?, None)
             .await
-            .map_err(|e| AppError::Database(format!("insert_one() {e:?}")))?;
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        document
+            .ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))
+            .and_then(versioned_link)
+    }
 
-        let id = result.inserted_id.as_object_id().map_or_else(
-            || Err(AppError::Database("unexpected inserted_id()".into())),
-            |id| Ok(id.to_hex()),
-        )?;
-        let query = doc! {"_id": result.inserted_id};
-        let update = doc! {"$set": doc! { "id": &id } };
-        self.links_collection
-            .update_one(query, update, None)
+    async fn create(&self, item: &LinkItem) -> Result<LinkItem> {
+        let id = ObjectId::new();
+        let link = LinkItemBuilder::from(item.clone()).id(&id.to_hex()).build();
+        let mut document = link_document(&link)?;
+        document.insert("version", 1i64);
+        insert_with_id(&self.links_collection, document, id)
             .await
-            .map_err(|e| AppError::Database(format!("update_one() {e:?}")))?;
+            .map_err(|e| AppError::Database(format!("insert_one() {e:?}")))?;
+        Ok(link)
+    }
 
-        Ok(LinkItemBuilder::from(item.clone()).id(&id).build())
+    async fn update(&self, query: &LinkQuery, item: &LinkItem) -> Result<VersionedLink> {
+        self.update_link(live_links(query)?, item)
+            .await?
+            .ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))
     }
 
-    async fn update(&self, query: &LinkQuery, item: &LinkItem) -> Result<LinkItem> {
-        let db_query =
-            to_document(&query).map_err(|_| AppError::Database("to_document failed".into()))?;
-        let opts = ReplaceOptions::builder().upsert(true).build();
-        self.links_collection
-            .replace_one(db_query, item, Some(opts))
-            .await
-            .map_err(|e| AppError::Database(format!("replace_one() {e:?}")))?;
-        Ok(item.clone())
+    async fn update_if_unmodified(
+        &self,
+        query: &LinkQuery,
+        item: &LinkItem,
+        version: u64,
+    ) -> Result<VersionedLink> {
+        let mut db_query = live_links(query)?;
+        db_query.insert("version", i64::try_from(version).unwrap_or(i64::MAX));
+        self.update_link(db_query, item)
+            .await?
+            .ok_or_else(|| AppError::PreconditionFailed(query.id().to_owned()))
     }
 
     async fn delete(&self, query: &LinkQuery) -> Result<()> {
@@ -106,6 +634,88 @@ impl LinksRepository for LinksRepositoryProvider {
             .map_err(|e| AppError::Database(format!("delete_one() {e:?}")))?;
         Ok(())
     }
+
+    async fn trash(&self, query: &LinkQuery, deleted_at: &DateTime<Utc>) -> Result<()> {
+        let deleted_at =
+            to_bson(deleted_at).map_err(|e| AppError::Database(format!("to_bson() {e:?}")))?;
+        let result = self
+            .links_collection
+            .update_one(
+                live_links(query)?,
+                doc! {"$set": {"deleted_at": deleted_at}, "$inc": {"version": 1i64}},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("update_one() {e:?}")))?;
+        if result.matched_count == 0 {
+            return Err(AppError::LinkNotFound(query.id().to_owned()));
+        }
+        Ok(())
+    }
+
+    async fn find_trashed(&self, owner: &str) -> Result<Vec<TrashedLink>> {
+        let mut db_query = doc! {"deleted_at": {"$ne": null}};
+        if !owner.is_empty() {
+            db_query.insert("owner", owner);
+        }
+        self.find_trashed_links(db_query).await
+    }
+
+    async fn find_expired(&self, deleted_before: &DateTime<Utc>) -> Result<Vec<TrashedLink>> {
+        let deleted_before =
+            to_bson(deleted_before).map_err(|e| AppError::Database(format!("to_bson() {e:?}")))?;
+        self.find_trashed_links(doc! {"deleted_at": {"$lt": deleted_before}})
+            .await
+    }
+
+    async fn get_trashed(&self, id: &str) -> Result<TrashedLink> {
+        let trashed_link = self
+            .links_collection
+            .clone_with_type::<TrashedLink>()
+            .find_one(doc! {"id": id, "deleted_at": {"$ne": null}}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        trashed_link.ok_or_else(|| AppError::LinkNotFound(id.to_owned()))
+    }
+
+    async fn restore(&self, id: &str) -> Result<LinkItem> {
+        let opts = FindOneAndUpdateOptions::builder()
+            .return_document(ReturnDocument::After)
+            .build();
+        let item = self
+            .links_collection
+            .find_one_and_update(
+                doc! {"id": id, "deleted_at": {"$ne": null}},
+                doc! {"$unset": {"deleted_at": ""}, "$inc": {"version": 1i64}},
+                opts,
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("find_one_and_update() {e:?}")))?;
+        item.ok_or_else(|| AppError::LinkNotFound(id.to_owned()))
+    }
+
+    async fn stats(
+        &self,
+        owner: &str,
+        since: &NaiveDate,
+        today: &NaiveDate,
+        domains: usize,
+    ) -> Result<LibraryStats> {
+        let domains =
+            i64::try_from(domains).map_err(|e| AppError::Database(format!("stats() {e:?}")))?;
+        let facets = self
+            .links_collection
+            .aggregate(links_stats_pipeline(owner, *since, *today, domains), None)
+            .await
+            .map_err(|e| AppError::Database(format!("aggregate() {e:?}")))?
+            .try_next()
+            .await
+            .map_err(|e| AppError::Database(format!("try_next() {e:?}")))?
+            .ok_or_else(|| AppError::Database("aggregate() returned no facets".into()))?;
+        let facets: LinksStatsFacets = bson::from_document(facets)
+            .map_err(|e| AppError::Database(format!("from_document() {e:?}")))?;
+        LibraryStats::try_from(facets)
+    }
 }
 
 #[async_trait]
@@ -122,23 +732,754 @@ impl UsersRepository for UsersRepositoryProvider {
     }
 
     async fn create(&self, info: &UserInfo) -> Result<UserInfo> {
+        let id = ObjectId::new();
+        let user = UserInfoBuilder::from(info.clone()).id(&id.to_hex()).build();
+        let document =
+            to_document(&user).map_err(|_| AppError::Database("to_document failed".into()))?;
+        insert_with_id(&self.users_collection, document, id)
+            .await
+            .map_err(|e| {
+                // the unique index closes the race between two registrations
+                // of the same email that the service's lookup cannot
+                if is_duplicate_key(&e) {
+                    AppError::UserAlreadyExists(info.email().to_owned())
+                } else {
+                    AppError::Database(format!("insert_one() {e:?}"))
+                }
+            })?;
+        Ok(user)
+    }
+}
+
+#[async_trait]
+impl SnapshotsRepository for SnapshotsRepositoryProvider {
+    async fn get(&self, id: &str) -> Result<Snapshot> {
+        let file = self
+            .find_files(id)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| AppError::SnapshotNotFound(id.to_owned()))?;
+        let metadata = file.metadata.unwrap_or_default();
+
+        let mut content = Vec::new();
+        self.snapshots_bucket
+            .download_to_futures_0_3_writer(file.id, &mut content)
+            .await
+            .map_err(|e| AppError::Database(format!("download_to_futures_0_3_writer() {e:?}")))?;
+        let content = String::from_utf8(content)
+            .map_err(|e| AppError::Database(format!("from_utf8() {e:?}")))?;
+
+        let captured_at = metadata
+            .get_datetime("captured_at")
+            .unwrap_or(&file.upload_date)
+            .timestamp_millis();
+        let captured_at = Utc
+            .timestamp_millis_opt(captured_at)
+            .single()
+            .unwrap_or_default();
+
+        Ok(Snapshot::new(
+            id,
+            metadata.get_str("url").unwrap_or_default(),
+            metadata.get_str("content_type").unwrap_or_default(),
+            &content,
+            &captured_at,
+        ))
+    }
+
+    async fn save(&self, snapshot: &Snapshot) -> Result<Snapshot> {
+        let previous_files = self.find_files(snapshot.id()).await?;
+
+        let options = GridFsUploadOptions::builder()
+            .metadata(doc! {
+                "url": snapshot.url(),
+                "content_type": snapshot.content_type(),
+                "captured_at": bson::DateTime::from_millis(snapshot.captured_at().timestamp_millis()),
+            })
+            .build();
+        self.snapshots_bucket
+            .upload_from_futures_0_3_reader(
+                snapshot.id(),
+                futures::io::Cursor::new(snapshot.content().as_bytes()),
+                options,
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("upload_from_futures_0_3_reader() {e:?}")))?;
+
+        for file in previous_files {
+            self.snapshots_bucket
+                .delete(file.id)
+                .await
+                .map_err(|e| AppError::Database(format!("delete() {e:?}")))?;
+        }
+
+        self.get(snapshot.id()).await
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        for file in self.find_files(id).await? {
+            self.snapshots_bucket
+                .delete(file.id)
+                .await
+                .map_err(|e| AppError::Database(format!("delete() {e:?}")))?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HealthRepository for HealthRepositoryProvider {
+    async fn find(&self, ids: &[String]) -> Result<Vec<LinkHealth>> {
         let result = self
-            .users_collection
-            .insert_one(info, None)
+            .health_collection
+            .find(doc! {"id": {"$in": ids}}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+
+    async fn get(&self, id: &str) -> Result<LinkHealth> {
+        let health = self
+            .health_collection
+            .find_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        health.ok_or_else(|| AppError::HealthNotFound(id.to_owned()))
+    }
+
+    async fn save(&self, health: &LinkHealth) -> Result<LinkHealth> {
+        let opts = ReplaceOptions::builder().upsert(true).build();
+        self.health_collection
+            .replace_one(doc! {"id": health.id()}, health, Some(opts))
+            .await
+            .map_err(|e| AppError::Database(format!("replace_one() {e:?}")))?;
+        Ok(health.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.health_collection
+            .delete_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_one() {e:?}")))?;
+        Ok(())
+    }
+
+    async fn retain(&self, ids: &[String]) -> Result<()> {
+        self.health_collection
+            .delete_many(doc! {"id": {"$nin": ids}}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_many() {e:?}")))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl WebhooksRepository for WebhooksRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<Webhook>> {
+        let result = self
+            .webhooks_collection
+            .find(doc! {"owner": owner}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .map_ok(Webhook::from)
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+
+    async fn get(&self, id: &str) -> Result<Webhook> {
+        let webhook = self
+            .webhooks_collection
+            .find_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        webhook
+            .map(Webhook::from)
+            .ok_or_else(|| AppError::WebhookNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, webhook: &Webhook) -> Result<Webhook> {
+        let id = ObjectId::new();
+        let webhook = webhook.clone().with_id(&id.to_hex());
+        let document = to_document(&StoredWebhook::from(&webhook))
+            .map_err(|_| AppError::Database("to_document failed".into()))?;
+        insert_with_id(&self.webhooks_collection, document, id)
+            .await
+            .map_err(|e| AppError::Database(format!("insert_one() {e:?}")))?;
+        Ok(webhook)
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.webhooks_collection
+            .delete_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_one() {e:?}")))?;
+        self.deliveries_collection
+            .delete_many(doc! {"webhook_id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_many() {e:?}")))?;
+        Ok(())
+    }
+
+    async fn find_deliveries(&self, webhook_id: &str) -> Result<Vec<WebhookDelivery>> {
+        let options = FindOptions::builder().sort(doc! {"_id": -1}).build();
+        let result = self
+            .deliveries_collection
+            .find(doc! {"webhook_id": webhook_id}, options)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+
+    async fn create_delivery(&self, delivery: &WebhookDelivery) -> Result<WebhookDelivery> {
+        self.deliveries_collection
+            .insert_one(delivery, None)
             .await
             .map_err(|e| AppError::Database(format!("insert_one() {e:?}")))?;
+        Ok(delivery.clone())
+    }
+}
+
+#[async_trait]
+impl RevisionsRepository for RevisionsRepositoryProvider {
+    async fn find(&self, link_id: &str) -> Result<Vec<Revision>> {
+        let options = FindOptions::builder().sort(doc! {"_id": 1}).build();
+        let result = self
+            .revisions_collection
+            .find(doc! {"link_id": link_id}, options)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+
+    async fn get(&self, id: &str) -> Result<Revision> {
+        let revision = self
+            .revisions_collection
+            .find_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        revision.ok_or_else(|| AppError::RevisionNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, revision: &Revision) -> Result<Revision> {
+        let id = ObjectId::new();
+        let revision = revision.clone().with_id(&id.to_hex());
+        let document =
+            to_document(&revision).map_err(|_| AppError::Database("to_document failed".into()))?;
+        insert_with_id(&self.revisions_collection, document, id)
+            .await
+            .map_err(|e| AppError::Database(format!("insert_one() {e:?}")))?;
+        Ok(revision)
+    }
+
+    async fn delete(&self, link_id: &str) -> Result<()> {
+        self.revisions_collection
+            .delete_many(doc! {"link_id": link_id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_many() {e:?}")))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CollectionsRepository for CollectionsRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<LinkCollection>> {
+        let db_query = if owner.is_empty() {
+            doc! {}
+        } else {
+            doc! {"owner": owner}
+        };
+        let options = FindOptions::builder()
+            .sort(doc! {"position": 1, "created_at": 1})
+            .build();
+        let result = self
+            .collections_collection
+            .find(db_query, options)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+
+    async fn find_shared(&self, member: &str) -> Result<Vec<LinkCollection>> {
+        let options = FindOptions::builder()
+            .sort(doc! {"position": 1, "created_at": 1})
+            .build();
+        let result = self
+            .collections_collection
+            .find(doc! {"members.user": member}, options)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
 
-        let id = result.inserted_id.as_object_id().map_or_else(
-            || Err(AppError::Database("unexpected inserted_id()".into())),
-            |id| Ok(id.to_hex()),
-        )?;
-        let query = doc! {"_id": result.inserted_id};
-        let update = doc! {"$set": doc! { "id": &id } };
-        self.users_collection
-            .update_one(query, update, None)
+    async fn get(&self, id: &str) -> Result<LinkCollection> {
+        let collection = self
+            .collections_collection
+            .find_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        collection.ok_or_else(|| AppError::CollectionNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, collection: &LinkCollection) -> Result<LinkCollection> {
+        let id = ObjectId::new();
+        let collection = collection.clone().with_id(&id.to_hex());
+        let document = to_document(&collection)
+            .map_err(|_| AppError::Database("to_document failed".into()))?;
+        insert_with_id(&self.collections_collection, document, id)
+            .await
+            .map_err(|e| AppError::Database(format!("insert_one() {e:?}")))?;
+        Ok(collection)
+    }
+
+    async fn update(&self, collection: &LinkCollection) -> Result<LinkCollection> {
+        let result = self
+            .collections_collection
+            .replace_one(doc! {"id": collection.id()}, collection, None)
+            .await
+            .map_err(|e| AppError::Database(format!("replace_one() {e:?}")))?;
+        if result.matched_count == 0 {
+            return Err(AppError::CollectionNotFound(collection.id().to_owned()));
+        }
+        Ok(collection.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        let result = self
+            .collections_collection
+            .delete_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_one() {e:?}")))?;
+        if result.deleted_count == 0 {
+            return Err(AppError::CollectionNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SharesRepository for SharesRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<Share>> {
+        let db_query = if owner.is_empty() {
+            doc! {}
+        } else {
+            doc! {"owner": owner}
+        };
+        let options = FindOptions::builder().sort(doc! {"created_at": 1}).build();
+        let result = self
+            .shares_collection
+            .find(db_query, options)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+
+    async fn get(&self, id: &str) -> Result<Share> {
+        let share = self
+            .shares_collection
+            .find_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        share.ok_or_else(|| AppError::ShareNotFound(id.to_owned()))
+    }
+
+    async fn get_by_token(&self, token: &str) -> Result<Share> {
+        let share = self
+            .shares_collection
+            .find_one(doc! {"token": token}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        share.ok_or_else(|| AppError::ShareNotFound(token.to_owned()))
+    }
+
+    async fn create(&self, share: &Share) -> Result<Share> {
+        let id = ObjectId::new();
+        let share = share.clone().with_id(&id.to_hex());
+        let document =
+            to_document(&share).map_err(|_| AppError::Database("to_document failed".into()))?;
+        insert_with_id(&self.shares_collection, document, id)
+            .await
+            .map_err(|e| AppError::Database(format!("insert_one() {e:?}")))?;
+        Ok(share)
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        let result = self
+            .shares_collection
+            .delete_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_one() {e:?}")))?;
+        if result.deleted_count == 0 {
+            return Err(AppError::ShareNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl RemindersRepository for RemindersRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<Reminder>> {
+        let db_query = if owner.is_empty() {
+            doc! {}
+        } else {
+            doc! {"owner": owner}
+        };
+        let mut reminders: Vec<Reminder> = self
+            .reminders_collection
+            .find(db_query, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))?;
+        // times are stored as strings, which do not sort by the time they hold
+        reminders.sort_by_key(|reminder| *reminder.remind_at());
+        Ok(reminders)
+    }
+
+    async fn find_due(&self, now: &DateTime<Utc>) -> Result<Vec<Reminder>> {
+        let db_now = to_bson(now).map_err(|e| AppError::Database(format!("to_bson() {e:?}")))?;
+        let mut reminders: Vec<Reminder> = self
+            .reminders_collection
+            .find(
+                doc! {"notified_at": null, "remind_at": {"$lte": db_now}},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))?;
+        // the strings only compare like the times they hold down to the second
+        reminders.retain(|reminder| reminder.is_due(now));
+        reminders.sort_by_key(|reminder| *reminder.remind_at());
+        Ok(reminders)
+    }
+
+    async fn get(&self, id: &str) -> Result<Reminder> {
+        let reminder = self
+            .reminders_collection
+            .find_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        reminder.ok_or_else(|| AppError::ReminderNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, reminder: &Reminder) -> Result<Reminder> {
+        let id = ObjectId::new();
+        let reminder = reminder.clone().with_id(&id.to_hex());
+        let document =
+            to_document(&reminder).map_err(|_| AppError::Database("to_document failed".into()))?;
+        insert_with_id(&self.reminders_collection, document, id)
+            .await
+            .map_err(|e| AppError::Database(format!("insert_one() {e:?}")))?;
+        Ok(reminder)
+    }
+
+    async fn update(&self, reminder: &Reminder) -> Result<Reminder> {
+        let result = self
+            .reminders_collection
+            .replace_one(doc! {"id": reminder.id()}, reminder, None)
+            .await
+            .map_err(|e| AppError::Database(format!("replace_one() {e:?}")))?;
+        if result.matched_count == 0 {
+            return Err(AppError::ReminderNotFound(reminder.id().to_owned()));
+        }
+        Ok(reminder.clone())
+    }
+
+    async fn claim(&self, reminder: &Reminder, notified_at: &DateTime<Utc>) -> Result<bool> {
+        let remind_at = to_bson(reminder.remind_at())
+            .map_err(|e| AppError::Database(format!("to_bson() {e:?}")))?;
+        let notified_at =
+            to_bson(notified_at).map_err(|e| AppError::Database(format!("to_bson() {e:?}")))?;
+        let result = self
+            .reminders_collection
+            .update_one(
+                doc! {"id": reminder.id(), "notified_at": null, "remind_at": remind_at},
+                doc! {"$set": {"notified_at": notified_at}},
+                None,
+            )
             .await
             .map_err(|e| AppError::Database(format!("update_one() {e:?}")))?;
+        Ok(result.modified_count == 1)
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        let result = self
+            .reminders_collection
+            .delete_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_one() {e:?}")))?;
+        if result.deleted_count == 0 {
+            return Err(AppError::ReminderNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DetailsRepository for DetailsRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<LinkDetails>> {
+        let db_query = if owner.is_empty() {
+            doc! {}
+        } else {
+            doc! {"owner": owner}
+        };
+        let result = self
+            .details_collection
+            .find(db_query, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+
+    async fn get(&self, id: &str) -> Result<LinkDetails> {
+        let details = self
+            .details_collection
+            .find_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        details.ok_or_else(|| AppError::DetailsNotFound(id.to_owned()))
+    }
+
+    async fn save(&self, details: &LinkDetails) -> Result<LinkDetails> {
+        let opts = ReplaceOptions::builder().upsert(true).build();
+        self.details_collection
+            .replace_one(doc! {"id": details.id()}, details, Some(opts))
+            .await
+            .map_err(|e| AppError::Database(format!("replace_one() {e:?}")))?;
+        Ok(details.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.details_collection
+            .delete_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_one() {e:?}")))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl HighlightsRepository for HighlightsRepositoryProvider {
+    async fn find(&self, link_id: &str) -> Result<Vec<Highlight>> {
+        let options = FindOptions::builder().sort(doc! {"_id": 1}).build();
+        let result = self
+            .highlights_collection
+            .find(doc! {"link_id": link_id}, options)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+
+    async fn find_by_owner(&self, owner: &str) -> Result<Vec<Highlight>> {
+        let options = FindOptions::builder().sort(doc! {"_id": 1}).build();
+        let result = self
+            .highlights_collection
+            .find(doc! {"owner": owner}, options)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+
+    async fn get(&self, id: &str) -> Result<Highlight> {
+        let highlight = self
+            .highlights_collection
+            .find_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        highlight.ok_or_else(|| AppError::HighlightNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, highlight: &Highlight) -> Result<Highlight> {
+        let id = ObjectId::new();
+        let highlight = highlight.clone().with_id(&id.to_hex());
+        let document =
+            to_document(&highlight).map_err(|_| AppError::Database("to_document failed".into()))?;
+        insert_with_id(&self.highlights_collection, document, id)
+            .await
+            .map_err(|e| AppError::Database(format!("insert_one() {e:?}")))?;
+        Ok(highlight)
+    }
+
+    async fn update(&self, highlight: &Highlight) -> Result<Highlight> {
+        let result = self
+            .highlights_collection
+            .replace_one(doc! {"id": highlight.id()}, highlight, None)
+            .await
+            .map_err(|e| AppError::Database(format!("replace_one() {e:?}")))?;
+        if result.matched_count == 0 {
+            return Err(AppError::HighlightNotFound(highlight.id().to_owned()));
+        }
+        Ok(highlight.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        let result = self
+            .highlights_collection
+            .delete_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_one() {e:?}")))?;
+        if result.deleted_count == 0 {
+            return Err(AppError::HighlightNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+
+    async fn delete_all(&self, link_id: &str) -> Result<()> {
+        self.highlights_collection
+            .delete_many(doc! {"link_id": link_id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_many() {e:?}")))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProgressRepository for ProgressRepositoryProvider {
+    async fn find(&self, owner: &str) -> Result<Vec<ReadingProgress>> {
+        let db_query = if owner.is_empty() {
+            doc! {}
+        } else {
+            doc! {"owner": owner}
+        };
+        let result = self
+            .progress_collection
+            .find(db_query, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+        result
+            .try_collect()
+            .await
+            .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))
+    }
+
+    async fn get(&self, id: &str) -> Result<ReadingProgress> {
+        let progress = self
+            .progress_collection
+            .find_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("find_one() {e:?}")))?;
+        progress.ok_or_else(|| AppError::ProgressNotFound(id.to_owned()))
+    }
+
+    async fn save(&self, progress: &ReadingProgress) -> Result<ReadingProgress> {
+        let opts = ReplaceOptions::builder().upsert(true).build();
+        self.progress_collection
+            .replace_one(doc! {"id": progress.id()}, progress, Some(opts))
+            .await
+            .map_err(|e| AppError::Database(format!("replace_one() {e:?}")))?;
+        Ok(progress.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.progress_collection
+            .delete_one(doc! {"id": id}, None)
+            .await
+            .map_err(|e| AppError::Database(format!("delete_one() {e:?}")))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn documents() -> Vec<Document> {
+        let link = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user-id")
+            .build();
+        vec![
+            to_document(&link).unwrap(),
+            doc! {"_id": ObjectId::new(), "id": "2", "owner": "user-id", "url": 42},
+        ]
+    }
+
+    #[test]
+    fn test_from_documents_malformed() {
+        let items = from_documents::<LinkItem>(documents(), false);
+
+        assert!(matches!(items, Err(AppError::Database(_))));
+    }
+
+    #[test]
+    fn test_from_documents_skip_malformed() {
+        let items = from_documents::<LinkItem>(documents(), true).unwrap();
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id(), "1");
+    }
+
+    #[test]
+    fn test_normalized_url() {
+        assert_eq!(
+            normalized_url("HTTP://Example.COM:80/Path?q=1#section"),
+            "http://example.com/Path?q=1"
+        );
+        assert_eq!(
+            normalized_url("https://example.com"),
+            "https://example.com/"
+        );
+        assert_eq!(normalized_url(" not a url "), "not a url");
+    }
+
+    #[test]
+    fn test_links_stats_from_facets() {
+        let facets = doc! {
+            "counts": [{"_id": false, "count": 4}, {"_id": true, "count": 1}],
+            "domains": [{"_id": "example.com", "count": 3}],
+            "saved": [{"_id": "2024-05-14", "count": 1}, {"_id": "2024-05-10", "count": 1}],
+            "backlog": [
+                {"_id": 0, "count": 2, "oldest": "2024-05-10"},
+                {"_id": 3, "count": 1, "oldest": "2024-01-01"},
+            ],
+        };
+        let facets: LinksStatsFacets = bson::from_document(facets).unwrap();
+        let day = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
 
-        Ok(UserInfoBuilder::from(info.clone()).id(&id).build())
+        assert_eq!(
+            LibraryStats::try_from(facets),
+            Ok(LibraryStats::new(
+                LinkCounts::new(4, 1),
+                &[DomainCount::new("example.com", 3)],
+                &[
+                    DailyCount::new(day(5, 10), 1, 0),
+                    DailyCount::new(day(5, 14), 1, 0)
+                ],
+                BacklogAge::new(2, 0, 0, 1, Some(day(1, 1))),
+            ))
+        );
     }
 }
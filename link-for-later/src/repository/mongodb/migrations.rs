@@ -0,0 +1,300 @@
+//! Versioned migrations for the mongodb repositories.
+//!
+//! Each migration runs once, in order, and is recorded in the migrations
+//! collection when it completes. Migrations have to be safe to run again,
+//! since two instances starting together may both apply a pending one before
+//! either records it.
+
+use std::collections::HashSet;
+
+use bson::{doc, Document};
+use chrono::Utc;
+use futures::TryStreamExt;
+use mongodb::{
+    options::{FindOptions, IndexOptions},
+    Database, IndexModel,
+};
+
+use crate::types::{AppError, Result};
+
+use super::{
+    collection_name, is_duplicate_key, normalized_url, COLLECTIONS_COLLECTION_NAME_DEFAULT,
+    COLLECTIONS_COLLECTION_NAME_KEY, DETAILS_COLLECTION_NAME_DEFAULT, DETAILS_COLLECTION_NAME_KEY,
+    HIGHLIGHTS_COLLECTION_NAME_DEFAULT, HIGHLIGHTS_COLLECTION_NAME_KEY,
+    LINKS_COLLECTION_NAME_DEFAULT, LINKS_COLLECTION_NAME_KEY, PROGRESS_COLLECTION_NAME_DEFAULT,
+    PROGRESS_COLLECTION_NAME_KEY, REMINDERS_COLLECTION_NAME_DEFAULT, REMINDERS_COLLECTION_NAME_KEY,
+    REVISIONS_COLLECTION_NAME_DEFAULT, REVISIONS_COLLECTION_NAME_KEY,
+    SHARES_COLLECTION_NAME_DEFAULT, SHARES_COLLECTION_NAME_KEY, USERS_COLLECTION_NAME_DEFAULT,
+    USERS_COLLECTION_NAME_KEY, WEBHOOKS_COLLECTION_NAME_DEFAULT, WEBHOOKS_COLLECTION_NAME_KEY,
+};
+
+const MIGRATIONS_COLLECTION_NAME_KEY: &str = "MIGRATIONS_COLLECTION_NAME";
+const MIGRATIONS_COLLECTION_NAME_DEFAULT: &str = "v1/migrations";
+
+const MIGRATIONS: &[(i64, &str)] = &[
+    (1, "repair ids that do not match _id"),
+    (2, "store normalized urls of links"),
+    (3, "create indexes"),
+    (4, "number the versions of links"),
+    (5, "index the edit history of links"),
+    (6, "index collections by owner"),
+    (7, "index collections by member"),
+    (8, "index shares by token"),
+    (9, "index reminders by owner"),
+    (10, "index link details by owner"),
+    (11, "index highlights by link and owner"),
+    (12, "index reading progress by owner"),
+];
+
+pub async fn run(db: &Database) -> Result<()> {
+    let migrations_collection_name = collection_name(
+        MIGRATIONS_COLLECTION_NAME_KEY,
+        MIGRATIONS_COLLECTION_NAME_DEFAULT,
+    );
+    create_index(db, &migrations_collection_name, doc! {"version": 1}, true).await?;
+    let migrations_collection = db.collection::<Document>(&migrations_collection_name);
+
+    let applied_versions: HashSet<i64> = migrations_collection
+        .find(
+            None,
+            FindOptions::builder()
+                .projection(doc! {"version": 1})
+                .build(),
+        )
+        .await
+        .map_err(|e| AppError::Database(format!("find() {e:?}")))?
+        .try_collect::<Vec<Document>>()
+        .await
+        .map_err(|e| AppError::Database(format!("try_collect() {e:?}")))?
+        .iter()
+        .filter_map(|migration| migration.get_i64("version").ok())
+        .collect();
+
+    for &(version, description) in MIGRATIONS {
+        if applied_versions.contains(&version) {
+            continue;
+        }
+        tracing::info!("Applying migration {}: {}", version, description);
+        apply(db, version).await?;
+
+        let record = doc! {
+            "version": version,
+            "description": description,
+            "applied_at": bson::DateTime::from_millis(Utc::now().timestamp_millis()),
+        };
+        match migrations_collection.insert_one(record, None).await {
+            // another instance finished the same migration first
+            Err(e) if is_duplicate_key(&e) => {}
+            result => {
+                result.map_err(|e| AppError::Database(format!("insert_one() {e:?}")))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn apply(db: &Database, version: i64) -> Result<()> {
+    match version {
+        1 => repair_ids(db).await?,
+        2 => store_normalized_urls(db).await?,
+        3 => create_indexes(db).await?,
+        4 => number_versions(db).await?,
+        5 => {
+            let revisions_collection_name = collection_name(
+                REVISIONS_COLLECTION_NAME_KEY,
+                REVISIONS_COLLECTION_NAME_DEFAULT,
+            );
+            create_index(db, &revisions_collection_name, doc! {"link_id": 1}, false).await?;
+        }
+        6 => {
+            let collections_collection_name = collection_name(
+                COLLECTIONS_COLLECTION_NAME_KEY,
+                COLLECTIONS_COLLECTION_NAME_DEFAULT,
+            );
+            create_index(db, &collections_collection_name, doc! {"id": 1}, true).await?;
+            create_index(db, &collections_collection_name, doc! {"owner": 1}, false).await?;
+        }
+        7 => {
+            let collections_collection_name = collection_name(
+                COLLECTIONS_COLLECTION_NAME_KEY,
+                COLLECTIONS_COLLECTION_NAME_DEFAULT,
+            );
+            create_index(
+                db,
+                &collections_collection_name,
+                doc! {"members.user": 1},
+                false,
+            )
+            .await?;
+        }
+        8 => {
+            let shares_collection_name =
+                collection_name(SHARES_COLLECTION_NAME_KEY, SHARES_COLLECTION_NAME_DEFAULT);
+            create_index(db, &shares_collection_name, doc! {"token": 1}, true).await?;
+            create_index(db, &shares_collection_name, doc! {"owner": 1}, false).await?;
+        }
+        9 => {
+            let reminders_collection_name = collection_name(
+                REMINDERS_COLLECTION_NAME_KEY,
+                REMINDERS_COLLECTION_NAME_DEFAULT,
+            );
+            create_index(db, &reminders_collection_name, doc! {"id": 1}, true).await?;
+            create_index(db, &reminders_collection_name, doc! {"owner": 1}, false).await?;
+            create_index(
+                db,
+                &reminders_collection_name,
+                doc! {"notified_at": 1},
+                false,
+            )
+            .await?;
+        }
+        10 => {
+            let details_collection_name =
+                collection_name(DETAILS_COLLECTION_NAME_KEY, DETAILS_COLLECTION_NAME_DEFAULT);
+            create_index(db, &details_collection_name, doc! {"id": 1}, true).await?;
+            create_index(db, &details_collection_name, doc! {"owner": 1}, false).await?;
+        }
+        11 => {
+            let highlights_collection_name = collection_name(
+                HIGHLIGHTS_COLLECTION_NAME_KEY,
+                HIGHLIGHTS_COLLECTION_NAME_DEFAULT,
+            );
+            create_index(db, &highlights_collection_name, doc! {"id": 1}, true).await?;
+            create_index(db, &highlights_collection_name, doc! {"link_id": 1}, false).await?;
+            create_index(db, &highlights_collection_name, doc! {"owner": 1}, false).await?;
+        }
+        12 => {
+            let progress_collection_name = collection_name(
+                PROGRESS_COLLECTION_NAME_KEY,
+                PROGRESS_COLLECTION_NAME_DEFAULT,
+            );
+            create_index(db, &progress_collection_name, doc! {"id": 1}, true).await?;
+            create_index(db, &progress_collection_name, doc! {"owner": 1}, false).await?;
+        }
+        _ => unreachable!("migration {version} is not implemented"),
+    }
+    Ok(())
+}
+
+/// Repairs documents written before ids were assigned ahead of the insert,
+/// where a failure between the insert and the follow-up update left the
+/// placeholder `id` behind instead of the hex string of `_id`.
+async fn repair_ids(db: &Database) -> Result<()> {
+    let collection_names = [
+        (LINKS_COLLECTION_NAME_KEY, LINKS_COLLECTION_NAME_DEFAULT),
+        (USERS_COLLECTION_NAME_KEY, USERS_COLLECTION_NAME_DEFAULT),
+        (
+            WEBHOOKS_COLLECTION_NAME_KEY,
+            WEBHOOKS_COLLECTION_NAME_DEFAULT,
+        ),
+    ];
+    for (key, default) in collection_names {
+        let collection_name = collection_name(key, default);
+        let result = db
+            .collection::<Document>(&collection_name)
+            .update_many(
+                doc! {"$expr": {"$ne": ["$id", {"$toString": "$_id"}]}},
+                vec![doc! {"$set": {"id": {"$toString": "$_id"}}}],
+                None,
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("update_many() {e:?}")))?;
+        if result.modified_count > 0 {
+            tracing::info!(
+                "Repaired the id of {} documents in {}",
+                result.modified_count,
+                collection_name
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn store_normalized_urls(db: &Database) -> Result<()> {
+    let links_collection = db.collection::<Document>(&collection_name(
+        LINKS_COLLECTION_NAME_KEY,
+        LINKS_COLLECTION_NAME_DEFAULT,
+    ));
+    let mut links = links_collection
+        .find(doc! {"normalized_url": {"$exists": false}}, None)
+        .await
+        .map_err(|e| AppError::Database(format!("find() {e:?}")))?;
+    while let Some(link) = links
+        .try_next()
+        .await
+        .map_err(|e| AppError::Database(format!("try_next() {e:?}")))?
+    {
+        let (Ok(id), Ok(url)) = (link.get_object_id("_id"), link.get_str("url")) else {
+            continue;
+        };
+        links_collection
+            .update_one(
+                doc! {"_id": id},
+                doc! {"$set": {"normalized_url": normalized_url(url)}},
+                None,
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("update_one() {e:?}")))?;
+    }
+    Ok(())
+}
+
+/// Starts the links written before they had versions from the first one.
+async fn number_versions(db: &Database) -> Result<()> {
+    db.collection::<Document>(&collection_name(
+        LINKS_COLLECTION_NAME_KEY,
+        LINKS_COLLECTION_NAME_DEFAULT,
+    ))
+    .update_many(
+        doc! {"version": {"$exists": false}},
+        doc! {"$set": {"version": 1i64}},
+        None,
+    )
+    .await
+    .map_err(|e| AppError::Database(format!("update_many() {e:?}")))?;
+    Ok(())
+}
+
+async fn create_indexes(db: &Database) -> Result<()> {
+    let links_collection_name =
+        collection_name(LINKS_COLLECTION_NAME_KEY, LINKS_COLLECTION_NAME_DEFAULT);
+    let users_collection_name =
+        collection_name(USERS_COLLECTION_NAME_KEY, USERS_COLLECTION_NAME_DEFAULT);
+
+    // fails if duplicate emails were registered before, those have to be
+    // resolved by hand since there is no telling which account to keep
+    create_index(db, &users_collection_name, doc! {"email": 1}, true).await?;
+    create_index(db, &links_collection_name, doc! {"id": 1}, true).await?;
+    create_index(
+        db,
+        &links_collection_name,
+        doc! {"owner": 1, "created_at": 1},
+        false,
+    )
+    .await?;
+    create_index(db, &links_collection_name, doc! {"deleted_at": 1}, false).await?;
+    create_index(
+        db,
+        &links_collection_name,
+        doc! {"owner": 1, "normalized_url": 1},
+        false,
+    )
+    .await
+}
+
+async fn create_index(
+    db: &Database,
+    collection_name: &str,
+    keys: Document,
+    unique: bool,
+) -> Result<()> {
+    let index = IndexModel::builder()
+        .keys(keys)
+        .options(IndexOptions::builder().unique(unique).build())
+        .build();
+    db.collection::<Document>(collection_name)
+        .create_index(index, None)
+        .await
+        .map_err(|e| AppError::Database(format!("create_index() {e:?}")))?;
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use axum::async_trait;
+use sqlx::{migrate::Migrator, postgres::PgArguments, Arguments, PgPool};
+
+use crate::types::{AppError, Result};
+
+use super::sql::{Backend, Param, Query, Record};
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/postgres");
+
+/// Brings the schema up to date, this needs to run before the repositories
+/// are used.
+pub async fn migrate(pool: &PgPool) -> Result<()> {
+    MIGRATOR
+        .run(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("migrate() {e:?}")))
+}
+
+fn arguments(params: Vec<Param>) -> PgArguments {
+    let mut arguments = PgArguments::default();
+    for param in params {
+        match param {
+            Param::Text(value) => arguments.add(value),
+            Param::Int(value) => arguments.add(value),
+            Param::OptionalInt(value) => arguments.add(value),
+            Param::BigInt(value) => arguments.add(value),
+            Param::Boolean(value) => arguments.add(value),
+            Param::Timestamp(value) => arguments.add(value),
+            Param::OptionalTimestamp(value) => arguments.add(value),
+            Param::Json(value) => arguments.add(value),
+        }
+    }
+    arguments
+}
+
+#[async_trait]
+impl Backend for PgPool {
+    async fn execute(&self, query: Query) -> sqlx::Result<u64> {
+        let (sql, params) = query.into_parts();
+        let result = sqlx::query_with(&sql, arguments(params))
+            .execute(self)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn fetch_all<R: Record + 'static>(&self, query: Query) -> sqlx::Result<Vec<R>> {
+        let (sql, params) = query.into_parts();
+        sqlx::query_as_with(&sql, arguments(params))
+            .fetch_all(self)
+            .await
+    }
+
+    async fn fetch_optional<R: Record + 'static>(&self, query: Query) -> sqlx::Result<Option<R>> {
+        let (sql, params) = query.into_parts();
+        sqlx::query_as_with(&sql, arguments(params))
+            .fetch_optional(self)
+            .await
+    }
+
+    async fn fetch_one<R: Record + 'static>(&self, query: Query) -> sqlx::Result<R> {
+        let (sql, params) = query.into_parts();
+        sqlx::query_as_with(&sql, arguments(params))
+            .fetch_one(self)
+            .await
+    }
+
+    fn timestamp(expression: &str) -> String {
+        expression.to_owned()
+    }
+
+    fn contains(column: &str, placeholder: &str) -> String {
+        format!("{column} IN (SELECT jsonb_array_elements_text({placeholder}))")
+    }
+
+    fn has_member(column: &str, placeholder: &str) -> String {
+        format!("{column} @> jsonb_build_array(jsonb_build_object('user', {placeholder}::text))")
+    }
+}
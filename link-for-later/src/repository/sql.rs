@@ -0,0 +1,1765 @@
+//! Repositories shared by the SQL backends. The queries are written once,
+//! with `$N` placeholders, and run through a [`Backend`] that takes care of
+//! what `PostgreSQL` and `SQLite` do differently.
+
+use axum::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::{postgres::PgRow, sqlite::SqliteRow, types::Json, FromRow};
+
+use crate::types::{
+    AppError, Collection, FieldChange, Highlight, LibraryStats, LinkDetails, LinkHealth, LinkItem,
+    LinkItemBuilder, LinkQuery, LinkQueryBuilder, Member, Priority, ReadingProgress, Reminder,
+    Result, Revision, Selector, Share, Snapshot, TrashedLink, UserInfo, UserInfoBuilder, UserQuery,
+    VersionedLink, Webhook, WebhookDelivery,
+};
+
+use super::{
+    inmemory::library_stats, Collections as CollectionsRepository, Details as DetailsRepository,
+    Health as HealthRepository, Highlights as HighlightsRepository, Links as LinksRepository,
+    Progress as ProgressRepository, Reminders as RemindersRepository,
+    Revisions as RevisionsRepository, Shares as SharesRepository, Snapshots as SnapshotsRepository,
+    Users as UsersRepository, Webhooks as WebhooksRepository,
+};
+
+const LINK_COLUMNS: &str = "id, owner, url, title, description, word_count, reading_time, \
+                            summary, label, created_at, updated_at";
+const USER_COLUMNS: &str = "id, email, password, verified, admin, created_at, updated_at";
+const SNAPSHOT_COLUMNS: &str = "id, url, content_type, content, captured_at";
+const HEALTH_COLUMNS: &str = "id, url, status_code, redirect_url, checked_at";
+const WEBHOOK_COLUMNS: &str = "id, owner, url, secret, events, created_at";
+const REVISION_COLUMNS: &str = "id, actor, action, changes, link, created_at";
+const COLLECTION_COLUMNS: &str =
+    "id, owner, name, description, position, links, members, created_at, updated_at";
+const SHARE_COLUMNS: &str = "id, owner, kind, target, token, expires_at, created_at";
+const REMINDER_COLUMNS: &str = "id, owner, kind, link_id, remind_at, notified_at, created_at";
+const DETAILS_COLUMNS: &str = "id, owner, priority, rating, notes, updated_at";
+const PROGRESS_COLUMNS: &str =
+    "id, owner, percentage, position, time_spent, finished_at, updated_at";
+const HIGHLIGHT_COLUMNS: &str =
+    "id, link_id, owner, quote, note, selectors, created_at, updated_at";
+const DELIVERY_COLUMNS: &str =
+    "webhook_id, event, link_id, status_code, attempts, error, delivered_at";
+
+/// A statement and the values bound to its placeholders, in order.
+#[derive(Debug)]
+pub struct Query {
+    sql: String,
+    params: Vec<Param>,
+}
+
+impl Query {
+    fn new(sql: impl Into<String>) -> Self {
+        Self {
+            sql: sql.into(),
+            params: vec![],
+        }
+    }
+
+    fn bind(mut self, param: impl Into<Param>) -> Self {
+        self.params.push(param.into());
+        self
+    }
+
+    pub fn into_parts(self) -> (String, Vec<Param>) {
+        (self.sql, self.params)
+    }
+}
+
+/// A value bound to a placeholder. Every backend knows how to encode each of
+/// these, which is what lets the queries be shared.
+#[derive(Debug)]
+pub enum Param {
+    Text(String),
+    Int(i32),
+    OptionalInt(Option<i32>),
+    BigInt(i64),
+    Boolean(bool),
+    Timestamp(DateTime<Utc>),
+    OptionalTimestamp(Option<DateTime<Utc>>),
+    Json(serde_json::Value),
+}
+
+impl From<&str> for Param {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_owned())
+    }
+}
+
+impl From<i32> for Param {
+    fn from(value: i32) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<Option<i32>> for Param {
+    fn from(value: Option<i32>) -> Self {
+        Self::OptionalInt(value)
+    }
+}
+
+impl From<i64> for Param {
+    fn from(value: i64) -> Self {
+        Self::BigInt(value)
+    }
+}
+
+impl From<bool> for Param {
+    fn from(value: bool) -> Self {
+        Self::Boolean(value)
+    }
+}
+
+impl From<&DateTime<Utc>> for Param {
+    fn from(value: &DateTime<Utc>) -> Self {
+        Self::Timestamp(*value)
+    }
+}
+
+impl From<Option<&DateTime<Utc>>> for Param {
+    fn from(value: Option<&DateTime<Utc>>) -> Self {
+        Self::OptionalTimestamp(value.copied())
+    }
+}
+
+impl<T: Serialize> From<Json<T>> for Param {
+    fn from(value: Json<T>) -> Self {
+        Self::Json(serde_json::to_value(value.0).unwrap_or_default())
+    }
+}
+
+/// A row that can be read from any of the backends.
+pub trait Record:
+    for<'r> FromRow<'r, PgRow> + for<'r> FromRow<'r, SqliteRow> + Send + Unpin
+{
+}
+
+impl<T> Record for T where
+    T: for<'r> FromRow<'r, PgRow> + for<'r> FromRow<'r, SqliteRow> + Send + Unpin
+{
+}
+
+/// The connection pool of a SQL database, along with the bits of SQL that
+/// differ between the databases.
+#[async_trait]
+pub trait Backend: Clone + Send + Sync + 'static {
+    /// Runs the statement and returns the number of rows it affected.
+    async fn execute(&self, query: Query) -> sqlx::Result<u64>;
+    async fn fetch_all<R: Record + 'static>(&self, query: Query) -> sqlx::Result<Vec<R>>;
+    async fn fetch_optional<R: Record + 'static>(&self, query: Query) -> sqlx::Result<Option<R>>;
+    async fn fetch_one<R: Record + 'static>(&self, query: Query) -> sqlx::Result<R>;
+
+    /// Turns a timestamp column or placeholder into something that compares
+    /// in time order.
+    fn timestamp(expression: &str) -> String;
+    /// Checks that `column` is one of the strings of the JSON array bound to
+    /// `placeholder`.
+    fn contains(column: &str, placeholder: &str) -> String;
+    /// Checks that the JSON array of members in `column` has the user bound
+    /// to `placeholder`.
+    fn has_member(column: &str, placeholder: &str) -> String;
+}
+
+#[derive(Debug)]
+pub struct LinksRepositoryProvider<B> {
+    pool: B,
+}
+
+#[derive(Debug)]
+pub struct UsersRepositoryProvider<B> {
+    pool: B,
+}
+
+#[derive(Debug)]
+pub struct RevisionsRepositoryProvider<B> {
+    pool: B,
+}
+
+#[derive(Debug)]
+pub struct CollectionsRepositoryProvider<B> {
+    pool: B,
+}
+
+#[derive(Debug)]
+pub struct SharesRepositoryProvider<B> {
+    pool: B,
+}
+
+#[derive(Debug)]
+pub struct RemindersRepositoryProvider<B> {
+    pool: B,
+}
+
+#[derive(Debug)]
+pub struct DetailsRepositoryProvider<B> {
+    pool: B,
+}
+
+#[derive(Debug)]
+pub struct HighlightsRepositoryProvider<B> {
+    pool: B,
+}
+
+#[derive(Debug)]
+pub struct ProgressRepositoryProvider<B> {
+    pool: B,
+}
+
+#[derive(Debug)]
+pub struct SnapshotsRepositoryProvider<B> {
+    pool: B,
+}
+
+#[derive(Debug)]
+pub struct HealthRepositoryProvider<B> {
+    pool: B,
+}
+
+#[derive(Debug)]
+pub struct WebhooksRepositoryProvider<B> {
+    pool: B,
+}
+
+impl<B: Backend> LinksRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl<B: Backend> UsersRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl<B: Backend> RevisionsRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl<B: Backend> CollectionsRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl<B: Backend> SharesRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl<B: Backend> RemindersRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl<B: Backend> DetailsRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl<B: Backend> HighlightsRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl<B: Backend> ProgressRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl<B: Backend> SnapshotsRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl<B: Backend> HealthRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+impl<B: Backend> WebhooksRepositoryProvider<B> {
+    pub fn new(pool: &B) -> Self {
+        Self { pool: pool.clone() }
+    }
+}
+
+#[derive(FromRow)]
+struct LinkRow {
+    id: String,
+    owner: String,
+    url: String,
+    title: String,
+    description: String,
+    word_count: i64,
+    reading_time: i64,
+    summary: String,
+    label: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<LinkRow> for LinkItem {
+    fn from(row: LinkRow) -> Self {
+        LinkItemBuilder::new(&row.url)
+            .id(&row.id)
+            .owner(&row.owner)
+            .title(&row.title)
+            .description(&row.description)
+            .word_count(usize::try_from(row.word_count).unwrap_or_default())
+            .reading_time(usize::try_from(row.reading_time).unwrap_or_default())
+            .summary(&row.summary)
+            .label(&row.label)
+            .created_at(&row.created_at)
+            .updated_at(&row.updated_at)
+            .build()
+    }
+}
+
+#[derive(FromRow)]
+struct TrashRow {
+    #[sqlx(flatten)]
+    link: LinkRow,
+    deleted_at: DateTime<Utc>,
+}
+
+#[derive(FromRow)]
+struct VersionedRow {
+    #[sqlx(flatten)]
+    link: LinkRow,
+    version: i64,
+}
+
+impl From<VersionedRow> for VersionedLink {
+    fn from(row: VersionedRow) -> Self {
+        Self::new(&LinkItem::from(row.link), to_u64(row.version))
+    }
+}
+
+impl From<TrashRow> for TrashedLink {
+    fn from(row: TrashRow) -> Self {
+        Self::new(&LinkItem::from(row.link), &row.deleted_at)
+    }
+}
+
+#[derive(FromRow)]
+struct RevisionRow {
+    id: String,
+    actor: String,
+    action: String,
+    changes: Json<Vec<FieldChange>>,
+    link: Json<LinkItem>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<RevisionRow> for Revision {
+    fn from(row: RevisionRow) -> Self {
+        Self::new(
+            &row.link,
+            &row.actor,
+            &row.action,
+            &row.changes,
+            &row.created_at,
+        )
+        .with_id(&row.id)
+    }
+}
+
+#[derive(FromRow)]
+struct CollectionRow {
+    id: String,
+    owner: String,
+    name: String,
+    description: String,
+    position: i64,
+    links: Json<Vec<String>>,
+    members: Json<Vec<Member>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<CollectionRow> for Collection {
+    fn from(row: CollectionRow) -> Self {
+        Self::new(
+            &row.owner,
+            &row.name,
+            &row.description,
+            row.position,
+            &row.created_at,
+        )
+        .with_id(&row.id)
+        .with_links(&row.links)
+        .with_members(&row.members)
+        .with_updated_at(&row.updated_at)
+    }
+}
+
+#[derive(FromRow)]
+struct ShareRow {
+    id: String,
+    owner: String,
+    kind: String,
+    target: String,
+    token: String,
+    expires_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ShareRow> for Share {
+    fn from(row: ShareRow) -> Self {
+        Self::new(
+            &row.owner,
+            &row.kind,
+            &row.target,
+            row.expires_at.as_ref(),
+            &row.created_at,
+        )
+        .with_id(&row.id)
+        .with_token(&row.token)
+    }
+}
+
+#[derive(FromRow)]
+struct ReminderRow {
+    id: String,
+    owner: String,
+    kind: String,
+    link_id: String,
+    remind_at: DateTime<Utc>,
+    notified_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<ReminderRow> for Reminder {
+    fn from(row: ReminderRow) -> Self {
+        let reminder = Self::new(
+            &row.owner,
+            &row.kind,
+            &row.link_id,
+            &row.remind_at,
+            &row.created_at,
+        )
+        .with_id(&row.id);
+        match row.notified_at {
+            Some(notified_at) => reminder.with_notified_at(&notified_at),
+            None => reminder,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct DetailsRow {
+    id: String,
+    owner: String,
+    priority: String,
+    rating: Option<i32>,
+    notes: String,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<DetailsRow> for LinkDetails {
+    fn from(row: DetailsRow) -> Self {
+        let priority = match row.priority.as_str() {
+            "low" => Priority::Low,
+            "high" => Priority::High,
+            _ => Priority::Normal,
+        };
+        Self::new(
+            &row.id,
+            &row.owner,
+            priority,
+            row.rating.and_then(|rating| u8::try_from(rating).ok()),
+            &row.notes,
+            &row.updated_at,
+        )
+    }
+}
+
+#[derive(FromRow)]
+struct ProgressRow {
+    id: String,
+    owner: String,
+    percentage: i32,
+    position: i64,
+    time_spent: i64,
+    finished_at: Option<DateTime<Utc>>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ProgressRow> for ReadingProgress {
+    fn from(row: ProgressRow) -> Self {
+        let progress = Self::new(
+            &row.id,
+            &row.owner,
+            u8::try_from(row.percentage).unwrap_or_default(),
+            u32::try_from(row.position).unwrap_or_default(),
+            u32::try_from(row.time_spent).unwrap_or_default(),
+            &row.updated_at,
+        );
+        match row.finished_at {
+            Some(finished_at) => progress.with_finished_at(&finished_at),
+            None => progress,
+        }
+    }
+}
+
+#[derive(FromRow)]
+struct HighlightRow {
+    id: String,
+    link_id: String,
+    owner: String,
+    quote: String,
+    note: String,
+    selectors: Json<Vec<Selector>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<HighlightRow> for Highlight {
+    fn from(row: HighlightRow) -> Self {
+        Self::new(
+            &row.link_id,
+            &row.owner,
+            &row.quote,
+            &row.note,
+            &row.selectors,
+            &row.created_at,
+        )
+        .with_id(&row.id)
+        .with_updated_at(&row.updated_at)
+    }
+}
+
+#[derive(FromRow)]
+struct UserRow {
+    id: String,
+    email: String,
+    password: String,
+    verified: bool,
+    admin: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<UserRow> for UserInfo {
+    fn from(row: UserRow) -> Self {
+        UserInfoBuilder::new(&row.email, &row.password)
+            .id(&row.id)
+            .verified(row.verified)
+            .admin(row.admin)
+            .created_at(&row.created_at)
+            .updated_at(&row.updated_at)
+            .build()
+    }
+}
+
+#[derive(FromRow)]
+struct SnapshotRow {
+    id: String,
+    url: String,
+    content_type: String,
+    content: String,
+    captured_at: DateTime<Utc>,
+}
+
+impl From<SnapshotRow> for Snapshot {
+    fn from(row: SnapshotRow) -> Self {
+        Self::new(
+            &row.id,
+            &row.url,
+            &row.content_type,
+            &row.content,
+            &row.captured_at,
+        )
+    }
+}
+
+#[derive(FromRow)]
+struct HealthRow {
+    id: String,
+    url: String,
+    status_code: i32,
+    redirect_url: String,
+    checked_at: DateTime<Utc>,
+}
+
+impl From<HealthRow> for LinkHealth {
+    fn from(row: HealthRow) -> Self {
+        Self::new(
+            &row.id,
+            &row.url,
+            u16::try_from(row.status_code).unwrap_or_default(),
+            &row.redirect_url,
+            &row.checked_at,
+        )
+    }
+}
+
+#[derive(FromRow)]
+struct DeliveryRow {
+    webhook_id: String,
+    event: String,
+    link_id: String,
+    status_code: i32,
+    attempts: i32,
+    error: String,
+    delivered_at: DateTime<Utc>,
+}
+
+impl From<DeliveryRow> for WebhookDelivery {
+    fn from(row: DeliveryRow) -> Self {
+        Self::new(
+            &row.webhook_id,
+            &row.event,
+            &row.link_id,
+            u16::try_from(row.status_code).unwrap_or_default(),
+            u32::try_from(row.attempts).unwrap_or_default(),
+            &row.error,
+            &row.delivered_at,
+        )
+    }
+}
+
+#[derive(FromRow)]
+struct WebhookRow {
+    id: String,
+    owner: String,
+    url: String,
+    secret: String,
+    events: Json<Vec<String>>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<WebhookRow> for Webhook {
+    fn from(row: WebhookRow) -> Self {
+        Self::new(
+            &row.owner,
+            &row.url,
+            &row.secret,
+            &row.events,
+            &row.created_at,
+        )
+        .with_id(&row.id)
+    }
+}
+
+#[async_trait]
+impl<B: Backend> LinksRepository for LinksRepositoryProvider<B> {
+    async fn find(&self, query: &LinkQuery) -> Result<Vec<LinkItem>> {
+        let rows = self
+            .pool
+            .fetch_all::<LinkRow>(
+                Query::new(format!(
+                    "SELECT {LINK_COLUMNS} FROM links \
+             WHERE ($1 = '' OR id = $1) AND ($2 = '' OR owner = $2) \
+             AND deleted_at IS NULL ORDER BY created_at"
+                ))
+                .bind(query.id())
+                .bind(query.user()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(LinkItem::from).collect())
+    }
+
+    async fn get(&self, query: &LinkQuery) -> Result<LinkItem> {
+        let row = self
+            .pool
+            .fetch_optional::<LinkRow>(
+                Query::new(format!(
+                    "SELECT {LINK_COLUMNS} FROM links \
+             WHERE ($1 = '' OR id = $1) AND ($2 = '' OR owner = $2) \
+             AND deleted_at IS NULL"
+                ))
+                .bind(query.id())
+                .bind(query.user()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(LinkItem::from)
+            .ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))
+    }
+
+    async fn get_versioned(&self, query: &LinkQuery) -> Result<VersionedLink> {
+        let row = self
+            .pool
+            .fetch_optional::<VersionedRow>(
+                Query::new(format!(
+                    "SELECT {LINK_COLUMNS}, version FROM links \
+             WHERE ($1 = '' OR id = $1) AND ($2 = '' OR owner = $2) \
+             AND deleted_at IS NULL"
+                ))
+                .bind(query.id())
+                .bind(query.user()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(VersionedLink::from)
+            .ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))
+    }
+
+    async fn create(&self, item: &LinkItem) -> Result<LinkItem> {
+        let (id,) = self
+            .pool
+            .fetch_one::<(String,)>(
+                Query::new(
+                    "INSERT INTO links \
+             (owner, url, title, description, word_count, reading_time, summary, label, \
+              created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING id",
+                )
+                .bind(item.owner())
+                .bind(item.url())
+                .bind(item.title())
+                .bind(item.description())
+                .bind(to_i64(item.word_count()))
+                .bind(to_i64(item.reading_time()))
+                .bind(item.summary())
+                .bind(item.label())
+                .bind(item.created_at())
+                .bind(item.updated_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_one() {e:?}")))?;
+
+        Ok(LinkItemBuilder::from(item.clone()).id(&id).build())
+    }
+
+    async fn update(&self, query: &LinkQuery, item: &LinkItem) -> Result<VersionedLink> {
+        let row = self
+            .pool
+            .fetch_optional::<(i64,)>(
+                Query::new(
+                    "UPDATE links SET \
+             owner = $2, url = $3, title = $4, description = $5, word_count = $6, \
+             reading_time = $7, summary = $8, label = $9, created_at = $10, updated_at = $11, \
+             version = version + 1 \
+             WHERE id = $1 AND deleted_at IS NULL RETURNING version",
+                )
+                .bind(query.id())
+                .bind(item.owner())
+                .bind(item.url())
+                .bind(item.title())
+                .bind(item.description())
+                .bind(to_i64(item.word_count()))
+                .bind(to_i64(item.reading_time()))
+                .bind(item.summary())
+                .bind(item.label())
+                .bind(item.created_at())
+                .bind(item.updated_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        let (version,) = row.ok_or_else(|| AppError::LinkNotFound(query.id().to_owned()))?;
+        Ok(VersionedLink::new(item, to_u64(version)))
+    }
+
+    async fn update_if_unmodified(
+        &self,
+        query: &LinkQuery,
+        item: &LinkItem,
+        version: u64,
+    ) -> Result<VersionedLink> {
+        let row = self
+            .pool
+            .fetch_optional::<(i64,)>(
+                Query::new(
+                    "UPDATE links SET \
+             owner = $2, url = $3, title = $4, description = $5, word_count = $6, \
+             reading_time = $7, summary = $8, label = $9, created_at = $10, updated_at = $11, \
+             version = version + 1 \
+             WHERE id = $1 AND version = $12 AND deleted_at IS NULL RETURNING version",
+                )
+                .bind(query.id())
+                .bind(item.owner())
+                .bind(item.url())
+                .bind(item.title())
+                .bind(item.description())
+                .bind(to_i64(item.word_count()))
+                .bind(to_i64(item.reading_time()))
+                .bind(item.summary())
+                .bind(item.label())
+                .bind(item.created_at())
+                .bind(item.updated_at())
+                .bind(i64::try_from(version).unwrap_or(i64::MAX)),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        let (version,) = row.ok_or_else(|| AppError::PreconditionFailed(query.id().to_owned()))?;
+        Ok(VersionedLink::new(item, to_u64(version)))
+    }
+
+    async fn delete(&self, query: &LinkQuery) -> Result<()> {
+        self.pool
+            .execute(
+                Query::new(
+                    "DELETE FROM links WHERE ($1 = '' OR id = $1) AND ($2 = '' OR owner = $2)",
+                )
+                .bind(query.id())
+                .bind(query.user()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(())
+    }
+
+    async fn trash(&self, query: &LinkQuery, deleted_at: &DateTime<Utc>) -> Result<()> {
+        let rows_affected = self
+            .pool
+            .execute(
+                Query::new(
+                    "UPDATE links SET deleted_at = $3, version = version + 1 \
+             WHERE id = $1 AND ($2 = '' OR owner = $2) AND deleted_at IS NULL",
+                )
+                .bind(query.id())
+                .bind(query.user())
+                .bind(deleted_at),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        if rows_affected == 0 {
+            return Err(AppError::LinkNotFound(query.id().to_owned()));
+        }
+        Ok(())
+    }
+
+    async fn find_trashed(&self, owner: &str) -> Result<Vec<TrashedLink>> {
+        let rows = self
+            .pool
+            .fetch_all::<TrashRow>(
+                Query::new(format!(
+                    "SELECT {LINK_COLUMNS}, deleted_at FROM links \
+             WHERE deleted_at IS NOT NULL AND ($1 = '' OR owner = $1) ORDER BY deleted_at"
+                ))
+                .bind(owner),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(TrashedLink::from).collect())
+    }
+
+    async fn find_expired(&self, deleted_before: &DateTime<Utc>) -> Result<Vec<TrashedLink>> {
+        let rows = self
+            .pool
+            .fetch_all::<TrashRow>(
+                Query::new(format!(
+                    "SELECT {LINK_COLUMNS}, deleted_at FROM links \
+             WHERE {} < {} ORDER BY deleted_at",
+                    B::timestamp("deleted_at"),
+                    B::timestamp("$1"),
+                ))
+                .bind(deleted_before),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(TrashedLink::from).collect())
+    }
+
+    async fn get_trashed(&self, id: &str) -> Result<TrashedLink> {
+        let row = self
+            .pool
+            .fetch_optional::<TrashRow>(
+                Query::new(format!(
+                    "SELECT {LINK_COLUMNS}, deleted_at FROM links \
+             WHERE id = $1 AND deleted_at IS NOT NULL"
+                ))
+                .bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(TrashedLink::from)
+            .ok_or_else(|| AppError::LinkNotFound(id.to_owned()))
+    }
+
+    async fn restore(&self, id: &str) -> Result<LinkItem> {
+        let row = self
+            .pool
+            .fetch_optional::<LinkRow>(
+                Query::new(format!(
+                    "UPDATE links SET deleted_at = NULL, version = version + 1 \
+             WHERE id = $1 AND deleted_at IS NOT NULL RETURNING {LINK_COLUMNS}"
+                ))
+                .bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(LinkItem::from)
+            .ok_or_else(|| AppError::LinkNotFound(id.to_owned()))
+    }
+
+    async fn stats(
+        &self,
+        owner: &str,
+        since: &NaiveDate,
+        today: &NaiveDate,
+        domains: usize,
+    ) -> Result<LibraryStats> {
+        let links = self
+            .find(&LinkQueryBuilder::default().user(owner).build())
+            .await?;
+        Ok(library_stats(&links, *since, *today, domains))
+    }
+}
+
+#[async_trait]
+impl<B: Backend> UsersRepository for UsersRepositoryProvider<B> {
+    async fn get(&self, query: &UserQuery) -> Result<UserInfo> {
+        let row = self
+            .pool
+            .fetch_optional::<UserRow>(
+                Query::new(format!("SELECT {USER_COLUMNS} FROM users WHERE email = $1"))
+                    .bind(query.email()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(UserInfo::from)
+            .ok_or_else(|| AppError::UserNotFound(query.email().to_owned()))
+    }
+
+    async fn create(&self, info: &UserInfo) -> Result<UserInfo> {
+        let (id,) = self
+            .pool
+            .fetch_one::<(String,)>(
+                Query::new(
+                    "INSERT INTO users (email, password, verified, admin, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+                )
+                .bind(info.email())
+                .bind(info.password())
+                .bind(info.verified())
+                .bind(info.admin())
+                .bind(info.created_at())
+                .bind(info.updated_at()),
+            )
+            .await
+            .map_err(|e| match e {
+                // the unique constraint closes the race between two registrations
+                // of the same email that the service's lookup cannot
+                sqlx::Error::Database(ref db_error) if db_error.is_unique_violation() => {
+                    AppError::UserAlreadyExists(info.email().to_owned())
+                }
+                e => AppError::Database(format!("fetch_one() {e:?}")),
+            })?;
+
+        Ok(UserInfoBuilder::from(info.clone()).id(&id).build())
+    }
+}
+
+#[async_trait]
+impl<B: Backend> RevisionsRepository for RevisionsRepositoryProvider<B> {
+    async fn find(&self, link_id: &str) -> Result<Vec<Revision>> {
+        let rows = self
+            .pool
+            .fetch_all::<RevisionRow>(
+                Query::new(format!(
+                    "SELECT {REVISION_COLUMNS} FROM revisions WHERE link_id = $1 ORDER BY seq"
+                ))
+                .bind(link_id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(Revision::from).collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<Revision> {
+        let row = self
+            .pool
+            .fetch_optional::<RevisionRow>(
+                Query::new(format!(
+                    "SELECT {REVISION_COLUMNS} FROM revisions WHERE id = $1"
+                ))
+                .bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(Revision::from)
+            .ok_or_else(|| AppError::RevisionNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, revision: &Revision) -> Result<Revision> {
+        let (id,) = self
+            .pool
+            .fetch_one::<(String,)>(
+                Query::new(
+                    "INSERT INTO revisions (link_id, actor, action, changes, link, created_at) \
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+                )
+                .bind(revision.link_id())
+                .bind(revision.actor())
+                .bind(revision.action())
+                .bind(Json(revision.changes()))
+                .bind(Json(revision.link()))
+                .bind(revision.created_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_one() {e:?}")))?;
+        Ok(revision.clone().with_id(&id))
+    }
+
+    async fn delete(&self, link_id: &str) -> Result<()> {
+        self.pool
+            .execute(Query::new("DELETE FROM revisions WHERE link_id = $1").bind(link_id))
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: Backend> CollectionsRepository for CollectionsRepositoryProvider<B> {
+    async fn find(&self, owner: &str) -> Result<Vec<Collection>> {
+        let rows = self
+            .pool
+            .fetch_all::<CollectionRow>(
+                Query::new(format!(
+                    "SELECT {COLLECTION_COLUMNS} FROM collections \
+             WHERE owner = $1 OR $1 = '' ORDER BY position, created_at"
+                ))
+                .bind(owner),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(Collection::from).collect())
+    }
+
+    async fn find_shared(&self, member: &str) -> Result<Vec<Collection>> {
+        let rows = self
+            .pool
+            .fetch_all::<CollectionRow>(
+                Query::new(format!(
+                    "SELECT {COLLECTION_COLUMNS} FROM collections WHERE {} \
+             ORDER BY position, created_at",
+                    B::has_member("members", "$1"),
+                ))
+                .bind(member),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(Collection::from).collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<Collection> {
+        let row = self
+            .pool
+            .fetch_optional::<CollectionRow>(
+                Query::new(format!(
+                    "SELECT {COLLECTION_COLUMNS} FROM collections WHERE id = $1"
+                ))
+                .bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(Collection::from)
+            .ok_or_else(|| AppError::CollectionNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, collection: &Collection) -> Result<Collection> {
+        let (id,) = self
+            .pool
+            .fetch_one::<(String,)>(
+                Query::new(
+                    "INSERT INTO collections \
+             (owner, name, description, position, links, members, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING id",
+                )
+                .bind(collection.owner())
+                .bind(collection.name())
+                .bind(collection.description())
+                .bind(collection.position())
+                .bind(Json(collection.links()))
+                .bind(Json(collection.members()))
+                .bind(collection.created_at())
+                .bind(collection.updated_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_one() {e:?}")))?;
+        Ok(collection.clone().with_id(&id))
+    }
+
+    async fn update(&self, collection: &Collection) -> Result<Collection> {
+        let rows_affected = self
+            .pool
+            .execute(
+                Query::new(
+                    "UPDATE collections SET name = $2, description = $3, position = $4, \
+             links = $5, members = $6, updated_at = $7 WHERE id = $1",
+                )
+                .bind(collection.id())
+                .bind(collection.name())
+                .bind(collection.description())
+                .bind(collection.position())
+                .bind(Json(collection.links()))
+                .bind(Json(collection.members()))
+                .bind(collection.updated_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        if rows_affected == 0 {
+            return Err(AppError::CollectionNotFound(collection.id().to_owned()));
+        }
+        Ok(collection.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        let rows_affected = self
+            .pool
+            .execute(Query::new("DELETE FROM collections WHERE id = $1").bind(id))
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        if rows_affected == 0 {
+            return Err(AppError::CollectionNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: Backend> SharesRepository for SharesRepositoryProvider<B> {
+    async fn find(&self, owner: &str) -> Result<Vec<Share>> {
+        let rows = self
+            .pool
+            .fetch_all::<ShareRow>(
+                Query::new(format!(
+                    "SELECT {SHARE_COLUMNS} FROM shares \
+             WHERE ($1 = '' OR owner = $1) ORDER BY created_at"
+                ))
+                .bind(owner),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(Share::from).collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<Share> {
+        let row = self
+            .pool
+            .fetch_optional::<ShareRow>(
+                Query::new(format!("SELECT {SHARE_COLUMNS} FROM shares WHERE id = $1")).bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(Share::from)
+            .ok_or_else(|| AppError::ShareNotFound(id.to_owned()))
+    }
+
+    async fn get_by_token(&self, token: &str) -> Result<Share> {
+        let row = self
+            .pool
+            .fetch_optional::<ShareRow>(
+                Query::new(format!(
+                    "SELECT {SHARE_COLUMNS} FROM shares WHERE token = $1"
+                ))
+                .bind(token),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(Share::from)
+            .ok_or_else(|| AppError::ShareNotFound(token.to_owned()))
+    }
+
+    async fn create(&self, share: &Share) -> Result<Share> {
+        let (id,) = self
+            .pool
+            .fetch_one::<(String,)>(
+                Query::new(
+                    "INSERT INTO shares (owner, kind, target, token, expires_at, created_at) \
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+                )
+                .bind(share.owner())
+                .bind(share.kind())
+                .bind(share.target())
+                .bind(share.token())
+                .bind(share.expires_at())
+                .bind(share.created_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_one() {e:?}")))?;
+        Ok(share.clone().with_id(&id))
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        let rows_affected = self
+            .pool
+            .execute(Query::new("DELETE FROM shares WHERE id = $1").bind(id))
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        if rows_affected == 0 {
+            return Err(AppError::ShareNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: Backend> RemindersRepository for RemindersRepositoryProvider<B> {
+    async fn find(&self, owner: &str) -> Result<Vec<Reminder>> {
+        let rows = self
+            .pool
+            .fetch_all::<ReminderRow>(
+                Query::new(format!(
+                    "SELECT {REMINDER_COLUMNS} FROM reminders \
+             WHERE ($1 = '' OR owner = $1) ORDER BY remind_at"
+                ))
+                .bind(owner),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(Reminder::from).collect())
+    }
+
+    async fn find_due(&self, now: &DateTime<Utc>) -> Result<Vec<Reminder>> {
+        let rows = self
+            .pool
+            .fetch_all::<ReminderRow>(
+                Query::new(format!(
+                    "SELECT {REMINDER_COLUMNS} FROM reminders \
+             WHERE notified_at IS NULL AND {} <= {} ORDER BY remind_at",
+                    B::timestamp("remind_at"),
+                    B::timestamp("$1"),
+                ))
+                .bind(now),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(Reminder::from).collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<Reminder> {
+        let row = self
+            .pool
+            .fetch_optional::<ReminderRow>(
+                Query::new(format!(
+                    "SELECT {REMINDER_COLUMNS} FROM reminders WHERE id = $1"
+                ))
+                .bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(Reminder::from)
+            .ok_or_else(|| AppError::ReminderNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, reminder: &Reminder) -> Result<Reminder> {
+        let (id,) = self.pool.fetch_one::<(String,)>(Query::new("INSERT INTO reminders (owner, kind, link_id, remind_at, notified_at, created_at) \
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING id").bind(reminder.owner()).bind(reminder.kind()).bind(reminder.link_id()).bind(reminder.remind_at()).bind(reminder.notified_at()).bind(reminder.created_at()))
+        .await
+        .map_err(|e| AppError::Database(format!("fetch_one() {e:?}")))?;
+        Ok(reminder.clone().with_id(&id))
+    }
+
+    async fn update(&self, reminder: &Reminder) -> Result<Reminder> {
+        let rows_affected = self
+            .pool
+            .execute(
+                Query::new("UPDATE reminders SET remind_at = $2, notified_at = $3 WHERE id = $1")
+                    .bind(reminder.id())
+                    .bind(reminder.remind_at())
+                    .bind(reminder.notified_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        if rows_affected == 0 {
+            return Err(AppError::ReminderNotFound(reminder.id().to_owned()));
+        }
+        Ok(reminder.clone())
+    }
+
+    async fn claim(&self, reminder: &Reminder, notified_at: &DateTime<Utc>) -> Result<bool> {
+        let rows_affected = self
+            .pool
+            .execute(
+                Query::new(format!(
+                    "UPDATE reminders SET notified_at = $2 \
+             WHERE id = $1 AND notified_at IS NULL AND {} = {}",
+                    B::timestamp("remind_at"),
+                    B::timestamp("$3"),
+                ))
+                .bind(reminder.id())
+                .bind(notified_at)
+                .bind(reminder.remind_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(rows_affected == 1)
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        let rows_affected = self
+            .pool
+            .execute(Query::new("DELETE FROM reminders WHERE id = $1").bind(id))
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        if rows_affected == 0 {
+            return Err(AppError::ReminderNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: Backend> SnapshotsRepository for SnapshotsRepositoryProvider<B> {
+    async fn get(&self, id: &str) -> Result<Snapshot> {
+        let row = self
+            .pool
+            .fetch_optional::<SnapshotRow>(
+                Query::new(format!(
+                    "SELECT {SNAPSHOT_COLUMNS} FROM snapshots WHERE id = $1"
+                ))
+                .bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(Snapshot::from)
+            .ok_or_else(|| AppError::SnapshotNotFound(id.to_owned()))
+    }
+
+    async fn save(&self, snapshot: &Snapshot) -> Result<Snapshot> {
+        self.pool
+            .execute(
+                Query::new(
+                    "INSERT INTO snapshots (id, url, content_type, content, captured_at) \
+             VALUES ($1, $2, $3, $4, $5) \
+             ON CONFLICT (id) DO UPDATE SET \
+             url = EXCLUDED.url, content_type = EXCLUDED.content_type, \
+             content = EXCLUDED.content, captured_at = EXCLUDED.captured_at",
+                )
+                .bind(snapshot.id())
+                .bind(snapshot.url())
+                .bind(snapshot.content_type())
+                .bind(snapshot.content())
+                .bind(snapshot.captured_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(snapshot.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.pool
+            .execute(Query::new("DELETE FROM snapshots WHERE id = $1").bind(id))
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: Backend> HealthRepository for HealthRepositoryProvider<B> {
+    async fn find(&self, ids: &[String]) -> Result<Vec<LinkHealth>> {
+        let rows = self
+            .pool
+            .fetch_all::<HealthRow>(
+                Query::new(format!(
+                    "SELECT {HEALTH_COLUMNS} FROM health WHERE {}",
+                    B::contains("id", "$1"),
+                ))
+                .bind(Json(ids)),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(LinkHealth::from).collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<LinkHealth> {
+        let row = self
+            .pool
+            .fetch_optional::<HealthRow>(
+                Query::new(format!("SELECT {HEALTH_COLUMNS} FROM health WHERE id = $1")).bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(LinkHealth::from)
+            .ok_or_else(|| AppError::HealthNotFound(id.to_owned()))
+    }
+
+    async fn save(&self, health: &LinkHealth) -> Result<LinkHealth> {
+        self.pool
+            .execute(
+                Query::new(
+                    "INSERT INTO health (id, url, status_code, redirect_url, checked_at) \
+             VALUES ($1, $2, $3, $4, $5) \
+             ON CONFLICT (id) DO UPDATE SET \
+             url = EXCLUDED.url, status_code = EXCLUDED.status_code, \
+             redirect_url = EXCLUDED.redirect_url, checked_at = EXCLUDED.checked_at",
+                )
+                .bind(health.id())
+                .bind(health.url())
+                .bind(i32::from(health.status_code()))
+                .bind(health.redirect_url())
+                .bind(health.checked_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(health.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.pool
+            .execute(Query::new("DELETE FROM health WHERE id = $1").bind(id))
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(())
+    }
+
+    async fn retain(&self, ids: &[String]) -> Result<()> {
+        self.pool
+            .execute(
+                Query::new(format!(
+                    "DELETE FROM health WHERE NOT {}",
+                    B::contains("id", "$1"),
+                ))
+                .bind(Json(ids)),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: Backend> DetailsRepository for DetailsRepositoryProvider<B> {
+    async fn find(&self, owner: &str) -> Result<Vec<LinkDetails>> {
+        let rows = self
+            .pool
+            .fetch_all::<DetailsRow>(
+                Query::new(format!(
+                    "SELECT {DETAILS_COLUMNS} FROM details WHERE ($1 = '' OR owner = $1)"
+                ))
+                .bind(owner),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(LinkDetails::from).collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<LinkDetails> {
+        let row = self
+            .pool
+            .fetch_optional::<DetailsRow>(
+                Query::new(format!(
+                    "SELECT {DETAILS_COLUMNS} FROM details WHERE id = $1"
+                ))
+                .bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(LinkDetails::from)
+            .ok_or_else(|| AppError::DetailsNotFound(id.to_owned()))
+    }
+
+    async fn save(&self, details: &LinkDetails) -> Result<LinkDetails> {
+        self.pool
+            .execute(
+                Query::new(
+                    "INSERT INTO details (id, owner, priority, rating, notes, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6) \
+             ON CONFLICT (id) DO UPDATE SET \
+             owner = EXCLUDED.owner, priority = EXCLUDED.priority, rating = EXCLUDED.rating, \
+             notes = EXCLUDED.notes, updated_at = EXCLUDED.updated_at",
+                )
+                .bind(details.id())
+                .bind(details.owner())
+                .bind(details.priority().as_str())
+                .bind(details.rating().map(i32::from))
+                .bind(details.notes())
+                .bind(details.updated_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(details.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.pool
+            .execute(Query::new("DELETE FROM details WHERE id = $1").bind(id))
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: Backend> HighlightsRepository for HighlightsRepositoryProvider<B> {
+    async fn find(&self, link_id: &str) -> Result<Vec<Highlight>> {
+        let rows = self
+            .pool
+            .fetch_all::<HighlightRow>(
+                Query::new(format!(
+                    "SELECT {HIGHLIGHT_COLUMNS} FROM highlights WHERE link_id = $1 ORDER BY seq"
+                ))
+                .bind(link_id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(Highlight::from).collect())
+    }
+
+    async fn find_by_owner(&self, owner: &str) -> Result<Vec<Highlight>> {
+        let rows = self
+            .pool
+            .fetch_all::<HighlightRow>(
+                Query::new(format!(
+                    "SELECT {HIGHLIGHT_COLUMNS} FROM highlights WHERE owner = $1 ORDER BY seq"
+                ))
+                .bind(owner),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(Highlight::from).collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<Highlight> {
+        let row = self
+            .pool
+            .fetch_optional::<HighlightRow>(
+                Query::new(format!(
+                    "SELECT {HIGHLIGHT_COLUMNS} FROM highlights WHERE id = $1"
+                ))
+                .bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(Highlight::from)
+            .ok_or_else(|| AppError::HighlightNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, highlight: &Highlight) -> Result<Highlight> {
+        let (id,) = self
+            .pool
+            .fetch_one::<(String,)>(
+                Query::new(
+                    "INSERT INTO highlights \
+             (link_id, owner, quote, note, selectors, created_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING id",
+                )
+                .bind(highlight.link_id())
+                .bind(highlight.owner())
+                .bind(highlight.quote())
+                .bind(highlight.note())
+                .bind(Json(highlight.selectors()))
+                .bind(highlight.created_at())
+                .bind(highlight.updated_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_one() {e:?}")))?;
+        Ok(highlight.clone().with_id(&id))
+    }
+
+    async fn update(&self, highlight: &Highlight) -> Result<Highlight> {
+        let rows_affected = self
+            .pool
+            .execute(
+                Query::new(
+                    "UPDATE highlights SET quote = $2, note = $3, selectors = $4, \
+             updated_at = $5 WHERE id = $1",
+                )
+                .bind(highlight.id())
+                .bind(highlight.quote())
+                .bind(highlight.note())
+                .bind(Json(highlight.selectors()))
+                .bind(highlight.updated_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        if rows_affected == 0 {
+            return Err(AppError::HighlightNotFound(highlight.id().to_owned()));
+        }
+        Ok(highlight.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        let rows_affected = self
+            .pool
+            .execute(Query::new("DELETE FROM highlights WHERE id = $1").bind(id))
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        if rows_affected == 0 {
+            return Err(AppError::HighlightNotFound(id.to_owned()));
+        }
+        Ok(())
+    }
+
+    async fn delete_all(&self, link_id: &str) -> Result<()> {
+        self.pool
+            .execute(Query::new("DELETE FROM highlights WHERE link_id = $1").bind(link_id))
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: Backend> ProgressRepository for ProgressRepositoryProvider<B> {
+    async fn find(&self, owner: &str) -> Result<Vec<ReadingProgress>> {
+        let rows = self
+            .pool
+            .fetch_all::<ProgressRow>(
+                Query::new(format!(
+                    "SELECT {PROGRESS_COLUMNS} FROM progress WHERE ($1 = '' OR owner = $1)"
+                ))
+                .bind(owner),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(ReadingProgress::from).collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<ReadingProgress> {
+        let row = self
+            .pool
+            .fetch_optional::<ProgressRow>(
+                Query::new(format!(
+                    "SELECT {PROGRESS_COLUMNS} FROM progress WHERE id = $1"
+                ))
+                .bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(ReadingProgress::from)
+            .ok_or_else(|| AppError::ProgressNotFound(id.to_owned()))
+    }
+
+    async fn save(&self, progress: &ReadingProgress) -> Result<ReadingProgress> {
+        self.pool
+            .execute(
+                Query::new(
+                    "INSERT INTO progress \
+             (id, owner, percentage, position, time_spent, finished_at, updated_at) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7) \
+             ON CONFLICT (id) DO UPDATE SET \
+             owner = EXCLUDED.owner, percentage = EXCLUDED.percentage, \
+             position = EXCLUDED.position, time_spent = EXCLUDED.time_spent, \
+             finished_at = EXCLUDED.finished_at, updated_at = EXCLUDED.updated_at",
+                )
+                .bind(progress.id())
+                .bind(progress.owner())
+                .bind(i32::from(progress.percentage()))
+                .bind(i64::from(progress.position()))
+                .bind(i64::from(progress.time_spent()))
+                .bind(progress.finished_at())
+                .bind(progress.updated_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(progress.clone())
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        self.pool
+            .execute(Query::new("DELETE FROM progress WHERE id = $1").bind(id))
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: Backend> WebhooksRepository for WebhooksRepositoryProvider<B> {
+    async fn find(&self, owner: &str) -> Result<Vec<Webhook>> {
+        let rows = self
+            .pool
+            .fetch_all::<WebhookRow>(
+                Query::new(format!(
+                    "SELECT {WEBHOOK_COLUMNS} FROM webhooks WHERE owner = $1 ORDER BY created_at"
+                ))
+                .bind(owner),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(Webhook::from).collect())
+    }
+
+    async fn get(&self, id: &str) -> Result<Webhook> {
+        let row = self
+            .pool
+            .fetch_optional::<WebhookRow>(
+                Query::new(format!(
+                    "SELECT {WEBHOOK_COLUMNS} FROM webhooks WHERE id = $1"
+                ))
+                .bind(id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_optional() {e:?}")))?;
+        row.map(Webhook::from)
+            .ok_or_else(|| AppError::WebhookNotFound(id.to_owned()))
+    }
+
+    async fn create(&self, webhook: &Webhook) -> Result<Webhook> {
+        let (id,) = self
+            .pool
+            .fetch_one::<(String,)>(
+                Query::new(
+                    "INSERT INTO webhooks (owner, url, secret, events, created_at) \
+             VALUES ($1, $2, $3, $4, $5) RETURNING id",
+                )
+                .bind(webhook.owner())
+                .bind(webhook.url())
+                .bind(webhook.secret())
+                .bind(Json(webhook.events()))
+                .bind(webhook.created_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_one() {e:?}")))?;
+        Ok(webhook.clone().with_id(&id))
+    }
+
+    async fn delete(&self, id: &str) -> Result<()> {
+        // deliveries are removed along with the webhook through the foreign key
+        self.pool
+            .execute(Query::new("DELETE FROM webhooks WHERE id = $1").bind(id))
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(())
+    }
+
+    async fn find_deliveries(&self, webhook_id: &str) -> Result<Vec<WebhookDelivery>> {
+        let rows = self
+            .pool
+            .fetch_all::<DeliveryRow>(
+                Query::new(format!(
+                    "SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries \
+             WHERE webhook_id = $1 ORDER BY seq DESC"
+                ))
+                .bind(webhook_id),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("fetch_all() {e:?}")))?;
+        Ok(rows.into_iter().map(WebhookDelivery::from).collect())
+    }
+
+    async fn create_delivery(&self, delivery: &WebhookDelivery) -> Result<WebhookDelivery> {
+        self.pool
+            .execute(
+                Query::new(format!(
+                    "INSERT INTO webhook_deliveries ({DELIVERY_COLUMNS}) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7)"
+                ))
+                .bind(delivery.webhook_id())
+                .bind(delivery.event())
+                .bind(delivery.link_id())
+                .bind(i32::from(delivery.status_code()))
+                .bind(i32::try_from(delivery.attempts()).unwrap_or(i32::MAX))
+                .bind(delivery.error())
+                .bind(delivery.delivered_at()),
+            )
+            .await
+            .map_err(|e| AppError::Database(format!("execute() {e:?}")))?;
+        Ok(delivery.clone())
+    }
+}
+
+fn to_i64(value: usize) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+fn to_u64(value: i64) -> u64 {
+    u64::try_from(value).unwrap_or_default()
+}
@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use axum::async_trait;
+use sqlx::{
+    migrate::Migrator,
+    sqlite::{SqliteArguments, SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions},
+    Arguments, SqlitePool,
+};
+
+use crate::types::{AppError, Result};
+
+use super::sql::{Backend, Param, Query, Record};
+
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+/// Opens a pool on the database file, creating it on first use. Connections
+/// are established lazily so this can be called outside of an async context.
+pub fn connect(path: &Path) -> SqlitePool {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .foreign_keys(true);
+    SqlitePoolOptions::new().connect_lazy_with(options)
+}
+
+/// Brings the schema up to date, this needs to run before the repositories
+/// are used.
+pub async fn migrate(pool: &SqlitePool) -> Result<()> {
+    MIGRATOR
+        .run(pool)
+        .await
+        .map_err(|e| AppError::Database(format!("migrate() {e:?}")))
+}
+
+/// Splits the query into its statement, with the `$N` placeholders of the
+/// shared queries turned into the `?N` of `SQLite`, and its arguments.
+fn prepare(query: Query) -> (String, SqliteArguments<'static>) {
+    let (sql, params) = query.into_parts();
+
+    let mut statement = String::with_capacity(sql.len());
+    let mut chars = sql.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '$' && chars.peek().is_some_and(char::is_ascii_digit) {
+            statement.push('?');
+        } else {
+            statement.push(c);
+        }
+    }
+
+    let mut arguments = SqliteArguments::default();
+    for param in params {
+        match param {
+            Param::Text(value) => arguments.add(value),
+            Param::Int(value) => arguments.add(value),
+            Param::OptionalInt(value) => arguments.add(value),
+            Param::BigInt(value) => arguments.add(value),
+            Param::Boolean(value) => arguments.add(value),
+            Param::Timestamp(value) => arguments.add(value),
+            Param::OptionalTimestamp(value) => arguments.add(value),
+            Param::Json(value) => arguments.add(value),
+        }
+    }
+    (statement, arguments)
+}
+
+#[async_trait]
+impl Backend for SqlitePool {
+    async fn execute(&self, query: Query) -> sqlx::Result<u64> {
+        let (sql, arguments) = prepare(query);
+        let result = sqlx::query_with(&sql, arguments).execute(self).await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn fetch_all<R: Record + 'static>(&self, query: Query) -> sqlx::Result<Vec<R>> {
+        let (sql, arguments) = prepare(query);
+        sqlx::query_as_with(&sql, arguments).fetch_all(self).await
+    }
+
+    async fn fetch_optional<R: Record + 'static>(&self, query: Query) -> sqlx::Result<Option<R>> {
+        let (sql, arguments) = prepare(query);
+        sqlx::query_as_with(&sql, arguments)
+            .fetch_optional(self)
+            .await
+    }
+
+    async fn fetch_one<R: Record + 'static>(&self, query: Query) -> sqlx::Result<R> {
+        let (sql, arguments) = prepare(query);
+        sqlx::query_as_with(&sql, arguments).fetch_one(self).await
+    }
+
+    // timestamps are stored as RFC 3339 text with as many fractional digits as
+    // they need, so comparing the text does not always follow time order
+    fn timestamp(expression: &str) -> String {
+        format!("julianday({expression})")
+    }
+
+    fn contains(column: &str, placeholder: &str) -> String {
+        format!("{column} IN (SELECT value FROM json_each({placeholder}))")
+    }
+
+    fn has_member(column: &str, placeholder: &str) -> String {
+        format!(
+            "EXISTS (SELECT 1 FROM json_each({column}) \
+             WHERE json_extract(json_each.value, '$.user') = {placeholder})"
+        )
+    }
+}
@@ -1,73 +1,361 @@
 use std::sync::Arc;
 
 use axum::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 #[cfg(test)]
 use mockall::{automock, predicate::*};
 
-use crate::{
-    repository, service,
-    types::{LinkItem, LinkQuery, Result, Token, UserInfo},
+use crate::types::{
+    AppContext, Collection, DetailsFilter, Feed, Highlight, LibraryStats, LinkDetails, LinkHealth,
+    LinkItem, LinkPatchRequest, LinkQuery, NextStrategy, ReadingProgress, ReadingStats, Reminder,
+    Result, Revision, Role, Share, SharedView, Snapshot, Token, TrashedLink, UserInfo,
+    VersionedLink, Webhook, WebhookDelivery,
 };
 
 pub type DynLinks = Arc<dyn Links + Send + Sync>;
 pub type DynUsers = Arc<dyn Users + Send + Sync>;
 pub type DynAnalysis = Arc<dyn Analysis + Send + Sync>;
+pub type DynSnapshots = Arc<dyn Snapshots + Send + Sync>;
+pub type DynHealth = Arc<dyn Health + Send + Sync>;
+pub type DynWebhooks = Arc<dyn Webhooks + Send + Sync>;
+pub type DynCollections = Arc<dyn Collections + Send + Sync>;
+pub type DynShares = Arc<dyn Shares + Send + Sync>;
+pub type DynReminders = Arc<dyn Reminders + Send + Sync>;
+pub type DynNotifier = Arc<dyn Notifier + Send + Sync>;
+pub type DynDetails = Arc<dyn Details + Send + Sync>;
+pub type DynHighlights = Arc<dyn Highlights + Send + Sync>;
+pub type DynProgress = Arc<dyn Progress + Send + Sync>;
 
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait Links {
-    async fn search(
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<LinkItem>>;
+
+    /// Retrieves a link for its owner, an admin, or a member of a collection
+    /// holding the link.
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<LinkItem>;
+
+    /// Same as [`Links::get`], along with the version of the link.
+    async fn get_versioned(&self, context: &AppContext, query: &LinkQuery)
+        -> Result<VersionedLink>;
+
+    /// Picks the unread link the user of `query` should read next, among
+    /// those that can be read within `minutes` when it is given. Returns
+    /// `None` when no unread link fits.
+    async fn next(
         &self,
-        links_repo: Box<repository::DynLinks>,
+        context: &AppContext,
         query: &LinkQuery,
-    ) -> Result<Vec<LinkItem>>;
+        strategy: NextStrategy,
+        minutes: Option<usize>,
+    ) -> Result<Option<LinkItem>>;
 
-    async fn get(
+    async fn create(&self, context: &AppContext, item: &LinkItem) -> Result<LinkItem>;
+
+    async fn update(
         &self,
-        links_repo: Box<repository::DynLinks>,
+        context: &AppContext,
         query: &LinkQuery,
-    ) -> Result<LinkItem>;
+        item: &LinkItem,
+        if_match: Option<String>,
+    ) -> Result<VersionedLink>;
 
-    async fn create(
+    async fn patch(
         &self,
-        analysis_service: Box<service::DynAnalysis>,
-        links_repo: Box<repository::DynLinks>,
-        item: &LinkItem,
-    ) -> Result<LinkItem>;
+        context: &AppContext,
+        query: &LinkQuery,
+        patch: &LinkPatchRequest,
+        if_match: Option<String>,
+    ) -> Result<VersionedLink>;
 
-    async fn update(
+    async fn delete(
         &self,
-        analysis_service: Box<service::DynAnalysis>,
-        links_repo: Box<repository::DynLinks>,
+        context: &AppContext,
         query: &LinkQuery,
-        item: &LinkItem,
-    ) -> Result<LinkItem>;
+        if_match: Option<String>,
+    ) -> Result<()>;
+
+    async fn trash(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<TrashedLink>>;
+
+    async fn restore(&self, context: &AppContext, query: &LinkQuery) -> Result<LinkItem>;
+
+    async fn purge(&self, context: &AppContext, deleted_before: &DateTime<Utc>) -> Result<()>;
+
+    async fn history(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Revision>>;
 
-    async fn delete(&self, links_repo: Box<repository::DynLinks>, query: &LinkQuery) -> Result<()>;
+    async fn revert(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        revision_id: &str,
+    ) -> Result<VersionedLink>;
 }
 
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait Users {
-    async fn register(
-        &self,
-        users_repo: Box<repository::DynUsers>,
-        user_info: &UserInfo,
-    ) -> Result<UserInfo>;
+    async fn register(&self, context: &AppContext, user_info: &UserInfo) -> Result<UserInfo>;
+
+    async fn login(&self, context: &AppContext, user_info: &UserInfo) -> Result<Token>;
 
-    async fn login(
+    /// Adds up the library of `user`, day by day over the `days` up to
+    /// `today`.
+    async fn stats(
         &self,
-        users_repo: Box<repository::DynUsers>,
-        user_info: &UserInfo,
-    ) -> Result<Token>;
+        context: &AppContext,
+        user: &str,
+        days: u32,
+        today: &NaiveDate,
+    ) -> Result<LibraryStats>;
 }
 
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait Analysis {
-    async fn analyze(&self, link_item: &LinkItem) -> Result<()>;
+    async fn analyze(&self, link_item: &LinkItem) -> Result<LinkItem>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Snapshots {
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<Snapshot>;
+
+    async fn capture(&self, context: &AppContext, link_item: &LinkItem) -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Health {
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<LinkItem>>;
+
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<LinkHealth>;
+
+    async fn check(&self, context: &AppContext) -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Webhooks {
+    async fn search(&self, context: &AppContext, owner: &str) -> Result<Vec<Webhook>>;
+
+    async fn create(&self, context: &AppContext, webhook: &Webhook) -> Result<Webhook>;
+
+    async fn delete(&self, context: &AppContext, owner: &str, id: &str) -> Result<()>;
+
+    async fn deliveries(
+        &self,
+        context: &AppContext,
+        owner: &str,
+        id: &str,
+    ) -> Result<Vec<WebhookDelivery>>;
+
+    async fn notify(&self, context: &AppContext, event: &str, link_item: &LinkItem) -> Result<()>;
+}
+
+/// Collections are addressed with the same query as links, carrying the id of
+/// the collection and the user making the request.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Collections {
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Collection>>;
+
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<Collection>;
+
+    /// Lists the collections shared with the user of the query.
+    async fn shared(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Collection>>;
+
+    async fn create(&self, context: &AppContext, collection: &Collection) -> Result<Collection>;
+
+    async fn update(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        collection: &Collection,
+    ) -> Result<Collection>;
+
+    async fn delete(&self, context: &AppContext, query: &LinkQuery) -> Result<()>;
+
+    async fn links(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<LinkItem>>;
+
+    async fn add_link(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        link_id: &str,
+    ) -> Result<Collection>;
+
+    async fn remove_link(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        link_id: &str,
+    ) -> Result<Collection>;
+
+    async fn reorder(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        links: &[String],
+    ) -> Result<Collection>;
+
+    async fn share(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        member: &str,
+        role: Role,
+    ) -> Result<Collection>;
+
+    async fn unshare(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        member: &str,
+    ) -> Result<Collection>;
+}
+
+/// Shares are managed with the same query as links, carrying the id of the
+/// share and the user making the request, while viewing one or reading it as
+/// a feed only takes its token.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Shares {
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Share>>;
+
+    async fn create(&self, context: &AppContext, share: &Share) -> Result<Share>;
+
+    async fn delete(&self, context: &AppContext, query: &LinkQuery) -> Result<()>;
+
+    async fn view(&self, context: &AppContext, token: &str) -> Result<SharedView>;
+
+    async fn feed(&self, context: &AppContext, token: &str) -> Result<Feed>;
+}
+
+/// Reminders are managed with the same query as links, carrying the id of the
+/// reminder and the user making the request.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Reminders {
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Reminder>>;
+
+    /// Sets a reminder for a link, or the daily digest, which replaces the
+    /// one set before.
+    async fn create(&self, context: &AppContext, reminder: &Reminder) -> Result<Reminder>;
+
+    async fn snooze(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        until: &DateTime<Utc>,
+    ) -> Result<Reminder>;
+
+    async fn dismiss(&self, context: &AppContext, query: &LinkQuery) -> Result<()>;
+
+    /// Sends out every reminder that is due at `now`.
+    async fn fire(
+        &self,
+        context: &AppContext,
+        notifier: Box<DynNotifier>,
+        now: &DateTime<Utc>,
+    ) -> Result<()>;
+}
+
+/// Delivers reminders to the user they belong to.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Notifier {
+    async fn notify(&self, owner: &str, subject: &str, links: &[LinkItem]) -> Result<()>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Details {
+    /// Keeps the links among `items` that match `filter`, in the order it
+    /// asks for.
+    async fn search(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        items: Vec<LinkItem>,
+        filter: &DetailsFilter,
+    ) -> Result<Vec<LinkItem>>;
+
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<LinkDetails>;
+
+    /// Replaces the details of a link, for anyone who may edit the link.
+    async fn update(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        details: &LinkDetails,
+    ) -> Result<LinkDetails>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Highlights {
+    /// Lists the highlights of the link of `query`, oldest first.
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Highlight>>;
+
+    /// Highlights a passage of the link of `query`, for anyone who may edit
+    /// the link.
+    async fn create(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        highlight: &Highlight,
+    ) -> Result<Highlight>;
+
+    async fn update(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        highlight: &Highlight,
+    ) -> Result<Highlight>;
+
+    async fn delete(&self, context: &AppContext, query: &LinkQuery, id: &str) -> Result<()>;
+
+    /// Renders the highlights on the links of the user of `query` as
+    /// Markdown, one section per link.
+    async fn export(&self, context: &AppContext, query: &LinkQuery) -> Result<String>;
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Progress {
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<ReadingProgress>;
+
+    /// Records how far a link has been read, marking the link as read the
+    /// first time it is read to the end.
+    async fn update(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        progress: &ReadingProgress,
+    ) -> Result<ReadingProgress>;
+
+    /// Adds up the reading progress of the user of `query` over the `weeks`
+    /// up to `now`.
+    async fn stats(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        weeks: u32,
+        now: &DateTime<Utc>,
+    ) -> Result<ReadingStats>;
 }
 
 pub mod analysis;
+pub mod collections;
+pub mod details;
+pub mod health;
+pub mod highlights;
 pub mod links;
+pub mod notifiers;
+pub mod progress;
+pub mod reminders;
+pub mod shares;
+pub mod snapshots;
+pub mod summary;
 pub mod users;
+pub mod webhooks;
@@ -1,6 +1,7 @@
 use axum::async_trait;
 
 use crate::{
+    outbound,
     service::Analysis as AnalysisService,
     types::{AppError, LinkItem, Result},
 };
@@ -8,31 +9,39 @@ use crate::{
 const ANALYSIS_SERVICE_URL: &str = "ANALYSIS_SERVICE_URL";
 
 pub struct ServiceProvider {
-    http_client: reqwest::Client,
+    http_client: outbound::Client,
     analysis_service_url: String,
 }
 
 #[async_trait]
 impl AnalysisService for ServiceProvider {
-    async fn analyze(&self, link_item: &LinkItem) -> Result<()> {
+    async fn analyze(&self, link_item: &LinkItem) -> Result<LinkItem> {
         if self.analysis_service_url.is_empty() {
             tracing::warn!("Analysis Service URL is not set");
         } else {
+            let body = serde_json::to_string(link_item)
+                .map_err(|e| AppError::Server(format!("serde_json::to_string() {e:?}")))?;
             self.http_client
-                .post(self.analysis_service_url.clone())
-                .json(&link_item)
-                .send()
+                .post(
+                    &self.analysis_service_url,
+                    &[("Content-Type", "application/json")],
+                    body,
+                )
                 .await
-                .map_err(|e| AppError::Server(format!("client.post() {e:?}")))?;
+                .map_err(|e| AppError::Server(format!("client.post() {e}")))?;
         }
-        Ok(())
+        Ok(link_item.clone())
     }
 }
 
 impl Default for ServiceProvider {
     fn default() -> Self {
         Self {
-            http_client: reqwest::Client::new(),
+            // the analysis service is configured by the operator and usually
+            // runs next to this one, so it is allowed to be on a private network
+            http_client: outbound::Client::new(
+                outbound::Policy::default().allow_private_networks(),
+            ),
             analysis_service_url: std::env::var(ANALYSIS_SERVICE_URL)
                 .map_or_else(|_| String::default(), |url| url),
         }
@@ -66,13 +75,15 @@ mod tests {
             .create();
 
         let analysis_service = ServiceProvider {
-            http_client: reqwest::Client::new(),
+            http_client: outbound::Client::new(
+                outbound::Policy::default().allow_private_networks(),
+            ),
             analysis_service_url: server.url(),
         };
 
         let response = analysis_service.analyze(&item).await;
 
         mock.assert();
-        assert!(response.is_ok());
+        assert_eq!(response, Ok(item));
     }
 }
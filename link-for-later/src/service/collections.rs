@@ -0,0 +1,688 @@
+use axum::async_trait;
+use chrono::Utc;
+
+use crate::{
+    repository,
+    service::Collections as CollectionsService,
+    types::{
+        AppContext, AppError, Collection, LinkItem, LinkQuery, LinkQueryBuilder, Member, Result,
+        Role, UserQueryBuilder,
+    },
+};
+
+/// The highest role `member` holds on a link through the collections shared
+/// with them, if any of those collections holds it.
+pub async fn shared_role(
+    collections_repo: &repository::DynCollections,
+    member: &str,
+    link_id: &str,
+) -> Result<Option<Role>> {
+    Ok(collections_repo
+        .find_shared(member)
+        .await?
+        .iter()
+        .filter(|collection| collection.links().iter().any(|id| id == link_id))
+        .filter_map(|collection| collection.role(member))
+        .max())
+}
+
+#[derive(Default)]
+pub struct ServiceProvider {}
+
+#[async_trait]
+impl CollectionsService for ServiceProvider {
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Collection>> {
+        let owner = if query.is_from_admin() {
+            ""
+        } else {
+            query.user()
+        };
+        context.collections_repo().find(owner).await
+    }
+
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<Collection> {
+        authorize(context.collections_repo(), query, Role::Viewer).await
+    }
+
+    async fn shared(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Collection>> {
+        context.collections_repo().find_shared(query.user()).await
+    }
+
+    async fn create(&self, context: &AppContext, collection: &Collection) -> Result<Collection> {
+        let collection = Collection::new(
+            collection.owner(),
+            collection.name(),
+            collection.description(),
+            collection.position(),
+            &Utc::now(),
+        );
+        context.collections_repo().create(&collection).await
+    }
+
+    async fn update(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        collection: &Collection,
+    ) -> Result<Collection> {
+        let retrieved_collection =
+            authorize(context.collections_repo(), query, Role::Admin).await?;
+
+        // the links and members of a collection are changed on their own,
+        // see `add_link`, `reorder` and `share`
+        let updated_collection = Collection::new(
+            retrieved_collection.owner(),
+            collection.name(),
+            collection.description(),
+            collection.position(),
+            retrieved_collection.created_at(),
+        )
+        .with_id(retrieved_collection.id())
+        .with_links(retrieved_collection.links())
+        .with_members(retrieved_collection.members())
+        .with_updated_at(&Utc::now());
+        context.collections_repo().update(&updated_collection).await
+    }
+
+    async fn delete(&self, context: &AppContext, query: &LinkQuery) -> Result<()> {
+        // only the owner can delete a collection, whatever the role of a member
+        let retrieved_collection = context.collections_repo().get(query.id()).await?;
+        if query.user() != retrieved_collection.owner() && !query.is_from_admin() {
+            return Err(AppError::Authorization(String::from(
+                "User is not authorized to access resource",
+            )));
+        }
+        context
+            .collections_repo()
+            .delete(retrieved_collection.id())
+            .await
+    }
+
+    async fn links(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<LinkItem>> {
+        let collection = self.get(context, query).await?;
+
+        let mut items = Vec::with_capacity(collection.links().len());
+        for link_id in collection.links() {
+            let get_query = LinkQueryBuilder::default().id(link_id).build();
+            match context.links_repo().get(&get_query).await {
+                Ok(item) => items.push(item),
+                // deleted links stay in the collection so that restoring them brings them back
+                Err(AppError::LinkNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(items)
+    }
+
+    async fn add_link(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        link_id: &str,
+    ) -> Result<Collection> {
+        let collection = authorize(context.collections_repo(), query, Role::Editor).await?;
+
+        // the link is looked up on behalf of the owner, as only their links
+        // can go into the collection and an editor may not see them otherwise
+        let link_query = LinkQueryBuilder::new(link_id, collection.owner()).build();
+        let item = context.links_service().get(context, &link_query).await?;
+        if item.owner() != collection.owner() {
+            return Err(AppError::Validation(format!(
+                "add_link() link {link_id} belongs to another user"
+            )));
+        }
+
+        if collection.links().iter().any(|id| id == link_id) {
+            return Ok(collection);
+        }
+        let mut links = collection.links().to_vec();
+        links.push(link_id.to_owned());
+        let updated_collection = collection.with_links(&links).with_updated_at(&Utc::now());
+        context.collections_repo().update(&updated_collection).await
+    }
+
+    async fn remove_link(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        link_id: &str,
+    ) -> Result<Collection> {
+        let collection = authorize(context.collections_repo(), query, Role::Editor).await?;
+
+        if !collection.links().iter().any(|id| id == link_id) {
+            return Err(AppError::LinkNotFound(link_id.to_owned()));
+        }
+        let links: Vec<String> = collection
+            .links()
+            .iter()
+            .filter(|id| *id != link_id)
+            .cloned()
+            .collect();
+        let updated_collection = collection.with_links(&links).with_updated_at(&Utc::now());
+        context.collections_repo().update(&updated_collection).await
+    }
+
+    async fn reorder(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        links: &[String],
+    ) -> Result<Collection> {
+        let collection = authorize(context.collections_repo(), query, Role::Editor).await?;
+
+        // the new order has to name every link of the collection exactly once
+        let mut current_links = collection.links().to_vec();
+        current_links.sort();
+        let mut requested_links = links.to_vec();
+        requested_links.sort();
+        if current_links != requested_links {
+            return Err(AppError::Validation(String::from(
+                "reorder() links do not match the links of the collection",
+            )));
+        }
+
+        let updated_collection = collection.with_links(links).with_updated_at(&Utc::now());
+        context.collections_repo().update(&updated_collection).await
+    }
+
+    async fn share(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        member: &str,
+        role: Role,
+    ) -> Result<Collection> {
+        let collection = authorize(context.collections_repo(), query, Role::Admin).await?;
+        if member == collection.owner() {
+            return Err(AppError::Validation(format!(
+                "share() {member} already owns the collection"
+            )));
+        }
+
+        // only registered users can be invited
+        let user_query = UserQueryBuilder::new(member).build();
+        context.users_repo().get(&user_query).await?;
+
+        let mut members: Vec<Member> = collection
+            .members()
+            .iter()
+            .filter(|current| current.user() != member)
+            .cloned()
+            .collect();
+        members.push(Member::new(member, role));
+        let updated_collection = collection
+            .with_members(&members)
+            .with_updated_at(&Utc::now());
+        context.collections_repo().update(&updated_collection).await
+    }
+
+    async fn unshare(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        member: &str,
+    ) -> Result<Collection> {
+        // members can always leave a collection on their own
+        let role = if member == query.user() {
+            Role::Viewer
+        } else {
+            Role::Admin
+        };
+        let collection = authorize(context.collections_repo(), query, role).await?;
+        if collection.role(member).is_none() {
+            return Err(AppError::UserNotFound(member.to_owned()));
+        }
+
+        let members: Vec<Member> = collection
+            .members()
+            .iter()
+            .filter(|current| current.user() != member)
+            .cloned()
+            .collect();
+        let updated_collection = collection
+            .with_members(&members)
+            .with_updated_at(&Utc::now());
+        context.collections_repo().update(&updated_collection).await
+    }
+}
+
+/// Retrieves the collection of `query` when its user owns it, is an admin,
+/// or has been given at least `role` on it.
+async fn authorize(
+    collections_repo: &repository::DynCollections,
+    query: &LinkQuery,
+    role: Role,
+) -> Result<Collection> {
+    let collection = collections_repo.get(query.id()).await?;
+
+    let is_authorized = query.user() == collection.owner()
+        || query.is_from_admin()
+        || collection
+            .role(query.user())
+            .is_some_and(|member_role| member_role >= role);
+    if is_authorized {
+        Ok(collection)
+    } else {
+        Err(AppError::Authorization(String::from(
+            "User is not authorized to access resource",
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rstest::rstest;
+
+    use crate::{
+        app::ContextBuilder,
+        repository::{
+            MockCollections as MockCollectionsRepo, MockLinks as MockLinksRepo,
+            MockUsers as MockUsersRepo,
+        },
+        service::MockLinks as MockLinksService,
+        types::{LinkItemBuilder, UserInfoBuilder},
+    };
+
+    use super::*;
+
+    fn collection(owner: &str, links: &[&str]) -> Collection {
+        let links: Vec<String> = links.iter().map(|&id| id.to_owned()).collect();
+        Collection::new(owner, "Reading", "", 0, &Utc::now())
+            .with_id("1")
+            .with_links(&links)
+    }
+
+    #[rstest]
+    #[case(true, "admin", "")]
+    #[case(false, "user", "user")]
+    #[tokio::test]
+    async fn test_search_collections(
+        #[case] is_admin: bool,
+        #[case] user: &str,
+        #[case] owner: &str,
+    ) {
+        let request_query = LinkQueryBuilder::default()
+            .user(user)
+            .is_from_admin(is_admin)
+            .build();
+        let expected_owner = owner.to_owned();
+        let retrieved_collections = vec![collection("user", &[])];
+        let response_collections = retrieved_collections.clone();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_find()
+            .withf(move |owner| owner == expected_owner)
+            .times(1)
+            .returning(move |_| Ok(retrieved_collections.clone()));
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service.search(&context, &request_query).await;
+
+        assert_eq!(response, Ok(response_collections));
+    }
+
+    #[tokio::test]
+    async fn test_get_collection_unauthorized() {
+        let request_query = LinkQueryBuilder::new("1", "unauthorized-user").build();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(collection("user", &[])));
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service.get(&context, &request_query).await;
+
+        assert!(matches!(response, Err(AppError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_collection_keeps_links() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let request_collection = Collection::new("", "Renamed", "later", 3, &Utc::now());
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(collection("user", &["2", "1"])));
+        mock_collections_repo
+            .expect_update()
+            .withf(|collection| {
+                collection.id() == "1"
+                    && collection.owner() == "user"
+                    && collection.name() == "Renamed"
+                    && collection.position() == 3
+                    && collection.links() == ["2", "1"]
+            })
+            .times(1)
+            .returning(|collection| Ok(collection.clone()));
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service
+            .update(&context, &request_query, &request_collection)
+            .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_collection_unauthorized() {
+        let request_query = LinkQueryBuilder::new("1", "unauthorized-user").build();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(collection("user", &[])));
+        mock_collections_repo.expect_delete().times(0);
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service.delete(&context, &request_query).await;
+
+        assert!(matches!(response, Err(AppError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_collection_links_in_order() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(collection("user", &["3", "2", "1"])));
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo.expect_get().times(3).returning(|query| {
+            if query.id() == "2" {
+                Err(AppError::LinkNotFound("2".into()))
+            } else {
+                Ok(LinkItemBuilder::new("http://link")
+                    .id(query.id())
+                    .owner("user")
+                    .build())
+            }
+        });
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service
+            .links(&context, &request_query)
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = response.iter().map(LinkItem::id).collect();
+        assert_eq!(ids, ["3", "1"]);
+    }
+
+    #[tokio::test]
+    async fn test_add_link() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(collection("user", &["1"])));
+        mock_collections_repo
+            .expect_update()
+            .withf(|collection| collection.links() == ["1", "2"])
+            .times(1)
+            .returning(|collection| Ok(collection.clone()));
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_get()
+            .withf(|_, query| query.id() == "2" && query.user() == "user")
+            .times(1)
+            .returning(|_, _| {
+                Ok(LinkItemBuilder::new("http://link")
+                    .id("2")
+                    .owner("user")
+                    .build())
+            });
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_service(Arc::new(mock_links_service))
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service
+            .add_link(&context, &request_query, "2")
+            .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_add_link_of_another_user() {
+        let request_query = LinkQueryBuilder::new("1", "admin")
+            .is_from_admin(true)
+            .build();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(collection("user", &[])));
+        mock_collections_repo.expect_update().times(0);
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service.expect_get().times(1).returning(|_, _| {
+            Ok(LinkItemBuilder::new("http://link")
+                .id("2")
+                .owner("other-user")
+                .build())
+        });
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_service(Arc::new(mock_links_service))
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service
+            .add_link(&context, &request_query, "2")
+            .await;
+
+        assert!(matches!(response, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_remove_link_not_in_collection() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(collection("user", &["1"])));
+        mock_collections_repo.expect_update().times(0);
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service
+            .remove_link(&context, &request_query, "2")
+            .await;
+
+        assert_eq!(response, Err(AppError::LinkNotFound("2".into())));
+    }
+
+    #[rstest]
+    #[case(Role::Viewer, false)]
+    #[case(Role::Editor, true)]
+    #[case(Role::Admin, true)]
+    #[tokio::test]
+    async fn test_remove_link_as_member(#[case] role: Role, #[case] is_authorized: bool) {
+        let request_query = LinkQueryBuilder::new("1", "member").build();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .times(1)
+            .returning(move |_| {
+                Ok(collection("user", &["1"]).with_members(&[Member::new("member", role)]))
+            });
+        mock_collections_repo
+            .expect_update()
+            .withf(|collection| collection.links().is_empty())
+            .times(usize::from(is_authorized))
+            .returning(|collection| Ok(collection.clone()));
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service
+            .remove_link(&context, &request_query, "1")
+            .await;
+
+        assert_eq!(response.is_ok(), is_authorized);
+    }
+
+    #[tokio::test]
+    async fn test_share_collection() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo.expect_get().times(1).returning(|_| {
+            Ok(collection("user", &[]).with_members(&[Member::new("member", Role::Viewer)]))
+        });
+        mock_collections_repo
+            .expect_update()
+            .withf(|collection| collection.members() == [Member::new("member", Role::Editor)])
+            .times(1)
+            .returning(|collection| Ok(collection.clone()));
+
+        let mut mock_users_repo = MockUsersRepo::new();
+        mock_users_repo
+            .expect_get()
+            .withf(|query| query.email() == "member")
+            .times(1)
+            .returning(|_| Ok(UserInfoBuilder::new("member", "password").build()));
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .users_repo(Arc::new(mock_users_repo))
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service
+            .share(&context, &request_query, "member", Role::Editor)
+            .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_share_collection_with_unknown_user() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(collection("user", &[])));
+        mock_collections_repo.expect_update().times(0);
+
+        let mut mock_users_repo = MockUsersRepo::new();
+        mock_users_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Err(AppError::UserNotFound("unknown".into())));
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .users_repo(Arc::new(mock_users_repo))
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service
+            .share(&context, &request_query, "unknown", Role::Viewer)
+            .await;
+
+        assert_eq!(response, Err(AppError::UserNotFound("unknown".into())));
+    }
+
+    #[rstest]
+    #[case("member", true)]
+    #[case("other-member", false)]
+    #[tokio::test]
+    async fn test_unshare_collection_as_viewer(#[case] member: &str, #[case] is_authorized: bool) {
+        let request_query = LinkQueryBuilder::new("1", "member").build();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo.expect_get().times(1).returning(|_| {
+            Ok(collection("user", &[]).with_members(&[
+                Member::new("member", Role::Viewer),
+                Member::new("other-member", Role::Viewer),
+            ]))
+        });
+        mock_collections_repo
+            .expect_update()
+            .times(usize::from(is_authorized))
+            .returning(|collection| Ok(collection.clone()));
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service
+            .unshare(&context, &request_query, member)
+            .await;
+
+        assert_eq!(response.is_ok(), is_authorized);
+    }
+
+    #[rstest]
+    #[case(&["3", "1", "2"], true)]
+    #[case(&["3", "1"], false)]
+    #[case(&["3", "1", "1"], false)]
+    #[case(&["3", "1", "4"], false)]
+    #[tokio::test]
+    async fn test_reorder(#[case] links: &[&str], #[case] is_valid: bool) {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let links: Vec<String> = links.iter().map(|&id| id.to_owned()).collect();
+        let expected_links = links.clone();
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(collection("user", &["1", "2", "3"])));
+        mock_collections_repo
+            .expect_update()
+            .withf(move |collection| collection.links() == expected_links)
+            .times(usize::from(is_valid))
+            .returning(|collection| Ok(collection.clone()));
+
+        let collections_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = collections_service
+            .reorder(&context, &request_query, &links)
+            .await;
+
+        assert_eq!(response.is_ok(), is_valid);
+    }
+}
@@ -0,0 +1,329 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use axum::async_trait;
+use chrono::Utc;
+
+use crate::{
+    service::{links::authorize, Details as DetailsService},
+    types::{
+        AppContext, AppError, DetailsFilter, LinkDetails, LinkItem, LinkQuery, Priority, Result,
+        Role,
+    },
+};
+
+pub const SORT_PRIORITY: &str = "priority";
+pub const SORT_RATING: &str = "rating";
+
+#[derive(Default)]
+pub struct ServiceProvider {}
+
+#[async_trait]
+impl DetailsService for ServiceProvider {
+    async fn search(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        items: Vec<LinkItem>,
+        filter: &DetailsFilter,
+    ) -> Result<Vec<LinkItem>> {
+        if let Some(sort) = filter.sort() {
+            if sort != SORT_PRIORITY && sort != SORT_RATING {
+                return Err(AppError::Validation(format!(
+                    "search() unknown sort {sort}"
+                )));
+            }
+        }
+
+        let owner = if query.is_from_admin() {
+            ""
+        } else {
+            query.user()
+        };
+        let details: HashMap<String, LinkDetails> = context
+            .details_repo()
+            .find(owner)
+            .await?
+            .into_iter()
+            .map(|details| (details.id().to_owned(), details))
+            .collect();
+        let priority = |item: &LinkItem| {
+            details
+                .get(item.id())
+                .map_or_else(Priority::default, LinkDetails::priority)
+        };
+        let rating = |item: &LinkItem| details.get(item.id()).and_then(LinkDetails::rating);
+
+        let mut items: Vec<LinkItem> = items
+            .into_iter()
+            .filter(|item| filter.priority().is_none() || filter.priority() == Some(priority(item)))
+            // `None` orders before any rating, so no minimum lets every link through
+            // and unrated links never reach a minimum
+            .filter(|item| filter.min_rating() <= rating(item))
+            .collect();
+        // the sorts are stable, so links that tie keep the order they came in
+        match filter.sort() {
+            Some(SORT_PRIORITY) => items.sort_by_key(|item| Reverse(priority(item))),
+            Some(SORT_RATING) => items.sort_by_key(|item| Reverse(rating(item))),
+            _ => {}
+        }
+        Ok(items)
+    }
+
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<LinkDetails> {
+        let item = context.links_service().get(context, query).await?;
+        match context.details_repo().get(item.id()).await {
+            Err(AppError::DetailsNotFound(_)) => Ok(LinkDetails::new(
+                item.id(),
+                item.owner(),
+                Priority::default(),
+                None,
+                "",
+                item.updated_at(),
+            )),
+            result => result,
+        }
+    }
+
+    async fn update(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        details: &LinkDetails,
+    ) -> Result<LinkDetails> {
+        let item = authorize(context, query, Role::Editor).await?;
+
+        let now = Utc::now();
+        context
+            .details_repo()
+            .save(&LinkDetails::new(
+                item.id(),
+                item.owner(),
+                details.priority(),
+                details.rating(),
+                details.notes(),
+                &now,
+            ))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rstest::rstest;
+
+    use crate::{
+        app::ContextBuilder,
+        repository::{
+            MockCollections as MockCollectionsRepo, MockDetails as MockDetailsRepo,
+            MockLinks as MockLinksRepo,
+        },
+        service::MockLinks as MockLinksService,
+        types::{Collection, LinkItemBuilder, LinkQueryBuilder, Member},
+    };
+
+    use super::*;
+
+    fn items() -> Vec<LinkItem> {
+        ["1", "2", "3", "4"]
+            .iter()
+            .map(|id| {
+                LinkItemBuilder::new(&format!("http://link/{id}"))
+                    .id(id)
+                    .owner("user")
+                    .build()
+            })
+            .collect()
+    }
+
+    fn details() -> Vec<LinkDetails> {
+        let now = Utc::now();
+        vec![
+            LinkDetails::new("1", "user", Priority::Low, Some(5), "", &now),
+            LinkDetails::new("2", "user", Priority::High, None, "", &now),
+            LinkDetails::new("3", "user", Priority::High, Some(3), "", &now),
+        ]
+    }
+
+    #[rstest]
+    #[case(DetailsFilter::default(), &["1", "2", "3", "4"])]
+    #[case(DetailsFilter::new(Some(Priority::High), None, None), &["2", "3"])]
+    #[case(DetailsFilter::new(Some(Priority::Normal), None, None), &["4"])]
+    #[case(DetailsFilter::new(None, Some(4), None), &["1"])]
+    #[case(DetailsFilter::new(None, None, Some(SORT_PRIORITY)), &["2", "3", "4", "1"])]
+    #[case(DetailsFilter::new(None, None, Some(SORT_RATING)), &["1", "3", "2", "4"])]
+    #[case(
+        DetailsFilter::new(Some(Priority::High), Some(1), Some(SORT_RATING)),
+        &["3"]
+    )]
+    #[tokio::test]
+    async fn test_search_links(#[case] filter: DetailsFilter, #[case] expected_ids: &[&str]) {
+        let request_query = LinkQueryBuilder::default().user("user").build();
+
+        let mut mock_details_repo = MockDetailsRepo::new();
+        mock_details_repo
+            .expect_find()
+            .withf(|owner| owner == "user")
+            .times(1)
+            .returning(|_| Ok(details()));
+
+        let details_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .details_repo(Arc::new(mock_details_repo))
+            .build();
+        let response = details_service
+            .search(&context, &request_query, items(), &filter)
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = response.iter().map(LinkItem::id).collect();
+        assert_eq!(ids, expected_ids);
+    }
+
+    #[tokio::test]
+    async fn test_search_links_unknown_sort() {
+        let request_query = LinkQueryBuilder::default().user("user").build();
+
+        let mut mock_details_repo = MockDetailsRepo::new();
+        mock_details_repo.expect_find().times(0);
+
+        let details_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .details_repo(Arc::new(mock_details_repo))
+            .build();
+        let response = details_service
+            .search(
+                &context,
+                &request_query,
+                items(),
+                &DetailsFilter::new(None, None, Some("title")),
+            )
+            .await;
+
+        assert!(matches!(response, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_get_details_not_set() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let expected_details =
+            LinkDetails::new("1", "user", Priority::Normal, None, "", item.updated_at());
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_get()
+            .times(1)
+            .returning(move |_, _| Ok(item.clone()));
+
+        let mut mock_details_repo = MockDetailsRepo::new();
+        mock_details_repo
+            .expect_get()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(|id| Err(AppError::DetailsNotFound(id.to_owned())));
+
+        let details_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .links_service(Arc::new(mock_links_service))
+            .details_repo(Arc::new(mock_details_repo))
+            .build();
+        let response = details_service.get(&context, &request_query).await;
+
+        assert_eq!(response, Ok(expected_details));
+    }
+
+    #[tokio::test]
+    async fn test_update_details() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let request_details =
+            LinkDetails::new("", "", Priority::High, Some(4), "*worth it*", &Utc::now());
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .withf(|query| query.id() == "1")
+            .times(1)
+            .returning(move |_| Ok(item.clone()));
+
+        let mut mock_details_repo = MockDetailsRepo::new();
+        mock_details_repo
+            .expect_save()
+            .withf(|details| {
+                details.id() == "1"
+                    && details.owner() == "user"
+                    && details.priority() == Priority::High
+                    && details.rating() == Some(4)
+                    && details.notes() == "*worth it*"
+            })
+            .times(1)
+            .returning(|details| Ok(details.clone()));
+
+        let details_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .details_repo(Arc::new(mock_details_repo))
+            .build();
+        let response = details_service
+            .update(&context, &request_query, &request_details)
+            .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[rstest]
+    #[case(Role::Viewer, false)]
+    #[case(Role::Editor, true)]
+    #[tokio::test]
+    async fn test_update_details_of_shared_link(#[case] role: Role, #[case] is_allowed: bool) {
+        let request_query = LinkQueryBuilder::new("1", "member").build();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let collection = Collection::new("user", "reading", "", 0, &Utc::now())
+            .with_id("1")
+            .with_links(&["1".to_owned()])
+            .with_members(&[Member::new("member", role)]);
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .withf(|query| query.id() == "1")
+            .times(1)
+            .returning(move |_| Ok(item.clone()));
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_find_shared()
+            .times(1)
+            .returning(move |_| Ok(vec![collection.clone()]));
+
+        let mut mock_details_repo = MockDetailsRepo::new();
+        mock_details_repo
+            .expect_save()
+            .withf(|details| details.owner() == "user")
+            .times(usize::from(is_allowed))
+            .returning(|details| Ok(details.clone()));
+
+        let details_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .collections_repo(Arc::new(mock_collections_repo))
+            .details_repo(Arc::new(mock_details_repo))
+            .build();
+        let response = details_service
+            .update(&context, &request_query, &LinkDetails::default())
+            .await;
+
+        assert_eq!(response.is_ok(), is_allowed);
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    time::Duration,
+};
+
+use axum::async_trait;
+use chrono::Utc;
+use reqwest::{StatusCode, Url};
+use tokio::time::Instant;
+
+use crate::{
+    outbound,
+    service::Health as HealthService,
+    types::{AppContext, LinkHealth, LinkItem, LinkQuery, LinkQueryBuilder, Result},
+};
+
+const HOST_REQUEST_INTERVAL_MILLIS: u64 = 1000;
+
+pub struct ServiceProvider {
+    http_client: outbound::Client,
+    host_request_interval: Duration,
+}
+
+#[async_trait]
+impl HealthService for ServiceProvider {
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<LinkItem>> {
+        let items = context.links_service().search(context, query).await?;
+        let ids: Vec<String> = items.iter().map(|item| item.id().to_owned()).collect();
+        let broken_ids: HashSet<String> = context
+            .health_repo()
+            .find(&ids)
+            .await?
+            .into_iter()
+            .filter(LinkHealth::is_broken)
+            .map(|health| health.id().to_owned())
+            .collect();
+        Ok(items
+            .into_iter()
+            .filter(|item| broken_ids.contains(item.id()))
+            .collect())
+    }
+
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<LinkHealth> {
+        let item = context.links_service().get(context, query).await?;
+        context.health_repo().get(item.id()).await
+    }
+
+    async fn check(&self, context: &AppContext) -> Result<()> {
+        let items = context
+            .links_repo()
+            .find(&LinkQueryBuilder::default().user("").build())
+            .await?;
+
+        let mut rate_limiter = HostRateLimiter::new(self.host_request_interval);
+        for item in interleave_by_host(&items) {
+            let delay = rate_limiter.delay(&host(item.url()), Instant::now());
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            context
+                .health_repo()
+                .save(&self.check_link(item).await)
+                .await?;
+        }
+
+        // drop results of links that were deleted since the previous check
+        let ids: Vec<String> = items.iter().map(|item| item.id().to_owned()).collect();
+        context.health_repo().retain(&ids).await
+    }
+}
+
+impl ServiceProvider {
+    async fn check_link(&self, item: &LinkItem) -> LinkHealth {
+        let mut response = self.http_client.head(item.url()).await;
+        if let Ok(ref head_response) = response {
+            if matches!(
+                head_response.status(),
+                StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+            ) {
+                response = self.http_client.get(item.url()).await;
+            }
+        }
+
+        let checked_at = Utc::now();
+        match response {
+            Ok(response) => {
+                let is_redirected =
+                    Url::parse(item.url()).map_or(true, |url| &url != response.url());
+                let redirect_url = if is_redirected {
+                    response.url().as_str()
+                } else {
+                    ""
+                };
+                LinkHealth::new(
+                    item.id(),
+                    item.url(),
+                    response.status().as_u16(),
+                    redirect_url,
+                    &checked_at,
+                )
+            }
+            Err(e) => {
+                tracing::debug!("Unable to reach {}: {}", item.url(), e);
+                LinkHealth::new(item.id(), item.url(), 0, "", &checked_at)
+            }
+        }
+    }
+}
+
+impl Default for ServiceProvider {
+    fn default() -> Self {
+        Self {
+            http_client: outbound::Client::default(),
+            host_request_interval: Duration::from_millis(HOST_REQUEST_INTERVAL_MILLIS),
+        }
+    }
+}
+
+/// Runs the health check of all saved links in the background every `interval`.
+pub fn schedule(context: AppContext, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval_at(Instant::now() + interval, interval);
+        loop {
+            ticker.tick().await;
+            tracing::info!("Checking health of saved links");
+            if let Err(e) = context.health_service().check(&context).await {
+                tracing::error!("Unable to check health of saved links: {}", e);
+            }
+        }
+    });
+}
+
+/// Spaces out requests to the same host so that checking a library with many
+/// links from one site does not hammer that site.
+struct HostRateLimiter {
+    interval: Duration,
+    next_requests: HashMap<String, Instant>,
+}
+
+impl HostRateLimiter {
+    fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next_requests: HashMap::new(),
+        }
+    }
+
+    fn delay(&mut self, host: &str, now: Instant) -> Duration {
+        let scheduled = self
+            .next_requests
+            .get(host)
+            .map_or(now, |next_request| (*next_request).max(now));
+        self.next_requests
+            .insert(host.to_owned(), scheduled + self.interval);
+        scheduled - now
+    }
+}
+
+fn host(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_owned))
+        .unwrap_or_default()
+}
+
+/// Orders the links so that consecutive requests go to different hosts
+/// whenever possible, keeping the rate limiter from stalling the check.
+fn interleave_by_host(items: &[LinkItem]) -> Vec<&LinkItem> {
+    let mut hosts: BTreeMap<String, VecDeque<&LinkItem>> = BTreeMap::new();
+    for item in items {
+        hosts.entry(host(item.url())).or_default().push_back(item);
+    }
+
+    let mut interleaved = Vec::with_capacity(items.len());
+    while interleaved.len() < items.len() {
+        for host_items in hosts.values_mut() {
+            if let Some(item) = host_items.pop_front() {
+                interleaved.push(item);
+            }
+        }
+    }
+    interleaved
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{
+        app::ContextBuilder,
+        repository::{MockHealth as MockHealthRepo, MockLinks as MockLinksRepo},
+        service::MockLinks as MockLinksService,
+        types::{AppError, LinkItemBuilder},
+    };
+
+    use super::*;
+
+    fn test_service() -> ServiceProvider {
+        ServiceProvider {
+            http_client: outbound::Client::new(
+                outbound::Policy::default().allow_private_networks(),
+            ),
+            host_request_interval: Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_broken_links() {
+        let request_query = LinkQueryBuilder::default().user("user").build();
+        let search_query = request_query.clone();
+        let broken_item = LinkItemBuilder::new("http://broken")
+            .id("1")
+            .owner("user")
+            .build();
+        let healthy_item = LinkItemBuilder::new("http://healthy")
+            .id("2")
+            .owner("user")
+            .build();
+        let items = vec![broken_item.clone(), healthy_item];
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_search()
+            .withf(move |_, query| query == &search_query)
+            .times(1)
+            .returning(move |_, _| Ok(items.clone()));
+
+        let mut mock_health_repo = MockHealthRepo::new();
+        mock_health_repo
+            .expect_find()
+            .withf(|ids| ids == ["1".to_owned(), "2".to_owned()])
+            .times(1)
+            .returning(|_| {
+                let now = Utc::now();
+                Ok(vec![
+                    LinkHealth::new("1", "http://broken", 404, "", &now),
+                    LinkHealth::new("2", "http://healthy", 200, "", &now),
+                ])
+            });
+
+        let health_service = test_service();
+        let context = ContextBuilder::default()
+            .links_service(Arc::new(mock_links_service))
+            .health_repo(Arc::new(mock_health_repo))
+            .build();
+        let response = health_service.search(&context, &request_query).await;
+
+        assert_eq!(response, Ok(vec![broken_item]));
+    }
+
+    #[tokio::test]
+    async fn test_get_health_unauthorized() {
+        let request_query = LinkQueryBuilder::new("1", "unauthorized-user").build();
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_get()
+            .times(1)
+            .returning(|_, _| Err(AppError::Authorization("unauthorized".into())));
+
+        let mut mock_health_repo = MockHealthRepo::new();
+        mock_health_repo.expect_get().times(0);
+
+        let health_service = test_service();
+        let context = ContextBuilder::default()
+            .links_service(Arc::new(mock_links_service))
+            .health_repo(Arc::new(mock_health_repo))
+            .build();
+        let response = health_service.get(&context, &request_query).await;
+
+        assert_eq!(
+            response,
+            Err(AppError::Authorization("unauthorized".into()))
+        );
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_check_links() {
+        let mut server = mockito::Server::new_async().await;
+        let ok_mock = server
+            .mock("HEAD", "/ok")
+            .with_status(200)
+            .expect(2)
+            .create_async()
+            .await;
+        let missing_mock = server
+            .mock("HEAD", "/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+        let moved_mock = server
+            .mock("HEAD", "/moved")
+            .with_status(301)
+            .with_header("location", &format!("{}/ok", server.url()))
+            .create_async()
+            .await;
+        let head_not_allowed_mock = server
+            .mock("HEAD", "/get-only")
+            .with_status(405)
+            .create_async()
+            .await;
+        let get_mock = server
+            .mock("GET", "/get-only")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let ok_url = format!("{}/ok", server.url());
+        let items: Vec<LinkItem> = ["ok", "missing", "moved", "get-only"]
+            .iter()
+            .enumerate()
+            .map(|(id, path)| {
+                LinkItemBuilder::new(&format!("{}/{path}", server.url()))
+                    .id(&id.to_string())
+                    .owner("user")
+                    .build()
+            })
+            .collect();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_find()
+            .withf(|query| query == &LinkQueryBuilder::default().user("").build())
+            .times(1)
+            .returning(move |_| Ok(items.clone()));
+
+        let mut mock_health_repo = MockHealthRepo::new();
+        mock_health_repo
+            .expect_save()
+            .withf(|health| {
+                health.id() == "0"
+                    && health.status_code() == 200
+                    && health.redirect_url().is_empty()
+            })
+            .times(1)
+            .returning(|health| Ok(health.clone()));
+        mock_health_repo
+            .expect_save()
+            .withf(|health| health.id() == "1" && health.status_code() == 404 && health.is_broken())
+            .times(1)
+            .returning(|health| Ok(health.clone()));
+        mock_health_repo
+            .expect_save()
+            .withf(move |health| {
+                health.id() == "2" && health.status_code() == 200 && health.redirect_url() == ok_url
+            })
+            .times(1)
+            .returning(|health| Ok(health.clone()));
+        mock_health_repo
+            .expect_save()
+            .withf(|health| health.id() == "3" && health.status_code() == 200)
+            .times(1)
+            .returning(|health| Ok(health.clone()));
+        mock_health_repo
+            .expect_retain()
+            .withf(|ids| ids == ["0", "1", "2", "3"].map(String::from))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let health_service = test_service();
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .health_repo(Arc::new(mock_health_repo))
+            .build();
+        let response = health_service.check(&context).await;
+
+        ok_mock.assert_async().await;
+        missing_mock.assert_async().await;
+        moved_mock.assert_async().await;
+        head_not_allowed_mock.assert_async().await;
+        get_mock.assert_async().await;
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_check_link_unreachable() {
+        let item = LinkItemBuilder::new("http://127.0.0.1:1/unreachable")
+            .id("1")
+            .owner("user")
+            .build();
+
+        let health = test_service().check_link(&item).await;
+
+        assert_eq!(health.status_code(), 0);
+        assert!(health.is_broken());
+    }
+
+    #[test]
+    fn test_rate_limiter_spaces_requests_to_same_host() {
+        let interval = Duration::from_secs(1);
+        let now = Instant::now();
+        let mut rate_limiter = HostRateLimiter::new(interval);
+
+        assert_eq!(rate_limiter.delay("a.com", now), Duration::ZERO);
+        assert_eq!(rate_limiter.delay("b.com", now), Duration::ZERO);
+        assert_eq!(rate_limiter.delay("a.com", now), interval);
+        assert_eq!(rate_limiter.delay("a.com", now), interval * 2);
+        assert_eq!(
+            rate_limiter.delay("a.com", now + interval * 5),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn test_interleave_by_host() {
+        let items: Vec<LinkItem> = [
+            "http://a.com/1",
+            "http://a.com/2",
+            "http://a.com/3",
+            "http://b.com/1",
+        ]
+        .iter()
+        .map(|url| LinkItemBuilder::new(url).build())
+        .collect();
+
+        let urls: Vec<&str> = interleave_by_host(&items)
+            .iter()
+            .map(|item| item.url())
+            .collect();
+
+        assert_eq!(
+            urls,
+            vec![
+                "http://a.com/1",
+                "http://b.com/1",
+                "http://a.com/2",
+                "http://a.com/3"
+            ]
+        );
+    }
+}
@@ -0,0 +1,476 @@
+use std::collections::HashMap;
+
+use axum::async_trait;
+use chrono::Utc;
+
+use crate::{
+    repository,
+    service::{links::authorize, Highlights as HighlightsService},
+    types::{
+        AppContext, AppError, Highlight, LinkItem, LinkQuery, LinkQueryBuilder, Result, Role,
+        Selector,
+    },
+};
+
+#[derive(Default)]
+pub struct ServiceProvider {}
+
+#[async_trait]
+impl HighlightsService for ServiceProvider {
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Highlight>> {
+        let item = authorize(context, query, Role::Viewer).await?;
+        context.highlights_repo().find(item.id()).await
+    }
+
+    async fn create(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        highlight: &Highlight,
+    ) -> Result<Highlight> {
+        check_selectors(highlight.selectors())?;
+        let item = authorize(context, query, Role::Editor).await?;
+
+        let now = Utc::now();
+        context
+            .highlights_repo()
+            .create(&Highlight::new(
+                item.id(),
+                item.owner(),
+                highlight.quote(),
+                highlight.note(),
+                highlight.selectors(),
+                &now,
+            ))
+            .await
+    }
+
+    async fn update(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        highlight: &Highlight,
+    ) -> Result<Highlight> {
+        check_selectors(highlight.selectors())?;
+        let item = authorize(context, query, Role::Editor).await?;
+        let existing = get_on_link(context.highlights_repo(), item.id(), highlight.id()).await?;
+
+        let now = Utc::now();
+        context
+            .highlights_repo()
+            .update(
+                &Highlight::new(
+                    existing.link_id(),
+                    existing.owner(),
+                    highlight.quote(),
+                    highlight.note(),
+                    highlight.selectors(),
+                    existing.created_at(),
+                )
+                .with_id(existing.id())
+                .with_updated_at(&now),
+            )
+            .await
+    }
+
+    async fn delete(&self, context: &AppContext, query: &LinkQuery, id: &str) -> Result<()> {
+        let item = authorize(context, query, Role::Editor).await?;
+        let existing = get_on_link(context.highlights_repo(), item.id(), id).await?;
+        context.highlights_repo().delete(existing.id()).await
+    }
+
+    async fn export(&self, context: &AppContext, query: &LinkQuery) -> Result<String> {
+        let items = context
+            .links_repo()
+            .find(&LinkQueryBuilder::default().user(query.user()).build())
+            .await?;
+        let highlights = context
+            .highlights_repo()
+            .find_by_owner(query.user())
+            .await?;
+        Ok(to_markdown(&items, &highlights))
+    }
+}
+
+/// Renders `highlights` under a heading for each of `items` they belong to,
+/// leaving out the links without any.
+pub fn to_markdown(items: &[LinkItem], highlights: &[Highlight]) -> String {
+    let mut highlights_by_link: HashMap<&str, Vec<&Highlight>> = HashMap::new();
+    for highlight in highlights {
+        highlights_by_link
+            .entry(highlight.link_id())
+            .or_default()
+            .push(highlight);
+    }
+
+    let sections = items.iter().filter_map(|item| {
+        let highlights = highlights_by_link.get(item.id())?;
+        let title = if item.title().is_empty() {
+            item.url()
+        } else {
+            item.title()
+        };
+        let heading = format!("## [{}]({})\n", escape_title(title), escape_url(item.url()));
+        let entries = highlights.iter().map(|highlight| {
+            let quote: String = highlight
+                .quote()
+                .lines()
+                .map(|line| {
+                    if line.is_empty() {
+                        ">\n".to_owned()
+                    } else {
+                        format!("> {line}\n")
+                    }
+                })
+                .collect();
+            if highlight.note().is_empty() {
+                format!("\n{quote}")
+            } else {
+                format!("\n{quote}\n{}\n", highlight.note().trim_end())
+            }
+        });
+        Some(
+            std::iter::once(heading)
+                .chain(entries)
+                .collect::<Vec<_>>()
+                .concat(),
+        )
+    });
+
+    std::iter::once("# Highlights\n".to_owned())
+        .chain(sections.map(|section| format!("\n{section}")))
+        .collect::<Vec<_>>()
+        .concat()
+}
+
+fn escape_title(title: &str) -> String {
+    title
+        .replace('\\', "\\\\")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+}
+
+fn escape_url(url: &str) -> String {
+    url.replace(' ', "%20")
+        .replace('(', "%28")
+        .replace(')', "%29")
+}
+
+fn check_selectors(selectors: &[Selector]) -> Result<()> {
+    for selector in selectors {
+        if let Selector::TextPositionSelector { start, end } = selector {
+            if start > end {
+                return Err(AppError::Validation(format!(
+                    "check_selectors() position {start} is after {end}"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Looks up a highlight through the link in the path, so that it cannot be
+/// reached through a link that it does not belong to.
+async fn get_on_link(
+    highlights_repo: &repository::DynHighlights,
+    link_id: &str,
+    id: &str,
+) -> Result<Highlight> {
+    let highlight = highlights_repo.get(id).await?;
+    if highlight.link_id() != link_id {
+        return Err(AppError::HighlightNotFound(id.to_owned()));
+    }
+    Ok(highlight)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rstest::rstest;
+
+    use crate::{
+        app::ContextBuilder,
+        repository::{
+            MockCollections as MockCollectionsRepo, MockHighlights as MockHighlightsRepo,
+            MockLinks as MockLinksRepo,
+        },
+        types::{Collection, LinkItemBuilder, Member},
+    };
+
+    use super::*;
+
+    fn mock_links_repo(item: LinkItem) -> MockLinksRepo {
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .withf(|query| query.id() == "1")
+            .times(1)
+            .returning(move |_| Ok(item.clone()));
+        mock_links_repo
+    }
+
+    fn item() -> LinkItem {
+        LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_search_highlights() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let highlights = vec![Highlight::new("1", "user", "quote", "", &[], &Utc::now())];
+        let expected_highlights = highlights.clone();
+
+        let mut mock_highlights_repo = MockHighlightsRepo::new();
+        mock_highlights_repo
+            .expect_find()
+            .withf(|link_id| link_id == "1")
+            .times(1)
+            .returning(move |_| Ok(highlights.clone()));
+
+        let highlights_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo(item())))
+            .highlights_repo(Arc::new(mock_highlights_repo))
+            .build();
+        let response = highlights_service.search(&context, &request_query).await;
+
+        assert_eq!(response, Ok(expected_highlights));
+    }
+
+    #[tokio::test]
+    async fn test_create_highlight() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let selectors = [Selector::TextQuoteSelector {
+            exact: "quote".into(),
+            prefix: "a ".into(),
+            suffix: " here".into(),
+        }];
+        let request_highlight = Highlight::new("", "", "quote", "note", &selectors, &Utc::now());
+
+        let mut mock_highlights_repo = MockHighlightsRepo::new();
+        mock_highlights_repo
+            .expect_create()
+            .withf(move |highlight| {
+                highlight.link_id() == "1"
+                    && highlight.owner() == "user"
+                    && highlight.quote() == "quote"
+                    && highlight.note() == "note"
+                    && highlight.selectors() == selectors
+            })
+            .times(1)
+            .returning(|highlight| Ok(highlight.clone().with_id("1")));
+
+        let highlights_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo(item())))
+            .highlights_repo(Arc::new(mock_highlights_repo))
+            .build();
+        let response = highlights_service
+            .create(&context, &request_query, &request_highlight)
+            .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_create_highlight_invalid_position() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let selectors = [Selector::TextPositionSelector { start: 20, end: 10 }];
+        let request_highlight = Highlight::new("", "", "quote", "", &selectors, &Utc::now());
+
+        let mut mock_highlights_repo = MockHighlightsRepo::new();
+        mock_highlights_repo.expect_create().times(0);
+
+        let highlights_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .highlights_repo(Arc::new(mock_highlights_repo))
+            .build();
+        let response = highlights_service
+            .create(&context, &request_query, &request_highlight)
+            .await;
+
+        assert!(matches!(response, Err(AppError::Validation(_))));
+    }
+
+    #[rstest]
+    #[case(Role::Viewer, false)]
+    #[case(Role::Editor, true)]
+    #[tokio::test]
+    async fn test_create_highlight_on_shared_link(#[case] role: Role, #[case] is_allowed: bool) {
+        let request_query = LinkQueryBuilder::new("1", "member").build();
+        let collection = Collection::new("user", "reading", "", 0, &Utc::now())
+            .with_id("1")
+            .with_links(&["1".to_owned()])
+            .with_members(&[Member::new("member", role)]);
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_find_shared()
+            .times(1)
+            .returning(move |_| Ok(vec![collection.clone()]));
+
+        let mut mock_highlights_repo = MockHighlightsRepo::new();
+        mock_highlights_repo
+            .expect_create()
+            .withf(|highlight| highlight.owner() == "user")
+            .times(usize::from(is_allowed))
+            .returning(|highlight| Ok(highlight.clone()));
+
+        let highlights_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo(item())))
+            .collections_repo(Arc::new(mock_collections_repo))
+            .highlights_repo(Arc::new(mock_highlights_repo))
+            .build();
+        let response = highlights_service
+            .create(&context, &request_query, &Highlight::default())
+            .await;
+
+        assert_eq!(response.is_ok(), is_allowed);
+    }
+
+    #[tokio::test]
+    async fn test_update_highlight() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let created_at = Utc::now() - chrono::Duration::days(1);
+        let existing = Highlight::new("1", "user", "quote", "", &[], &created_at).with_id("2");
+        let request_highlight =
+            Highlight::new("", "", "quote", "a note", &[], &Utc::now()).with_id("2");
+
+        let mut mock_highlights_repo = MockHighlightsRepo::new();
+        mock_highlights_repo
+            .expect_get()
+            .withf(|id| id == "2")
+            .times(1)
+            .returning(move |_| Ok(existing.clone()));
+        mock_highlights_repo
+            .expect_update()
+            .withf(move |highlight| {
+                highlight.id() == "2"
+                    && highlight.link_id() == "1"
+                    && highlight.note() == "a note"
+                    && highlight.created_at() == &created_at
+                    && highlight.updated_at() > &created_at
+            })
+            .times(1)
+            .returning(|highlight| Ok(highlight.clone()));
+
+        let highlights_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo(item())))
+            .highlights_repo(Arc::new(mock_highlights_repo))
+            .build();
+        let response = highlights_service
+            .update(&context, &request_query, &request_highlight)
+            .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_highlight_of_another_link() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let existing = Highlight::new("3", "user", "quote", "", &[], &Utc::now()).with_id("2");
+
+        let mut mock_highlights_repo = MockHighlightsRepo::new();
+        mock_highlights_repo
+            .expect_get()
+            .withf(|id| id == "2")
+            .times(1)
+            .returning(move |_| Ok(existing.clone()));
+        mock_highlights_repo.expect_delete().times(0);
+
+        let highlights_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo(item())))
+            .highlights_repo(Arc::new(mock_highlights_repo))
+            .build();
+        let response = highlights_service
+            .delete(&context, &request_query, "2")
+            .await;
+
+        assert_eq!(response, Err(AppError::HighlightNotFound("2".into())));
+    }
+
+    #[tokio::test]
+    async fn test_export_highlights() {
+        let request_query = LinkQueryBuilder::default().user("user").build();
+        let now = Utc::now();
+        let highlights = vec![
+            Highlight::new("1", "user", "first", "", &[], &now),
+            Highlight::new("2", "user", "second", "", &[], &now),
+        ];
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_find()
+            .withf(|query| query.user() == "user")
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    LinkItemBuilder::new("http://link/1").id("1").build(),
+                    LinkItemBuilder::new("http://link/2").id("2").build(),
+                ])
+            });
+
+        let mut mock_highlights_repo = MockHighlightsRepo::new();
+        mock_highlights_repo
+            .expect_find_by_owner()
+            .withf(|owner| owner == "user")
+            .times(1)
+            .returning(move |_| Ok(highlights.clone()));
+
+        let highlights_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .highlights_repo(Arc::new(mock_highlights_repo))
+            .build();
+        let response = highlights_service
+            .export(&context, &request_query)
+            .await
+            .unwrap();
+
+        assert!(response.contains("> first\n"));
+        assert!(response.contains("> second\n"));
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let now = Utc::now();
+        let items = [
+            LinkItemBuilder::new("http://link/(1)")
+                .id("1")
+                .title("A [good] read")
+                .build(),
+            LinkItemBuilder::new("http://link/2").id("2").build(),
+            LinkItemBuilder::new("http://link/3").id("3").build(),
+        ];
+        let highlights = [
+            Highlight::new("1", "user", "first line\n\nsecond line", "", &[], &now),
+            Highlight::new("1", "user", "another", "*why* it matters\n", &[], &now),
+            Highlight::new("3", "user", "untitled", "", &[], &now),
+        ];
+
+        assert_eq!(
+            to_markdown(&items, &highlights),
+            "# Highlights\n\
+             \n\
+             ## [A \\[good\\] read](http://link/%281%29)\n\
+             \n\
+             > first line\n\
+             >\n\
+             > second line\n\
+             \n\
+             > another\n\
+             \n\
+             *why* it matters\n\
+             \n\
+             ## [http://link/3](http://link/3)\n\
+             \n\
+             > untitled\n"
+        );
+    }
+}
@@ -1,114 +1,777 @@
+use std::{collections::HashMap, time::Duration};
+
 use axum::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use rand::seq::SliceRandom;
+use tokio::time::Instant;
 
 use crate::{
-    repository, service,
+    service,
+    service::shares::SHARE_LINK,
+    service::webhooks::{LINK_CREATED, LINK_DELETED, LINK_READ, LINK_UPDATED},
     service::Links as LinksService,
-    types::{AppError, LinkItem, LinkItemBuilder, LinkQuery, LinkQueryBuilder, Result},
+    types::{
+        AppContext, AppError, FieldChange, LinkItem, LinkItemBuilder, LinkPatchRequest, LinkQuery,
+        LinkQueryBuilder, NextStrategy, Priority, Result, Revision, Role, TrashedLink,
+        VersionedLink,
+    },
 };
 
+/// Label marking a link as read, which is announced as its own webhook event.
+pub const READ_LABEL: &str = "read";
+
+const REVISION_UPDATE: &str = "update";
+const REVISION_REVERT: &str = "revert";
+const REVISION_DELETE: &str = "delete";
+
+/// How much likelier a link is to be picked next than a low priority one.
+const fn priority_weight(priority: Priority) -> u32 {
+    match priority {
+        Priority::Low => 1,
+        Priority::Normal => 2,
+        Priority::High => 4,
+    }
+}
+
+/// Strong entity tag of a link, which is its version and so changes with
+/// every write to the link.
+pub fn etag(link: &VersionedLink) -> String {
+    format!("\"{}\"", link.version())
+}
+
+/// Whether the list of entity tags in an `If-Match` or `If-None-Match` header
+/// covers `etag`. Weak tags only match when `weak` comparison is asked for.
+pub fn etag_matches(tags: &str, etag: &str, weak: bool) -> bool {
+    tags.split(',').map(str::trim).any(|tag| {
+        let tag = if weak {
+            tag.strip_prefix("W/").unwrap_or(tag)
+        } else {
+            tag
+        };
+        tag == "*" || tag == etag
+    })
+}
+
+fn check_precondition(if_match: Option<&str>, link: &VersionedLink) -> Result<()> {
+    match if_match {
+        Some(tags) if !etag_matches(tags, &etag(link), false) => {
+            Err(AppError::PreconditionFailed(link.link().id().to_owned()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Lists the fields that differ between two versions of a link.
+fn changes(before: &LinkItem, after: &LinkItem) -> Vec<FieldChange> {
+    [
+        ("url", before.url().to_owned(), after.url().to_owned()),
+        ("title", before.title().to_owned(), after.title().to_owned()),
+        (
+            "description",
+            before.description().to_owned(),
+            after.description().to_owned(),
+        ),
+        (
+            "word_count",
+            before.word_count().to_string(),
+            after.word_count().to_string(),
+        ),
+        (
+            "reading_time",
+            before.reading_time().to_string(),
+            after.reading_time().to_string(),
+        ),
+        (
+            "summary",
+            before.summary().to_owned(),
+            after.summary().to_owned(),
+        ),
+        ("label", before.label().to_owned(), after.label().to_owned()),
+    ]
+    .into_iter()
+    .filter(|(_, old, new)| old != new)
+    .map(|(field, old, new)| FieldChange::new(field, &old, &new))
+    .collect()
+}
+
 #[derive(Default)]
 pub struct ServiceProvider {}
 
 #[async_trait]
 impl LinksService for ServiceProvider {
-    async fn search(
-        &self,
-        links_repo: Box<repository::DynLinks>,
-        query: &LinkQuery,
-    ) -> Result<Vec<LinkItem>> {
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<LinkItem>> {
         let find_query = if query.is_from_admin() {
             LinkQueryBuilder::default().user("").build()
         } else {
             LinkQueryBuilder::default().user(query.user()).build()
         };
-        links_repo.find(&find_query).await
+        context.links_repo().find(&find_query).await
     }
 
-    async fn get(
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<LinkItem> {
+        authorize(context, query, Role::Viewer).await
+    }
+
+    async fn get_versioned(
         &self,
-        links_repo: Box<repository::DynLinks>,
+        context: &AppContext,
         query: &LinkQuery,
-    ) -> Result<LinkItem> {
-        let get_query = LinkQueryBuilder::default().id(query.id()).build();
-        let retrieved_item = links_repo.get(&get_query).await?;
-
-        if query.user() == retrieved_item.owner() || query.is_from_admin() {
-            Ok(retrieved_item)
-        } else {
-            Err(AppError::Authorization(String::from(
-                "User is not authorized to access resource",
-            )))
-        }
+    ) -> Result<VersionedLink> {
+        authorize_versioned(context, query, Role::Viewer).await
     }
 
-    async fn create(
+    async fn next(
         &self,
-        analysis_service: Box<service::DynAnalysis>,
-        links_repo: Box<repository::DynLinks>,
-        item: &LinkItem,
-    ) -> Result<LinkItem> {
+        context: &AppContext,
+        query: &LinkQuery,
+        strategy: NextStrategy,
+        minutes: Option<usize>,
+    ) -> Result<Option<LinkItem>> {
+        let find_query = LinkQueryBuilder::default().user(query.user()).build();
+        // links without a reading time estimate never fit in a number of minutes
+        let fits = |item: &LinkItem| {
+            !minutes
+                .is_some_and(|minutes| item.reading_time() == 0 || item.reading_time() > minutes)
+        };
+        let items: Vec<LinkItem> = context
+            .links_repo()
+            .find(&find_query)
+            .await?
+            .into_iter()
+            .filter(|item| item.label() != READ_LABEL && fits(item))
+            .collect();
+
+        let next = match strategy {
+            NextStrategy::Oldest => items.into_iter().min_by_key(|item| *item.created_at()),
+            NextStrategy::Random => items.choose(&mut rand::thread_rng()).cloned(),
+            NextStrategy::Shortest => items
+                .into_iter()
+                .filter(|item| item.reading_time() != 0)
+                .min_by_key(|item| (item.reading_time(), *item.created_at())),
+            NextStrategy::Priority => {
+                let priorities: HashMap<String, Priority> = context
+                    .details_repo()
+                    .find(query.user())
+                    .await?
+                    .into_iter()
+                    .map(|details| (details.id().to_owned(), details.priority()))
+                    .collect();
+                items
+                    .choose_weighted(&mut rand::thread_rng(), |item| {
+                        priority_weight(priorities.get(item.id()).copied().unwrap_or_default())
+                    })
+                    .ok()
+                    .cloned()
+            }
+        };
+        Ok(next)
+    }
+
+    async fn create(&self, context: &AppContext, item: &LinkItem) -> Result<LinkItem> {
         let now = Utc::now();
         let created_item = LinkItemBuilder::from(item.clone())
             .created_at(&now)
             .updated_at(&now)
             .build();
 
-        let created_item = links_repo.create(&created_item).await?;
+        let created_item = context.links_repo().create(&created_item).await?;
+
+        let analyzed_item = context.analysis_service().analyze(&created_item).await?;
+        let created_item = if analyzed_item == created_item {
+            created_item
+        } else {
+            let update_query = LinkQueryBuilder::default().id(created_item.id()).build();
+            context
+                .links_repo()
+                .update(&update_query, &analyzed_item)
+                .await?
+                .into_link()
+        };
+
+        process_in_background(context, &created_item);
 
-        analysis_service.analyze(&created_item).await?;
+        context
+            .webhooks_service()
+            .notify(context, LINK_CREATED, &created_item)
+            .await?;
 
         Ok(created_item)
     }
 
     async fn update(
         &self,
-        analysis_service: Box<service::DynAnalysis>,
-        links_repo: Box<repository::DynLinks>,
+        context: &AppContext,
         query: &LinkQuery,
         item: &LinkItem,
-    ) -> Result<LinkItem> {
-        let retrieved_item = self.get(links_repo.clone(), query).await?;
+        if_match: Option<String>,
+    ) -> Result<VersionedLink> {
+        self.update_link(
+            context,
+            query,
+            |_| item.clone(),
+            if_match.as_deref(),
+            REVISION_UPDATE,
+        )
+        .await
+    }
+
+    async fn patch(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        patch: &LinkPatchRequest,
+        if_match: Option<String>,
+    ) -> Result<VersionedLink> {
+        self.update_link(
+            context,
+            query,
+            |retrieved_item| patch.apply(retrieved_item),
+            if_match.as_deref(),
+            REVISION_UPDATE,
+        )
+        .await
+    }
+
+    async fn delete(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        if_match: Option<String>,
+    ) -> Result<()> {
+        let retrieved_link = authorize_versioned(context, query, Role::Admin).await?;
+        check_precondition(if_match.as_deref(), &retrieved_link)?;
+        let retrieved_item = retrieved_link.into_link();
+
+        // the link is only marked as deleted, its snapshot, history and
+        // everything else about it are kept until the link is purged
+        let now = Utc::now();
+        let trash_query = LinkQueryBuilder::default().id(query.id()).build();
+        context.links_repo().trash(&trash_query, &now).await?;
+
+        context
+            .revisions_repo()
+            .create(&Revision::new(
+                &retrieved_item,
+                query.user(),
+                REVISION_DELETE,
+                &[],
+                &now,
+            ))
+            .await?;
+
+        context
+            .webhooks_service()
+            .notify(context, LINK_DELETED, &retrieved_item)
+            .await
+    }
+
+    async fn trash(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<TrashedLink>> {
+        if query.is_from_admin() {
+            return context.links_repo().find_trashed("").await;
+        }
+
+        // an admin of a shared collection may restore the links deleted from
+        // it, so those are listed next to the links of the user
+        let mut trashed_links = context.links_repo().find_trashed(query.user()).await?;
+        let shared_collections = context
+            .collections_repo()
+            .find_shared(query.user())
+            .await?
+            .into_iter()
+            .filter(|collection| {
+                collection
+                    .role(query.user())
+                    .is_some_and(|role| role >= Role::Admin)
+            });
+        for collection in shared_collections {
+            for trashed_link in context
+                .links_repo()
+                .find_trashed(collection.owner())
+                .await?
+            {
+                let is_listed = trashed_links
+                    .iter()
+                    .any(|listed_link| listed_link.id() == trashed_link.id());
+                if !is_listed && collection.links().iter().any(|id| id == trashed_link.id()) {
+                    trashed_links.push(trashed_link);
+                }
+            }
+        }
+        trashed_links.sort_by_key(|trashed_link| *trashed_link.deleted_at());
+        Ok(trashed_links)
+    }
+
+    async fn restore(&self, context: &AppContext, query: &LinkQuery) -> Result<LinkItem> {
+        let trashed_link = context.links_repo().get_trashed(query.id()).await?;
+        if query.user() == trashed_link.owner() || query.is_from_admin() {
+            return context.links_repo().restore(trashed_link.id()).await;
+        }
+
+        let shared_role =
+            service::collections::shared_role(context.collections_repo(), query.user(), query.id())
+                .await?;
+        if shared_role.is_some_and(|shared_role| shared_role >= Role::Admin) {
+            context.links_repo().restore(trashed_link.id()).await
+        } else {
+            Err(AppError::Authorization(String::from(
+                "User is not authorized to access resource",
+            )))
+        }
+    }
+
+    async fn purge(&self, context: &AppContext, deleted_before: &DateTime<Utc>) -> Result<()> {
+        for trashed_link in context.links_repo().find_expired(deleted_before).await? {
+            purge_link(context, &trashed_link).await?;
+        }
+        Ok(())
+    }
+
+    async fn history(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Revision>> {
+        let item = self.get(context, query).await?;
+        context.revisions_repo().find(item.id()).await
+    }
+
+    async fn revert(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        revision_id: &str,
+    ) -> Result<VersionedLink> {
+        let revision = context.revisions_repo().get(revision_id).await?;
+        if revision.link_id() != query.id() {
+            return Err(AppError::RevisionNotFound(revision_id.to_owned()));
+        }
+
+        // the link goes back to how it was before the change of the revision
+        self.update_link(
+            context,
+            query,
+            |_| revision.link().clone(),
+            None,
+            REVISION_REVERT,
+        )
+        .await
+    }
+}
+
+impl ServiceProvider {
+    /// Applies an update to a link and records it in the history of the link
+    /// under `action`. The update is made by `update` from the link as it is.
+    async fn update_link(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        update: impl FnOnce(&LinkItem) -> LinkItem + Send,
+        if_match: Option<&str>,
+        action: &str,
+    ) -> Result<VersionedLink> {
+        let retrieved_link = authorize_versioned(context, query, Role::Editor).await?;
+        check_precondition(if_match, &retrieved_link)?;
+        let retrieved_item = retrieved_link.link();
 
         let now = Utc::now();
-        let updated_item = LinkItemBuilder::from(item.clone())
+        let updated_item = LinkItemBuilder::from(update(retrieved_item))
             .owner(retrieved_item.owner())
             .created_at(retrieved_item.created_at())
             .updated_at(&now)
             .build();
 
         let update_query = LinkQueryBuilder::default().id(query.id()).build();
-        let updated_item = links_repo.update(&update_query, &updated_item).await?;
+        let updated_link = if if_match.is_some() {
+            // the link may still change between the check and the update
+            context
+                .links_repo()
+                .update_if_unmodified(&update_query, &updated_item, retrieved_link.version())
+                .await?
+        } else {
+            context
+                .links_repo()
+                .update(&update_query, &updated_item)
+                .await?
+        };
+
+        let updated_link = if updated_link.link().url() == retrieved_item.url() {
+            updated_link
+        } else {
+            let analyzed_item = context
+                .analysis_service()
+                .analyze(updated_link.link())
+                .await?;
+            let updated_link = if &analyzed_item == updated_link.link() {
+                updated_link
+            } else {
+                context
+                    .links_repo()
+                    .update(&update_query, &analyzed_item)
+                    .await?
+            };
+
+            process_in_background(context, updated_link.link());
+
+            updated_link
+        };
+        let updated_item = updated_link.link();
+
+        let changes = changes(retrieved_item, updated_item);
+        if !changes.is_empty() {
+            context
+                .revisions_repo()
+                .create(&Revision::new(
+                    retrieved_item,
+                    query.user(),
+                    action,
+                    &changes,
+                    &now,
+                ))
+                .await?;
+        }
+
+        context
+            .webhooks_service()
+            .notify(context, LINK_UPDATED, updated_item)
+            .await?;
+        if updated_item.label() == READ_LABEL && retrieved_item.label() != READ_LABEL {
+            context
+                .webhooks_service()
+                .notify(context, LINK_READ, updated_item)
+                .await?;
+        }
+
+        Ok(updated_link)
+    }
+}
+
+/// Summarizes `item` and captures a snapshot of it without holding up the
+/// request. Each of them fetches the page on its own.
+fn process_in_background(context: &AppContext, item: &LinkItem) {
+    let context = context.clone();
+    let item = item.clone();
+    tokio::spawn(async move {
+        if let Err(e) = summarize(&context, &item).await {
+            tracing::error!("Unable to summarize {}: {}", item.url(), e);
+        }
+        if let Err(e) = context.snapshots_service().capture(&context, &item).await {
+            tracing::error!("Unable to capture snapshot of {}: {}", item.url(), e);
+        }
+    });
+}
+
+/// Stores the summary of the contents of `item` on the link. The summary is
+/// dropped when the link has changed since, so that it never overwrites an
+/// edit, nor ends up on a link that was moved to another url.
+async fn summarize(context: &AppContext, item: &LinkItem) -> Result<()> {
+    let summarized_item = context.summary_service().analyze(item).await?;
+    if summarized_item.summary() == item.summary() {
+        return Ok(());
+    }
 
-        if updated_item.url() != retrieved_item.url() {
-            analysis_service.analyze(&updated_item).await?;
+    let query = LinkQueryBuilder::default().id(item.id()).build();
+    let current_link = context.links_repo().get_versioned(&query).await?;
+    if current_link.link().url() != item.url() {
+        return Ok(());
+    }
+    let summarized_item = LinkItemBuilder::from(current_link.link().clone())
+        .summary(summarized_item.summary())
+        .build();
+    match context
+        .links_repo()
+        .update_if_unmodified(&query, &summarized_item, current_link.version())
+        .await
+    {
+        Err(AppError::PreconditionFailed(_)) => {
+            tracing::info!("Dropping summary of {}, it changed meanwhile", item.url());
+            Ok(())
         }
+        result => result.map(|_| ()),
+    }
+}
+
+/// Retrieves the link of `query` when its user owns it, is an admin, or holds
+/// at least `role` on a collection the link is in.
+pub async fn authorize(context: &AppContext, query: &LinkQuery, role: Role) -> Result<LinkItem> {
+    let get_query = LinkQueryBuilder::default().id(query.id()).build();
+    let retrieved_item = context.links_repo().get(&get_query).await?;
+    check_role(context, query, retrieved_item.owner(), role).await?;
+    Ok(retrieved_item)
+}
+
+/// Same as [`authorize`], along with the version of the link.
+async fn authorize_versioned(
+    context: &AppContext,
+    query: &LinkQuery,
+    role: Role,
+) -> Result<VersionedLink> {
+    let get_query = LinkQueryBuilder::default().id(query.id()).build();
+    let retrieved_link = context.links_repo().get_versioned(&get_query).await?;
+    check_role(context, query, retrieved_link.link().owner(), role).await?;
+    Ok(retrieved_link)
+}
 
-        Ok(updated_item)
+async fn check_role(
+    context: &AppContext,
+    query: &LinkQuery,
+    owner: &str,
+    role: Role,
+) -> Result<()> {
+    if query.user() == owner || query.is_from_admin() {
+        return Ok(());
     }
 
-    async fn delete(&self, links_repo: Box<repository::DynLinks>, query: &LinkQuery) -> Result<()> {
-        self.get(links_repo.clone(), query).await?;
+    let shared_role =
+        service::collections::shared_role(context.collections_repo(), query.user(), query.id())
+            .await?;
+    if shared_role.is_some_and(|shared_role| shared_role >= role) {
+        Ok(())
+    } else {
+        Err(AppError::Authorization(String::from(
+            "User is not authorized to access resource",
+        )))
+    }
+}
 
-        let delete_query = LinkQueryBuilder::default().id(query.id()).build();
-        links_repo.delete(&delete_query).await
+/// Removes a link from the trash for good, together with everything kept
+/// about it. The link itself goes last, so that a failure in between leaves
+/// it in the trash to be purged again.
+async fn purge_link(context: &AppContext, trashed_link: &TrashedLink) -> Result<()> {
+    let id = trashed_link.id();
+    context.snapshots_repo().delete(id).await?;
+    context.revisions_repo().delete(id).await?;
+    context.details_repo().delete(id).await?;
+    context.highlights_repo().delete_all(id).await?;
+    context.progress_repo().delete(id).await?;
+    context.health_repo().delete(id).await?;
+
+    for share in context.shares_repo().find(trashed_link.owner()).await? {
+        if share.kind() == SHARE_LINK && share.target() == id {
+            context.shares_repo().delete(share.id()).await?;
+        }
+    }
+    for reminder in context.reminders_repo().find(trashed_link.owner()).await? {
+        if reminder.link_id() == id {
+            context.reminders_repo().delete(reminder.id()).await?;
+        }
     }
+    // only the links of the owner of a collection can be in it
+    for collection in context
+        .collections_repo()
+        .find(trashed_link.owner())
+        .await?
+    {
+        if collection.links().iter().any(|link_id| link_id == id) {
+            let links: Vec<String> = collection
+                .links()
+                .iter()
+                .filter(|link_id| *link_id != id)
+                .cloned()
+                .collect();
+            context
+                .collections_repo()
+                .update(&collection.with_links(&links))
+                .await?;
+        }
+    }
+
+    let delete_query = LinkQueryBuilder::default().id(id).build();
+    context.links_repo().delete(&delete_query).await
+}
+
+/// Permanently removes links that have been in the trash for longer than
+/// `retention`, checking every `interval` in the background.
+pub fn schedule_purge(context: AppContext, retention: chrono::Duration, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval_at(Instant::now() + interval, interval);
+        loop {
+            ticker.tick().await;
+            tracing::info!("Purging links from the trash");
+            if let Err(e) = context
+                .links_service()
+                .purge(&context, &(Utc::now() - retention))
+                .await
+            {
+                tracing::error!("Unable to purge links from the trash: {}", e);
+            }
+        }
+    });
 }
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
 
+    use chrono::TimeZone;
     use mockall::Sequence;
     use rstest::rstest;
 
     use crate::{
-        repository::MockLinks as MockLinksRepo, service::MockAnalysis as MockAnalysisService,
-        types::AppError,
+        app::ContextBuilder,
+        repository::{
+            MockCollections as MockCollectionsRepo, MockDetails as MockDetailsRepo,
+            MockHealth as MockHealthRepo, MockHighlights as MockHighlightsRepo,
+            MockLinks as MockLinksRepo, MockProgress as MockProgressRepo,
+            MockReminders as MockRemindersRepo, MockRevisions as MockRevisionsRepo,
+            MockShares as MockSharesRepo, MockSnapshots as MockSnapshotsRepo,
+        },
+        service::{
+            reminders::REMINDER_LINK, shares::SHARE_COLLECTION,
+            MockAnalysis as MockAnalysisService, MockSnapshots as MockSnapshotsService,
+            MockWebhooks as MockWebhooksService,
+        },
+        types::{AppError, Collection, LinkDetails, Member, Reminder, Share},
     };
 
     use super::*;
 
+    fn mock_collections_repo(member: &str, shared: Vec<Collection>) -> MockCollectionsRepo {
+        let expected_member = member.to_owned();
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_find_shared()
+            .withf(move |member| member == expected_member)
+            .times(1)
+            .returning(move |_| Ok(shared.clone()));
+        mock_collections_repo
+    }
+
+    /// Waits for the snapshot that is captured after the response.
+    async fn captured_in_background(captured: &AtomicBool) {
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            while !captured.load(Ordering::SeqCst) {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
+    /// A summary service that finds nothing to summarize.
+    fn mock_summary_service() -> MockAnalysisService {
+        let mut mock_summary_service = MockAnalysisService::new();
+        mock_summary_service
+            .expect_analyze()
+            .returning(|item| Ok(item.clone()));
+        mock_summary_service
+    }
+
+    fn mock_revisions_repo() -> MockRevisionsRepo {
+        let mut mock_revisions_repo = MockRevisionsRepo::new();
+        mock_revisions_repo
+            .expect_create()
+            .returning(|revision| Ok(revision.clone()));
+        mock_revisions_repo
+    }
+
+    fn unread_links() -> Vec<LinkItem> {
+        let created_at = |day| Utc.with_ymd_and_hms(2024, 5, day, 12, 0, 0).unwrap();
+        vec![
+            LinkItemBuilder::new("http://read")
+                .id("1")
+                .owner("user")
+                .label(READ_LABEL)
+                .reading_time(1)
+                .created_at(&created_at(1))
+                .build(),
+            LinkItemBuilder::new("http://long")
+                .id("2")
+                .owner("user")
+                .reading_time(30)
+                .created_at(&created_at(2))
+                .build(),
+            LinkItemBuilder::new("http://unknown")
+                .id("3")
+                .owner("user")
+                .created_at(&created_at(3))
+                .build(),
+            LinkItemBuilder::new("http://short")
+                .id("4")
+                .owner("user")
+                .reading_time(5)
+                .created_at(&created_at(4))
+                .build(),
+        ]
+    }
+
+    #[rstest]
+    #[case(NextStrategy::Oldest, None, Some("2"))]
+    #[case(NextStrategy::Oldest, Some(10), Some("4"))]
+    #[case(NextStrategy::Shortest, None, Some("4"))]
+    #[case(NextStrategy::Shortest, Some(3), None)]
+    #[case(NextStrategy::Random, Some(10), Some("4"))]
+    #[case(NextStrategy::Priority, Some(10), Some("4"))]
+    #[tokio::test]
+    async fn test_next_link(
+        #[case] strategy: NextStrategy,
+        #[case] minutes: Option<usize>,
+        #[case] expected_id: Option<&str>,
+    ) {
+        let request_query = LinkQueryBuilder::default().user("user").build();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_find()
+            .withf(|query| query.user() == "user")
+            .times(1)
+            .returning(|_| Ok(unread_links()));
+        let mut mock_details_repo = MockDetailsRepo::new();
+        mock_details_repo
+            .expect_find()
+            .withf(|owner| owner == "user")
+            .times(usize::from(strategy == NextStrategy::Priority))
+            .returning(|_| Ok(vec![]));
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .details_repo(Arc::new(mock_details_repo))
+            .build();
+        let response = links_service
+            .next(&context, &request_query, strategy, minutes)
+            .await;
+
+        assert_eq!(
+            response.map(|item| item.map(|item| item.id().to_owned())),
+            Ok(expected_id.map(ToOwned::to_owned))
+        );
+    }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_next_link_never_read(
+        #[values(NextStrategy::Random, NextStrategy::Priority)] strategy: NextStrategy,
+    ) {
+        let request_query = LinkQueryBuilder::default().user("user").build();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_find()
+            .returning(|_| Ok(unread_links()));
+        let mut mock_details_repo = MockDetailsRepo::new();
+        mock_details_repo.expect_find().returning(|_| {
+            Ok(vec![LinkDetails::new(
+                "1",
+                "user",
+                Priority::High,
+                None,
+                "",
+                &Utc::now(),
+            )])
+        });
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .details_repo(Arc::new(mock_details_repo))
+            .build();
+        for _ in 0..20 {
+            let response = links_service
+                .next(&context, &request_query, strategy, None)
+                .await;
+            assert!(response.is_ok_and(|item| item.is_some_and(|item| item.label() != READ_LABEL)));
+        }
+    }
+
     #[rstest]
     #[case(true, "admin")]
     #[case(false, "user")]
@@ -132,9 +795,10 @@ mod tests {
             .returning(|_| Ok(vec![]));
 
         let links_service = ServiceProvider {};
-        let response = links_service
-            .search(Box::new(Arc::new(mock_links_repo)), &request_query)
-            .await;
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .build();
+        let response = links_service.search(&context, &request_query).await;
 
         assert!(response.is_ok());
         assert!(response.unwrap().is_empty());
@@ -169,9 +833,10 @@ mod tests {
             .returning(move |_| Ok(vec![item.clone()]));
 
         let links_service = ServiceProvider {};
-        let response = links_service
-            .search(Box::new(Arc::new(mock_links_repo)), &request_query)
-            .await;
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .build();
+        let response = links_service.search(&context, &request_query).await;
 
         assert!(response.is_ok());
 
@@ -205,9 +870,10 @@ mod tests {
             .returning(|_| Err(AppError::Test));
 
         let links_service = ServiceProvider {};
-        let response = links_service
-            .search(Box::new(Arc::new(mock_links_repo)), &request_query)
-            .await;
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .build();
+        let response = links_service.search(&context, &request_query).await;
 
         assert_eq!(response, Err(AppError::Test));
     }
@@ -238,9 +904,10 @@ mod tests {
             .returning(move |_| Ok(retrieved_item.clone()));
 
         let links_service = ServiceProvider {};
-        let response = links_service
-            .get(Box::new(Arc::new(mock_links_repo)), &request_query)
-            .await;
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .build();
+        let response = links_service.get(&context, &request_query).await;
 
         assert!(response.is_ok());
         assert_eq!(response.unwrap(), response_item);
@@ -263,9 +930,11 @@ mod tests {
             .returning(move |_| Ok(retrieved_item.clone()));
 
         let links_service = ServiceProvider {};
-        let response = links_service
-            .get(Box::new(Arc::new(mock_links_repo)), &request_query)
-            .await;
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .collections_repo(Arc::new(mock_collections_repo("unauthorized-user", vec![])))
+            .build();
+        let response = links_service.get(&context, &request_query).await;
 
         assert_eq!(
             response,
@@ -275,6 +944,39 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case(&["1"], true)]
+    #[case(&["2"], false)]
+    #[tokio::test]
+    async fn test_get_shared_link(#[case] links: &[&str], #[case] is_shared: bool) {
+        let request_query = LinkQueryBuilder::new("1", "member").build();
+        let links: Vec<String> = links.iter().map(|&id| id.to_owned()).collect();
+        let shared_collection = Collection::new("user", "Reading", "", 0, &Utc::now())
+            .with_id("1")
+            .with_links(&links)
+            .with_members(&[Member::new("member", Role::Viewer)]);
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo.expect_get().times(1).returning(|_| {
+            Ok(LinkItemBuilder::new("http://link")
+                .id("1")
+                .owner("user")
+                .build())
+        });
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .collections_repo(Arc::new(mock_collections_repo(
+                "member",
+                vec![shared_collection],
+            )))
+            .build();
+        let response = links_service.get(&context, &request_query).await;
+
+        assert_eq!(response.is_ok(), is_shared);
+    }
+
     #[rstest]
     #[case(true, "admin")]
     #[case(false, "user")]
@@ -293,9 +995,10 @@ mod tests {
             .returning(|_| Err(AppError::LinkNotFound("1".into())));
 
         let links_service = ServiceProvider {};
-        let response = links_service
-            .get(Box::new(Arc::new(mock_links_repo)), &request_query)
-            .await;
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .build();
+        let response = links_service.get(&context, &request_query).await;
 
         assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
     }
@@ -310,6 +1013,7 @@ mod tests {
         let item_to_create = request_item.clone();
         let created_item = response_item.clone();
         let item_to_analyze = created_item.clone();
+        let item_to_capture = created_item.clone();
 
         let mut seq = Sequence::new();
 
@@ -333,60 +1037,160 @@ mod tests {
             })
             .times(1)
             .in_sequence(&mut seq)
-            .returning(|_| Ok(()));
+            .returning(|item| Ok(item.clone()));
+
+        let captured = Arc::new(AtomicBool::new(false));
+        let capture = Arc::clone(&captured);
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service
+            .expect_capture()
+            .withf(move |_, item| item == &item_to_capture)
+            .times(1)
+            .returning(move |_, _| {
+                capture.store(true, Ordering::SeqCst);
+                Ok(())
+            });
+
+        let mock_snapshots_repo = MockSnapshotsRepo::new();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, item| event == LINK_CREATED && item.id() == "1")
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
 
         let links_service = ServiceProvider {};
-        let response = links_service
-            .create(
-                Box::new(Arc::new(mock_analysis_service)),
-                Box::new(Arc::new(mock_links_repo)),
-                &request_item,
-            )
-            .await;
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .build();
+        let response = links_service.create(&context, &request_item).await;
+        captured_in_background(&captured).await;
 
         assert!(response.is_ok());
         assert_eq!(response.unwrap(), response_item);
     }
 
+    #[rstest]
+    #[case("http://link", true)]
+    #[case("http://moved-link", false)]
     #[tokio::test]
-    async fn test_create_link_repo_error() {
+    async fn test_create_link_summarized_in_background(
+        #[case] current_url: &str,
+        #[case] is_summarized: bool,
+    ) {
         let request_item = LinkItemBuilder::new("http://link").owner("user").build();
-        let item_to_create = request_item.clone();
+        let created_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let current_link = VersionedLink::new(
+            &LinkItemBuilder::from(created_item.clone())
+                .url(current_url)
+                .title("title")
+                .build(),
+            2,
+        );
+
+        let mut seq = Sequence::new();
 
         let mut mock_links_repo = MockLinksRepo::new();
+        let response_item = created_item.clone();
         mock_links_repo
             .expect_create()
-            .withf(move |item| {
-                item.url() == item_to_create.url() && item.owner() == item_to_create.owner()
-            })
             .times(1)
-            .returning(|_| Err(AppError::Test));
+            .returning(move |_| Ok(response_item.clone()));
+        mock_links_repo
+            .expect_get_versioned()
+            .withf(|query| query.id() == "1" && query.user().is_empty())
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_| Ok(current_link.clone()));
+        mock_links_repo
+            .expect_update_if_unmodified()
+            .withf(|query, item, version| {
+                query.id() == "1"
+                    && item.title() == "title"
+                    && item.summary() == "summary of link"
+                    && *version == 2
+            })
+            .times(usize::from(is_summarized))
+            .in_sequence(&mut seq)
+            .returning(|_, item, _| Ok(VersionedLink::new(item, 3)));
 
         let mut mock_analysis_service = MockAnalysisService::new();
-        mock_analysis_service.expect_analyze().times(0);
+        mock_analysis_service
+            .expect_analyze()
+            .times(1)
+            .returning(|item| Ok(item.clone()));
+
+        let mut mock_summary_service = MockAnalysisService::new();
+        mock_summary_service
+            .expect_analyze()
+            .times(1)
+            .returning(|item| {
+                Ok(LinkItemBuilder::from(item.clone())
+                    .summary("summary of link")
+                    .build())
+            });
+
+        let captured = Arc::new(AtomicBool::new(false));
+        let capture = Arc::clone(&captured);
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service
+            .expect_capture()
+            .times(1)
+            .returning(move |_, _| {
+                capture.store(true, Ordering::SeqCst);
+                Ok(())
+            });
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, _| event == LINK_CREATED)
+            .times(1)
+            .returning(|_, _, _| Ok(()));
 
         let links_service = ServiceProvider {};
-        let response = links_service
-            .create(
-                Box::new(Arc::new(mock_analysis_service)),
-                Box::new(Arc::new(mock_links_repo)),
-                &request_item,
-            )
-            .await;
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .build();
+        let response = links_service.create(&context, &request_item).await;
+        captured_in_background(&captured).await;
 
-        assert_eq!(response, Err(AppError::Test));
+        // the link is answered with before it is summarized
+        assert_eq!(response, Ok(created_item));
     }
 
     #[tokio::test]
-    async fn test_create_link_analyze_error() {
+    async fn test_create_link_with_analysis_result() {
         let request_item = LinkItemBuilder::new("http://link").owner("user").build();
         let response_item = LinkItemBuilder::new("http://link")
             .id("1")
             .owner("user")
+            .summary("summary of link")
             .build();
         let item_to_create = request_item.clone();
-        let created_item = response_item.clone();
+        let created_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
         let item_to_analyze = created_item.clone();
+        let update_query = LinkQueryBuilder::default().id("1").build();
+        let item_to_update = response_item.clone();
+        let updated_item = response_item.clone();
+        let item_to_capture = response_item.clone();
 
         let mut seq = Sequence::new();
 
@@ -403,23 +1207,151 @@ mod tests {
         let mut mock_analysis_service = MockAnalysisService::new();
         mock_analysis_service
             .expect_analyze()
-            .withf(move |item| {
-                item.id() == item_to_analyze.id()
-                    && item.url() == item_to_analyze.url()
-                    && item.owner() == item_to_analyze.owner()
-            })
+            .withf(move |item| item == &item_to_analyze)
             .times(1)
             .in_sequence(&mut seq)
-            .returning(|_| Err(AppError::Test));
+            .returning(|item| {
+                Ok(LinkItemBuilder::from(item.clone())
+                    .summary("summary of link")
+                    .build())
+            });
 
-        let links_service = ServiceProvider {};
-        let response = links_service
-            .create(
-                Box::new(Arc::new(mock_analysis_service)),
-                Box::new(Arc::new(mock_links_repo)),
-                &request_item,
-            )
-            .await;
+        mock_links_repo
+            .expect_update()
+            .withf(move |query, item| query == &update_query && item == &item_to_update)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_, _| Ok(VersionedLink::new(&updated_item, 2)));
+
+        let captured = Arc::new(AtomicBool::new(false));
+        let capture = Arc::clone(&captured);
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service
+            .expect_capture()
+            .withf(move |_, item| item == &item_to_capture)
+            .times(1)
+            .returning(move |_, _| {
+                capture.store(true, Ordering::SeqCst);
+                Ok(())
+            });
+
+        let mock_snapshots_repo = MockSnapshotsRepo::new();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, item| event == LINK_CREATED && item.id() == "1")
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .build();
+        let response = links_service.create(&context, &request_item).await;
+        captured_in_background(&captured).await;
+
+        assert_eq!(response, Ok(response_item));
+    }
+
+    #[tokio::test]
+    async fn test_create_link_repo_error() {
+        let request_item = LinkItemBuilder::new("http://link").owner("user").build();
+        let item_to_create = request_item.clone();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_create()
+            .withf(move |item| {
+                item.url() == item_to_create.url() && item.owner() == item_to_create.owner()
+            })
+            .times(1)
+            .returning(|_| Err(AppError::Test));
+
+        let mut mock_analysis_service = MockAnalysisService::new();
+        mock_analysis_service.expect_analyze().times(0);
+
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service.expect_capture().times(0);
+
+        let mock_snapshots_repo = MockSnapshotsRepo::new();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_notify().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .build();
+        let response = links_service.create(&context, &request_item).await;
+
+        assert_eq!(response, Err(AppError::Test));
+    }
+
+    #[tokio::test]
+    async fn test_create_link_analyze_error() {
+        let request_item = LinkItemBuilder::new("http://link").owner("user").build();
+        let response_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let item_to_create = request_item.clone();
+        let created_item = response_item.clone();
+        let item_to_analyze = created_item.clone();
+
+        let mut seq = Sequence::new();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_create()
+            .withf(move |item| {
+                item.url() == item_to_create.url() && item.owner() == item_to_create.owner()
+            })
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_| Ok(created_item.clone()));
+
+        let mut mock_analysis_service = MockAnalysisService::new();
+        mock_analysis_service
+            .expect_analyze()
+            .withf(move |item| {
+                item.id() == item_to_analyze.id()
+                    && item.url() == item_to_analyze.url()
+                    && item.owner() == item_to_analyze.owner()
+            })
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_| Err(AppError::Test));
+
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service.expect_capture().times(0);
+
+        let mock_snapshots_repo = MockSnapshotsRepo::new();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_notify().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .build();
+        let response = links_service.create(&context, &request_item).await;
 
         assert_eq!(response, Err(AppError::Test));
     }
@@ -456,11 +1388,11 @@ mod tests {
 
         let mut mock_links_repo = MockLinksRepo::new();
         mock_links_repo
-            .expect_get()
+            .expect_get_versioned()
             .withf(move |query| query == &get_query)
             .times(1)
             .in_sequence(&mut seq)
-            .returning(move |_| Ok(retrieved_item.clone()));
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
         mock_links_repo
             .expect_update()
             .withf(move |query, item| {
@@ -471,243 +1403,277 @@ mod tests {
             })
             .times(1)
             .in_sequence(&mut seq)
-            .returning(move |_, _| Ok(updated_item.clone()));
+            .returning(move |_, _| Ok(VersionedLink::new(&updated_item, 2)));
 
         let mut mock_analysis_service = MockAnalysisService::new();
         mock_analysis_service.expect_analyze().times(0);
 
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service.expect_capture().times(0);
+
+        let mock_snapshots_repo = MockSnapshotsRepo::new();
+
+        let actor = user.to_owned();
+        let mut mock_revisions_repo = MockRevisionsRepo::new();
+        mock_revisions_repo
+            .expect_create()
+            .withf(move |revision| {
+                revision.link_id() == "1"
+                    && revision.actor() == actor
+                    && revision.action() == "update"
+                    && revision.changes() == [FieldChange::new("description", "", "sample link")]
+            })
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|revision| Ok(revision.clone()));
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, item| event == LINK_UPDATED && item.id() == "1")
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
+
         let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo))
+            .build();
         let response = links_service
-            .update(
-                Box::new(Arc::new(mock_analysis_service)),
-                Box::new(Arc::new(mock_links_repo)),
-                &request_query,
-                &request_item,
-            )
+            .update(&context, &request_query, &request_item, None)
             .await;
 
         assert!(response.is_ok());
-        assert_eq!(response.unwrap(), response_item);
+        assert_eq!(response.unwrap().link(), &response_item);
     }
 
-    #[rstest]
-    #[case(true, "admin")]
-    #[case(false, "user")]
     #[tokio::test]
-    async fn test_update_link_update_url(#[case] is_admin: bool, #[case] user: &str) {
-        let request_query = LinkQueryBuilder::new("1", user)
-            .is_from_admin(is_admin)
-            .build();
-        let request_item = LinkItemBuilder::new("http://updated-link").build();
-        let response_item = LinkItemBuilder::new("http://updated-link")
-            .id("1")
-            .owner("user")
+    async fn test_update_link_mark_as_read() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let request_item = LinkItemBuilder::new("http://link")
+            .label(READ_LABEL)
             .build();
-        let get_query = LinkQueryBuilder::default().id("1").build();
         let retrieved_item = LinkItemBuilder::new("http://link")
             .id("1")
             .owner("user")
             .build();
-        let update_query = LinkQueryBuilder::default().id("1").build();
-        let item_to_update = LinkItemBuilder::new("http://updated-link")
+        let updated_item = LinkItemBuilder::new("http://link")
+            .id("1")
             .owner("user")
+            .label(READ_LABEL)
             .build();
-        let updated_item = response_item.clone();
-        let item_to_analyze = updated_item.clone();
+        let response_item = updated_item.clone();
 
         let mut seq = Sequence::new();
 
         let mut mock_links_repo = MockLinksRepo::new();
         mock_links_repo
-            .expect_get()
-            .withf(move |query| query == &get_query)
+            .expect_get_versioned()
             .times(1)
             .in_sequence(&mut seq)
-            .returning(move |_| Ok(retrieved_item.clone()));
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
         mock_links_repo
             .expect_update()
-            .withf(move |query, item| {
-                query == &update_query
-                    && item.id() == item_to_update.id()
-                    && item.url() == item_to_update.url()
-                    && item.owner() == item_to_update.owner()
-            })
+            .withf(|_, item| item.label() == READ_LABEL)
             .times(1)
             .in_sequence(&mut seq)
-            .returning(move |_, _| Ok(updated_item.clone()));
+            .returning(move |_, _| Ok(VersionedLink::new(&updated_item, 2)));
 
         let mut mock_analysis_service = MockAnalysisService::new();
-        mock_analysis_service
-            .expect_analyze()
-            .withf(move |item| {
-                item.id() == item_to_analyze.id()
-                    && item.url() == item_to_analyze.url()
-                    && item.owner() == item_to_analyze.owner()
-            })
+        mock_analysis_service.expect_analyze().times(0);
+
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service.expect_capture().times(0);
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, _| event == LINK_UPDATED)
             .times(1)
             .in_sequence(&mut seq)
-            .returning(|_| Ok(()));
+            .returning(|_, _, _| Ok(()));
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, _| event == LINK_READ)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
 
         let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
         let response = links_service
-            .update(
-                Box::new(Arc::new(mock_analysis_service)),
-                Box::new(Arc::new(mock_links_repo)),
-                &request_query,
-                &request_item,
-            )
+            .update(&context, &request_query, &request_item, None)
             .await;
 
-        assert!(response.is_ok());
-        assert_eq!(response.unwrap(), response_item);
+        assert_eq!(response, Ok(VersionedLink::new(&response_item, 2)));
     }
 
     #[tokio::test]
-    async fn test_update_link_unauthorized() {
-        let request_query = LinkQueryBuilder::new("1", "unauthorized-user").build();
+    async fn test_update_link_if_match() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
         let request_item = LinkItemBuilder::new("http://link")
-            .owner("unauthorized-user")
             .description("sample link")
             .build();
-        let get_query = LinkQueryBuilder::default().id("1").build();
-        let retrieved_item = LinkItemBuilder::new("http://link")
+        let retrieved_link = VersionedLink::new(
+            &LinkItemBuilder::new("http://link")
+                .id("1")
+                .owner("user")
+                .build(),
+            3,
+        );
+        let if_match = etag(&retrieved_link);
+        let updated_item = LinkItemBuilder::new("http://link")
             .id("1")
             .owner("user")
+            .description("sample link")
             .build();
+        let response_item = updated_item.clone();
 
         let mut mock_links_repo = MockLinksRepo::new();
         mock_links_repo
-            .expect_get()
-            .withf(move |query| query == &get_query)
+            .expect_get_versioned()
             .times(1)
-            .returning(move |_| Ok(retrieved_item.clone()));
+            .returning(move |_| Ok(retrieved_link.clone()));
         mock_links_repo.expect_update().times(0);
+        mock_links_repo
+            .expect_update_if_unmodified()
+            .withf(|query, _, version| query.id() == "1" && *version == 3)
+            .times(1)
+            .returning(move |_, _, _| Ok(VersionedLink::new(&updated_item, 4)));
 
-        let mut mock_analysis_service = MockAnalysisService::new();
-        mock_analysis_service.expect_analyze().times(0);
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, item| event == LINK_UPDATED && item.id() == "1")
+            .times(1)
+            .returning(|_, _, _| Ok(()));
 
         let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
         let response = links_service
-            .update(
-                Box::new(Arc::new(mock_analysis_service)),
-                Box::new(Arc::new(mock_links_repo)),
-                &request_query,
-                &request_item,
-            )
+            .update(&context, &request_query, &request_item, Some(if_match))
             .await;
 
-        assert_eq!(
-            response,
-            Err(AppError::Authorization(
-                "User is not authorized to access resource".into()
-            ))
-        );
+        assert_eq!(response, Ok(VersionedLink::new(&response_item, 4)));
     }
 
-    #[rstest]
-    #[case(true, "admin")]
-    #[case(false, "user")]
     #[tokio::test]
-    async fn test_update_link_not_found(#[case] is_admin: bool, #[case] user: &str) {
-        let request_query = LinkQueryBuilder::new("1", user)
-            .is_from_admin(is_admin)
-            .build();
+    async fn test_update_link_precondition_failed() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
         let request_item = LinkItemBuilder::new("http://link")
-            .owner("user")
             .description("sample link")
             .build();
-        let get_query = LinkQueryBuilder::default().id("1").build();
+        let retrieved_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
 
         let mut mock_links_repo = MockLinksRepo::new();
         mock_links_repo
-            .expect_get()
-            .withf(move |query| query == &get_query)
+            .expect_get_versioned()
             .times(1)
-            .returning(|_| Err(AppError::LinkNotFound("1".into())));
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
         mock_links_repo.expect_update().times(0);
+        mock_links_repo.expect_update_if_unmodified().times(0);
 
-        let mut mock_analysis_service = MockAnalysisService::new();
-        mock_analysis_service.expect_analyze().times(0);
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_notify().times(0);
 
         let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
         let response = links_service
             .update(
-                Box::new(Arc::new(mock_analysis_service)),
-                Box::new(Arc::new(mock_links_repo)),
+                &context,
                 &request_query,
                 &request_item,
+                Some("\"outdated\"".into()),
             )
             .await;
 
-        assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
+        assert_eq!(response, Err(AppError::PreconditionFailed("1".into())));
     }
 
-    #[rstest]
-    #[case(true, "admin")]
-    #[case(false, "user")]
     #[tokio::test]
-    async fn test_update_link_repo_error(#[case] is_admin: bool, #[case] user: &str) {
-        let request_query = LinkQueryBuilder::new("1", user)
-            .is_from_admin(is_admin)
-            .build();
-        let request_item = LinkItemBuilder::new("http://link")
-            .owner("user")
-            .description("sample link")
-            .build();
-        let get_query = LinkQueryBuilder::default().id("1").build();
+    async fn test_patch_link() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let patch: LinkPatchRequest =
+            serde_json::from_value(serde_json::json!({"description": "sample link"})).unwrap();
         let retrieved_item = LinkItemBuilder::new("http://link")
             .id("1")
             .owner("user")
+            .title("Link")
             .build();
-        let update_query = LinkQueryBuilder::default().id("1").build();
-        let item_to_update = request_item.clone();
-
-        let mut seq = Sequence::new();
 
         let mut mock_links_repo = MockLinksRepo::new();
         mock_links_repo
-            .expect_get()
-            .withf(move |query| query == &get_query)
+            .expect_get_versioned()
             .times(1)
-            .in_sequence(&mut seq)
-            .returning(move |_| Ok(retrieved_item.clone()));
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
         mock_links_repo
             .expect_update()
-            .withf(move |query, item| {
-                query == &update_query
-                    && item.id() == item_to_update.id()
-                    && item.url() == item_to_update.url()
-                    && item.owner() == item_to_update.owner()
+            .withf(|query, item| {
+                query.id() == "1"
+                    && item.url() == "http://link"
+                    && item.title() == "Link"
+                    && item.description() == "sample link"
             })
             .times(1)
-            .in_sequence(&mut seq)
-            .returning(|_, _| Err(AppError::Test));
+            .returning(|_, item| Ok(VersionedLink::new(item, 2)));
 
-        let mut mock_analysis_service = MockAnalysisService::new();
-        mock_analysis_service.expect_analyze().times(0);
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, item| event == LINK_UPDATED && item.id() == "1")
+            .times(1)
+            .returning(|_, _, _| Ok(()));
 
         let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
         let response = links_service
-            .update(
-                Box::new(Arc::new(mock_analysis_service)),
-                Box::new(Arc::new(mock_links_repo)),
-                &request_query,
-                &request_item,
-            )
-            .await;
+            .patch(&context, &request_query, &patch, None)
+            .await
+            .unwrap();
 
-        assert_eq!(response, Err(AppError::Test));
+        assert_eq!(response.link().title(), "Link");
+        assert_eq!(response.link().description(), "sample link");
     }
 
     #[rstest]
     #[case(true, "admin")]
     #[case(false, "user")]
     #[tokio::test]
-    async fn test_update_link_analyze_error(#[case] is_admin: bool, #[case] user: &str) {
+    async fn test_update_link_update_url(#[case] is_admin: bool, #[case] user: &str) {
         let request_query = LinkQueryBuilder::new("1", user)
             .is_from_admin(is_admin)
             .build();
-        let request_item = LinkItemBuilder::new("http://updated-link")
+        let request_item = LinkItemBuilder::new("http://updated-link").build();
+        let response_item = LinkItemBuilder::new("http://updated-link")
+            .id("1")
             .owner("user")
             .build();
         let get_query = LinkQueryBuilder::default().id("1").build();
@@ -719,21 +1685,19 @@ mod tests {
         let item_to_update = LinkItemBuilder::new("http://updated-link")
             .owner("user")
             .build();
-        let updated_item = LinkItemBuilder::new("http://updated-link")
-            .id("1")
-            .owner("user")
-            .build();
+        let updated_item = response_item.clone();
         let item_to_analyze = updated_item.clone();
+        let item_to_capture = updated_item.clone();
 
         let mut seq = Sequence::new();
 
         let mut mock_links_repo = MockLinksRepo::new();
         mock_links_repo
-            .expect_get()
+            .expect_get_versioned()
             .withf(move |query| query == &get_query)
             .times(1)
             .in_sequence(&mut seq)
-            .returning(move |_| Ok(retrieved_item.clone()));
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
         mock_links_repo
             .expect_update()
             .withf(move |query, item| {
@@ -744,7 +1708,7 @@ mod tests {
             })
             .times(1)
             .in_sequence(&mut seq)
-            .returning(move |_, _| Ok(updated_item.clone()));
+            .returning(move |_, _| Ok(VersionedLink::new(&updated_item, 2)));
 
         let mut mock_analysis_service = MockAnalysisService::new();
         mock_analysis_service
@@ -756,63 +1720,153 @@ mod tests {
             })
             .times(1)
             .in_sequence(&mut seq)
-            .returning(|_| Err(AppError::Test));
+            .returning(|item| Ok(item.clone()));
+
+        let captured = Arc::new(AtomicBool::new(false));
+        let capture = Arc::clone(&captured);
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service
+            .expect_capture()
+            .withf(move |_, item| item == &item_to_capture)
+            .times(1)
+            .returning(move |_, _| {
+                capture.store(true, Ordering::SeqCst);
+                Ok(())
+            });
+
+        let mock_snapshots_repo = MockSnapshotsRepo::new();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, item| event == LINK_UPDATED && item.id() == "1")
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
 
         let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
         let response = links_service
-            .update(
-                Box::new(Arc::new(mock_analysis_service)),
-                Box::new(Arc::new(mock_links_repo)),
-                &request_query,
-                &request_item,
-            )
+            .update(&context, &request_query, &request_item, None)
             .await;
+        captured_in_background(&captured).await;
 
-        assert_eq!(response, Err(AppError::Test));
+        assert!(response.is_ok());
+        assert_eq!(response.unwrap().link(), &response_item);
     }
 
-    #[rstest]
-    #[case(true, "admin")]
-    #[case(false, "user")]
     #[tokio::test]
-    async fn test_delete_link(#[case] is_admin: bool, #[case] user: &str) {
-        let request_query = LinkQueryBuilder::new("1", user)
-            .is_from_admin(is_admin)
+    async fn test_update_link_update_url_with_analysis_result() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let request_item = LinkItemBuilder::new("http://updated-link").build();
+        let response_item = LinkItemBuilder::new("http://updated-link")
+            .id("1")
+            .owner("user")
+            .summary("summary of updated link")
             .build();
         let get_query = LinkQueryBuilder::default().id("1").build();
         let retrieved_item = LinkItemBuilder::new("http://link")
             .id("1")
             .owner("user")
             .build();
-        let delete_query = LinkQueryBuilder::default().id("1").build();
+        let update_query = LinkQueryBuilder::default().id("1").build();
+        let analysis_update_query = update_query.clone();
+        let updated_item = LinkItemBuilder::new("http://updated-link")
+            .id("1")
+            .owner("user")
+            .build();
+        let item_to_analyze = updated_item.clone();
+        let analyzed_item = response_item.clone();
+        let item_to_capture = response_item.clone();
 
         let mut seq = Sequence::new();
 
         let mut mock_links_repo = MockLinksRepo::new();
         mock_links_repo
-            .expect_get()
+            .expect_get_versioned()
             .withf(move |query| query == &get_query)
             .times(1)
             .in_sequence(&mut seq)
-            .returning(move |_| Ok(retrieved_item.clone()));
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
         mock_links_repo
-            .expect_delete()
-            .withf(move |query| query == &delete_query)
+            .expect_update()
+            .withf(move |query, item| query == &update_query && item.summary().is_empty())
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_, _| Ok(VersionedLink::new(&updated_item, 2)));
+
+        let mut mock_analysis_service = MockAnalysisService::new();
+        mock_analysis_service
+            .expect_analyze()
+            .withf(move |item| item == &item_to_analyze)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_| Ok(analyzed_item.clone()));
+
+        mock_links_repo
+            .expect_update()
+            .withf(move |query, item| {
+                query == &analysis_update_query && item.summary() == "summary of updated link"
+            })
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, item| Ok(VersionedLink::new(item, 3)));
+
+        let captured = Arc::new(AtomicBool::new(false));
+        let capture = Arc::clone(&captured);
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service
+            .expect_capture()
+            .withf(move |_, item| item == &item_to_capture)
+            .times(1)
+            .returning(move |_, _| {
+                capture.store(true, Ordering::SeqCst);
+                Ok(())
+            });
+
+        let mock_snapshots_repo = MockSnapshotsRepo::new();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, item| event == LINK_UPDATED && item.id() == "1")
             .times(1)
             .in_sequence(&mut seq)
-            .returning(move |_| Ok(()));
+            .returning(|_, _, _| Ok(()));
 
         let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
         let response = links_service
-            .delete(Box::new(Arc::new(mock_links_repo)), &request_query)
+            .update(&context, &request_query, &request_item, None)
             .await;
+        captured_in_background(&captured).await;
 
-        assert!(response.is_ok());
+        assert_eq!(response, Ok(VersionedLink::new(&response_item, 3)));
     }
 
     #[tokio::test]
-    async fn test_delete_link_unauthorized() {
+    async fn test_update_link_unauthorized() {
         let request_query = LinkQueryBuilder::new("1", "unauthorized-user").build();
+        let request_item = LinkItemBuilder::new("http://link")
+            .owner("unauthorized-user")
+            .description("sample link")
+            .build();
         let get_query = LinkQueryBuilder::default().id("1").build();
         let retrieved_item = LinkItemBuilder::new("http://link")
             .id("1")
@@ -821,15 +1875,36 @@ mod tests {
 
         let mut mock_links_repo = MockLinksRepo::new();
         mock_links_repo
-            .expect_get()
+            .expect_get_versioned()
             .withf(move |query| query == &get_query)
             .times(1)
-            .returning(move |_| Ok(retrieved_item.clone()));
-        mock_links_repo.expect_delete().times(0);
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
+        mock_links_repo.expect_update().times(0);
+
+        let mut mock_analysis_service = MockAnalysisService::new();
+        mock_analysis_service.expect_analyze().times(0);
+
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service.expect_capture().times(0);
+
+        let mock_snapshots_repo = MockSnapshotsRepo::new();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_notify().times(0);
 
         let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .collections_repo(Arc::new(mock_collections_repo("unauthorized-user", vec![])))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
         let response = links_service
-            .delete(Box::new(Arc::new(mock_links_repo)), &request_query)
+            .update(&context, &request_query, &request_item, None)
             .await;
 
         assert_eq!(
@@ -840,27 +1915,88 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_update_shared_link_as_viewer() {
+        let request_query = LinkQueryBuilder::new("1", "member").build();
+        let item_to_update = LinkItemBuilder::new("http://link").title("title").build();
+        let shared_collection = Collection::new("user", "Reading", "", 0, &Utc::now())
+            .with_id("1")
+            .with_links(&["1".into()])
+            .with_members(&[Member::new("member", Role::Viewer)]);
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_versioned()
+            .times(1)
+            .returning(|_| {
+                let item = LinkItemBuilder::new("http://link")
+                    .id("1")
+                    .owner("user")
+                    .build();
+                Ok(VersionedLink::new(&item, 1))
+            });
+        mock_links_repo.expect_update().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .collections_repo(Arc::new(mock_collections_repo(
+                "member",
+                vec![shared_collection],
+            )))
+            .build();
+        let response = links_service
+            .update(&context, &request_query, &item_to_update, None)
+            .await;
+
+        assert!(matches!(response, Err(AppError::Authorization(_))));
+    }
+
     #[rstest]
     #[case(true, "admin")]
     #[case(false, "user")]
     #[tokio::test]
-    async fn test_delete_link_not_found(#[case] is_admin: bool, #[case] user: &str) {
+    async fn test_update_link_not_found(#[case] is_admin: bool, #[case] user: &str) {
         let request_query = LinkQueryBuilder::new("1", user)
             .is_from_admin(is_admin)
             .build();
+        let request_item = LinkItemBuilder::new("http://link")
+            .owner("user")
+            .description("sample link")
+            .build();
         let get_query = LinkQueryBuilder::default().id("1").build();
 
         let mut mock_links_repo = MockLinksRepo::new();
         mock_links_repo
-            .expect_get()
+            .expect_get_versioned()
             .withf(move |query| query == &get_query)
             .times(1)
             .returning(|_| Err(AppError::LinkNotFound("1".into())));
-        mock_links_repo.expect_delete().times(0);
+        mock_links_repo.expect_update().times(0);
+
+        let mut mock_analysis_service = MockAnalysisService::new();
+        mock_analysis_service.expect_analyze().times(0);
+
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service.expect_capture().times(0);
+
+        let mock_snapshots_repo = MockSnapshotsRepo::new();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_notify().times(0);
 
         let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
         let response = links_service
-            .delete(Box::new(Arc::new(mock_links_repo)), &request_query)
+            .update(&context, &request_query, &request_item, None)
             .await;
 
         assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
@@ -870,38 +2006,912 @@ mod tests {
     #[case(true, "admin")]
     #[case(false, "user")]
     #[tokio::test]
-    async fn test_delete_link_repo_error(#[case] is_admin: bool, #[case] user: &str) {
+    async fn test_update_link_repo_error(#[case] is_admin: bool, #[case] user: &str) {
         let request_query = LinkQueryBuilder::new("1", user)
             .is_from_admin(is_admin)
             .build();
+        let request_item = LinkItemBuilder::new("http://link")
+            .owner("user")
+            .description("sample link")
+            .build();
         let get_query = LinkQueryBuilder::default().id("1").build();
         let retrieved_item = LinkItemBuilder::new("http://link")
             .id("1")
             .owner("user")
             .build();
-        let delete_query = LinkQueryBuilder::default().id("1").build();
+        let update_query = LinkQueryBuilder::default().id("1").build();
+        let item_to_update = request_item.clone();
 
         let mut seq = Sequence::new();
 
         let mut mock_links_repo = MockLinksRepo::new();
         mock_links_repo
-            .expect_get()
+            .expect_get_versioned()
             .withf(move |query| query == &get_query)
             .times(1)
             .in_sequence(&mut seq)
-            .returning(move |_| Ok(retrieved_item.clone()));
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
         mock_links_repo
-            .expect_delete()
-            .withf(move |query| query == &delete_query)
+            .expect_update()
+            .withf(move |query, item| {
+                query == &update_query
+                    && item.id() == item_to_update.id()
+                    && item.url() == item_to_update.url()
+                    && item.owner() == item_to_update.owner()
+            })
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Err(AppError::Test));
+
+        let mut mock_analysis_service = MockAnalysisService::new();
+        mock_analysis_service.expect_analyze().times(0);
+
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service.expect_capture().times(0);
+
+        let mock_snapshots_repo = MockSnapshotsRepo::new();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_notify().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
+        let response = links_service
+            .update(&context, &request_query, &request_item, None)
+            .await;
+
+        assert_eq!(response, Err(AppError::Test));
+    }
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_update_link_analyze_error(#[case] is_admin: bool, #[case] user: &str) {
+        let request_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+        let request_item = LinkItemBuilder::new("http://updated-link")
+            .owner("user")
+            .build();
+        let get_query = LinkQueryBuilder::default().id("1").build();
+        let retrieved_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let update_query = LinkQueryBuilder::default().id("1").build();
+        let item_to_update = LinkItemBuilder::new("http://updated-link")
+            .owner("user")
+            .build();
+        let updated_item = LinkItemBuilder::new("http://updated-link")
+            .id("1")
+            .owner("user")
+            .build();
+        let item_to_analyze = updated_item.clone();
+
+        let mut seq = Sequence::new();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_versioned()
+            .withf(move |query| query == &get_query)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
+        mock_links_repo
+            .expect_update()
+            .withf(move |query, item| {
+                query == &update_query
+                    && item.id() == item_to_update.id()
+                    && item.url() == item_to_update.url()
+                    && item.owner() == item_to_update.owner()
+            })
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_, _| Ok(VersionedLink::new(&updated_item, 2)));
+
+        let mut mock_analysis_service = MockAnalysisService::new();
+        mock_analysis_service
+            .expect_analyze()
+            .withf(move |item| {
+                item.id() == item_to_analyze.id()
+                    && item.url() == item_to_analyze.url()
+                    && item.owner() == item_to_analyze.owner()
+            })
             .times(1)
             .in_sequence(&mut seq)
             .returning(|_| Err(AppError::Test));
 
+        let mut mock_snapshots_service = MockSnapshotsService::new();
+        mock_snapshots_service.expect_capture().times(0);
+
+        let mock_snapshots_repo = MockSnapshotsRepo::new();
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_notify().times(0);
+
         let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .analysis_service(Arc::new(mock_analysis_service))
+            .summary_service(Arc::new(mock_summary_service()))
+            .snapshots_service(Arc::new(mock_snapshots_service))
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
         let response = links_service
-            .delete(Box::new(Arc::new(mock_links_repo)), &request_query)
+            .update(&context, &request_query, &request_item, None)
             .await;
 
         assert_eq!(response, Err(AppError::Test));
     }
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_delete_link(#[case] is_admin: bool, #[case] user: &str) {
+        let request_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+        let get_query = LinkQueryBuilder::default().id("1").build();
+        let retrieved_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let trash_query = LinkQueryBuilder::default().id("1").build();
+
+        let mut seq = Sequence::new();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_versioned()
+            .withf(move |query| query == &get_query)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
+
+        mock_links_repo
+            .expect_trash()
+            .withf(move |query, _| query == &trash_query)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Ok(()));
+
+        let mut mock_revisions_repo = MockRevisionsRepo::new();
+        mock_revisions_repo
+            .expect_create()
+            .withf(|revision| {
+                revision.link_id() == "1"
+                    && revision.action() == "delete"
+                    && revision.changes().is_empty()
+            })
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|revision| Ok(revision.clone()));
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, item| event == LINK_DELETED && item.id() == "1")
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _, _| Ok(()));
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo))
+            .build();
+        let response = links_service.delete(&context, &request_query, None).await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_delete_link_unauthorized() {
+        let request_query = LinkQueryBuilder::new("1", "unauthorized-user").build();
+        let get_query = LinkQueryBuilder::default().id("1").build();
+        let retrieved_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_versioned()
+            .withf(move |query| query == &get_query)
+            .times(1)
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
+        mock_links_repo.expect_trash().times(0);
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_notify().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .collections_repo(Arc::new(mock_collections_repo("unauthorized-user", vec![])))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
+        let response = links_service.delete(&context, &request_query, None).await;
+
+        assert_eq!(
+            response,
+            Err(AppError::Authorization(
+                "User is not authorized to access resource".into()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_link_precondition_failed() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let retrieved_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_versioned()
+            .times(1)
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
+        mock_links_repo.expect_trash().times(0);
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_notify().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
+        let response = links_service
+            .delete(&context, &request_query, Some("\"outdated\"".into()))
+            .await;
+
+        assert_eq!(response, Err(AppError::PreconditionFailed("1".into())));
+    }
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_delete_link_not_found(#[case] is_admin: bool, #[case] user: &str) {
+        let request_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+        let get_query = LinkQueryBuilder::default().id("1").build();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_versioned()
+            .withf(move |query| query == &get_query)
+            .times(1)
+            .returning(|_| Err(AppError::LinkNotFound("1".into())));
+        mock_links_repo.expect_trash().times(0);
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_notify().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
+        let response = links_service.delete(&context, &request_query, None).await;
+
+        assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
+    }
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_delete_link_repo_error(#[case] is_admin: bool, #[case] user: &str) {
+        let request_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+        let get_query = LinkQueryBuilder::default().id("1").build();
+        let retrieved_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let trash_query = LinkQueryBuilder::default().id("1").build();
+
+        let mut seq = Sequence::new();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_versioned()
+            .withf(move |query| query == &get_query)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
+
+        mock_links_repo
+            .expect_trash()
+            .withf(move |query, _| query == &trash_query)
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(|_, _| Err(AppError::Test));
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service.expect_notify().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo()))
+            .build();
+        let response = links_service.delete(&context, &request_query, None).await;
+
+        assert_eq!(response, Err(AppError::Test));
+    }
+
+    #[rstest]
+    #[case(true, "admin", "")]
+    #[case(false, "user", "user")]
+    #[tokio::test]
+    async fn test_trash(#[case] is_admin: bool, #[case] user: &str, #[case] owner: &str) {
+        let request_query = LinkQueryBuilder::default()
+            .user(user)
+            .is_from_admin(is_admin)
+            .build();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let trashed_link = TrashedLink::new(&item, &Utc::now());
+        let expected_trash = vec![trashed_link.clone()];
+
+        let owner = owner.to_owned();
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_find_trashed()
+            .withf(move |find_owner| find_owner == owner)
+            .times(1)
+            .returning(move |_| Ok(vec![trashed_link.clone()]));
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .build();
+        let response = links_service.trash(&context, &request_query).await;
+
+        assert_eq!(response, Ok(expected_trash));
+    }
+
+    #[rstest]
+    #[case(true, "admin")]
+    #[case(false, "user")]
+    #[tokio::test]
+    async fn test_restore_link(#[case] is_admin: bool, #[case] user: &str) {
+        let request_query = LinkQueryBuilder::new("1", user)
+            .is_from_admin(is_admin)
+            .build();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let trashed_link = TrashedLink::new(&item, &Utc::now());
+        let expected_item = item.clone();
+
+        let mut seq = Sequence::new();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_trashed()
+            .withf(|id| id == "1")
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_| Ok(trashed_link.clone()));
+        mock_links_repo
+            .expect_restore()
+            .withf(|id| id == "1")
+            .times(1)
+            .in_sequence(&mut seq)
+            .returning(move |_| Ok(item.clone()));
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .build();
+        let response = links_service.restore(&context, &request_query).await;
+
+        assert_eq!(response, Ok(expected_item));
+    }
+
+    #[rstest]
+    #[case(Role::Admin, true)]
+    #[case(Role::Editor, false)]
+    #[tokio::test]
+    async fn test_trash_of_shared_collection(#[case] role: Role, #[case] is_listed: bool) {
+        let request_query = LinkQueryBuilder::default().user("member").build();
+        let now = Utc::now();
+        let shared_link = TrashedLink::new(
+            &LinkItemBuilder::new("http://link")
+                .id("1")
+                .owner("user")
+                .build(),
+            &now,
+        );
+        let other_link = TrashedLink::new(
+            &LinkItemBuilder::new("http://other")
+                .id("2")
+                .owner("user")
+                .build(),
+            &now,
+        );
+        let shared_collection = Collection::new("user", "Reading", "", 0, &now)
+            .with_id("1")
+            .with_links(&["1".into()])
+            .with_members(&[Member::new("member", role)]);
+        let expected_trash = if is_listed {
+            vec![shared_link.clone()]
+        } else {
+            vec![]
+        };
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_find_trashed()
+            .withf(|owner| owner == "member")
+            .times(1)
+            .returning(|_| Ok(vec![]));
+        mock_links_repo
+            .expect_find_trashed()
+            .withf(|owner| owner == "user")
+            .times(usize::from(is_listed))
+            .returning(move |_| Ok(vec![shared_link.clone(), other_link.clone()]));
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .collections_repo(Arc::new(mock_collections_repo(
+                "member",
+                vec![shared_collection],
+            )))
+            .build();
+        let response = links_service.trash(&context, &request_query).await;
+
+        assert_eq!(response, Ok(expected_trash));
+    }
+
+    #[rstest]
+    #[case(Role::Admin, true)]
+    #[case(Role::Editor, false)]
+    #[tokio::test]
+    async fn test_restore_shared_link(#[case] role: Role, #[case] is_authorized: bool) {
+        let request_query = LinkQueryBuilder::new("1", "member").build();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let trashed_link = TrashedLink::new(&item, &Utc::now());
+        let shared_collection = Collection::new("user", "Reading", "", 0, &Utc::now())
+            .with_id("1")
+            .with_links(&["1".into()])
+            .with_members(&[Member::new("member", role)]);
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_trashed()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(move |_| Ok(trashed_link.clone()));
+        mock_links_repo
+            .expect_restore()
+            .withf(|id| id == "1")
+            .times(usize::from(is_authorized))
+            .returning(move |_| Ok(item.clone()));
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .collections_repo(Arc::new(mock_collections_repo(
+                "member",
+                vec![shared_collection],
+            )))
+            .build();
+        let response = links_service.restore(&context, &request_query).await;
+
+        assert_eq!(response.is_ok(), is_authorized);
+    }
+
+    #[tokio::test]
+    async fn test_restore_link_unauthorized() {
+        let request_query = LinkQueryBuilder::new("1", "unauthorized-user").build();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let trashed_link = TrashedLink::new(&item, &Utc::now());
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_trashed()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(move |_| Ok(trashed_link.clone()));
+        mock_links_repo.expect_restore().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .build();
+        let response = links_service.restore(&context, &request_query).await;
+
+        assert_eq!(
+            response,
+            Err(AppError::Authorization(
+                "User is not authorized to access resource".into()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_link_not_found() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_trashed()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(|_| Err(AppError::LinkNotFound("1".into())));
+        mock_links_repo.expect_restore().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .build();
+        let response = links_service.restore(&context, &request_query).await;
+
+        assert_eq!(response, Err(AppError::LinkNotFound("1".into())));
+    }
+
+    #[allow(clippy::too_many_lines)]
+    #[tokio::test]
+    async fn test_purge() {
+        let now = Utc::now();
+        let deleted_before = now - chrono::Duration::days(30);
+        let expired_link = TrashedLink::new(
+            &LinkItemBuilder::new("http://link")
+                .id("1")
+                .owner("user")
+                .build(),
+            &(now - chrono::Duration::days(31)),
+        );
+        let delete_query = LinkQueryBuilder::default().id("1").build();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_find_expired()
+            .withf(move |before| before == &deleted_before)
+            .times(1)
+            .returning(move |_| Ok(vec![expired_link.clone()]));
+        mock_links_repo
+            .expect_delete()
+            .withf(move |query| query == &delete_query)
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_snapshots_repo = MockSnapshotsRepo::new();
+        mock_snapshots_repo
+            .expect_delete()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_revisions_repo = MockRevisionsRepo::new();
+        mock_revisions_repo
+            .expect_delete()
+            .withf(|link_id| link_id == "1")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_details_repo = MockDetailsRepo::new();
+        mock_details_repo
+            .expect_delete()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_highlights_repo = MockHighlightsRepo::new();
+        mock_highlights_repo
+            .expect_delete_all()
+            .withf(|link_id| link_id == "1")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_progress_repo = MockProgressRepo::new();
+        mock_progress_repo
+            .expect_delete()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_health_repo = MockHealthRepo::new();
+        mock_health_repo
+            .expect_delete()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_shares_repo = MockSharesRepo::new();
+        mock_shares_repo
+            .expect_find()
+            .withf(|owner| owner == "user")
+            .times(1)
+            .returning(move |_| {
+                Ok(vec![
+                    Share::new("user", SHARE_LINK, "1", None, &now).with_id("10"),
+                    Share::new("user", SHARE_LINK, "2", None, &now).with_id("11"),
+                    Share::new("user", SHARE_COLLECTION, "1", None, &now).with_id("12"),
+                ])
+            });
+        mock_shares_repo
+            .expect_delete()
+            .withf(|id| id == "10")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_reminders_repo = MockRemindersRepo::new();
+        mock_reminders_repo
+            .expect_find()
+            .withf(|owner| owner == "user")
+            .times(1)
+            .returning(move |_| {
+                Ok(vec![
+                    Reminder::new("user", REMINDER_LINK, "1", &now, &now).with_id("20"),
+                    Reminder::new("user", REMINDER_LINK, "2", &now, &now).with_id("21"),
+                ])
+            });
+        mock_reminders_repo
+            .expect_delete()
+            .withf(|id| id == "20")
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_find()
+            .withf(|owner| owner == "user")
+            .times(1)
+            .returning(move |_| {
+                Ok(vec![
+                    Collection::new("user", "Reading", "", 0, &now)
+                        .with_id("30")
+                        .with_links(&["2".into(), "1".into(), "3".into()]),
+                    Collection::new("user", "Other", "", 1, &now)
+                        .with_id("31")
+                        .with_links(&["2".into()]),
+                ])
+            });
+        mock_collections_repo
+            .expect_update()
+            .withf(|collection| collection.id() == "30" && collection.links() == ["2", "3"])
+            .times(1)
+            .returning(|collection| Ok(collection.clone()));
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo))
+            .details_repo(Arc::new(mock_details_repo))
+            .highlights_repo(Arc::new(mock_highlights_repo))
+            .progress_repo(Arc::new(mock_progress_repo))
+            .health_repo(Arc::new(mock_health_repo))
+            .shares_repo(Arc::new(mock_shares_repo))
+            .reminders_repo(Arc::new(mock_reminders_repo))
+            .collections_repo(Arc::new(mock_collections_repo))
+            .build();
+        let response = links_service.purge(&context, &deleted_before).await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_history() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let retrieved_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let revision = Revision::new(
+            &retrieved_item,
+            "user",
+            "update",
+            &[FieldChange::new("title", "", "Link")],
+            &Utc::now(),
+        )
+        .with_id("1");
+        let response_revisions = vec![revision.clone()];
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .times(1)
+            .returning(move |_| Ok(retrieved_item.clone()));
+
+        let mut mock_revisions_repo = MockRevisionsRepo::new();
+        mock_revisions_repo
+            .expect_find()
+            .withf(|link_id| link_id == "1")
+            .times(1)
+            .returning(move |_| Ok(vec![revision.clone()]));
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo))
+            .build();
+        let response = links_service.history(&context, &request_query).await;
+
+        assert_eq!(response, Ok(response_revisions));
+    }
+
+    #[tokio::test]
+    async fn test_history_unauthorized() {
+        let request_query = LinkQueryBuilder::new("1", "unauthorized-user").build();
+        let retrieved_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .times(1)
+            .returning(move |_| Ok(retrieved_item.clone()));
+
+        let mut mock_revisions_repo = MockRevisionsRepo::new();
+        mock_revisions_repo.expect_find().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .collections_repo(Arc::new(mock_collections_repo("unauthorized-user", vec![])))
+            .revisions_repo(Arc::new(mock_revisions_repo))
+            .build();
+        let response = links_service.history(&context, &request_query).await;
+
+        assert!(matches!(response, Err(AppError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_revert() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let previous_item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .title("Link")
+            .build();
+        let retrieved_item = LinkItemBuilder::from(previous_item.clone())
+            .title("Changed")
+            .build();
+        let revision = Revision::new(
+            &previous_item,
+            "user",
+            "update",
+            &[FieldChange::new("title", "Link", "Changed")],
+            &Utc::now(),
+        )
+        .with_id("1");
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get_versioned()
+            .times(1)
+            .returning(move |_| Ok(VersionedLink::new(&retrieved_item, 1)));
+        mock_links_repo
+            .expect_update()
+            .withf(|_, item| item.title() == "Link")
+            .times(1)
+            .returning(|_, item| Ok(VersionedLink::new(item, 2)));
+
+        let mut mock_revisions_repo = MockRevisionsRepo::new();
+        mock_revisions_repo
+            .expect_get()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(move |_| Ok(revision.clone()));
+        mock_revisions_repo
+            .expect_create()
+            .withf(|revision| {
+                revision.action() == "revert"
+                    && revision.link().title() == "Changed"
+                    && revision.changes() == [FieldChange::new("title", "Changed", "Link")]
+            })
+            .times(1)
+            .returning(|revision| Ok(revision.clone()));
+
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, _| event == LINK_UPDATED)
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .webhooks_service(Arc::new(mock_webhooks_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo))
+            .build();
+        let response = links_service.revert(&context, &request_query, "1").await;
+
+        assert!(response.is_ok());
+        assert_eq!(response.unwrap().link().title(), "Link");
+    }
+
+    #[tokio::test]
+    async fn test_revert_revision_of_other_link() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let revision = Revision::new(
+            &LinkItemBuilder::new("http://link").id("2").build(),
+            "user",
+            "update",
+            &[],
+            &Utc::now(),
+        )
+        .with_id("1");
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo.expect_update().times(0);
+
+        let mut mock_revisions_repo = MockRevisionsRepo::new();
+        mock_revisions_repo
+            .expect_get()
+            .times(1)
+            .returning(move |_| Ok(revision.clone()));
+        mock_revisions_repo.expect_create().times(0);
+
+        let links_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .revisions_repo(Arc::new(mock_revisions_repo))
+            .build();
+        let response = links_service.revert(&context, &request_query, "1").await;
+
+        assert_eq!(response, Err(AppError::RevisionNotFound("1".into())));
+    }
+
+    #[test]
+    fn test_etag() {
+        let item = LinkItemBuilder::new("http://link").id("1").build();
+        let link = VersionedLink::new(&item, 1);
+        let updated_link = VersionedLink::new(&item, 2);
+
+        assert_eq!(etag(&link), "\"1\"");
+        assert_ne!(etag(&link), etag(&updated_link));
+    }
+
+    #[rstest]
+    #[case("\"abc\"", false, true)]
+    #[case("\"xyz\", \"abc\"", false, true)]
+    #[case("*", false, true)]
+    #[case("\"xyz\"", false, false)]
+    #[case("W/\"abc\"", false, false)]
+    #[case("W/\"abc\"", true, true)]
+    fn test_etag_matches(#[case] tags: &str, #[case] weak: bool, #[case] expected: bool) {
+        assert_eq!(etag_matches(tags, "\"abc\"", weak), expected);
+    }
 }
@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use axum::async_trait;
+#[cfg(test)]
+use mockall::{automock, predicate::*};
+
+use crate::{
+    service::webhooks::LINK_REMINDER,
+    service::Notifier,
+    types::{AppContext, LinkItem, Result},
+};
+
+pub type DynMailer = Arc<dyn Mailer + Send + Sync>;
+
+/// Sends email, for deployments that plug in their own mail provider.
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait Mailer {
+    async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()>;
+}
+
+/// Only writes reminders to the log, which is enough for development and
+/// tests.
+#[derive(Default)]
+pub struct LogNotifier {}
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, owner: &str, subject: &str, links: &[LinkItem]) -> Result<()> {
+        tracing::info!("Reminding {} ({} links): {}", owner, links.len(), subject);
+        Ok(())
+    }
+}
+
+/// Delivers every link of a reminder as a `link.reminder` event to the
+/// webhooks of its owner.
+pub struct WebhookNotifier {
+    context: AppContext,
+}
+
+impl WebhookNotifier {
+    pub const fn new(context: AppContext) -> Self {
+        Self { context }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, _owner: &str, _subject: &str, links: &[LinkItem]) -> Result<()> {
+        for link in links {
+            self.context
+                .webhooks_service()
+                .notify(&self.context, LINK_REMINDER, link)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Emails reminders to their owner, whose user id is their email address.
+pub struct MailNotifier {
+    mailer: DynMailer,
+}
+
+impl MailNotifier {
+    pub fn new(mailer: DynMailer) -> Self {
+        Self { mailer }
+    }
+}
+
+#[async_trait]
+impl Notifier for MailNotifier {
+    async fn notify(&self, owner: &str, subject: &str, links: &[LinkItem]) -> Result<()> {
+        let body: String = links
+            .iter()
+            .map(|link| {
+                let title = if link.title().is_empty() {
+                    link.url()
+                } else {
+                    link.title()
+                };
+                [title, "\n", link.url(), "\n\n"].concat()
+            })
+            .collect();
+        self.mailer.send(owner, subject, &body).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        app::ContextBuilder, service::MockWebhooks as MockWebhooksService, types::LinkItemBuilder,
+    };
+
+    use super::*;
+
+    fn links() -> Vec<LinkItem> {
+        vec![
+            LinkItemBuilder::new("http://first")
+                .id("1")
+                .owner("user@test.com")
+                .title("First")
+                .build(),
+            LinkItemBuilder::new("http://second")
+                .id("2")
+                .owner("user@test.com")
+                .build(),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_webhook_notifier() {
+        let mut mock_webhooks_service = MockWebhooksService::new();
+        mock_webhooks_service
+            .expect_notify()
+            .withf(|_, event, _| event == LINK_REMINDER)
+            .times(2)
+            .returning(|_, _, _| Ok(()));
+
+        let notifier = WebhookNotifier::new(
+            ContextBuilder::default()
+                .webhooks_service(Arc::new(mock_webhooks_service))
+                .build(),
+        );
+        let response = notifier
+            .notify("user@test.com", "Your unread links", &links())
+            .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_mail_notifier() {
+        let mut mock_mailer = MockMailer::new();
+        mock_mailer
+            .expect_send()
+            .withf(|to, subject, body| {
+                to == "user@test.com"
+                    && subject == "Your unread links"
+                    && body == "First\nhttp://first\n\nhttp://second\nhttp://second\n\n"
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let notifier = MailNotifier::new(Arc::new(mock_mailer));
+        let response = notifier
+            .notify("user@test.com", "Your unread links", &links())
+            .await;
+
+        assert!(response.is_ok());
+    }
+}
@@ -0,0 +1,407 @@
+use axum::async_trait;
+use chrono::{DateTime, Datelike, Days, Utc};
+
+use crate::{
+    service::{
+        links::{authorize, READ_LABEL},
+        Progress as ProgressService,
+    },
+    types::{
+        AppContext, AppError, LinkItemBuilder, LinkQuery, ReadingProgress, ReadingStats, Result,
+        Role, WeeklyReads,
+    },
+};
+
+pub const DEFAULT_STATS_WEEKS: u32 = 12;
+pub const MAX_STATS_WEEKS: u32 = 52;
+
+const FINISHED_PERCENTAGE: u8 = 100;
+
+#[derive(Default)]
+pub struct ServiceProvider {}
+
+#[async_trait]
+impl ProgressService for ServiceProvider {
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<ReadingProgress> {
+        let item = context.links_service().get(context, query).await?;
+        match context.progress_repo().get(item.id()).await {
+            Err(AppError::ProgressNotFound(_)) => Ok(ReadingProgress::new(
+                item.id(),
+                item.owner(),
+                0,
+                0,
+                0,
+                item.updated_at(),
+            )),
+            result => result,
+        }
+    }
+
+    async fn update(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        progress: &ReadingProgress,
+    ) -> Result<ReadingProgress> {
+        let item = authorize(context, query, Role::Editor).await?;
+        let previous = match context.progress_repo().get(item.id()).await {
+            Ok(previous) => Some(previous),
+            Err(AppError::ProgressNotFound(_)) => None,
+            Err(e) => return Err(e),
+        };
+
+        let now = Utc::now();
+        let time_spent = previous
+            .as_ref()
+            .map_or(0, ReadingProgress::time_spent)
+            .saturating_add(progress.time_spent());
+        let updated_progress = ReadingProgress::new(
+            item.id(),
+            item.owner(),
+            progress.percentage(),
+            progress.position(),
+            time_spent,
+            &now,
+        );
+        // a link is only finished once, reading it again keeps the first time
+        let previously_finished_at = previous
+            .as_ref()
+            .and_then(ReadingProgress::finished_at)
+            .copied();
+        let is_finished =
+            previously_finished_at.is_none() && progress.percentage() >= FINISHED_PERCENTAGE;
+        let updated_progress = match previously_finished_at {
+            Some(finished_at) => updated_progress.with_finished_at(&finished_at),
+            None if is_finished => updated_progress.with_finished_at(&now),
+            None => updated_progress,
+        };
+        let updated_progress = context.progress_repo().save(&updated_progress).await?;
+
+        if is_finished && item.label() != READ_LABEL {
+            let read_item = LinkItemBuilder::from(item).label(READ_LABEL).build();
+            context
+                .links_service()
+                .update(context, query, &read_item, None)
+                .await?;
+        }
+
+        Ok(updated_progress)
+    }
+
+    async fn stats(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        weeks: u32,
+        now: &DateTime<Utc>,
+    ) -> Result<ReadingStats> {
+        if weeks == 0 || weeks > MAX_STATS_WEEKS {
+            return Err(AppError::Validation(format!(
+                "stats() weeks must be from 1 to {MAX_STATS_WEEKS}, not {weeks}"
+            )));
+        }
+
+        let finished: Vec<ReadingProgress> = context
+            .progress_repo()
+            .find(query.user())
+            .await?
+            .into_iter()
+            .filter(|progress| progress.finished_at().is_some())
+            .collect();
+
+        // weeks start on Monday, the current one being the last of the window
+        let today = now.date_naive();
+        let this_week = today - Days::new(u64::from(today.weekday().num_days_from_monday()));
+        let first_week = this_week - Days::new(7 * u64::from(weeks - 1));
+        let mut counts = vec![0; weeks as usize];
+        for finished_at in finished.iter().filter_map(ReadingProgress::finished_at) {
+            let days = (finished_at.date_naive() - first_week).num_days();
+            if let Some(count) = usize::try_from(days / 7)
+                .ok()
+                .filter(|_| days >= 0)
+                .and_then(|week| counts.get_mut(week))
+            {
+                *count += 1;
+            }
+        }
+        let read_per_week: Vec<WeeklyReads> = counts
+            .into_iter()
+            .zip(0..)
+            .map(|(count, week)| WeeklyReads::new(first_week + Days::new(7 * week), count))
+            .collect();
+
+        let total_time: u64 = finished
+            .iter()
+            .map(|progress| u64::from(progress.time_spent()))
+            .sum();
+        let average_reading_time = u64::try_from(finished.len())
+            .ok()
+            .and_then(|count| total_time.checked_div(count))
+            .and_then(|average| u32::try_from(average).ok())
+            .unwrap_or_default();
+
+        Ok(ReadingStats::new(&read_per_week, average_reading_time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{NaiveDate, TimeZone};
+    use rstest::rstest;
+
+    use crate::{
+        app::ContextBuilder,
+        repository::{MockLinks as MockLinksRepo, MockProgress as MockProgressRepo},
+        service::MockLinks as MockLinksService,
+        types::{LinkItem, LinkQueryBuilder, VersionedLink},
+    };
+
+    use super::*;
+
+    fn item(label: &str) -> LinkItem {
+        LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .label(label)
+            .build()
+    }
+
+    async fn update(
+        mock_links_service: MockLinksService,
+        mock_progress_repo: MockProgressRepo,
+        item: LinkItem,
+        progress: &ReadingProgress,
+    ) -> Result<ReadingProgress> {
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .withf(|query| query.id() == "1")
+            .times(1)
+            .returning(move |_| Ok(item.clone()));
+
+        let progress_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .links_service(Arc::new(mock_links_service))
+            .links_repo(Arc::new(mock_links_repo))
+            .progress_repo(Arc::new(mock_progress_repo))
+            .build();
+        progress_service
+            .update(
+                &context,
+                &LinkQueryBuilder::new("1", "user").build(),
+                progress,
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_get_progress_not_set() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let item = item("");
+        let expected_progress = ReadingProgress::new("1", "user", 0, 0, 0, item.updated_at());
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_get()
+            .times(1)
+            .returning(move |_, _| Ok(item.clone()));
+
+        let mut mock_progress_repo = MockProgressRepo::new();
+        mock_progress_repo
+            .expect_get()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(|id| Err(AppError::ProgressNotFound(id.to_owned())));
+
+        let progress_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .links_service(Arc::new(mock_links_service))
+            .progress_repo(Arc::new(mock_progress_repo))
+            .build();
+        let response = progress_service.get(&context, &request_query).await;
+
+        assert_eq!(response, Ok(expected_progress));
+    }
+
+    #[tokio::test]
+    async fn test_update_progress_adds_up_time_spent() {
+        let previous = ReadingProgress::new("1", "user", 20, 500, 60, &Utc::now());
+
+        let mut mock_progress_repo = MockProgressRepo::new();
+        mock_progress_repo
+            .expect_get()
+            .times(1)
+            .returning(move |_| Ok(previous.clone()));
+        mock_progress_repo
+            .expect_save()
+            .withf(|progress| {
+                progress.id() == "1"
+                    && progress.owner() == "user"
+                    && progress.percentage() == 50
+                    && progress.position() == 1500
+                    && progress.time_spent() == 90
+                    && progress.finished_at().is_none()
+            })
+            .times(1)
+            .returning(|progress| Ok(progress.clone()));
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service.expect_update().times(0);
+
+        let response = update(
+            mock_links_service,
+            mock_progress_repo,
+            item(""),
+            &ReadingProgress::new("", "", 50, 1500, 30, &Utc::now()),
+        )
+        .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[rstest]
+    #[case("", true)]
+    #[case(READ_LABEL, false)]
+    #[tokio::test]
+    async fn test_update_progress_finished(#[case] label: &str, #[case] is_marked_read: bool) {
+        let mut mock_progress_repo = MockProgressRepo::new();
+        mock_progress_repo
+            .expect_get()
+            .times(1)
+            .returning(|id| Err(AppError::ProgressNotFound(id.to_owned())));
+        mock_progress_repo
+            .expect_save()
+            .withf(|progress| progress.percentage() == 100 && progress.finished_at().is_some())
+            .times(1)
+            .returning(|progress| Ok(progress.clone()));
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_update()
+            .withf(|_, query, item, if_match| {
+                query.id() == "1" && item.label() == READ_LABEL && if_match.is_none()
+            })
+            .times(usize::from(is_marked_read))
+            .returning(|_, _, item, _| Ok(VersionedLink::new(item, 2)));
+
+        let response = update(
+            mock_links_service,
+            mock_progress_repo,
+            item(label),
+            &ReadingProgress::new("", "", 100, 3000, 30, &Utc::now()),
+        )
+        .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_update_progress_finished_again() {
+        let finished_at = Utc::now() - chrono::Duration::days(3);
+        let previous = ReadingProgress::new("1", "user", 100, 3000, 60, &finished_at)
+            .with_finished_at(&finished_at);
+
+        let mut mock_progress_repo = MockProgressRepo::new();
+        mock_progress_repo
+            .expect_get()
+            .times(1)
+            .returning(move |_| Ok(previous.clone()));
+        mock_progress_repo
+            .expect_save()
+            .withf(move |progress| progress.finished_at() == Some(&finished_at))
+            .times(1)
+            .returning(|progress| Ok(progress.clone()));
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service.expect_update().times(0);
+
+        let response = update(
+            mock_links_service,
+            mock_progress_repo,
+            item(""),
+            &ReadingProgress::new("", "", 100, 3000, 10, &Utc::now()),
+        )
+        .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_progress_stats() {
+        let request_query = LinkQueryBuilder::default().user("user").build();
+        // a Wednesday
+        let now = Utc.with_ymd_and_hms(2024, 5, 15, 12, 0, 0).unwrap();
+        let finished = |id: &str, date: DateTime<Utc>, time_spent: u32| {
+            ReadingProgress::new(id, "user", 100, 0, time_spent, &date).with_finished_at(&date)
+        };
+        let progress = vec![
+            finished(
+                "1",
+                Utc.with_ymd_and_hms(2024, 5, 13, 8, 0, 0).unwrap(),
+                300,
+            ),
+            finished(
+                "2",
+                Utc.with_ymd_and_hms(2024, 5, 12, 8, 0, 0).unwrap(),
+                100,
+            ),
+            finished("3", Utc.with_ymd_and_hms(2024, 5, 6, 8, 0, 0).unwrap(), 200),
+            finished("4", Utc.with_ymd_and_hms(2024, 1, 1, 8, 0, 0).unwrap(), 600),
+            ReadingProgress::new("5", "user", 50, 0, 1000, &now),
+        ];
+
+        let mut mock_progress_repo = MockProgressRepo::new();
+        mock_progress_repo
+            .expect_find()
+            .withf(|owner| owner == "user")
+            .times(1)
+            .returning(move |_| Ok(progress.clone()));
+
+        let progress_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .progress_repo(Arc::new(mock_progress_repo))
+            .build();
+        let response = progress_service
+            .stats(&context, &request_query, 3, &now)
+            .await;
+
+        let day = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+        assert_eq!(
+            response,
+            Ok(ReadingStats::new(
+                &[
+                    WeeklyReads::new(day(4, 29), 0),
+                    WeeklyReads::new(day(5, 6), 2),
+                    WeeklyReads::new(day(5, 13), 1),
+                ],
+                300
+            ))
+        );
+    }
+
+    #[rstest]
+    #[case(0)]
+    #[case(MAX_STATS_WEEKS + 1)]
+    #[tokio::test]
+    async fn test_progress_stats_invalid_weeks(#[case] weeks: u32) {
+        let mut mock_progress_repo = MockProgressRepo::new();
+        mock_progress_repo.expect_find().times(0);
+
+        let progress_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .progress_repo(Arc::new(mock_progress_repo))
+            .build();
+        let response = progress_service
+            .stats(
+                &context,
+                &LinkQueryBuilder::default().user("user").build(),
+                weeks,
+                &Utc::now(),
+            )
+            .await;
+
+        assert!(matches!(response, Err(AppError::Validation(_))));
+    }
+}
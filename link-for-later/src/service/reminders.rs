@@ -0,0 +1,613 @@
+use std::time::Duration;
+
+use axum::async_trait;
+use chrono::{DateTime, Timelike, Utc};
+use tokio::time::Instant;
+
+use crate::{
+    repository, service,
+    service::links::READ_LABEL,
+    service::Reminders as RemindersService,
+    types::{AppContext, AppError, LinkItem, LinkQuery, LinkQueryBuilder, Reminder, Result},
+};
+
+pub const REMINDER_LINK: &str = "link";
+/// Goes out every day with the unread links of its owner.
+pub const REMINDER_DIGEST: &str = "digest";
+
+const DIGEST_SUBJECT: &str = "Your unread links";
+
+/// The first time at `hour` o'clock, in UTC, that comes after `now`.
+pub fn next_digest_at(hour: u32, now: &DateTime<Utc>) -> DateTime<Utc> {
+    let today = now
+        .with_hour(hour)
+        .and_then(|time| time.with_minute(0))
+        .and_then(|time| time.with_second(0))
+        .and_then(|time| time.with_nanosecond(0))
+        .unwrap_or(*now);
+    if today > *now {
+        today
+    } else {
+        today + chrono::Duration::days(1)
+    }
+}
+
+async fn authorize(
+    reminders_repo: &repository::DynReminders,
+    query: &LinkQuery,
+) -> Result<Reminder> {
+    let reminder = reminders_repo.get(query.id()).await?;
+    if query.user() != reminder.owner() && !query.is_from_admin() {
+        return Err(AppError::Authorization(String::from(
+            "User is not authorized to access resource",
+        )));
+    }
+    Ok(reminder)
+}
+
+#[derive(Default)]
+pub struct ServiceProvider {}
+
+#[async_trait]
+impl RemindersService for ServiceProvider {
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Reminder>> {
+        let owner = if query.is_from_admin() {
+            ""
+        } else {
+            query.user()
+        };
+        context.reminders_repo().find(owner).await
+    }
+
+    async fn create(&self, context: &AppContext, reminder: &Reminder) -> Result<Reminder> {
+        let now = Utc::now();
+        match reminder.kind() {
+            REMINDER_LINK => {
+                if *reminder.remind_at() <= now {
+                    return Err(AppError::Validation(String::from(
+                        "create() reminder would already be due",
+                    )));
+                }
+                // notifiers reach the owner of the link, so nobody else can set one
+                let get_query = LinkQueryBuilder::default().id(reminder.link_id()).build();
+                if context.links_repo().get(&get_query).await?.owner() != reminder.owner() {
+                    return Err(AppError::Authorization(String::from(
+                        "User is not authorized to access resource",
+                    )));
+                }
+            }
+            REMINDER_DIGEST => {
+                for digest in context.reminders_repo().find(reminder.owner()).await? {
+                    if digest.kind() == REMINDER_DIGEST {
+                        context.reminders_repo().delete(digest.id()).await?;
+                    }
+                }
+            }
+            kind => {
+                return Err(AppError::Validation(format!(
+                    "create() unknown kind {kind}"
+                )));
+            }
+        }
+
+        let reminder = Reminder::new(
+            reminder.owner(),
+            reminder.kind(),
+            reminder.link_id(),
+            reminder.remind_at(),
+            &now,
+        );
+        context.reminders_repo().create(&reminder).await
+    }
+
+    async fn snooze(
+        &self,
+        context: &AppContext,
+        query: &LinkQuery,
+        until: &DateTime<Utc>,
+    ) -> Result<Reminder> {
+        let reminder = authorize(context.reminders_repo(), query).await?;
+        if reminder.kind() == REMINDER_DIGEST {
+            return Err(AppError::Validation(String::from(
+                "snooze() the digest goes out daily, dismiss it instead",
+            )));
+        }
+        if *until <= Utc::now() {
+            return Err(AppError::Validation(String::from(
+                "snooze() reminder would already be due",
+            )));
+        }
+        context
+            .reminders_repo()
+            .update(&reminder.with_remind_at(until))
+            .await
+    }
+
+    async fn dismiss(&self, context: &AppContext, query: &LinkQuery) -> Result<()> {
+        let reminder = authorize(context.reminders_repo(), query).await?;
+        context.reminders_repo().delete(reminder.id()).await
+    }
+
+    async fn fire(
+        &self,
+        context: &AppContext,
+        notifier: Box<service::DynNotifier>,
+        now: &DateTime<Utc>,
+    ) -> Result<()> {
+        for reminder in context.reminders_repo().find_due(now).await? {
+            // another instance may have picked the same reminder already
+            match context.reminders_repo().claim(&reminder, now).await {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => {
+                    tracing::error!("Unable to claim reminder {}: {}", reminder.id(), e);
+                    continue;
+                }
+            }
+
+            let result = if reminder.kind() == REMINDER_DIGEST {
+                send_digest(
+                    &notifier,
+                    context.links_repo(),
+                    context.reminders_repo(),
+                    &reminder,
+                    now,
+                )
+                .await
+            } else {
+                send_reminder(
+                    &notifier,
+                    context.links_repo(),
+                    context.reminders_repo(),
+                    &reminder,
+                )
+                .await
+            };
+            if let Err(e) = result {
+                tracing::error!("Unable to send reminder {}: {}", reminder.id(), e);
+                // a failed reminder is made due again, so it is tried again on the next run
+                if let Err(e) = context.reminders_repo().update(&reminder).await {
+                    tracing::error!("Unable to release reminder {}: {}", reminder.id(), e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn send_reminder(
+    notifier: &service::DynNotifier,
+    links_repo: &repository::DynLinks,
+    reminders_repo: &repository::DynReminders,
+    reminder: &Reminder,
+) -> Result<()> {
+    let get_query = LinkQueryBuilder::default().id(reminder.link_id()).build();
+    let item = match links_repo.get(&get_query).await {
+        Ok(item) => item,
+        // nothing left to read once the link is gone
+        Err(AppError::LinkNotFound(_)) => return reminders_repo.delete(reminder.id()).await,
+        Err(e) => return Err(e),
+    };
+
+    let title = if item.title().is_empty() {
+        item.url()
+    } else {
+        item.title()
+    };
+    notifier
+        .notify(
+            reminder.owner(),
+            &format!("Time to read {title}"),
+            std::slice::from_ref(&item),
+        )
+        .await
+}
+
+async fn send_digest(
+    notifier: &service::DynNotifier,
+    links_repo: &repository::DynLinks,
+    reminders_repo: &repository::DynReminders,
+    reminder: &Reminder,
+    now: &DateTime<Utc>,
+) -> Result<()> {
+    let find_query = LinkQueryBuilder::default().user(reminder.owner()).build();
+    let unread: Vec<LinkItem> = links_repo
+        .find(&find_query)
+        .await?
+        .into_iter()
+        .filter(|item| item.owner() == reminder.owner() && item.label() != READ_LABEL)
+        .collect();
+    if !unread.is_empty() {
+        notifier
+            .notify(reminder.owner(), DIGEST_SUBJECT, &unread)
+            .await?;
+    }
+
+    // days missed while the service was down are skipped, not sent all at once
+    let days = (*now - *reminder.remind_at()).num_days() + 1;
+    let next = *reminder.remind_at() + chrono::Duration::days(days);
+    reminders_repo
+        .update(&reminder.clone().with_remind_at(&next))
+        .await
+        .map(|_| ())
+}
+
+/// Sends out due reminders in the background every `interval`.
+pub fn schedule(context: AppContext, notifier: service::DynNotifier, interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval_at(Instant::now() + interval, interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = context
+                .reminders_service()
+                .fire(&context, Box::new(notifier.clone()), &Utc::now())
+                .await
+            {
+                tracing::error!("Unable to send reminders: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Duration;
+    use rstest::rstest;
+
+    use crate::{
+        app::ContextBuilder,
+        repository::{MockLinks as MockLinksRepo, MockReminders as MockRemindersRepo},
+        service::MockNotifier,
+        types::LinkItemBuilder,
+    };
+
+    use super::*;
+
+    fn link(id: &str, owner: &str, label: &str) -> LinkItem {
+        LinkItemBuilder::new("http://link")
+            .id(id)
+            .owner(owner)
+            .title("title")
+            .label(label)
+            .build()
+    }
+
+    #[rstest]
+    #[case("user", true)]
+    #[case("other-user", false)]
+    #[tokio::test]
+    async fn test_create_reminder(#[case] owner: &str, #[case] is_authorized: bool) {
+        let remind_at = Utc::now() + Duration::hours(1);
+        let request_reminder = Reminder::new("user", REMINDER_LINK, "1", &remind_at, &Utc::now());
+        let link_owner = owner.to_owned();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .withf(|query| query.id() == "1")
+            .times(1)
+            .returning(move |_| Ok(link("1", &link_owner, "")));
+
+        let mut mock_reminders_repo = MockRemindersRepo::new();
+        mock_reminders_repo
+            .expect_create()
+            .withf(move |reminder| reminder.owner() == "user" && *reminder.remind_at() == remind_at)
+            .times(usize::from(is_authorized))
+            .returning(|reminder| Ok(reminder.clone().with_id("1")));
+
+        let reminders_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .reminders_repo(Arc::new(mock_reminders_repo))
+            .build();
+        let response = reminders_service.create(&context, &request_reminder).await;
+
+        assert_eq!(response.is_ok(), is_authorized);
+    }
+
+    #[rstest]
+    #[case("bookmark")]
+    #[case(REMINDER_LINK)]
+    #[tokio::test]
+    async fn test_create_reminder_invalid(#[case] kind: &str) {
+        let remind_at = Utc::now() - Duration::hours(1);
+        let request_reminder = Reminder::new("user", kind, "1", &remind_at, &Utc::now());
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo.expect_get().times(0);
+        let mut mock_reminders_repo = MockRemindersRepo::new();
+        mock_reminders_repo.expect_create().times(0);
+
+        let reminders_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .reminders_repo(Arc::new(mock_reminders_repo))
+            .build();
+        let response = reminders_service.create(&context, &request_reminder).await;
+
+        assert!(matches!(response, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_digest_replaces_digest() {
+        let now = Utc::now();
+        let request_reminder = Reminder::new("user", REMINDER_DIGEST, "", &now, &now);
+
+        let mut mock_reminders_repo = MockRemindersRepo::new();
+        mock_reminders_repo
+            .expect_find()
+            .withf(|owner| owner == "user")
+            .times(1)
+            .returning(move |_| {
+                Ok(vec![
+                    Reminder::new("user", REMINDER_LINK, "1", &now, &now).with_id("1"),
+                    Reminder::new("user", REMINDER_DIGEST, "", &now, &now).with_id("2"),
+                ])
+            });
+        mock_reminders_repo
+            .expect_delete()
+            .withf(|id| id == "2")
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_reminders_repo
+            .expect_create()
+            .times(1)
+            .returning(|reminder| Ok(reminder.clone().with_id("3")));
+
+        let reminders_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .reminders_repo(Arc::new(mock_reminders_repo))
+            .build();
+        let response = reminders_service.create(&context, &request_reminder).await;
+
+        assert!(response.is_ok());
+    }
+
+    #[rstest]
+    #[case("user", REMINDER_LINK, 1, true)]
+    #[case("unauthorized-user", REMINDER_LINK, -1, false)]
+    #[case("user", REMINDER_DIGEST, 1, false)]
+    #[case("user", REMINDER_LINK, -1, false)]
+    #[tokio::test]
+    async fn test_snooze_reminder(
+        #[case] user: &str,
+        #[case] kind: &'static str,
+        #[case] hours: i64,
+        #[case] is_snoozed: bool,
+    ) {
+        let request_query = LinkQueryBuilder::new("1", user).build();
+        let until = Utc::now() + Duration::hours(hours);
+
+        let mut mock_reminders_repo = MockRemindersRepo::new();
+        mock_reminders_repo
+            .expect_get()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(move |_| {
+                let now = Utc::now();
+                Ok(Reminder::new("user", kind, "1", &now, &now)
+                    .with_id("1")
+                    .with_notified_at(&now))
+            });
+        mock_reminders_repo
+            .expect_update()
+            .withf(move |reminder| {
+                *reminder.remind_at() == until && reminder.notified_at().is_none()
+            })
+            .times(usize::from(is_snoozed))
+            .returning(|reminder| Ok(reminder.clone()));
+
+        let reminders_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .reminders_repo(Arc::new(mock_reminders_repo))
+            .build();
+        let response = reminders_service
+            .snooze(&context, &request_query, &until)
+            .await;
+
+        assert_eq!(response.is_ok(), is_snoozed);
+    }
+
+    #[tokio::test]
+    async fn test_dismiss_reminder_unauthorized() {
+        let request_query = LinkQueryBuilder::new("1", "unauthorized-user").build();
+
+        let mut mock_reminders_repo = MockRemindersRepo::new();
+        mock_reminders_repo.expect_get().times(1).returning(|_| {
+            Ok(Reminder::new("user", REMINDER_LINK, "1", &Utc::now(), &Utc::now()).with_id("1"))
+        });
+        mock_reminders_repo.expect_delete().times(0);
+
+        let reminders_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .reminders_repo(Arc::new(mock_reminders_repo))
+            .build();
+        let response = reminders_service.dismiss(&context, &request_query).await;
+
+        assert!(matches!(response, Err(AppError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_fire_reminders() {
+        let now = Utc::now();
+        let digest_at = now - Duration::days(2) - Duration::hours(1);
+
+        let mut mock_reminders_repo = MockRemindersRepo::new();
+        mock_reminders_repo
+            .expect_find_due()
+            .times(1)
+            .returning(move |_| {
+                Ok(vec![
+                    Reminder::new("user", REMINDER_LINK, "1", &now, &now).with_id("1"),
+                    Reminder::new("user", REMINDER_LINK, "2", &now, &now).with_id("2"),
+                    Reminder::new("user", REMINDER_DIGEST, "", &digest_at, &now).with_id("3"),
+                ])
+            });
+        mock_reminders_repo
+            .expect_claim()
+            .withf(move |_, notified_at| notified_at == &now)
+            .times(3)
+            .returning(|_, _| Ok(true));
+        mock_reminders_repo
+            .expect_delete()
+            .withf(|id| id == "2")
+            .times(1)
+            .returning(|_| Ok(()));
+        mock_reminders_repo
+            .expect_update()
+            .withf(move |reminder| {
+                reminder.id() == "3" && *reminder.remind_at() == digest_at + Duration::days(3)
+            })
+            .times(1)
+            .returning(|reminder| Ok(reminder.clone()));
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .times(2)
+            .returning(|query| match query.id() {
+                "1" => Ok(link("1", "user", "")),
+                id => Err(AppError::LinkNotFound(id.into())),
+            });
+        mock_links_repo
+            .expect_find()
+            .withf(|query| query.user() == "user")
+            .times(1)
+            .returning(|_| {
+                Ok(vec![
+                    link("1", "user", ""),
+                    link("3", "user", READ_LABEL),
+                    link("4", "user", "later"),
+                ])
+            });
+
+        let mut mock_notifier = MockNotifier::new();
+        mock_notifier
+            .expect_notify()
+            .withf(|owner, subject, links| {
+                owner == "user" && subject == "Time to read title" && links.len() == 1
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+        mock_notifier
+            .expect_notify()
+            .withf(|owner, subject, links| {
+                owner == "user"
+                    && subject == DIGEST_SUBJECT
+                    && links.iter().map(LinkItem::id).eq(["1", "4"])
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let reminders_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .reminders_repo(Arc::new(mock_reminders_repo))
+            .build();
+        let response = reminders_service
+            .fire(&context, Box::new(Arc::new(mock_notifier)), &now)
+            .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fire_reminder_notifier_failure() {
+        let now = Utc::now();
+
+        let mut mock_reminders_repo = MockRemindersRepo::new();
+        mock_reminders_repo
+            .expect_find_due()
+            .times(1)
+            .returning(move |_| {
+                Ok(vec![
+                    Reminder::new("user", REMINDER_LINK, "1", &now, &now).with_id("1")
+                ])
+            });
+        mock_reminders_repo
+            .expect_claim()
+            .times(1)
+            .returning(|_, _| Ok(true));
+        mock_reminders_repo
+            .expect_update()
+            .withf(|reminder| reminder.id() == "1" && reminder.notified_at().is_none())
+            .times(1)
+            .returning(|reminder| Ok(reminder.clone()));
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(link("1", "user", "")));
+
+        let mut mock_notifier = MockNotifier::new();
+        mock_notifier
+            .expect_notify()
+            .times(1)
+            .returning(|_, _, _| Err(AppError::Server("notify() failed".into())));
+
+        let reminders_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .reminders_repo(Arc::new(mock_reminders_repo))
+            .build();
+        let response = reminders_service
+            .fire(&context, Box::new(Arc::new(mock_notifier)), &now)
+            .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_fire_reminder_claimed_elsewhere() {
+        let now = Utc::now();
+
+        let mut mock_reminders_repo = MockRemindersRepo::new();
+        mock_reminders_repo
+            .expect_find_due()
+            .times(1)
+            .returning(move |_| {
+                Ok(vec![
+                    Reminder::new("user", REMINDER_LINK, "1", &now, &now).with_id("1")
+                ])
+            });
+        mock_reminders_repo
+            .expect_claim()
+            .times(1)
+            .returning(|_, _| Ok(false));
+        mock_reminders_repo.expect_update().times(0);
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo.expect_get().times(0);
+
+        let mut mock_notifier = MockNotifier::new();
+        mock_notifier.expect_notify().times(0);
+
+        let reminders_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .reminders_repo(Arc::new(mock_reminders_repo))
+            .build();
+        let response = reminders_service
+            .fire(&context, Box::new(Arc::new(mock_notifier)), &now)
+            .await;
+
+        assert!(response.is_ok());
+    }
+
+    #[rstest]
+    #[case("2024-01-02T03:04:05Z", 8, "2024-01-02T08:00:00Z")]
+    #[case("2024-01-02T08:00:00Z", 8, "2024-01-03T08:00:00Z")]
+    #[case("2024-01-02T23:30:00Z", 0, "2024-01-03T00:00:00Z")]
+    fn test_next_digest_at(#[case] now: &str, #[case] hour: u32, #[case] expected: &str) {
+        let now = DateTime::parse_from_rfc3339(now)
+            .unwrap()
+            .with_timezone(&Utc);
+        let expected = DateTime::parse_from_rfc3339(expected)
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(next_digest_at(hour, &now), expected);
+    }
+}
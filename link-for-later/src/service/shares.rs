@@ -0,0 +1,586 @@
+use axum::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    repository,
+    service::links::READ_LABEL,
+    service::Shares as SharesService,
+    types::{
+        AppContext, AppError, Collection, Feed, LinkItem, LinkQuery, LinkQueryBuilder,
+        PublicCollection, PublicLink, Result, Share, SharedView,
+    },
+};
+
+pub const SHARE_LINK: &str = "link";
+pub const SHARE_COLLECTION: &str = "collection";
+/// Shares the unread links of its owner, whose id is also its target.
+pub const SHARE_FEED: &str = "feed";
+
+const FEED_TITLE: &str = "Unread links";
+
+/// Renders a feed as an Atom document. Entries only carry what a public view
+/// of a link shows, so neither the owner nor their labels end up in it.
+pub fn atom(feed: &Feed) -> String {
+    let entries: String = feed.entries().iter().map(atom_entry).collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+         <feed xmlns=\"http://www.w3.org/2005/Atom\">\n\
+         <id>urn:link-for-later:share:{}</id>\n\
+         <title>{}</title>\n\
+         <updated>{}</updated>\n\
+         <author><name>Link for Later</name></author>\n\
+         {entries}\
+         </feed>\n",
+        escape(feed.id()),
+        escape(feed.title()),
+        timestamp(feed.updated()),
+    )
+}
+
+fn atom_entry(item: &LinkItem) -> String {
+    let title = if item.title().is_empty() {
+        item.url()
+    } else {
+        item.title()
+    };
+    let summary = if item.summary().is_empty() {
+        item.description()
+    } else {
+        item.summary()
+    };
+    format!(
+        "<entry>\n\
+         <id>urn:link-for-later:link:{}</id>\n\
+         <title>{}</title>\n\
+         <link href=\"{}\"/>\n\
+         <updated>{}</updated>\n\
+         <summary>{}</summary>\n\
+         </entry>\n",
+        escape(item.id()),
+        escape(title),
+        escape(item.url()),
+        timestamp(item.updated_at()),
+        escape(summary),
+    )
+}
+
+/// Strong entity tag of a rendered feed, so that feed readers polling it
+/// can ask for it only when something in it changed.
+pub fn feed_etag(atom: &str) -> String {
+    let digest = Sha256::digest(atom.as_bytes());
+    format!("\"{}\"", hex::encode(&digest[..16]))
+}
+
+fn timestamp(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Looks up a share by its token, treating an expired one as gone.
+async fn shared(shares_repo: &repository::DynShares, token: &str) -> Result<Share> {
+    let share = shares_repo.get_by_token(token).await?;
+    if share.is_expired(&Utc::now()) {
+        return Err(AppError::ShareNotFound(share.id().to_owned()));
+    }
+    Ok(share)
+}
+
+async fn shared_link(links_repo: &repository::DynLinks, share: &Share) -> Result<LinkItem> {
+    let get_query = LinkQueryBuilder::default().id(share.target()).build();
+    links_repo.get(&get_query).await
+}
+
+async fn collection_links(
+    links_repo: &repository::DynLinks,
+    collection: &Collection,
+) -> Result<Vec<LinkItem>> {
+    let mut items: Vec<LinkItem> = Vec::with_capacity(collection.links().len());
+    for link_id in collection.links() {
+        let get_query = LinkQueryBuilder::default().id(link_id).build();
+        match links_repo.get(&get_query).await {
+            // a collection only ever holds links of its owner, but
+            // nothing else of theirs may show up through the share
+            Ok(item) if item.owner() == collection.owner() => items.push(item),
+            Ok(_) | Err(AppError::LinkNotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(items)
+}
+
+async fn unread_links(links_repo: &repository::DynLinks, share: &Share) -> Result<Vec<LinkItem>> {
+    let find_query = LinkQueryBuilder::default().user(share.target()).build();
+    let mut items: Vec<LinkItem> = links_repo
+        .find(&find_query)
+        .await?
+        .into_iter()
+        .filter(|item| item.owner() == share.target() && item.label() != READ_LABEL)
+        .collect();
+    items.sort_by(|a, b| b.updated_at().cmp(a.updated_at()));
+    Ok(items)
+}
+
+#[derive(Default)]
+pub struct ServiceProvider {}
+
+#[async_trait]
+impl SharesService for ServiceProvider {
+    async fn search(&self, context: &AppContext, query: &LinkQuery) -> Result<Vec<Share>> {
+        let owner = if query.is_from_admin() {
+            ""
+        } else {
+            query.user()
+        };
+        context.shares_repo().find(owner).await
+    }
+
+    async fn create(&self, context: &AppContext, share: &Share) -> Result<Share> {
+        let now = Utc::now();
+        if share.is_expired(&now) {
+            return Err(AppError::Validation(String::from(
+                "create() share would already be expired",
+            )));
+        }
+
+        // only the owner can publish a link or a collection, not its members
+        let owner = match share.kind() {
+            SHARE_LINK => shared_link(context.links_repo(), share)
+                .await?
+                .owner()
+                .to_owned(),
+            SHARE_COLLECTION => context
+                .collections_repo()
+                .get(share.target())
+                .await?
+                .owner()
+                .to_owned(),
+            SHARE_FEED => share.target().to_owned(),
+            kind => {
+                return Err(AppError::Validation(format!(
+                    "create() unknown kind {kind}"
+                )));
+            }
+        };
+        if owner != share.owner() {
+            return Err(AppError::Authorization(String::from(
+                "User is not authorized to access resource",
+            )));
+        }
+
+        let share = Share::new(
+            share.owner(),
+            share.kind(),
+            share.target(),
+            share.expires_at(),
+            &now,
+        )
+        .with_token(&hex::encode(rand::thread_rng().gen::<[u8; 32]>()));
+        context.shares_repo().create(&share).await
+    }
+
+    async fn delete(&self, context: &AppContext, query: &LinkQuery) -> Result<()> {
+        let share = context.shares_repo().get(query.id()).await?;
+        if query.user() != share.owner() && !query.is_from_admin() {
+            return Err(AppError::Authorization(String::from(
+                "User is not authorized to access resource",
+            )));
+        }
+        context.shares_repo().delete(share.id()).await
+    }
+
+    async fn view(&self, context: &AppContext, token: &str) -> Result<SharedView> {
+        let share = shared(context.shares_repo(), token).await?;
+
+        match share.kind() {
+            SHARE_COLLECTION => {
+                let collection = context.collections_repo().get(share.target()).await?;
+                let items = collection_links(context.links_repo(), &collection).await?;
+                Ok(SharedView::Collection(PublicCollection::new(
+                    &collection,
+                    &items,
+                )))
+            }
+            SHARE_FEED => {
+                let items = unread_links(context.links_repo(), &share).await?;
+                Ok(SharedView::Links(
+                    items.iter().map(PublicLink::from).collect(),
+                ))
+            }
+            _ => {
+                let item = shared_link(context.links_repo(), &share).await?;
+                Ok(SharedView::Link(PublicLink::from(&item)))
+            }
+        }
+    }
+
+    async fn feed(&self, context: &AppContext, token: &str) -> Result<Feed> {
+        let share = shared(context.shares_repo(), token).await?;
+
+        let (title, items) = match share.kind() {
+            SHARE_COLLECTION => {
+                let collection = context.collections_repo().get(share.target()).await?;
+                let mut items = collection_links(context.links_repo(), &collection).await?;
+                items.sort_by(|a, b| b.updated_at().cmp(a.updated_at()));
+                (collection.name().to_owned(), items)
+            }
+            SHARE_FEED => (
+                FEED_TITLE.to_owned(),
+                unread_links(context.links_repo(), &share).await?,
+            ),
+            _ => {
+                let item = shared_link(context.links_repo(), &share).await?;
+                (item.title().to_owned(), vec![item])
+            }
+        };
+
+        // an empty feed was last updated when it was published
+        let updated = items
+            .iter()
+            .map(LinkItem::updated_at)
+            .max()
+            .unwrap_or_else(|| share.created_at());
+        Ok(Feed::new(share.id(), &title, updated, &items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Duration;
+    use rstest::rstest;
+
+    use crate::{
+        app::ContextBuilder,
+        repository::{
+            MockCollections as MockCollectionsRepo, MockLinks as MockLinksRepo,
+            MockShares as MockSharesRepo,
+        },
+        types::{Collection, LinkItemBuilder},
+    };
+
+    use super::*;
+
+    fn link(id: &str, owner: &str) -> LinkItem {
+        LinkItemBuilder::new("http://link")
+            .id(id)
+            .owner(owner)
+            .title("title")
+            .label("private")
+            .build()
+    }
+
+    #[rstest]
+    #[case("user", true)]
+    #[case("other-user", false)]
+    #[tokio::test]
+    async fn test_create_share(#[case] owner: &str, #[case] is_authorized: bool) {
+        let request_share = Share::new("user", SHARE_LINK, "1", None, &Utc::now());
+        let link_owner = owner.to_owned();
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .withf(|query| query.id() == "1")
+            .times(1)
+            .returning(move |_| Ok(link("1", &link_owner)));
+
+        let mut mock_shares_repo = MockSharesRepo::new();
+        mock_shares_repo
+            .expect_create()
+            .withf(|share| share.owner() == "user" && share.token().len() == 64)
+            .times(usize::from(is_authorized))
+            .returning(|share| Ok(share.clone().with_id("1")));
+
+        let shares_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .shares_repo(Arc::new(mock_shares_repo))
+            .build();
+        let response = shares_service.create(&context, &request_share).await;
+
+        assert_eq!(response.is_ok(), is_authorized);
+    }
+
+    #[rstest]
+    #[case("bookmark", false)]
+    #[case(SHARE_LINK, true)]
+    #[tokio::test]
+    async fn test_create_share_invalid(#[case] kind: &str, #[case] is_expired: bool) {
+        let expires_at = Utc::now() - Duration::days(1);
+        let request_share = Share::new(
+            "user",
+            kind,
+            "1",
+            is_expired.then_some(&expires_at),
+            &Utc::now(),
+        );
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo.expect_get().times(0);
+        let mut mock_shares_repo = MockSharesRepo::new();
+        mock_shares_repo.expect_create().times(0);
+
+        let shares_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .shares_repo(Arc::new(mock_shares_repo))
+            .build();
+        let response = shares_service.create(&context, &request_share).await;
+
+        assert!(matches!(response, Err(AppError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_delete_share_unauthorized() {
+        let request_query = LinkQueryBuilder::new("1", "unauthorized-user").build();
+
+        let mut mock_shares_repo = MockSharesRepo::new();
+        mock_shares_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(Share::new("user", SHARE_LINK, "1", None, &Utc::now()).with_id("1")));
+        mock_shares_repo.expect_delete().times(0);
+
+        let shares_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .shares_repo(Arc::new(mock_shares_repo))
+            .build();
+        let response = shares_service.delete(&context, &request_query).await;
+
+        assert!(matches!(response, Err(AppError::Authorization(_))));
+    }
+
+    #[tokio::test]
+    async fn test_view_shared_link() {
+        let mut mock_shares_repo = MockSharesRepo::new();
+        mock_shares_repo
+            .expect_get_by_token()
+            .withf(|token| token == "token")
+            .times(1)
+            .returning(|_| Ok(Share::new("user", SHARE_LINK, "1", None, &Utc::now())));
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .withf(|query| query.id() == "1")
+            .times(1)
+            .returning(|_| Ok(link("1", "user")));
+
+        let shares_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .shares_repo(Arc::new(mock_shares_repo))
+            .build();
+        let response = shares_service.view(&context, "token").await;
+
+        assert_eq!(
+            response,
+            Ok(SharedView::Link(PublicLink::from(&link("1", "user"))))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_view_shared_collection() {
+        let mut mock_shares_repo = MockSharesRepo::new();
+        mock_shares_repo
+            .expect_get_by_token()
+            .times(1)
+            .returning(|_| Ok(Share::new("user", SHARE_COLLECTION, "1", None, &Utc::now())));
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(|_| {
+                Ok(Collection::new("user", "Reading", "", 0, &Utc::now())
+                    .with_id("1")
+                    .with_links(&["3".into(), "2".into(), "1".into()]))
+            });
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_get()
+            .times(3)
+            .returning(|query| match query.id() {
+                "2" => Err(AppError::LinkNotFound("2".into())),
+                "3" => Ok(link("3", "other-user")),
+                id => Ok(link(id, "user")),
+            });
+
+        let shares_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .collections_repo(Arc::new(mock_collections_repo))
+            .shares_repo(Arc::new(mock_shares_repo))
+            .build();
+        let response = shares_service.view(&context, "token").await.unwrap();
+
+        let SharedView::Collection(collection) = response else {
+            panic!("expected a collection");
+        };
+        assert_eq!(collection.links(), [PublicLink::from(&link("1", "user"))]);
+    }
+
+    #[tokio::test]
+    async fn test_view_expired_share() {
+        let mut mock_shares_repo = MockSharesRepo::new();
+        mock_shares_repo
+            .expect_get_by_token()
+            .times(1)
+            .returning(|_| {
+                let expires_at = Utc::now() - Duration::minutes(1);
+                Ok(
+                    Share::new("user", SHARE_LINK, "1", Some(&expires_at), &Utc::now())
+                        .with_id("1"),
+                )
+            });
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo.expect_get().times(0);
+
+        let shares_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .shares_repo(Arc::new(mock_shares_repo))
+            .build();
+        let response = shares_service.view(&context, "token").await;
+
+        assert_eq!(response, Err(AppError::ShareNotFound("1".into())));
+    }
+
+    #[rstest]
+    #[case("user", true)]
+    #[case("other-user", false)]
+    #[tokio::test]
+    async fn test_create_feed_share(#[case] target: &str, #[case] is_authorized: bool) {
+        let request_share = Share::new("user", SHARE_FEED, target, None, &Utc::now());
+
+        let mut mock_shares_repo = MockSharesRepo::new();
+        mock_shares_repo
+            .expect_create()
+            .times(usize::from(is_authorized))
+            .returning(|share| Ok(share.clone().with_id("1")));
+
+        let shares_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .shares_repo(Arc::new(mock_shares_repo))
+            .build();
+        let response = shares_service.create(&context, &request_share).await;
+
+        assert_eq!(response.is_ok(), is_authorized);
+    }
+
+    #[tokio::test]
+    async fn test_feed_of_unread_links() {
+        let published_at = Utc::now() - Duration::days(7);
+        let mut mock_shares_repo = MockSharesRepo::new();
+        mock_shares_repo
+            .expect_get_by_token()
+            .withf(|token| token == "token")
+            .times(1)
+            .returning(move |_| {
+                Ok(Share::new("user", SHARE_FEED, "user", None, &published_at).with_id("1"))
+            });
+
+        let now = Utc::now();
+        let older = LinkItemBuilder::from(link("1", "user"))
+            .updated_at(&(now - Duration::days(1)))
+            .build();
+        let newer = LinkItemBuilder::from(link("2", "user"))
+            .updated_at(&now)
+            .build();
+        let read = LinkItemBuilder::from(link("3", "user"))
+            .label(READ_LABEL)
+            .updated_at(&(now + Duration::days(1)))
+            .build();
+        let items = vec![older.clone(), read, newer.clone()];
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_find()
+            .withf(|query| query.user() == "user")
+            .times(1)
+            .returning(move |_| Ok(items.clone()));
+
+        let shares_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .shares_repo(Arc::new(mock_shares_repo))
+            .build();
+        let response = shares_service.feed(&context, "token").await;
+
+        assert_eq!(
+            response,
+            Ok(Feed::new("1", FEED_TITLE, &now, &[newer, older]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_feed_without_links() {
+        let published_at = Utc::now() - Duration::days(7);
+        let mut mock_shares_repo = MockSharesRepo::new();
+        mock_shares_repo
+            .expect_get_by_token()
+            .times(1)
+            .returning(move |_| {
+                Ok(Share::new("user", SHARE_COLLECTION, "1", None, &published_at).with_id("1"))
+            });
+
+        let mut mock_collections_repo = MockCollectionsRepo::new();
+        mock_collections_repo
+            .expect_get()
+            .times(1)
+            .returning(|_| Ok(Collection::new("user", "Reading", "", 0, &Utc::now()).with_id("1")));
+
+        let shares_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .collections_repo(Arc::new(mock_collections_repo))
+            .shares_repo(Arc::new(mock_shares_repo))
+            .build();
+        let response = shares_service.feed(&context, "token").await;
+
+        assert_eq!(response, Ok(Feed::new("1", "Reading", &published_at, &[])));
+    }
+
+    #[test]
+    fn test_atom() {
+        let updated_at = DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let item = LinkItemBuilder::new("http://link?a=1&b=2")
+            .id("1")
+            .owner("user@test.com")
+            .title("<Title>")
+            .updated_at(&updated_at)
+            .build();
+        let feed = Feed::new("1", "Tom & Jerry", &updated_at, &[item]);
+
+        let atom = atom(&feed);
+
+        assert!(atom.starts_with("<?xml"));
+        assert!(atom.contains("<title>Tom &amp; Jerry</title>"));
+        assert!(atom.contains("<title>&lt;Title&gt;</title>"));
+        assert!(atom.contains("<link href=\"http://link?a=1&amp;b=2\"/>"));
+        assert!(atom.contains("<updated>2024-01-02T03:04:05Z</updated>"));
+        assert!(!atom.contains("user@test.com"));
+        assert_eq!(feed_etag(&atom), feed_etag(&atom.clone()));
+    }
+}
@@ -0,0 +1,343 @@
+use ammonia::{Url, UrlRelative};
+use axum::async_trait;
+use chrono::Utc;
+
+use crate::{
+    outbound,
+    service::Snapshots as SnapshotsService,
+    types::{AppContext, LinkItem, LinkQuery, Result, Snapshot},
+};
+
+const MAX_SNAPSHOT_SIZE: usize = 5 * 1024 * 1024;
+
+const HTML_CONTENT_TYPE: &str = "text/html; charset=utf-8";
+const TEXT_CONTENT_TYPE: &str = "text/plain; charset=utf-8";
+
+pub struct ServiceProvider {
+    http_client: outbound::Client,
+}
+
+#[async_trait]
+impl SnapshotsService for ServiceProvider {
+    async fn get(&self, context: &AppContext, query: &LinkQuery) -> Result<Snapshot> {
+        let item = context.links_service().get(context, query).await?;
+        context.snapshots_repo().get(item.id()).await
+    }
+
+    async fn capture(&self, context: &AppContext, link_item: &LinkItem) -> Result<()> {
+        let (content_type, body) = match self.fetch(link_item.url()).await {
+            Ok(contents) => contents,
+            Err(e) => {
+                tracing::warn!("Unable to capture snapshot of {}: {}", link_item.url(), e);
+                return Ok(());
+            }
+        };
+
+        let snapshot = Snapshot::new(
+            link_item.id(),
+            link_item.url(),
+            content_type,
+            &body,
+            &Utc::now(),
+        );
+        context.snapshots_repo().save(&snapshot).await?;
+        Ok(())
+    }
+}
+
+impl ServiceProvider {
+    async fn fetch(&self, url: &str) -> std::result::Result<(&'static str, String), String> {
+        let response = self
+            .http_client
+            .get(url)
+            .await?
+            .error_for_status()
+            .map_err(|e| format!("error_for_status() {e:?}"))?;
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .unwrap_or("text/html")
+            .to_lowercase();
+
+        let body = self.http_client.bytes(response).await?;
+        let body = String::from_utf8_lossy(&body);
+
+        if content_type.contains("html") {
+            Ok((HTML_CONTENT_TYPE, clean(url, &body)))
+        } else if content_type.starts_with("text/plain") {
+            Ok((TEXT_CONTENT_TYPE, body.into_owned()))
+        } else {
+            Err(format!("unsupported content type {content_type}"))
+        }
+    }
+}
+
+impl Default for ServiceProvider {
+    fn default() -> Self {
+        Self {
+            http_client: outbound::Client::new(
+                outbound::Policy::default().max_response_size(MAX_SNAPSHOT_SIZE),
+            ),
+        }
+    }
+}
+
+/// Removes scripts, styles, event handlers and other active content from the
+/// page so that it can be served back safely, rewriting relative links
+/// against the original url so that they keep working offline.
+fn clean(url: &str, html: &str) -> String {
+    let mut builder = ammonia::Builder::default();
+    if let Ok(base) = Url::parse(url) {
+        builder.url_relative(UrlRelative::RewriteWithBase(base));
+    }
+    builder.clean(html).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::Utc;
+
+    use crate::{
+        app::ContextBuilder,
+        repository::MockSnapshots as MockSnapshotsRepo,
+        service::MockLinks as MockLinksService,
+        types::{AppError, LinkItemBuilder, LinkQueryBuilder},
+    };
+
+    use super::*;
+
+    fn test_service() -> ServiceProvider {
+        ServiceProvider {
+            http_client: outbound::Client::new(
+                outbound::Policy::default()
+                    .max_response_size(MAX_SNAPSHOT_SIZE)
+                    .allow_private_networks(),
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_snapshot() {
+        let request_query = LinkQueryBuilder::new("1", "user").build();
+        let get_query = request_query.clone();
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+        let snapshot = Snapshot::new("1", "http://link", "text/html", "<p>link</p>", &Utc::now());
+        let retrieved_snapshot = snapshot.clone();
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_get()
+            .withf(move |_, query| query == &get_query)
+            .times(1)
+            .returning(move |_, _| Ok(item.clone()));
+
+        let mut mock_snapshots_repo = MockSnapshotsRepo::new();
+        mock_snapshots_repo
+            .expect_get()
+            .withf(|id| id == "1")
+            .times(1)
+            .returning(move |_| Ok(retrieved_snapshot.clone()));
+
+        let snapshots_service = test_service();
+        let context = ContextBuilder::default()
+            .links_service(Arc::new(mock_links_service))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .build();
+        let response = snapshots_service.get(&context, &request_query).await;
+
+        assert_eq!(response, Ok(snapshot));
+    }
+
+    #[tokio::test]
+    async fn test_get_snapshot_unauthorized() {
+        let request_query = LinkQueryBuilder::new("1", "unauthorized-user").build();
+
+        let mut mock_links_service = MockLinksService::new();
+        mock_links_service
+            .expect_get()
+            .times(1)
+            .returning(|_, _| Err(AppError::Authorization("unauthorized".into())));
+
+        let mut mock_snapshots_repo = MockSnapshotsRepo::new();
+        mock_snapshots_repo.expect_get().times(0);
+
+        let snapshots_service = test_service();
+        let context = ContextBuilder::default()
+            .links_service(Arc::new(mock_links_service))
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .build();
+        let response = snapshots_service.get(&context, &request_query).await;
+
+        assert_eq!(
+            response,
+            Err(AppError::Authorization("unauthorized".into()))
+        );
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_capture_snapshot_private_address() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/article")
+            .expect(0)
+            .create_async()
+            .await;
+
+        let url = format!("{}/article", server.url());
+        let item = LinkItemBuilder::new(&url).id("1").owner("user").build();
+
+        let mut mock_snapshots_repo = MockSnapshotsRepo::new();
+        mock_snapshots_repo.expect_save().times(0);
+
+        let snapshots_service = ServiceProvider::default();
+        let context = ContextBuilder::default()
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .build();
+        let response = snapshots_service.capture(&context, &item).await;
+
+        mock.assert_async().await;
+        assert!(response.is_ok());
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_capture_snapshot() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/article")
+            .with_status(200)
+            .with_header("content-type", "text/html")
+            .with_body(
+                r#"<html><head><script>alert("x")</script></head>
+                <body><p onclick="steal()">Saved <a href="/next">page</a></p></body></html>"#,
+            )
+            .create_async()
+            .await;
+
+        let url = format!("{}/article", server.url());
+        let item = LinkItemBuilder::new(&url).id("1").owner("user").build();
+        let expected_link = format!(r#"href="{}/next""#, server.url());
+
+        let mut mock_snapshots_repo = MockSnapshotsRepo::new();
+        mock_snapshots_repo
+            .expect_save()
+            .withf(move |snapshot| {
+                snapshot.id() == "1"
+                    && snapshot.content_type() == HTML_CONTENT_TYPE
+                    && snapshot.content().contains("Saved")
+                    && snapshot.content().contains(&expected_link)
+                    && !snapshot.content().contains("script")
+                    && !snapshot.content().contains("onclick")
+            })
+            .times(1)
+            .returning(|snapshot| Ok(snapshot.clone()));
+
+        let snapshots_service = test_service();
+        let context = ContextBuilder::default()
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .build();
+        let response = snapshots_service.capture(&context, &item).await;
+
+        mock.assert_async().await;
+        assert!(response.is_ok());
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_capture_snapshot_text() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/notes.txt")
+            .with_status(200)
+            .with_header("content-type", "text/plain")
+            .with_body("plain <b>notes</b>")
+            .create_async()
+            .await;
+
+        let url = format!("{}/notes.txt", server.url());
+        let item = LinkItemBuilder::new(&url).id("1").owner("user").build();
+
+        let mut mock_snapshots_repo = MockSnapshotsRepo::new();
+        mock_snapshots_repo
+            .expect_save()
+            .withf(|snapshot| {
+                snapshot.content_type() == TEXT_CONTENT_TYPE
+                    && snapshot.content() == "plain <b>notes</b>"
+            })
+            .times(1)
+            .returning(|snapshot| Ok(snapshot.clone()));
+
+        let snapshots_service = test_service();
+        let context = ContextBuilder::default()
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .build();
+        let response = snapshots_service.capture(&context, &item).await;
+
+        mock.assert_async().await;
+        assert!(response.is_ok());
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_capture_snapshot_unavailable() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let url = format!("{}/missing", server.url());
+        let item = LinkItemBuilder::new(&url).id("1").owner("user").build();
+
+        let mut mock_snapshots_repo = MockSnapshotsRepo::new();
+        mock_snapshots_repo.expect_save().times(0);
+
+        let snapshots_service = test_service();
+        let context = ContextBuilder::default()
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .build();
+        let response = snapshots_service.capture(&context, &item).await;
+
+        mock.assert_async().await;
+        assert!(response.is_ok());
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_capture_snapshot_repo_error() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("GET", "/article")
+            .with_status(200)
+            .with_header("content-type", "text/html")
+            .with_body("<p>article</p>")
+            .create_async()
+            .await;
+
+        let url = format!("{}/article", server.url());
+        let item = LinkItemBuilder::new(&url).id("1").owner("user").build();
+
+        let mut mock_snapshots_repo = MockSnapshotsRepo::new();
+        mock_snapshots_repo
+            .expect_save()
+            .times(1)
+            .returning(|_| Err(AppError::Test));
+
+        let snapshots_service = test_service();
+        let context = ContextBuilder::default()
+            .snapshots_repo(Arc::new(mock_snapshots_repo))
+            .build();
+        let response = snapshots_service.capture(&context, &item).await;
+
+        assert_eq!(response, Err(AppError::Test));
+    }
+}
@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+
+use axum::async_trait;
+use scraper::{ElementRef, Html, Selector};
+
+use crate::{
+    outbound,
+    service::Analysis as AnalysisService,
+    types::{LinkItem, LinkItemBuilder, Result},
+};
+
+const MAX_SUMMARY_SENTENCES: usize = 3;
+const MAX_SUMMARY_LENGTH: usize = 600;
+const MIN_SENTENCE_WORDS: usize = 6;
+
+const CONTENT_ROOTS: &[&str] = &["article", "main", "body"];
+const EXCLUDED_ANCESTORS: &[&str] = &["nav", "header", "footer", "aside", "form", "figure"];
+
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "because",
+    "been", "before", "being", "but", "by", "can", "could", "did", "do", "does", "each", "for",
+    "from", "had", "has", "have", "he", "her", "his", "how", "i", "if", "in", "into", "is", "it",
+    "its", "just", "more", "most", "much", "my", "no", "not", "of", "on", "one", "only", "or",
+    "other", "our", "out", "over", "own", "same", "she", "should", "so", "some", "such", "than",
+    "that", "the", "their", "them", "then", "there", "these", "they", "this", "those", "through",
+    "to", "too", "under", "up", "very", "was", "we", "were", "what", "when", "where", "which",
+    "while", "who", "why", "will", "with", "would", "you", "your",
+];
+
+#[derive(Default)]
+pub struct ServiceProvider {
+    http_client: outbound::Client,
+}
+
+#[async_trait]
+impl AnalysisService for ServiceProvider {
+    async fn analyze(&self, link_item: &LinkItem) -> Result<LinkItem> {
+        let html = match self.fetch(link_item.url()).await {
+            Ok(html) => html,
+            Err(e) => {
+                tracing::warn!("Unable to retrieve contents of {}: {}", link_item.url(), e);
+                return Ok(link_item.clone());
+            }
+        };
+
+        let summary = summarize(&html);
+        if summary.is_empty() {
+            return Ok(link_item.clone());
+        }
+
+        Ok(LinkItemBuilder::from(link_item.clone())
+            .summary(&summary)
+            .build())
+    }
+}
+
+impl ServiceProvider {
+    async fn fetch(&self, url: &str) -> std::result::Result<String, String> {
+        let response = self
+            .http_client
+            .get(url)
+            .await?
+            .error_for_status()
+            .map_err(|e| format!("error_for_status() {e:?}"))?;
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok());
+        if content_type.is_some_and(|content_type| !content_type.contains("html")) {
+            return Err(String::from("contents are not html"));
+        }
+
+        let body = self.http_client.bytes(response).await?;
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+}
+
+/// Builds an extractive summary of an html page by picking the sentences whose
+/// words occur most frequently across the whole article text.
+pub fn summarize(html: &str) -> String {
+    let sentences: Vec<String> = extract_paragraphs(html)
+        .iter()
+        .flat_map(|paragraph| split_sentences(paragraph))
+        .filter(|sentence| sentence.split_whitespace().count() >= MIN_SENTENCE_WORDS)
+        .collect();
+    if sentences.is_empty() {
+        return String::new();
+    }
+
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    for sentence in &sentences {
+        for word in content_words(sentence) {
+            *frequencies.entry(word).or_default() += 1;
+        }
+    }
+
+    let mut scores: Vec<(usize, usize)> = sentences
+        .iter()
+        .enumerate()
+        .map(|(position, sentence)| {
+            let words = content_words(sentence);
+            let total: usize = words
+                .iter()
+                .map(|word| frequencies.get(word).copied().unwrap_or_default())
+                .sum();
+            (position, total * 1000 / words.len().max(1))
+        })
+        .collect();
+    scores.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    let mut selected: Vec<usize> = scores
+        .iter()
+        .take(MAX_SUMMARY_SENTENCES)
+        .map(|(position, _)| *position)
+        .collect();
+    selected.sort_unstable();
+
+    let mut summary = String::new();
+    for position in selected {
+        let sentence = &sentences[position];
+        let separator = usize::from(!summary.is_empty());
+        if summary.len() + separator + sentence.len() > MAX_SUMMARY_LENGTH {
+            continue;
+        }
+        if !summary.is_empty() {
+            summary.push(' ');
+        }
+        summary.push_str(sentence);
+    }
+
+    if summary.is_empty() {
+        summary = truncate(&sentences[scores[0].0], MAX_SUMMARY_LENGTH);
+    }
+    summary
+}
+
+fn extract_paragraphs(html: &str) -> Vec<String> {
+    let document = Html::parse_document(html);
+    let Ok(paragraph_selector) = Selector::parse("p") else {
+        return vec![];
+    };
+
+    for root in CONTENT_ROOTS {
+        let Ok(root_selector) = Selector::parse(root) else {
+            continue;
+        };
+        let paragraphs: Vec<String> = document
+            .select(&root_selector)
+            .flat_map(|root| root.select(&paragraph_selector))
+            .filter(|paragraph| !is_excluded(paragraph))
+            .map(|paragraph| normalize_whitespace(&paragraph.text().collect::<String>()))
+            .filter(|paragraph| !paragraph.is_empty())
+            .collect();
+        if !paragraphs.is_empty() {
+            return paragraphs;
+        }
+    }
+    vec![]
+}
+
+fn is_excluded(element: &ElementRef) -> bool {
+    element.ancestors().any(|node| {
+        node.value()
+            .as_element()
+            .is_some_and(|element| EXCLUDED_ANCESTORS.contains(&element.name()))
+    })
+}
+
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+fn split_sentences(paragraph: &str) -> Vec<String> {
+    let mut sentences = vec![];
+    let mut current = String::new();
+    let mut chars = paragraph.chars().peekable();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        let is_boundary = !matches!(chars.peek(), Some(next) if !next.is_whitespace());
+        if matches!(c, '.' | '!' | '?') && is_boundary {
+            let sentence = current.trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_owned());
+            }
+            current.clear();
+        }
+    }
+
+    let sentence = current.trim();
+    if !sentence.is_empty() {
+        sentences.push(sentence.to_owned());
+    }
+    sentences
+}
+
+fn content_words(sentence: &str) -> Vec<String> {
+    sentence
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() > 1)
+        .map(str::to_lowercase)
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+fn truncate(sentence: &str, max_length: usize) -> String {
+    let mut truncated = String::new();
+    for word in sentence.split_whitespace() {
+        if truncated.len() + word.len() + 1 > max_length {
+            break;
+        }
+        if !truncated.is_empty() {
+            truncated.push(' ');
+        }
+        truncated.push_str(word);
+    }
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::types::LinkItemBuilder;
+
+    use super::*;
+
+    fn test_service() -> ServiceProvider {
+        ServiceProvider {
+            http_client: outbound::Client::new(
+                outbound::Policy::default().allow_private_networks(),
+            ),
+        }
+    }
+
+    const ARTICLE_FIXTURE: &str = include_str!("../../tests/fixtures/article.html");
+    const NAVIGATION_FIXTURE: &str = include_str!("../../tests/fixtures/navigation.html");
+    const EMPTY_FIXTURE: &str = include_str!("../../tests/fixtures/empty.html");
+
+    #[test]
+    fn test_summarize_article() {
+        let summary = summarize(ARTICLE_FIXTURE);
+
+        assert_eq!(
+            summary,
+            "Rust ownership rules let the compiler check memory safety without a garbage collector. \
+             The borrow checker enforces ownership rules so that every value has a single owner at a time. \
+             References allow code to borrow a value without taking ownership of it."
+        );
+    }
+
+    #[test]
+    fn test_summarize_is_deterministic() {
+        assert_eq!(summarize(ARTICLE_FIXTURE), summarize(ARTICLE_FIXTURE));
+    }
+
+    #[test]
+    fn test_summarize_ignores_navigation() {
+        let summary = summarize(NAVIGATION_FIXTURE);
+
+        assert!(!summary.is_empty());
+        assert!(!summary.contains("Subscribe"));
+        assert!(!summary.contains("Copyright"));
+        assert!(summary.contains("sourdough"));
+    }
+
+    #[test]
+    fn test_summarize_empty_page() {
+        assert!(summarize(EMPTY_FIXTURE).is_empty());
+    }
+
+    #[test]
+    fn test_summarize_length_limit() {
+        let sentence = "word ".repeat(200);
+        let html = format!("<html><body><p>{sentence}</p></body></html>");
+
+        let summary = summarize(&html);
+
+        assert!(!summary.is_empty());
+        assert!(summary.len() <= MAX_SUMMARY_LENGTH);
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_analyze_link() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/article")
+            .with_status(200)
+            .with_header("content-type", "text/html; charset=utf-8")
+            .with_body(ARTICLE_FIXTURE)
+            .create_async()
+            .await;
+
+        let item = LinkItemBuilder::new(&format!("{}/article", server.url()))
+            .id("1")
+            .owner("user-id")
+            .build();
+
+        let summary_service = test_service();
+        let response = summary_service.analyze(&item).await;
+
+        mock.assert_async().await;
+        let analyzed_item = response.unwrap();
+        assert_eq!(analyzed_item.id(), "1");
+        assert_eq!(analyzed_item.summary(), summarize(ARTICLE_FIXTURE));
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_analyze_link_not_html() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/document")
+            .with_status(200)
+            .with_header("content-type", "application/pdf")
+            .with_body("%PDF-1.4")
+            .create_async()
+            .await;
+
+        let item = LinkItemBuilder::new(&format!("{}/document", server.url()))
+            .id("1")
+            .owner("user-id")
+            .build();
+
+        let summary_service = test_service();
+        let response = summary_service.analyze(&item).await;
+
+        mock.assert_async().await;
+        assert_eq!(response, Ok(item));
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_analyze_link_unreachable() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/missing")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let item = LinkItemBuilder::new(&format!("{}/missing", server.url()))
+            .id("1")
+            .owner("user-id")
+            .build();
+
+        let summary_service = test_service();
+        let response = summary_service.analyze(&item).await;
+
+        mock.assert_async().await;
+        assert_eq!(response, Ok(item));
+    }
+}
@@ -3,30 +3,32 @@ use argon2::{
     Argon2,
 };
 use axum::async_trait;
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use jsonwebtoken::{encode, EncodingKey, Header};
-use std::convert::TryInto;
+use std::{collections::HashMap, convert::TryInto};
 
 use crate::{
-    repository,
     service::Users as UsersService,
-    types::{AppError, Claims, Result, Token, UserInfo, UserInfoBuilder, UserQueryBuilder},
+    types::{
+        AppContext, AppError, Claims, DailyCount, LibraryStats, ReadingProgress, Result, Token,
+        UserInfo, UserInfoBuilder, UserQueryBuilder,
+    },
 };
 
 const JWT_SECRET_KEY: &str = "JWT_SECRET";
 
+pub const DEFAULT_STATS_DAYS: u32 = 30;
+pub const MAX_STATS_DAYS: u32 = 365;
+const TOP_DOMAINS: usize = 10;
+
 #[derive(Default)]
 pub struct ServiceProvider {}
 
 #[async_trait]
 impl UsersService for ServiceProvider {
-    async fn register(
-        &self,
-        users_repo: Box<repository::DynUsers>,
-        user_info: &UserInfo,
-    ) -> Result<UserInfo> {
+    async fn register(&self, context: &AppContext, user_info: &UserInfo) -> Result<UserInfo> {
         let user_query = UserQueryBuilder::new(user_info.email()).build();
-        let user_info = match users_repo.get(&user_query).await {
+        let user_info = match context.users_repo().get(&user_query).await {
             Ok(_) => return Err(AppError::UserAlreadyExists(user_info.email().to_owned())),
             Err(AppError::UserNotFound(_)) => user_info.clone(),
             Err(e) => return Err(e),
@@ -47,16 +49,12 @@ impl UsersService for ServiceProvider {
             .verified(true)
             .build();
 
-        users_repo.create(&registered_user_info).await
+        context.users_repo().create(&registered_user_info).await
     }
 
-    async fn login(
-        &self,
-        users_repo: Box<repository::DynUsers>,
-        user_info: &UserInfo,
-    ) -> Result<Token> {
+    async fn login(&self, context: &AppContext, user_info: &UserInfo) -> Result<Token> {
         let user_query = UserQueryBuilder::new(user_info.email()).build();
-        let retrieved_user_info = users_repo.get(&user_query).await?;
+        let retrieved_user_info = context.users_repo().get(&user_query).await?;
 
         let parsed_hash = PasswordHash::new(retrieved_user_info.password())
             .map_err(|e| AppError::Server(format!("PasswordHash::new() {e:?}")))?;
@@ -91,6 +89,58 @@ impl UsersService for ServiceProvider {
 
         Ok(Token::new(&token))
     }
+
+    async fn stats(
+        &self,
+        context: &AppContext,
+        user: &str,
+        days: u32,
+        today: &NaiveDate,
+    ) -> Result<LibraryStats> {
+        if days == 0 || days > MAX_STATS_DAYS {
+            return Err(AppError::Validation(format!(
+                "stats() days must be from 1 to {MAX_STATS_DAYS}, not {days}"
+            )));
+        }
+
+        let since = *today - Duration::days(i64::from(days - 1));
+        let stats = context
+            .links_repo()
+            .stats(user, &since, today, TOP_DOMAINS)
+            .await?;
+
+        // links count as read on the day they were finished, the same as in
+        // the reading stats
+        let mut read: HashMap<NaiveDate, usize> = HashMap::new();
+        for finished_at in context
+            .progress_repo()
+            .find(user)
+            .await?
+            .iter()
+            .filter_map(ReadingProgress::finished_at)
+        {
+            *read.entry(finished_at.date_naive()).or_default() += 1;
+        }
+        let saved: HashMap<NaiveDate, usize> = stats
+            .daily()
+            .iter()
+            .map(|count| (count.day(), count.saved()))
+            .collect();
+
+        // the repositories leave out the days without any links saved
+        let daily: Vec<DailyCount> = since
+            .iter_days()
+            .take_while(|day| day <= today)
+            .map(|day| {
+                DailyCount::new(
+                    day,
+                    saved.get(&day).copied().unwrap_or_default(),
+                    read.get(&day).copied().unwrap_or_default(),
+                )
+            })
+            .collect();
+        Ok(stats.with_daily(&daily))
+    }
 }
 
 #[cfg(test)]
@@ -98,7 +148,17 @@ mod tests {
 
     use std::sync::Arc;
 
-    use crate::{repository::MockUsers as MockUsersRepo, types::AppError};
+    use chrono::TimeZone;
+    use rstest::rstest;
+
+    use crate::{
+        app::ContextBuilder,
+        repository::{
+            MockLinks as MockLinksRepo, MockProgress as MockProgressRepo,
+            MockUsers as MockUsersRepo,
+        },
+        types::{AppError, BacklogAge, DomainCount, LinkCounts},
+    };
 
     use super::*;
 
@@ -123,9 +183,10 @@ mod tests {
             .returning(move |_| Ok(registered_user.clone()));
 
         let users_service = ServiceProvider {};
-        let response = users_service
-            .register(Box::new(Arc::new(mock_users_repo)), &request_item)
-            .await;
+        let context = ContextBuilder::default()
+            .users_repo(Arc::new(mock_users_repo))
+            .build();
+        let response = users_service.register(&context, &request_item).await;
 
         assert!(response.is_ok());
         assert_eq!(response.unwrap(), response_item);
@@ -147,9 +208,10 @@ mod tests {
         mock_users_repo.expect_create().times(0);
 
         let users_service = ServiceProvider {};
-        let response = users_service
-            .register(Box::new(Arc::new(mock_users_repo)), &request_item)
-            .await;
+        let context = ContextBuilder::default()
+            .users_repo(Arc::new(mock_users_repo))
+            .build();
+        let response = users_service.register(&context, &request_item).await;
 
         assert_eq!(
             response,
@@ -172,9 +234,10 @@ mod tests {
         mock_users_repo.expect_create().times(0);
 
         let users_service = ServiceProvider {};
-        let response = users_service
-            .register(Box::new(Arc::new(mock_users_repo)), &request_item)
-            .await;
+        let context = ContextBuilder::default()
+            .users_repo(Arc::new(mock_users_repo))
+            .build();
+        let response = users_service.register(&context, &request_item).await;
 
         assert_eq!(response, Err(AppError::Test));
     }
@@ -198,9 +261,10 @@ mod tests {
             .returning(move |_| Err(AppError::Test));
 
         let users_service = ServiceProvider {};
-        let response = users_service
-            .register(Box::new(Arc::new(mock_users_repo)), &request_item)
-            .await;
+        let context = ContextBuilder::default()
+            .users_repo(Arc::new(mock_users_repo))
+            .build();
+        let response = users_service.register(&context, &request_item).await;
 
         assert_eq!(response, Err(AppError::Test));
     }
@@ -225,9 +289,10 @@ mod tests {
             .returning(move |_| Ok(registered_user.clone()));
 
         let users_service = ServiceProvider {};
-        let response = users_service
-            .login(Box::new(Arc::new(mock_users_repo)), &request_item)
-            .await;
+        let context = ContextBuilder::default()
+            .users_repo(Arc::new(mock_users_repo))
+            .build();
+        let response = users_service.login(&context, &request_item).await;
 
         assert!(response.is_ok());
     }
@@ -246,9 +311,10 @@ mod tests {
             .returning(move |_| Err(AppError::UserNotFound("user@test.com".into())));
 
         let users_service = ServiceProvider {};
-        let response = users_service
-            .login(Box::new(Arc::new(mock_users_repo)), &request_item)
-            .await;
+        let context = ContextBuilder::default()
+            .users_repo(Arc::new(mock_users_repo))
+            .build();
+        let response = users_service.login(&context, &request_item).await;
 
         assert_eq!(
             response,
@@ -276,13 +342,95 @@ mod tests {
             .returning(move |_| Ok(registered_user.clone()));
 
         let users_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .users_repo(Arc::new(mock_users_repo))
+            .build();
+        let response = users_service.login(&context, &request_item).await;
+
+        assert_eq!(
+            response,
+            Err(AppError::IncorrectPassword("user@test.com".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_stats() {
+        let day = |day| NaiveDate::from_ymd_opt(2024, 5, day).unwrap();
+        let stats = LibraryStats::new(
+            LinkCounts::new(3, 2),
+            &[DomainCount::new("example.com", 4)],
+            &[
+                DailyCount::new(day(13), 2, 0),
+                DailyCount::new(day(15), 1, 0),
+            ],
+            BacklogAge::new(2, 1, 0, 0, Some(day(1))),
+        );
+        let retrieved_stats = stats.clone();
+        let finished_at = |day| Utc.with_ymd_and_hms(2024, 5, day, 12, 0, 0).unwrap();
+        let progress = vec![
+            ReadingProgress::new("1", "user@test.com", 100, 0, 0, &finished_at(13))
+                .with_finished_at(&finished_at(13)),
+            ReadingProgress::new("2", "user@test.com", 100, 0, 0, &finished_at(14))
+                .with_finished_at(&finished_at(14)),
+            ReadingProgress::new("3", "user@test.com", 100, 0, 0, &finished_at(1))
+                .with_finished_at(&finished_at(1)),
+            ReadingProgress::new("4", "user@test.com", 50, 0, 0, &finished_at(15)),
+        ];
+
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo
+            .expect_stats()
+            .withf(move |owner, since, today, domains| {
+                owner == "user@test.com"
+                    && since == &day(12)
+                    && today == &day(15)
+                    && *domains == TOP_DOMAINS
+            })
+            .times(1)
+            .returning(move |_, _, _, _| Ok(retrieved_stats.clone()));
+        let mut mock_progress_repo = MockProgressRepo::new();
+        mock_progress_repo
+            .expect_find()
+            .withf(|owner| owner == "user@test.com")
+            .times(1)
+            .returning(move |_| Ok(progress.clone()));
+
+        let users_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .progress_repo(Arc::new(mock_progress_repo))
+            .build();
         let response = users_service
-            .login(Box::new(Arc::new(mock_users_repo)), &request_item)
+            .stats(&context, "user@test.com", 4, &day(15))
             .await;
 
         assert_eq!(
             response,
-            Err(AppError::IncorrectPassword("user@test.com".into()))
+            Ok(stats.with_daily(&[
+                DailyCount::new(day(12), 0, 0),
+                DailyCount::new(day(13), 2, 1),
+                DailyCount::new(day(14), 0, 1),
+                DailyCount::new(day(15), 1, 0),
+            ]))
         );
     }
+
+    #[rstest]
+    #[case(0)]
+    #[case(MAX_STATS_DAYS + 1)]
+    #[tokio::test]
+    async fn test_stats_invalid_days(#[case] days: u32) {
+        let mut mock_links_repo = MockLinksRepo::new();
+        mock_links_repo.expect_stats().times(0);
+
+        let users_service = ServiceProvider {};
+        let context = ContextBuilder::default()
+            .links_repo(Arc::new(mock_links_repo))
+            .build();
+        let response = users_service
+            .stats(&context, "user@test.com", days, &Utc::now().date_naive())
+            .await;
+
+        assert!(matches!(response, Err(AppError::Validation(_))));
+    }
 }
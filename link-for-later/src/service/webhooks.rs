@@ -0,0 +1,474 @@
+use std::time::Duration;
+
+use axum::async_trait;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde_json::json;
+use sha2::Sha256;
+
+use crate::{
+    outbound, repository,
+    service::Webhooks as WebhooksService,
+    types::{AppContext, AppError, LinkItem, Result, Webhook, WebhookDelivery},
+};
+
+pub const LINK_CREATED: &str = "link.created";
+pub const LINK_UPDATED: &str = "link.updated";
+pub const LINK_READ: &str = "link.read";
+pub const LINK_DELETED: &str = "link.deleted";
+pub const LINK_REMINDER: &str = "link.reminder";
+
+const EVENTS: &[&str] = &[
+    LINK_CREATED,
+    LINK_UPDATED,
+    LINK_READ,
+    LINK_DELETED,
+    LINK_REMINDER,
+];
+
+const EVENT_HEADER: &str = "X-Webhook-Event";
+const SIGNATURE_HEADER: &str = "X-Webhook-Signature-256";
+
+const MAX_DELIVERY_ATTEMPTS: u32 = 4;
+const RETRY_BACKOFF_MILLIS: u64 = 1000;
+const SECRET_LENGTH: usize = 32;
+
+#[derive(Clone)]
+pub struct ServiceProvider {
+    http_client: outbound::Client,
+    retry_backoff: Duration,
+}
+
+#[async_trait]
+impl WebhooksService for ServiceProvider {
+    async fn search(&self, context: &AppContext, owner: &str) -> Result<Vec<Webhook>> {
+        context.webhooks_repo().find(owner).await
+    }
+
+    async fn create(&self, context: &AppContext, webhook: &Webhook) -> Result<Webhook> {
+        if let Some(event) = webhook
+            .events()
+            .iter()
+            .find(|event| !EVENTS.contains(&event.as_str()))
+        {
+            return Err(AppError::Validation(format!(
+                "create_webhook() unknown event {event}"
+            )));
+        }
+
+        let secret = if webhook.secret().is_empty() {
+            generate_secret()
+        } else {
+            webhook.secret().to_owned()
+        };
+        let webhook = Webhook::new(
+            webhook.owner(),
+            webhook.url(),
+            &secret,
+            webhook.events(),
+            &Utc::now(),
+        );
+        context.webhooks_repo().create(&webhook).await
+    }
+
+    async fn delete(&self, context: &AppContext, owner: &str, id: &str) -> Result<()> {
+        self.get(context.webhooks_repo(), owner, id).await?;
+        context.webhooks_repo().delete(id).await
+    }
+
+    async fn deliveries(
+        &self,
+        context: &AppContext,
+        owner: &str,
+        id: &str,
+    ) -> Result<Vec<WebhookDelivery>> {
+        self.get(context.webhooks_repo(), owner, id).await?;
+        context.webhooks_repo().find_deliveries(id).await
+    }
+
+    async fn notify(&self, context: &AppContext, event: &str, link_item: &LinkItem) -> Result<()> {
+        let webhooks: Vec<Webhook> = context
+            .webhooks_repo()
+            .find(link_item.owner())
+            .await?
+            .into_iter()
+            .filter(|webhook| webhook.is_subscribed(event))
+            .collect();
+        if webhooks.is_empty() {
+            return Ok(());
+        }
+
+        let payload = json!({
+            "event": event,
+            "link": link_item,
+            "occurred_at": Utc::now(),
+        })
+        .to_string();
+
+        // deliveries are retried with backoff, so they must not hold up the request
+        for webhook in webhooks {
+            let webhooks_service = self.clone();
+            let webhooks_repo = context.webhooks_repo().clone();
+            let event = event.to_owned();
+            let link_id = link_item.id().to_owned();
+            let payload = payload.clone();
+            tokio::spawn(async move {
+                if let Err(e) = webhooks_service
+                    .deliver(&webhooks_repo, &webhook, &event, &link_id, &payload)
+                    .await
+                {
+                    tracing::error!("Unable to record delivery to {}: {}", webhook.url(), e);
+                }
+            });
+        }
+        Ok(())
+    }
+}
+
+impl ServiceProvider {
+    async fn get(
+        &self,
+        webhooks_repo: &repository::DynWebhooks,
+        owner: &str,
+        id: &str,
+    ) -> Result<Webhook> {
+        let webhook = webhooks_repo.get(id).await?;
+        if webhook.owner() == owner {
+            Ok(webhook)
+        } else {
+            Err(AppError::Authorization(String::from(
+                "User is not authorized to access resource",
+            )))
+        }
+    }
+
+    async fn deliver(
+        &self,
+        webhooks_repo: &repository::DynWebhooks,
+        webhook: &Webhook,
+        event: &str,
+        link_id: &str,
+        payload: &str,
+    ) -> Result<WebhookDelivery> {
+        let signature = format!("sha256={}", sign(webhook.secret(), payload));
+
+        let mut attempts = 0;
+        let mut backoff = self.retry_backoff;
+        let (status_code, error) = loop {
+            attempts += 1;
+            let (status_code, error) = match self
+                .http_client
+                .post(
+                    webhook.url(),
+                    &[
+                        ("Content-Type", "application/json"),
+                        (EVENT_HEADER, event),
+                        (SIGNATURE_HEADER, &signature),
+                    ],
+                    payload.to_owned(),
+                )
+                .await
+            {
+                Ok(response) if response.status().is_success() => {
+                    (response.status().as_u16(), String::new())
+                }
+                Ok(response) => (
+                    response.status().as_u16(),
+                    format!("unexpected status {}", response.status()),
+                ),
+                Err(e) => (0, e),
+            };
+
+            if error.is_empty() || attempts >= MAX_DELIVERY_ATTEMPTS {
+                break (status_code, error);
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        };
+
+        let delivery = WebhookDelivery::new(
+            webhook.id(),
+            event,
+            link_id,
+            status_code,
+            attempts,
+            &error,
+            &Utc::now(),
+        );
+        webhooks_repo.create_delivery(&delivery).await
+    }
+}
+
+impl Default for ServiceProvider {
+    fn default() -> Self {
+        Self {
+            http_client: outbound::Client::default(),
+            retry_backoff: Duration::from_millis(RETRY_BACKOFF_MILLIS),
+        }
+    }
+}
+
+/// Hex encoded HMAC-SHA256 of the payload, letting receivers verify that a
+/// delivery really came from us.
+fn sign(secret: &str, payload: &str) -> String {
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return String::new();
+    };
+    mac.update(payload.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn generate_secret() -> String {
+    let mut secret = [0u8; SECRET_LENGTH];
+    rand::thread_rng().fill_bytes(&mut secret);
+    hex::encode(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use mockall::predicate::eq;
+
+    use crate::{
+        app::ContextBuilder, repository::MockWebhooks as MockWebhooksRepo, types::LinkItemBuilder,
+    };
+
+    use super::*;
+
+    fn test_service() -> ServiceProvider {
+        ServiceProvider {
+            http_client: outbound::Client::new(
+                outbound::Policy::default().allow_private_networks(),
+            ),
+            retry_backoff: Duration::ZERO,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_webhook_generates_secret() {
+        let request_webhook = Webhook::new(
+            "user",
+            "http://hook",
+            "",
+            &[String::from(LINK_CREATED)],
+            &Utc::now(),
+        );
+
+        let mut mock_webhooks_repo = MockWebhooksRepo::new();
+        mock_webhooks_repo
+            .expect_create()
+            .withf(|webhook| {
+                webhook.owner() == "user"
+                    && webhook.url() == "http://hook"
+                    && webhook.secret().len() == SECRET_LENGTH * 2
+            })
+            .times(1)
+            .returning(|webhook| Ok(webhook.clone().with_id("1")));
+
+        let webhooks_service = test_service();
+        let context = ContextBuilder::default()
+            .webhooks_repo(Arc::new(mock_webhooks_repo))
+            .build();
+        let response = webhooks_service.create(&context, &request_webhook).await;
+
+        assert_eq!(response.unwrap().id(), "1");
+    }
+
+    #[tokio::test]
+    async fn test_create_webhook_unknown_event() {
+        let request_webhook = Webhook::new(
+            "user",
+            "http://hook",
+            "secret",
+            &[String::from("link.unknown")],
+            &Utc::now(),
+        );
+
+        let mut mock_webhooks_repo = MockWebhooksRepo::new();
+        mock_webhooks_repo.expect_create().times(0);
+
+        let webhooks_service = test_service();
+        let context = ContextBuilder::default()
+            .webhooks_repo(Arc::new(mock_webhooks_repo))
+            .build();
+        let response = webhooks_service.create(&context, &request_webhook).await;
+
+        assert_eq!(
+            response,
+            Err(AppError::Validation(
+                "create_webhook() unknown event link.unknown".into()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_webhook_unauthorized() {
+        let mut mock_webhooks_repo = MockWebhooksRepo::new();
+        mock_webhooks_repo
+            .expect_get()
+            .with(eq("1"))
+            .times(1)
+            .returning(|_| {
+                Ok(Webhook::new("user", "http://hook", "secret", &[], &Utc::now()).with_id("1"))
+            });
+        mock_webhooks_repo.expect_delete().times(0);
+
+        let webhooks_service = test_service();
+        let context = ContextBuilder::default()
+            .webhooks_repo(Arc::new(mock_webhooks_repo))
+            .build();
+        let response = webhooks_service
+            .delete(&context, "unauthorized-user", "1")
+            .await;
+
+        assert_eq!(
+            response,
+            Err(AppError::Authorization(
+                "User is not authorized to access resource".into()
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notify_without_subscriptions() {
+        let item = LinkItemBuilder::new("http://link")
+            .id("1")
+            .owner("user")
+            .build();
+
+        let mut mock_webhooks_repo = MockWebhooksRepo::new();
+        mock_webhooks_repo
+            .expect_find()
+            .with(eq("user"))
+            .times(1)
+            .returning(|_| {
+                Ok(vec![Webhook::new(
+                    "user",
+                    "http://hook",
+                    "secret",
+                    &[String::from(LINK_DELETED)],
+                    &Utc::now(),
+                )
+                .with_id("1")])
+            });
+        mock_webhooks_repo.expect_create_delivery().times(0);
+
+        let webhooks_service = test_service();
+        let context = ContextBuilder::default()
+            .webhooks_repo(Arc::new(mock_webhooks_repo))
+            .build();
+        let response = webhooks_service.notify(&context, LINK_CREATED, &item).await;
+
+        assert!(response.is_ok());
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_deliver_signed_payload() {
+        let payload = r#"{"event":"link.created"}"#;
+        let signature = format!("sha256={}", sign("secret", payload));
+
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .match_header(EVENT_HEADER, LINK_CREATED)
+            .match_header(SIGNATURE_HEADER, signature.as_str())
+            .match_body(payload)
+            .with_status(204)
+            .create_async()
+            .await;
+
+        let webhook = Webhook::new(
+            "user",
+            &format!("{}/hook", server.url()),
+            "secret",
+            &[],
+            &Utc::now(),
+        )
+        .with_id("1");
+
+        let mut mock_webhooks_repo = MockWebhooksRepo::new();
+        mock_webhooks_repo
+            .expect_create_delivery()
+            .withf(|delivery| {
+                delivery.webhook_id() == "1"
+                    && delivery.link_id() == "2"
+                    && delivery.status_code() == 204
+                    && delivery.attempts() == 1
+                    && delivery.is_success()
+            })
+            .times(1)
+            .returning(|delivery| Ok(delivery.clone()));
+
+        let webhooks_service = test_service();
+        let response = webhooks_service
+            .deliver(
+                &(Arc::new(mock_webhooks_repo) as repository::DynWebhooks),
+                &webhook,
+                LINK_CREATED,
+                "2",
+                payload,
+            )
+            .await;
+
+        mock.assert_async().await;
+        assert!(response.is_ok());
+    }
+
+    #[allow(clippy::significant_drop_tightening)]
+    #[tokio::test]
+    async fn test_deliver_retries_failures() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/hook")
+            .with_status(500)
+            .expect(MAX_DELIVERY_ATTEMPTS as usize)
+            .create_async()
+            .await;
+
+        let webhook = Webhook::new(
+            "user",
+            &format!("{}/hook", server.url()),
+            "secret",
+            &[],
+            &Utc::now(),
+        )
+        .with_id("1");
+
+        let mut mock_webhooks_repo = MockWebhooksRepo::new();
+        mock_webhooks_repo
+            .expect_create_delivery()
+            .withf(|delivery| {
+                delivery.status_code() == 500
+                    && delivery.attempts() == MAX_DELIVERY_ATTEMPTS
+                    && !delivery.is_success()
+                    && !delivery.error().is_empty()
+            })
+            .times(1)
+            .returning(|delivery| Ok(delivery.clone()));
+
+        let webhooks_service = test_service();
+        let response = webhooks_service
+            .deliver(
+                &(Arc::new(mock_webhooks_repo) as repository::DynWebhooks),
+                &webhook,
+                LINK_UPDATED,
+                "2",
+                "{}",
+            )
+            .await;
+
+        mock.assert_async().await;
+        assert!(response.is_ok());
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        assert_eq!(
+            sign("key", "The quick brown fox jumps over the lazy dog"),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+}
@@ -7,12 +7,27 @@ pub use link_for_later_types::dto::{
 pub use link_for_later_types::entity::{LinkItem, LinkItemBuilder, UserInfo, UserInfoBuilder};
 
 pub use crate::auth::{Claims, Token};
+pub use crate::dto::{
+    CollectionLinksRequest, CollectionRequest, DetailsFilter, DetailsRequest, DigestRequest,
+    HighlightRequest, LinkPatchRequest, MemberRequest, NextStrategy, ProgressRequest,
+    ReminderRequest, ShareRequest, SnoozeRequest, WebhookCreatedResponse, WebhookRequest,
+};
+pub use crate::entity::{
+    BacklogAge, Collection, DailyCount, DomainCount, Feed, FieldChange, Highlight, LibraryStats,
+    LinkCounts, LinkDetails, LinkHealth, Member, Priority, PublicCollection, PublicLink,
+    ReadingProgress, ReadingStats, Reminder, Revision, Role, Selector, Share, SharedView, Snapshot,
+    StoredWebhook, TrashedLink, VersionedLink, Webhook, WebhookDelivery, WeeklyReads,
+};
 
 pub type AppState = crate::app::State;
+pub type AppContext = crate::app::Context;
 pub type AppError = crate::app::Error;
 
 #[derive(Debug)]
 pub enum Database {
     MongoDb(mongodb::Database),
+    Postgres(sqlx::PgPool),
+    Sqlite(std::path::PathBuf),
     InMemory,
+    PersistentInMemory(std::path::PathBuf),
 }
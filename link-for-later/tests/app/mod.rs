@@ -1,6 +1,6 @@
 use axum::Router;
 
-use crate::repository::{mongodb, DatabaseType};
+use crate::repository::{mongodb, postgres, sqlite, DatabaseType};
 
 pub async fn new(db_type: &DatabaseType) -> Router {
     let _ = tracing_subscriber::fmt()
@@ -11,8 +11,23 @@ pub async fn new(db_type: &DatabaseType) -> Router {
     match db_type {
         DatabaseType::InMemory => link_for_later::app::new(link_for_later::DatabaseType::InMemory),
         DatabaseType::MongoDb => {
-            let db = mongodb::database().await;
-            link_for_later::app::new(link_for_later::DatabaseType::MongoDb(db))
+            let db = link_for_later::DatabaseType::MongoDb(mongodb::database().await);
+            link_for_later::app::migrate(&db).await.unwrap();
+            link_for_later::app::new(db)
+        }
+        DatabaseType::Postgres => {
+            let pool = postgres::database().await;
+            link_for_later::app::new(link_for_later::DatabaseType::Postgres(pool))
+        }
+        DatabaseType::Sqlite => {
+            let path = sqlite::database().await;
+            link_for_later::app::new(link_for_later::DatabaseType::Sqlite(path))
         }
     }
 }
+
+pub async fn skipping_malformed_documents() -> Router {
+    let db = link_for_later::DatabaseType::MongoDb(mongodb::database().await);
+    link_for_later::app::migrate(&db).await.unwrap();
+    link_for_later::app::skipping_malformed_documents(db)
+}
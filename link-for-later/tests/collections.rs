@@ -0,0 +1,261 @@
+#![allow(dead_code)]
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use rstest::rstest;
+use serde_json::Value;
+use tower::ServiceExt;
+
+use crate::repository::DatabaseType;
+
+mod app;
+mod auth;
+mod repository;
+
+#[rstest]
+#[tokio::test]
+async fn test_collection_links(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let first_id = repository.add_link("user@test.com", "http://first").await;
+    let second_id = repository.add_link("user@test.com", "http://second").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    let request = r#"{
+        "name": "Reading",
+        "description": "for the weekend"
+    }"#;
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/collections")
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(request))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let collection: Value = serde_json::from_slice(&body).unwrap();
+    let id = collection["id"].as_str().unwrap();
+    assert!(collection["owner"] == "user@test.com");
+    assert!(collection["name"] == "Reading");
+
+    for link_id in [&first_id, &second_id] {
+        let response = app::new(&db_type)
+            .await
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri(format!("/v1/collections/{id}/links/{link_id}"))
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let request = format!(r#"{{"links": ["{second_id}", "{first_id}"]}}"#);
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/v1/collections/{id}/links"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(request))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/collections/{id}/links"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let links: Vec<Value> = serde_json::from_slice(&body).unwrap();
+    assert!(links.len() == 2);
+    assert!(links[0]["url"] == "http://second");
+    assert!(links[1]["url"] == "http://first");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_collection_of_another_user(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    repository::new(&db_type);
+
+    let token = auth::generate_token("user@test.com", false);
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/collections")
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(r#"{"name": "Reading"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let collection: Value = serde_json::from_slice(&body).unwrap();
+    let id = collection["id"].as_str().unwrap();
+
+    let token = auth::generate_token("other@test.com", false);
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/v1/collections/{id}"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_shared_collection(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let link_id = repository.add_link("user@test.com", "http://link").await;
+    repository.add_user("member@test.com", "test").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/collections")
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(r#"{"name": "Reading"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let collection: Value = serde_json::from_slice(&body).unwrap();
+    let id = collection["id"].as_str().unwrap();
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/v1/collections/{id}/links/{link_id}"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/v1/collections/{id}/members/member@test.com"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(r#"{"role": "viewer"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let token = auth::generate_token("member@test.com", false);
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/v1/collections/shared")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let collections: Vec<Value> = serde_json::from_slice(&body).unwrap();
+    assert!(collections.len() == 1);
+    assert!(collections[0]["id"] == id);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/links/{link_id}"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // viewers can see the links of the collection but not change them
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/v1/links/{link_id}"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(r#"{"url": "http://changed"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -0,0 +1,142 @@
+#![allow(dead_code)]
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use rstest::rstest;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use crate::repository::DatabaseType;
+
+mod app;
+mod auth;
+mod repository;
+
+#[rstest]
+#[tokio::test]
+async fn test_link_details(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let first_id = repository.add_link("user@test.com", "http://first").await;
+    let second_id = repository.add_link("user@test.com", "http://second").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .uri(format!("/v1/links/{first_id}/details"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let details: Value = serde_json::from_slice(&body).unwrap();
+    assert!(details["priority"] == "normal");
+    assert!(details["rating"].is_null());
+
+    for (id, request) in [
+        (
+            &first_id,
+            json!({"priority": "low", "rating": 5, "notes": "*great*"}),
+        ),
+        (&second_id, json!({"priority": "high", "rating": 3})),
+    ] {
+        let response = app::new(&db_type)
+            .await
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri(format!("/v1/links/{id}/details"))
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::from(request.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .uri(format!("/v1/links/{first_id}/details"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let details: Value = serde_json::from_slice(&body).unwrap();
+    assert!(details["priority"] == "low");
+    assert!(details["rating"] == 5);
+    assert!(details["notes"] == "*great*");
+
+    for (params, expected_ids) in [
+        ("sort=priority", vec![second_id.as_str(), first_id.as_str()]),
+        ("sort=rating", vec![first_id.as_str(), second_id.as_str()]),
+        ("priority=high", vec![second_id.as_str()]),
+        ("min_rating=4", vec![first_id.as_str()]),
+    ] {
+        let response = app::new(&db_type)
+            .await
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/v1/links?{params}"))
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let links: Vec<Value> = serde_json::from_slice(&body).unwrap();
+        let ids: Vec<&str> = links
+            .iter()
+            .map(|link| link["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, expected_ids, "{params}");
+    }
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_link_details_of_another_user(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let id = repository.add_link("user@test.com", "http://link").await;
+    let token = auth::generate_token("other@test.com", false);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/v1/links/{id}/details"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(r#"{"priority": "high"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
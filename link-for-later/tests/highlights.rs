@@ -0,0 +1,156 @@
+#![allow(dead_code)]
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use rstest::rstest;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use crate::repository::DatabaseType;
+
+mod app;
+mod auth;
+mod repository;
+
+#[rstest]
+#[tokio::test]
+async fn test_highlights(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let id = repository.add_link("user@test.com", "http://link").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    let mut highlight_ids = vec![];
+    for request in [
+        json!({
+            "quote": "first passage",
+            "selectors": [{"type": "TextPositionSelector", "start": 0, "end": 13}]
+        }),
+        json!({
+            "quote": "second passage",
+            "note": "worth *remembering*",
+            "selectors": [{"type": "TextQuoteSelector", "exact": "second passage"}]
+        }),
+    ] {
+        let response = app::new(&db_type)
+            .await
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(format!("/v1/links/{id}/highlights"))
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::from(request.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let highlight: Value = serde_json::from_slice(&body).unwrap();
+        highlight_ids.push(highlight["id"].as_str().unwrap().to_owned());
+    }
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/v1/links/{id}/highlights/{}", highlight_ids[0]))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(
+                    json!({"quote": "first passage", "note": "a note"}).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .uri(format!("/v1/links/{id}/highlights"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let highlights: Vec<Value> = serde_json::from_slice(&body).unwrap();
+    assert_eq!(highlights.len(), 2);
+    assert!(highlights[0]["note"] == "a note");
+    assert!(highlights[1]["selectors"][0]["type"] == "TextQuoteSelector");
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .uri("/v1/highlights/export")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let markdown = std::str::from_utf8(&body).unwrap();
+    assert!(markdown.contains("## [http://link](http://link)"));
+    assert!(markdown.contains("> first passage\n\na note\n"));
+    assert!(markdown.contains("> second passage\n\nworth *remembering*\n"));
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/v1/links/{id}/highlights/{}", highlight_ids[1]))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_highlights_of_another_user(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let id = repository.add_link("user@test.com", "http://link").await;
+    let token = auth::generate_token("other@test.com", false);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/v1/links/{id}/highlights"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(r#"{"quote": "passage"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -13,7 +13,7 @@ use tower::ServiceExt;
 
 use link_for_later_types::entity::LinkItem;
 
-use crate::repository::DatabaseType;
+use crate::repository::{DatabaseType, Repository};
 
 mod app;
 mod auth;
@@ -24,7 +24,13 @@ mod repository;
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_get_links_empty(
-    #[values(DatabaseType::InMemory, DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(
+        DatabaseType::InMemory,
+        DatabaseType::MongoDb,
+        DatabaseType::Postgres,
+        DatabaseType::Sqlite
+    )]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -56,7 +62,8 @@ async fn test_get_links_empty(
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_get_links_non_empty(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -94,7 +101,8 @@ async fn test_get_links_non_empty(
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_get_links_multiple_entries(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -144,12 +152,108 @@ async fn test_get_links_multiple_entries(
     }
 }
 
+#[tokio::test]
+async fn test_get_links_malformed_document() {
+    let db_type = DatabaseType::MongoDb;
+    let repository = repository::mongodb::RepositoryProvider::default();
+    repository.setup();
+
+    repository.add_link("user@test.com", "http://test").await;
+    repository.add_malformed_link("user@test.com").await;
+
+    let token = auth::generate_token("user@test.com", false);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/v1/links")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn test_get_links_skip_malformed_document() {
+    let repository = repository::mongodb::RepositoryProvider::default();
+    repository.setup();
+
+    let id = repository.add_link("user@test.com", "http://test").await;
+    repository.add_malformed_link("user@test.com").await;
+
+    let token = auth::generate_token("user@test.com", false);
+
+    let response = app::skipping_malformed_documents()
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/v1/links")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = std::str::from_utf8(&body).unwrap();
+    let body: Vec<LinkItem> = serde_json::from_str(body).unwrap();
+
+    assert!(body.len() == 1);
+    assert!(body[0].id() == id);
+}
+
+#[tokio::test]
+async fn test_get_link_item_with_repaired_id() {
+    let db_type = DatabaseType::MongoDb;
+    let repository = repository::mongodb::RepositoryProvider::default();
+    repository.setup();
+
+    let id = repository
+        .add_link_with_placeholder_id("user@test.com", "http://test")
+        .await;
+
+    let token = auth::generate_token("user@test.com", false);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/links/{}", id))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = std::str::from_utf8(&body).unwrap();
+    let body: LinkItem = serde_json::from_str(body).unwrap();
+
+    assert!(body.id() == id);
+    assert!(body.url() == "http://test");
+}
+
 #[rstest]
 #[case(true, "admin@test.com")]
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_get_link_item_found(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -186,7 +290,8 @@ async fn test_get_link_item_found(
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_get_link_item_not_found(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -218,7 +323,8 @@ async fn test_get_link_item_not_found(
 #[rstest]
 #[tokio::test]
 async fn test_post_link(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[values(true, false)] is_admin: bool,
 ) {
     let repository = repository::new(&db_type);
@@ -262,7 +368,8 @@ async fn test_post_link(
 #[rstest]
 #[tokio::test]
 async fn test_post_link_invalid_url(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[values(true, false)] is_admin: bool,
 ) {
     let repository = repository::new(&db_type);
@@ -301,7 +408,8 @@ async fn test_post_link_invalid_url(
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_put_link(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -352,7 +460,8 @@ async fn test_put_link(
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_put_link_invalid_url(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -394,7 +503,8 @@ async fn test_put_link_invalid_url(
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_put_link_item_not_found(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -436,12 +546,98 @@ async fn test_put_link_item_not_found(
     assert!(db_item.url() == "http://test"); // not updated
 }
 
+#[rstest]
+#[tokio::test]
+async fn test_put_link_with_etag(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let id = repository.add_link("user@test.com", "http://test").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/links/{id}"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response.headers()["ETag"].to_str().unwrap().to_owned();
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/links/{id}"))
+                .header("Authorization", format!("Bearer {}", token))
+                .header("If-None-Match", &etag)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+    let request = r#"{
+        "url": "http://update"
+    }"#;
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/v1/links/{id}"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .header("If-Match", &etag)
+                .body(Body::from(request))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers()["ETag"].to_str().unwrap(), etag);
+
+    // a second client still holding the old tag must not overwrite the update
+    let request = r#"{
+        "url": "http://conflict"
+    }"#;
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/v1/links/{id}"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .header("If-Match", &etag)
+                .body(Body::from(request))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+
+    let db_item = repository.get_link(&id).await;
+    assert!(db_item.url() == "http://update");
+}
+
 #[rstest]
 #[case(true, "admin@test.com")]
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_delete_link(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -478,7 +674,8 @@ async fn test_delete_link(
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_delete_link_item_not_found(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -516,10 +713,154 @@ async fn test_delete_link_item_not_found(
     assert!(db_item.url() == "http://test");
 }
 
+#[rstest]
+#[case(true, "admin@test.com")]
+#[case(false, "user@test.com")]
+#[tokio::test]
+async fn test_restore_deleted_link(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+    #[case] is_admin: bool,
+    #[case] user: &str,
+) {
+    let repository = repository::new(&db_type);
+
+    let id = repository.add_link("user@test.com", "http://test").await;
+    let token = auth::generate_token(user, is_admin);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/v1/links/{id}"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/v1/links/trash")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let trash: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+    assert!(trash.len() == 1);
+    assert!(trash[0]["id"] == id.as_str());
+    assert!(trash[0]["deleted_at"].is_string());
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/v1/links/{id}/restore"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let db_count = repository.count_links().await;
+    assert!(db_count == 1);
+
+    let db_item = repository.get_link(&id).await;
+    assert!(db_item.owner() == "user@test.com");
+    assert!(db_item.url() == "http://test");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_revert_link_to_previous_revision(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let id = repository.add_link("user@test.com", "http://test").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    let request = r#"{
+        "url": "http://test",
+        "title": "Updated"
+    }"#;
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/v1/links/{id}"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(request))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/links/{id}/history"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let history: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+    assert!(history.len() == 1);
+    assert!(history[0]["actor"] == "user@test.com");
+    assert!(history[0]["action"] == "update");
+    assert!(history[0]["changes"][0]["field"] == "title");
+    assert!(history[0]["changes"][0]["new"] == "Updated");
+    let revision_id = history[0]["id"].as_str().unwrap();
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/v1/links/{id}/history/{revision_id}/revert"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let db_item = repository.get_link(&id).await;
+    assert!(db_item.title().is_empty());
+    assert!(db_item.url() == "http://test");
+}
+
 #[rstest]
 #[tokio::test]
 async fn test_unauthorized_access_to_links_no_token(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
 ) {
     repository::new(&db_type);
 
@@ -548,7 +889,8 @@ async fn test_unauthorized_access_to_links_no_token(
 #[rstest]
 #[tokio::test]
 async fn test_unauthorized_access_to_links_invalid_token(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
 ) {
     repository::new(&db_type);
 
@@ -574,3 +916,46 @@ async fn test_unauthorized_access_to_links_invalid_token(
         json!({"error": "invalid authorization token"}).to_string()
     );
 }
+
+#[rstest]
+#[tokio::test]
+async fn test_next_link(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let first_id = repository.add_link("user@test.com", "http://first").await;
+    let second_id = repository.add_link("user@test.com", "http://second").await;
+    repository.add_link("other@test.com", "http://third").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    for (uri, expected_status) in [
+        ("/v1/links/next", StatusCode::OK),
+        ("/v1/links/next?strategy=random", StatusCode::OK),
+        ("/v1/links/next?strategy=priority", StatusCode::OK),
+        // neither link has a reading time estimate yet
+        ("/v1/links/next?strategy=shortest", StatusCode::NO_CONTENT),
+        ("/v1/links/next?minutes=10", StatusCode::NO_CONTENT),
+        ("/v1/links/next?strategy=newest", StatusCode::BAD_REQUEST),
+    ] {
+        let response = app::new(&db_type)
+            .await
+            .oneshot(
+                Request::builder()
+                    .uri(uri)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), expected_status, "{uri}");
+
+        if expected_status == StatusCode::OK {
+            let body = response.into_body().collect().await.unwrap().to_bytes();
+            let item: LinkItem = serde_json::from_slice(&body).unwrap();
+            assert!(item.id() == first_id || item.id() == second_id);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+#![allow(dead_code)]
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use rstest::rstest;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use crate::repository::DatabaseType;
+
+mod app;
+mod auth;
+mod repository;
+
+#[rstest]
+#[tokio::test]
+async fn test_reading_progress(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let id = repository.add_link("user@test.com", "http://link").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    for request in [
+        json!({"percentage": 40, "position": 1200, "time_spent": 90}),
+        json!({"percentage": 100, "position": 3000, "time_spent": 150}),
+    ] {
+        let response = app::new(&db_type)
+            .await
+            .oneshot(
+                Request::builder()
+                    .method("PUT")
+                    .uri(format!("/v1/links/{id}/progress"))
+                    .header("Content-Type", "application/json")
+                    .header("Authorization", format!("Bearer {}", token))
+                    .body(Body::from(request.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .uri(format!("/v1/links/{id}/progress"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let progress: Value = serde_json::from_slice(&body).unwrap();
+    assert!(progress["percentage"] == 100);
+    assert!(progress["time_spent"] == 240);
+    assert!(!progress["finished_at"].is_null());
+
+    let link = repository.get_link(&id).await;
+    assert_eq!(link.label(), "read");
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .uri("/v1/progress/stats?weeks=4")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let stats: Value = serde_json::from_slice(&body).unwrap();
+    let read_per_week = stats["read_per_week"].as_array().unwrap();
+    assert_eq!(read_per_week.len(), 4);
+    assert!(read_per_week[3]["count"] == 1);
+    assert!(stats["average_reading_time"] == 240);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_reading_progress_of_another_user(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let id = repository.add_link("user@test.com", "http://link").await;
+    let token = auth::generate_token("other@test.com", false);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri(format!("/v1/links/{id}/progress"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(r#"{"percentage": 100}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
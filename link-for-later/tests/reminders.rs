@@ -0,0 +1,148 @@
+#![allow(dead_code)]
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{Duration, Utc};
+use http_body_util::BodyExt;
+use rstest::rstest;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use crate::repository::DatabaseType;
+
+mod app;
+mod auth;
+mod repository;
+
+#[rstest]
+#[tokio::test]
+async fn test_reminders(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let link_id = repository.add_link("user@test.com", "http://link").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    let request = json!({
+        "link_id": link_id,
+        "remind_at": Utc::now() + Duration::hours(1),
+    });
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/reminders")
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(request.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let reminder: Value = serde_json::from_slice(&body).unwrap();
+    let id = reminder["id"].as_str().unwrap();
+    assert!(reminder["link_id"] == link_id.as_str());
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("PUT")
+                .uri("/v1/reminders/digest")
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(r#"{"hour": 8}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = json!({"until": Utc::now() + Duration::days(1)});
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/v1/reminders/{id}/snooze"))
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(request.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri(format!("/v1/reminders/{id}/dismiss"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri("/v1/reminders")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let reminders: Vec<Value> = serde_json::from_slice(&body).unwrap();
+    assert!(reminders.len() == 1);
+    assert!(reminders[0]["kind"] == "digest");
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_reminder_for_another_users_link(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let link_id = repository.add_link("user@test.com", "http://link").await;
+    let token = auth::generate_token("other@test.com", false);
+
+    let request = json!({
+        "link_id": link_id,
+        "remind_at": Utc::now() + Duration::hours(1),
+    });
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/reminders")
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(request.to_string()))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
@@ -4,15 +4,20 @@ use link_for_later_types::entity::{LinkItem, UserInfo};
 
 pub mod inmemory;
 pub mod mongodb;
+pub mod postgres;
+pub mod sqlite;
 
 #[derive(Clone)]
 pub enum DatabaseType {
     MongoDb,
+    Postgres,
+    Sqlite,
     InMemory,
 }
 
 #[async_trait]
 pub trait Repository {
+    /// Counts the links that are not in the trash.
     async fn count_links(&self) -> u64;
     async fn get_link(&self, id: &str) -> LinkItem;
     async fn add_link(&self, owner: &str, url: &str) -> String;
@@ -29,5 +34,15 @@ pub fn new(db_type: &DatabaseType) -> Box<dyn Repository> {
             repository.setup();
             Box::new(repository)
         }
+        DatabaseType::Postgres => {
+            let repository = postgres::RepositoryProvider::default();
+            repository.setup();
+            Box::new(repository)
+        }
+        DatabaseType::Sqlite => {
+            let repository = sqlite::RepositoryProvider::default();
+            repository.setup();
+            Box::new(repository)
+        }
     }
 }
@@ -14,6 +14,14 @@ const MONGODB_DATABASE_NAME_KEY: &str = "MONGODB_DATABASE_NAME";
 
 const LINKS_COLLECTION_NAME_KEY: &str = "LINKS_COLLECTION_NAME";
 const USERS_COLLECTION_NAME_KEY: &str = "USERS_COLLECTION_NAME";
+const MIGRATIONS_COLLECTION_NAME_KEY: &str = "MIGRATIONS_COLLECTION_NAME";
+const REVISIONS_COLLECTION_NAME_KEY: &str = "REVISIONS_COLLECTION_NAME";
+const COLLECTIONS_COLLECTION_NAME_KEY: &str = "COLLECTIONS_COLLECTION_NAME";
+const SHARES_COLLECTION_NAME_KEY: &str = "SHARES_COLLECTION_NAME";
+const REMINDERS_COLLECTION_NAME_KEY: &str = "REMINDERS_COLLECTION_NAME";
+const DETAILS_COLLECTION_NAME_KEY: &str = "DETAILS_COLLECTION_NAME";
+const HIGHLIGHTS_COLLECTION_NAME_KEY: &str = "HIGHLIGHTS_COLLECTION_NAME";
+const PROGRESS_COLLECTION_NAME_KEY: &str = "PROGRESS_COLLECTION_NAME";
 
 #[derive(Default)]
 pub struct RepositoryProvider {}
@@ -24,7 +32,7 @@ impl Repository for RepositoryProvider {
         database()
             .await
             .collection::<LinkItem>(&std::env::var(LINKS_COLLECTION_NAME_KEY).unwrap())
-            .count_documents(None, None)
+            .count_documents(doc! {"deleted_at": null}, None)
             .await
             .unwrap()
     }
@@ -94,12 +102,55 @@ impl Repository for RepositoryProvider {
 }
 
 impl RepositoryProvider {
+    /// Adds a link the way it was left when a create failed halfway, with
+    /// the placeholder id instead of the one from `_id`.
+    pub async fn add_link_with_placeholder_id(&self, owner: &str, url: &str) -> String {
+        let item = LinkItemBuilder::new(url).id("1").owner(owner).build();
+        database()
+            .await
+            .collection(&std::env::var(LINKS_COLLECTION_NAME_KEY).unwrap())
+            .insert_one(item, None)
+            .await
+            .unwrap()
+            .inserted_id
+            .as_object_id()
+            .unwrap()
+            .to_hex()
+    }
+
+    /// Adds a link document that can't be read back as a link.
+    pub async fn add_malformed_link(&self, owner: &str) {
+        database()
+            .await
+            .collection(&std::env::var(LINKS_COLLECTION_NAME_KEY).unwrap())
+            .insert_one(doc! {"id": "malformed", "owner": owner, "url": 42}, None)
+            .await
+            .unwrap();
+    }
+
     pub fn setup(&self) {
         let mut rng = rand::thread_rng();
         let id = rng.gen::<u32>();
 
         std::env::set_var(LINKS_COLLECTION_NAME_KEY, format!("v{}/links", id));
         std::env::set_var(USERS_COLLECTION_NAME_KEY, format!("v{}/users", id));
+        std::env::set_var(
+            MIGRATIONS_COLLECTION_NAME_KEY,
+            format!("v{}/migrations", id),
+        );
+        std::env::set_var(REVISIONS_COLLECTION_NAME_KEY, format!("v{}/revisions", id));
+        std::env::set_var(
+            COLLECTIONS_COLLECTION_NAME_KEY,
+            format!("v{}/collections", id),
+        );
+        std::env::set_var(SHARES_COLLECTION_NAME_KEY, format!("v{}/shares", id));
+        std::env::set_var(REMINDERS_COLLECTION_NAME_KEY, format!("v{}/reminders", id));
+        std::env::set_var(DETAILS_COLLECTION_NAME_KEY, format!("v{}/details", id));
+        std::env::set_var(
+            HIGHLIGHTS_COLLECTION_NAME_KEY,
+            format!("v{}/highlights", id),
+        );
+        std::env::set_var(PROGRESS_COLLECTION_NAME_KEY, format!("v{}/progress", id));
     }
 }
 
@@ -0,0 +1,120 @@
+use axum::async_trait;
+use rand::Rng;
+use sqlx::{postgres::PgPoolOptions, PgPool, Row};
+
+use link_for_later_types::entity::{LinkItem, LinkItemBuilder, UserInfo, UserInfoBuilder};
+
+use super::Repository;
+
+const POSTGRES_URL_KEY: &str = "POSTGRES_URL";
+const POSTGRES_SCHEMA_KEY: &str = "POSTGRES_SCHEMA";
+
+#[derive(Default)]
+pub struct RepositoryProvider {}
+
+#[async_trait]
+impl Repository for RepositoryProvider {
+    async fn count_links(&self) -> u64 {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM links WHERE deleted_at IS NULL")
+            .fetch_one(&database().await)
+            .await
+            .unwrap();
+        u64::try_from(count).unwrap()
+    }
+
+    async fn get_link(&self, id: &str) -> LinkItem {
+        let row = sqlx::query("SELECT id, owner, url, label FROM links WHERE id = $1")
+            .bind(id)
+            .fetch_one(&database().await)
+            .await
+            .unwrap();
+        LinkItemBuilder::new(row.get("url"))
+            .id(row.get("id"))
+            .owner(row.get("owner"))
+            .label(row.get("label"))
+            .build()
+    }
+
+    async fn add_link(&self, owner: &str, url: &str) -> String {
+        sqlx::query_scalar(
+            "INSERT INTO links (owner, url, created_at, updated_at) \
+             VALUES ($1, $2, now(), now()) RETURNING id",
+        )
+        .bind(owner)
+        .bind(url)
+        .fetch_one(&database().await)
+        .await
+        .unwrap()
+    }
+
+    async fn count_users(&self) -> u64 {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&database().await)
+            .await
+            .unwrap();
+        u64::try_from(count).unwrap()
+    }
+
+    async fn get_user(&self, email: &str) -> UserInfo {
+        let row = sqlx::query("SELECT id, email, password FROM users WHERE email = $1")
+            .bind(email)
+            .fetch_one(&database().await)
+            .await
+            .unwrap();
+        UserInfoBuilder::new(row.get("email"), row.get("password"))
+            .id(row.get("id"))
+            .build()
+    }
+
+    async fn add_user(&self, email: &str, password: &str) -> String {
+        sqlx::query_scalar(
+            "INSERT INTO users (email, password, created_at, updated_at) \
+             VALUES ($1, $2, now(), now()) RETURNING id",
+        )
+        .bind(email)
+        .bind(password)
+        .fetch_one(&database().await)
+        .await
+        .unwrap()
+    }
+}
+
+impl RepositoryProvider {
+    pub fn setup(&self) {
+        let mut rng = rand::thread_rng();
+        let id = rng.gen::<u32>();
+
+        std::env::set_var(POSTGRES_SCHEMA_KEY, format!("v{}", id));
+    }
+}
+
+/// Connects to a schema of its own for every test, creating it along with
+/// the tables the first time it is used.
+pub async fn database() -> PgPool {
+    let url = std::env::var(POSTGRES_URL_KEY).unwrap();
+    let schema = std::env::var(POSTGRES_SCHEMA_KEY).unwrap();
+
+    let pool = PgPoolOptions::new().connect(&url).await.unwrap();
+    sqlx::query(&format!("CREATE SCHEMA IF NOT EXISTS \"{}\"", schema))
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool.close().await;
+
+    let pool = PgPoolOptions::new()
+        .after_connect(move |conn, _| {
+            let schema = schema.clone();
+            Box::pin(async move {
+                sqlx::query(&format!("SET search_path TO \"{}\"", schema))
+                    .execute(conn)
+                    .await?;
+                Ok(())
+            })
+        })
+        .connect(&url)
+        .await
+        .unwrap();
+    let db = link_for_later::DatabaseType::Postgres(pool.clone());
+    link_for_later::app::migrate(&db).await.unwrap();
+    pool
+}
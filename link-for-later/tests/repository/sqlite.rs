@@ -0,0 +1,110 @@
+use std::path::PathBuf;
+
+use axum::async_trait;
+use chrono::Utc;
+use rand::Rng;
+use sqlx::{Row, SqlitePool};
+
+use link_for_later_types::entity::{LinkItem, LinkItemBuilder, UserInfo, UserInfoBuilder};
+
+use super::Repository;
+
+const SQLITE_PATH_KEY: &str = "SQLITE_PATH";
+
+#[derive(Default)]
+pub struct RepositoryProvider {}
+
+#[async_trait]
+impl Repository for RepositoryProvider {
+    async fn count_links(&self) -> u64 {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM links WHERE deleted_at IS NULL")
+            .fetch_one(&pool().await)
+            .await
+            .unwrap();
+        u64::try_from(count).unwrap()
+    }
+
+    async fn get_link(&self, id: &str) -> LinkItem {
+        let row = sqlx::query("SELECT id, owner, url, label FROM links WHERE id = ?1")
+            .bind(id)
+            .fetch_one(&pool().await)
+            .await
+            .unwrap();
+        LinkItemBuilder::new(row.get("url"))
+            .id(row.get("id"))
+            .owner(row.get("owner"))
+            .label(row.get("label"))
+            .build()
+    }
+
+    async fn add_link(&self, owner: &str, url: &str) -> String {
+        sqlx::query_scalar(
+            "INSERT INTO links (owner, url, created_at, updated_at) \
+             VALUES (?1, ?2, ?3, ?3) RETURNING id",
+        )
+        .bind(owner)
+        .bind(url)
+        .bind(Utc::now())
+        .fetch_one(&pool().await)
+        .await
+        .unwrap()
+    }
+
+    async fn count_users(&self) -> u64 {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&pool().await)
+            .await
+            .unwrap();
+        u64::try_from(count).unwrap()
+    }
+
+    async fn get_user(&self, email: &str) -> UserInfo {
+        let row = sqlx::query("SELECT id, email, password FROM users WHERE email = ?1")
+            .bind(email)
+            .fetch_one(&pool().await)
+            .await
+            .unwrap();
+        UserInfoBuilder::new(row.get("email"), row.get("password"))
+            .id(row.get("id"))
+            .build()
+    }
+
+    async fn add_user(&self, email: &str, password: &str) -> String {
+        sqlx::query_scalar(
+            "INSERT INTO users (email, password, created_at, updated_at) \
+             VALUES (?1, ?2, ?3, ?3) RETURNING id",
+        )
+        .bind(email)
+        .bind(password)
+        .bind(Utc::now())
+        .fetch_one(&pool().await)
+        .await
+        .unwrap()
+    }
+}
+
+impl RepositoryProvider {
+    pub fn setup(&self) {
+        let mut rng = rand::thread_rng();
+        let id = rng.gen::<u32>();
+
+        let path = std::env::temp_dir().join(format!("link-for-later-v{}.db", id));
+        std::env::set_var(SQLITE_PATH_KEY, path);
+    }
+}
+
+/// Points at a database file of its own for every test, creating it along
+/// with the tables the first time it is used.
+pub async fn database() -> PathBuf {
+    let path = PathBuf::from(std::env::var(SQLITE_PATH_KEY).unwrap());
+    let db = link_for_later::DatabaseType::Sqlite(path.clone());
+    link_for_later::app::migrate(&db).await.unwrap();
+    path
+}
+
+async fn pool() -> SqlitePool {
+    let path = database().await;
+    SqlitePool::connect(&format!("sqlite://{}", path.display()))
+        .await
+        .unwrap()
+}
@@ -0,0 +1,197 @@
+#![allow(dead_code)]
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use rstest::rstest;
+use serde_json::Value;
+use tower::ServiceExt;
+
+use crate::repository::DatabaseType;
+
+mod app;
+mod auth;
+mod repository;
+
+#[rstest]
+#[tokio::test]
+async fn test_public_share(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let link_id = repository.add_link("user@test.com", "http://link").await;
+    repository.add_link("user@test.com", "http://other").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    let request = format!(r#"{{"kind": "link", "id": "{link_id}"}}"#);
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/shares")
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(request))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let share: Value = serde_json::from_slice(&body).unwrap();
+    let id = share["id"].as_str().unwrap();
+    let public_token = share["token"].as_str().unwrap();
+    assert!(share["owner"] == "user@test.com");
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/public/{public_token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = std::str::from_utf8(&body).unwrap();
+    let view: Value = serde_json::from_str(body).unwrap();
+    assert!(view["link"]["url"] == "http://link");
+    assert!(!body.contains("user@test.com"));
+    assert!(!body.contains("http://other"));
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("DELETE")
+                .uri(format!("/v1/shares/{id}"))
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/public/{public_token}"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_share_of_another_users_link(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    let link_id = repository.add_link("user@test.com", "http://link").await;
+    let token = auth::generate_token("other@test.com", false);
+
+    let request = format!(r#"{{"kind": "link", "id": "{link_id}"}}"#);
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/shares")
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(request))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[rstest]
+#[tokio::test]
+async fn test_feed_of_unread_links(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    repository.add_link("user@test.com", "http://link").await;
+    repository.add_link("other@test.com", "http://other").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    let request = r#"{"kind": "feed", "id": "user@test.com"}"#;
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/shares")
+                .header("Content-Type", "application/json")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::from(request))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let share: Value = serde_json::from_slice(&body).unwrap();
+    let public_token = share["token"].as_str().unwrap();
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/public/{public_token}/feed"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers()["Content-Type"]
+        .to_str()
+        .unwrap()
+        .starts_with("application/atom+xml"));
+    let etag = response.headers()["ETag"].clone();
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body = std::str::from_utf8(&body).unwrap();
+    assert!(body.contains("http://link"));
+    assert!(!body.contains("http://other"));
+    assert!(!body.contains("user@test.com"));
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .method("GET")
+                .uri(format!("/v1/public/{public_token}/feed"))
+                .header("If-None-Match", etag)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+}
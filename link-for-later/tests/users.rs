@@ -24,7 +24,8 @@ mod repository;
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_register_user(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -68,7 +69,8 @@ async fn test_register_user(
 #[rstest]
 #[tokio::test]
 async fn test_register_user_invalid_email(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[values(true, false)] is_admin: bool,
 ) {
     let repository = repository::new(&db_type);
@@ -110,7 +112,8 @@ async fn test_register_user_invalid_email(
 #[case(false, "user@test.com")]
 #[tokio::test]
 async fn test_register_user_already_registered(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
     #[case] is_admin: bool,
     #[case] user: &str,
 ) {
@@ -155,7 +158,10 @@ async fn test_register_user_already_registered(
 
 #[rstest]
 #[tokio::test]
-async fn test_login_user(#[values(DatabaseType::MongoDb)] db_type: DatabaseType) {
+async fn test_login_user(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
     let repository = repository::new(&db_type);
 
     let password_hash = Argon2::default()
@@ -192,7 +198,10 @@ async fn test_login_user(#[values(DatabaseType::MongoDb)] db_type: DatabaseType)
 
 #[rstest]
 #[tokio::test]
-async fn test_login_user_invalid_email(#[values(DatabaseType::MongoDb)] db_type: DatabaseType) {
+async fn test_login_user_invalid_email(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
     repository::new(&db_type);
 
     let request = r#"{
@@ -222,7 +231,10 @@ async fn test_login_user_invalid_email(#[values(DatabaseType::MongoDb)] db_type:
 
 #[rstest]
 #[tokio::test]
-async fn test_login_user_not_found(#[values(DatabaseType::MongoDb)] db_type: DatabaseType) {
+async fn test_login_user_not_found(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
     let repository = repository::new(&db_type);
 
     let password_hash = Argon2::default()
@@ -259,7 +271,8 @@ async fn test_login_user_not_found(#[values(DatabaseType::MongoDb)] db_type: Dat
 #[rstest]
 #[tokio::test]
 async fn test_login_user_incorrect_password(
-    #[values(DatabaseType::MongoDb)] db_type: DatabaseType,
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
 ) {
     let repository = repository::new(&db_type);
 
@@ -296,3 +309,43 @@ async fn test_login_user_incorrect_password(
         json!({"error": "incorrect password for user"}).to_string()
     );
 }
+
+#[rstest]
+#[tokio::test]
+async fn test_user_stats(
+    #[values(DatabaseType::MongoDb, DatabaseType::Postgres, DatabaseType::Sqlite)]
+    db_type: DatabaseType,
+) {
+    let repository = repository::new(&db_type);
+
+    repository.add_link("user@test.com", "http://first").await;
+    repository.add_link("user@test.com", "http://second").await;
+    repository.add_link("other@test.com", "http://third").await;
+    let token = auth::generate_token("user@test.com", false);
+
+    let response = app::new(&db_type)
+        .await
+        .oneshot(
+            Request::builder()
+                .uri("/v1/users/me/stats?days=7")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let stats: Value = serde_json::from_slice(&body).unwrap();
+    assert!(stats["counts"] == json!({"unread": 2, "read": 0}));
+    assert_eq!(stats["daily"].as_array().unwrap().len(), 7);
+    let backlog = &stats["backlog"];
+    assert_eq!(
+        ["week", "month", "quarter", "older"]
+            .iter()
+            .map(|age| backlog[age].as_u64().unwrap())
+            .sum::<u64>(),
+        2
+    );
+}